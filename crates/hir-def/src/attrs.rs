@@ -136,10 +136,13 @@ fn match_attr_flags(attr_flags: &mut AttrFlags, attr: Meta) -> ControlFlow<Infal
         Meta::NamedKeyValue { name: Some(name), value, .. } => match name.text() {
             "deprecated" => attr_flags.insert(AttrFlags::IS_DEPRECATED),
             "ignore" => attr_flags.insert(AttrFlags::IS_IGNORE),
+            "must_use" => attr_flags.insert(AttrFlags::HAS_MUST_USE),
+            "should_panic" => attr_flags.insert(AttrFlags::IS_SHOULD_PANIC),
             "lang" => attr_flags.insert(AttrFlags::LANG_ITEM),
             "path" => attr_flags.insert(AttrFlags::HAS_PATH),
             "unstable" => attr_flags.insert(AttrFlags::IS_UNSTABLE),
             "export_name" => {
+                attr_flags.insert(AttrFlags::HAS_EXPORT_NAME);
                 if let Some(value) = value
                     && let Some(value) = ast::String::cast(value)
                     && let Ok(value) = value.value()
@@ -156,7 +159,9 @@ fn match_attr_flags(attr_flags: &mut AttrFlags, attr: Meta) -> ControlFlow<Infal
                 "cfg" => attr_flags.insert(AttrFlags::HAS_CFG),
                 "doc" => extract_doc_tt_attr(attr_flags, tt),
                 "repr" => attr_flags.insert(AttrFlags::HAS_REPR),
+                "should_panic" => attr_flags.insert(AttrFlags::IS_SHOULD_PANIC),
                 "target_feature" => attr_flags.insert(AttrFlags::HAS_TARGET_FEATURE),
+                "used" => attr_flags.insert(AttrFlags::IS_USED),
                 "proc_macro_derive" | "rustc_builtin_macro" => {
                     attr_flags.insert(AttrFlags::IS_DERIVE_OR_BUILTIN_MACRO)
                 }
@@ -203,11 +208,14 @@ fn match_attr_flags(attr_flags: &mut AttrFlags, attr: Meta) -> ControlFlow<Infal
                     "unstable" => attr_flags.insert(AttrFlags::IS_UNSTABLE),
                     "deprecated" => attr_flags.insert(AttrFlags::IS_DEPRECATED),
                     "macro_export" => attr_flags.insert(AttrFlags::IS_MACRO_EXPORT),
+                    "must_use" => attr_flags.insert(AttrFlags::HAS_MUST_USE),
                     "no_mangle" => attr_flags.insert(AttrFlags::NO_MANGLE),
+                    "used" => attr_flags.insert(AttrFlags::IS_USED),
                     "pointee" => attr_flags.insert(AttrFlags::IS_POINTEE),
                     "non_exhaustive" => attr_flags.insert(AttrFlags::NON_EXHAUSTIVE),
                     "ignore" => attr_flags.insert(AttrFlags::IS_IGNORE),
                     "bench" => attr_flags.insert(AttrFlags::IS_BENCH),
+                    "should_panic" => attr_flags.insert(AttrFlags::IS_SHOULD_PANIC),
                     "rustc_const_panic_str" => attr_flags.insert(AttrFlags::RUSTC_CONST_PANIC_STR),
                     "rustc_intrinsic" => attr_flags.insert(AttrFlags::RUSTC_INTRINSIC),
                     "rustc_safe_intrinsic" => attr_flags.insert(AttrFlags::RUSTC_SAFE_INTRINSIC),
@@ -311,6 +319,11 @@ bitflags::bitflags! {
         const MACRO_STYLE_BRACES = 1 << 46;
         const MACRO_STYLE_BRACKETS = 1 << 47;
         const MACRO_STYLE_PARENTHESES = 1 << 48;
+
+        const IS_SHOULD_PANIC = 1 << 49;
+        const HAS_MUST_USE = 1 << 50;
+        const HAS_EXPORT_NAME = 1 << 51;
+        const IS_USED = 1 << 52;
     }
 }
 
@@ -759,6 +772,56 @@ pub struct DeriveInfo {
     pub helpers: Box<[Symbol]>,
 }
 
+/// The contents of a `#[deprecated]` attribute.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Deprecation {
+    /// The version given by `since = "version"`.
+    pub since: Option<Symbol>,
+    /// The note given by `#[deprecated = "note"]` or `note = "note"`.
+    pub note: Option<Symbol>,
+}
+
+fn extract_deprecation(attr: Meta) -> ControlFlow<Deprecation> {
+    match attr {
+        Meta::Path { path } if path.is1("deprecated") => ControlFlow::Break(Deprecation::default()),
+        Meta::NamedKeyValue { name: Some(name), value, .. } if name.text() == "deprecated" => {
+            let note = value
+                .and_then(ast::String::cast)
+                .and_then(|value| Some(Symbol::intern(&value.value().ok()?)));
+            ControlFlow::Break(Deprecation { since: None, note })
+        }
+        Meta::TokenTree { path, tt } if path.is1("deprecated") => {
+            let mut result = Deprecation::default();
+            for atom in DocAtom::parse(tt) {
+                if let DocAtom::KeyValue { key, value } = atom {
+                    match &*key {
+                        "since" => result.since = Some(Symbol::intern(&value)),
+                        "note" => result.note = Some(Symbol::intern(&value)),
+                        _ => {}
+                    }
+                }
+            }
+            ControlFlow::Break(result)
+        }
+        _ => ControlFlow::Continue(()),
+    }
+}
+
+fn extract_unstable_feature(attr: Meta) -> ControlFlow<Symbol> {
+    if let Meta::TokenTree { path, tt } = attr
+        && path.is1("unstable")
+    {
+        for atom in DocAtom::parse(tt) {
+            if let DocAtom::KeyValue { key, value } = atom
+                && key == "feature"
+            {
+                return ControlFlow::Break(Symbol::intern(&value));
+            }
+        }
+    }
+    ControlFlow::Continue(())
+}
+
 fn extract_doc_aliases(result: &mut Vec<Symbol>, attr: Meta) -> ControlFlow<Infallible> {
     if let Meta::TokenTree { path, tt } = attr
         && path.is1("doc")
@@ -1020,6 +1083,109 @@ impl AttrFlags {
         }
     }
 
+    /// Returns the reason given by `#[must_use = "reason"]`. Check [`AttrFlags::HAS_MUST_USE`]
+    /// first to know whether the item is `#[must_use]` at all.
+    #[inline]
+    pub fn must_use_reason(db: &dyn DefDatabase, owner: AttrDefId) -> Option<Symbol> {
+        if !AttrFlags::query(db, owner).contains(AttrFlags::HAS_MUST_USE) {
+            // Don't create the query in case this is not `#[must_use]`, this wastes memory.
+            return None;
+        }
+
+        return must_use_reason(db, owner);
+
+        #[salsa::tracked]
+        fn must_use_reason(db: &dyn DefDatabase, owner: AttrDefId) -> Option<Symbol> {
+            collect_attrs(db, owner, |attr| {
+                if let Meta::NamedKeyValue { name: Some(name), value: Some(value), .. } = attr
+                    && name.text() == "must_use"
+                    && let Some(value) = ast::String::cast(value)
+                    && let Ok(value) = value.value()
+                {
+                    ControlFlow::Break(Symbol::intern(&value))
+                } else {
+                    ControlFlow::Continue(())
+                }
+            })
+        }
+    }
+
+    /// Returns the `since` and `note` of `#[deprecated]`, or `None` if the item is not deprecated.
+    #[inline]
+    pub fn deprecation(
+        self,
+        db: &dyn DefDatabase,
+        owner: Either<AttrDefId, FieldId>,
+    ) -> Option<&Deprecation> {
+        if !self.contains(AttrFlags::IS_DEPRECATED) {
+            // Don't create the query in case this is not deprecated, this wastes memory.
+            return None;
+        }
+        return match owner {
+            Either::Left(it) => deprecation(db, it).as_ref(),
+            Either::Right(field) => {
+                fields_deprecation(db, field.parent).get(field.local_id)?.as_ref()
+            }
+        };
+
+        #[salsa::tracked(returns(ref))]
+        fn deprecation(db: &dyn DefDatabase, owner: AttrDefId) -> Option<Deprecation> {
+            collect_attrs(db, owner, extract_deprecation)
+        }
+
+        #[salsa::tracked(returns(ref))]
+        fn fields_deprecation(
+            db: &dyn DefDatabase,
+            variant: VariantId,
+        ) -> ArenaMap<LocalFieldId, Option<Deprecation>> {
+            collect_field_attrs(db, variant, |cfg_options, field| {
+                expand_cfg_attr(
+                    field.value.attrs(),
+                    || cfg_options,
+                    |attr, _, _, _| extract_deprecation(attr),
+                )
+            })
+        }
+    }
+
+    /// Returns the feature given by `#[unstable(feature = "name")]`.
+    #[inline]
+    pub fn unstable_feature(
+        self,
+        db: &dyn DefDatabase,
+        owner: Either<AttrDefId, FieldId>,
+    ) -> Option<&Symbol> {
+        if !self.contains(AttrFlags::IS_UNSTABLE) {
+            // Don't create the query in case this is not unstable, this wastes memory.
+            return None;
+        }
+        return match owner {
+            Either::Left(it) => unstable_feature(db, it).as_ref(),
+            Either::Right(field) => {
+                fields_unstable_feature(db, field.parent).get(field.local_id)?.as_ref()
+            }
+        };
+
+        #[salsa::tracked(returns(ref))]
+        fn unstable_feature(db: &dyn DefDatabase, owner: AttrDefId) -> Option<Symbol> {
+            collect_attrs(db, owner, extract_unstable_feature)
+        }
+
+        #[salsa::tracked(returns(ref))]
+        fn fields_unstable_feature(
+            db: &dyn DefDatabase,
+            variant: VariantId,
+        ) -> ArenaMap<LocalFieldId, Option<Symbol>> {
+            collect_field_attrs(db, variant, |cfg_options, field| {
+                expand_cfg_attr(
+                    field.value.attrs(),
+                    || cfg_options,
+                    |attr, _, _, _| extract_unstable_feature(attr),
+                )
+            })
+        }
+    }
+
     #[inline]
     pub fn repr(db: &dyn DefDatabase, owner: AdtId) -> Option<ReprOptions> {
         if !AttrFlags::query(db, owner.into()).contains(AttrFlags::HAS_REPR) {
@@ -903,6 +903,16 @@ impl ExpressionStoreSourceMap {
         self.expr_or_synthetic()?.expr_map_back.get(expr).cloned().ok_or(SyntheticSyntax)
     }
 
+    /// Whether `expr` was created while desugaring its syntax node, instead of being the
+    /// expression the node itself lowers to.
+    pub fn is_desugared_expr(&self, expr: ExprId) -> bool {
+        let Ok(src) = self.expr_syntax(expr) else { return true };
+        let Some(src) = src.map(|it| it.cast::<ast::Expr>()).transpose() else { return true };
+        self.expr_only()
+            .and_then(|it| it.expr_map.get(&src))
+            .is_none_or(|&it| it != ExprOrPatId::ExprId(expr))
+    }
+
     pub fn node_expr(&self, node: InFile<&ast::Expr>) -> Option<ExprOrPatId> {
         let src = node.map(AstPtr::new);
         self.expr_only()?.expr_map.get(&src).cloned()
@@ -16,7 +16,7 @@ use rustc_type_ir::inherent::IntoKind;
 use triomphe::Arc;
 
 use crate::{
-    LifetimeElisionKind, MemoryMap, ParamEnvAndCrate, TyLoweringContext,
+    InferenceDiagnostic, LifetimeElisionKind, MemoryMap, ParamEnvAndCrate, TyLoweringContext,
     db::HirDatabase,
     display::DisplayTarget,
     infer::InferenceContext,
@@ -333,10 +333,19 @@ pub(crate) fn eval_to_const<'db>(expr: ExprId, ctx: &mut InferenceContext<'_, 'd
             return c;
         }
     }
-    if let Ok(mir_body) = lower_to_mir(ctx.db, ctx.owner, ctx.body, &infer, expr)
-        && let Ok((Ok(result), _)) = interpret_mir(ctx.db, Arc::new(mir_body), true, None)
-    {
-        return result;
+    if let Ok(mir_body) = lower_to_mir(ctx.db, ctx.owner, ctx.body, &infer, expr) {
+        match interpret_mir(ctx.db, Arc::new(mir_body), true, None) {
+            Ok((Ok(result), _)) => return result,
+            Ok((Err(e), _)) => {
+                if let Some(message) = e.is_panic() {
+                    ctx.push_diagnostic(InferenceDiagnostic::ConstEvalFailed {
+                        expr,
+                        message: message.to_owned(),
+                    });
+                }
+            }
+            Err(_) => {}
+        }
     }
     Const::error(ctx.interner())
 }
@@ -2316,6 +2316,24 @@ fn array_and_index() {
     );
 }
 
+#[test]
+fn array_index_out_of_bounds() {
+    check_fail(
+        r#"
+    //- minicore: coerce_unsized, index, slice
+    const GOAL: i32 = {
+        let a = [1, 2, 3];
+        let i = 5;
+        a[i]
+    };"#,
+        |e| {
+            e == ConstEvalError::MirEvalError(MirEvalError::Panic(
+                "index out of bounds: the length is 3 but the index is 5".to_owned(),
+            ))
+        },
+    );
+}
+
 #[test]
 fn string() {
     check_str(
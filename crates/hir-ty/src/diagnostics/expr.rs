@@ -7,18 +7,20 @@ use std::fmt;
 use base_db::Crate;
 use either::Either;
 use hir_def::{
-    AdtId, AssocItemId, DefWithBodyId, HasModule, ItemContainerId, Lookup,
+    AdtId, AssocItemId, CallableDefId, DefWithBodyId, FunctionId, HasModule, ItemContainerId,
+    Lookup,
+    attrs::AttrFlags,
     lang_item::LangItems,
     resolver::{HasResolver, ValueNs},
 };
 use intern::sym;
 use itertools::Itertools;
 use rustc_hash::FxHashSet;
-use rustc_pattern_analysis::constructor::Constructor;
+use rustc_pattern_analysis::{constructor::Constructor, usefulness::Usefulness};
 use rustc_type_ir::inherent::{AdtDef, IntoKind};
 use syntax::{
     AstNode,
-    ast::{self, UnaryOp},
+    ast::{self, ArithOp, BinaryOp, UnaryOp},
 };
 use tracing::debug;
 use triomphe::Arc;
@@ -26,6 +28,7 @@ use typed_arena::Arena;
 
 use crate::{
     Adjust, InferenceResult,
+    consteval::try_const_usize,
     db::HirDatabase,
     diagnostics::match_check::{
         self,
@@ -33,7 +36,7 @@ use crate::{
     },
     display::{DisplayTarget, HirDisplay},
     next_solver::{
-        DbInterner, ParamEnv, Ty, TyKind, TypingMode,
+        ClauseKind, DbInterner, ParamEnv, Ty, TyKind, TypingMode,
         infer::{DbInternerInferExt, InferCtxt},
     },
 };
@@ -41,7 +44,9 @@ use crate::{
 pub(crate) use hir_def::{
     LocalFieldId, VariantId,
     expr_store::Body,
-    hir::{Expr, ExprId, MatchArm, Pat, PatId, RecordSpread, Statement},
+    hir::{
+        Array, Expr, ExprId, ExprOrPatId, Literal, MatchArm, Pat, PatId, RecordSpread, Statement,
+    },
 };
 
 pub enum BodyValidationDiagnostic {
@@ -67,6 +72,44 @@ pub enum BodyValidationDiagnostic {
     RemoveUnnecessaryElse {
         if_expr: ExprId,
     },
+    UnreachableCode {
+        /// The first statement or tail expression after a diverging statement.
+        unreachable: ExprOrPatId,
+    },
+    UnreachablePattern {
+        pat: PatId,
+    },
+    UnusedMustUse {
+        expr: ExprId,
+        kind: UnusedMustUseKind,
+    },
+    ArithmeticOverflow {
+        expr: ExprId,
+        op: ArithOp,
+    },
+    UnconditionalPanic {
+        expr: ExprId,
+        kind: UnconditionalPanicKind,
+    },
+}
+
+/// Why the value of an expression statement must be used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnusedMustUseKind {
+    /// The type of the value is `#[must_use]`.
+    Type(AdtId),
+    /// The value is returned by a `#[must_use]` function.
+    Function(FunctionId),
+    /// The value is a future that is never awaited.
+    Future,
+}
+
+/// Why an operation is known to panic whenever it is executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnconditionalPanicKind {
+    DivisionByZero,
+    RemainderByZero,
+    IndexOutOfBounds { len: u128, index: u128 },
 }
 
 impl BodyValidationDiagnostic {
@@ -118,9 +161,13 @@ impl<'db> ExprValidator<'db> {
         // we'll pass &mut self while iterating over body.exprs, so they need to be disjoint
         let body = Arc::clone(&self.body);
 
-        if matches!(self.owner, DefWithBodyId::FunctionId(_)) {
+        let is_function = matches!(self.owner, DefWithBodyId::FunctionId(_));
+        if is_function {
             self.check_for_trailing_return(body.body_expr, &body);
         }
+        // Failures in array lengths are reported by const evaluation during inference.
+        let array_lengths =
+            if is_function { array_length_exprs(&body) } else { FxHashSet::default() };
 
         for (id, expr) in body.exprs() {
             if let Some((variant, missed_fields)) =
@@ -149,6 +196,16 @@ impl<'db> ExprValidator<'db> {
                 Expr::Block { .. } | Expr::Async { .. } | Expr::Unsafe { .. } => {
                     self.validate_block(expr);
                 }
+                &Expr::BinaryOp { lhs, rhs, op: Some(BinaryOp::ArithOp(op)) }
+                    if is_function && !array_lengths.contains(&id) =>
+                {
+                    if let Err(diagnostic) = self.eval_literal_arith(id, lhs, rhs, op) {
+                        self.diagnostics.push(diagnostic);
+                    }
+                }
+                &Expr::Index { base, index } if is_function => {
+                    self.check_for_out_of_bounds_index(id, base, index);
+                }
                 _ => {}
             }
         }
@@ -164,6 +221,22 @@ impl<'db> ExprValidator<'db> {
                 });
             }
         }
+
+        // A match with missing arms is already an error, so it doesn't make the code after it
+        // unreachable, even if it has no arms at all.
+        let non_exhaustive_matches = self
+            .diagnostics
+            .iter()
+            .filter_map(|it| match it {
+                BodyValidationDiagnostic::MissingMatchArms { match_expr, .. } => Some(*match_expr),
+                _ => None,
+            })
+            .collect::<FxHashSet<_>>();
+        for &(unreachable, cause) in &self.infer.unreachable_code {
+            if !contains_any_expr(&body, cause, &non_exhaustive_matches) {
+                self.diagnostics.push(BodyValidationDiagnostic::UnreachableCode { unreachable });
+            }
+        }
     }
 
     fn validate_call(
@@ -202,6 +275,158 @@ impl<'db> ExprValidator<'db> {
         }
     }
 
+    fn check_for_unused_must_use(&mut self, expr: ExprId) {
+        let db = self.db();
+        let ty = self.infer.expr_ty(expr);
+        if ty.references_non_lt_error() {
+            return;
+        }
+        let kind = if let TyKind::Adt(adt, _) = ty.kind()
+            && AttrFlags::query(db, adt.def_id().0.into()).contains(AttrFlags::HAS_MUST_USE)
+        {
+            UnusedMustUseKind::Type(adt.def_id().0)
+        } else if let Some(func) = self.called_function(expr)
+            && AttrFlags::query(db, func.into()).contains(AttrFlags::HAS_MUST_USE)
+        {
+            UnusedMustUseKind::Function(func)
+        } else if self.is_future(ty) {
+            UnusedMustUseKind::Future
+        } else {
+            return;
+        };
+        self.diagnostics.push(BodyValidationDiagnostic::UnusedMustUse { expr, kind });
+    }
+
+    fn called_function(&self, expr: ExprId) -> Option<FunctionId> {
+        match &self.body[expr] {
+            Expr::MethodCall { .. } => self.infer.method_resolution(expr).map(|(func, _)| func),
+            Expr::Call { callee, .. } => match self.infer.expr_ty(*callee).kind() {
+                TyKind::FnDef(def, _) => match def.0 {
+                    CallableDefId::FunctionId(func) => Some(func),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Whether `ty` is an opaque future, like the ones returned by `async fn`s and blocks.
+    fn is_future(&self, ty: Ty<'db>) -> bool {
+        let Some(future_trait) = self.infcx.interner.lang_items().Future else {
+            return false;
+        };
+        ty.impl_trait_bounds(self.db()).is_some_and(|bounds| {
+            bounds.iter().any(|bound| {
+                matches!(
+                    bound.kind().skip_binder(),
+                    ClauseKind::Trait(trait_pred) if trait_pred.def_id().0 == future_trait
+                )
+            })
+        })
+    }
+
+    /// Evaluates `lhs op rhs` when both operands are integers made of literals only, returning
+    /// `Ok(None)` when they are not and the diagnostic to report when the operation always panics.
+    fn eval_literal_arith(
+        &self,
+        expr: ExprId,
+        lhs: ExprId,
+        rhs: ExprId,
+        op: ArithOp,
+    ) -> Result<Option<i128>, BodyValidationDiagnostic> {
+        let (Some(l), Some(r)) = (self.literal_int_value(lhs), self.literal_int_value(rhs)) else {
+            return Ok(None);
+        };
+        let Some((min, max, bits)) = self.int_range(self.infer.expr_ty(expr)) else {
+            return Ok(None);
+        };
+        let value = match op {
+            ArithOp::Add => l.checked_add(r),
+            ArithOp::Sub => l.checked_sub(r),
+            ArithOp::Mul => l.checked_mul(r),
+            ArithOp::Div | ArithOp::Rem if r == 0 => {
+                let kind = match op {
+                    ArithOp::Div => UnconditionalPanicKind::DivisionByZero,
+                    _ => UnconditionalPanicKind::RemainderByZero,
+                };
+                return Err(BodyValidationDiagnostic::UnconditionalPanic { expr, kind });
+            }
+            ArithOp::Div => l.checked_div(r),
+            ArithOp::Rem => l.checked_rem(r),
+            ArithOp::Shl | ArithOp::Shr if r < 0 || r >= i128::from(bits) => None,
+            // The value of a shift depends on the bits that are shifted out, don't bother.
+            ArithOp::Shl | ArithOp::Shr => return Ok(None),
+            ArithOp::BitXor => Some(l ^ r),
+            ArithOp::BitOr => Some(l | r),
+            ArithOp::BitAnd => Some(l & r),
+        };
+        match value {
+            Some(value) if (min..=max).contains(&value) => Ok(Some(value)),
+            _ => Err(BodyValidationDiagnostic::ArithmeticOverflow { expr, op }),
+        }
+    }
+
+    /// Returns the value of `expr` if it is an integer made of literals only, like `1`, `-1` or
+    /// `1 + 2`.
+    fn literal_int_value(&self, expr: ExprId) -> Option<i128> {
+        let value = match self.body[expr] {
+            Expr::Literal(Literal::Int(value, _)) => value,
+            Expr::Literal(Literal::Uint(value, _)) => i128::try_from(value).ok()?,
+            Expr::UnaryOp { expr: inner, op: UnaryOp::Neg } => match self.body[inner] {
+                // `-128i8` is in range even though `128i8` is not.
+                Expr::Literal(Literal::Int(value, _)) => value.checked_neg()?,
+                _ => self.literal_int_value(inner)?.checked_neg()?,
+            },
+            Expr::BinaryOp { lhs, rhs, op: Some(BinaryOp::ArithOp(op)) } => {
+                self.eval_literal_arith(expr, lhs, rhs, op).ok()??
+            }
+            _ => return None,
+        };
+        let (min, max, _) = self.int_range(self.infer.expr_ty(expr))?;
+        (min..=max).contains(&value).then_some(value)
+    }
+
+    /// Returns the minimum and maximum values and the bit width of an integer type, if they fit
+    /// in an `i128`.
+    fn int_range(&self, ty: Ty<'db>) -> Option<(i128, i128, u64)> {
+        let (bits, signed) = match ty.kind() {
+            TyKind::Int(it) => (it.bit_width(), true),
+            TyKind::Uint(it) => (it.bit_width(), false),
+            _ => return None,
+        };
+        let bits = match bits {
+            Some(bits) => bits,
+            None => {
+                let krate = self.owner.krate(self.db());
+                self.db().target_data_layout(krate).ok()?.pointer_size().bits()
+            }
+        };
+        if signed {
+            let max = i128::MAX >> (128 - bits);
+            Some((-max - 1, max, bits))
+        } else {
+            Some((0, i128::try_from(u128::MAX >> (128 - bits)).ok()?, bits))
+        }
+    }
+
+    fn check_for_out_of_bounds_index(&mut self, expr: ExprId, base: ExprId, index: ExprId) {
+        let mut base_ty = self.infer.expr_ty(base);
+        while let TyKind::Ref(_, inner, _) = base_ty.kind() {
+            base_ty = inner;
+        }
+        let TyKind::Array(_, len) = base_ty.kind() else { return };
+        let Some(len) = try_const_usize(self.db(), len) else { return };
+        let Some(index) = self.literal_int_value(index) else { return };
+        let Ok(index) = u128::try_from(index) else { return };
+        if index >= len {
+            self.diagnostics.push(BodyValidationDiagnostic::UnconditionalPanic {
+                expr,
+                kind: UnconditionalPanicKind::IndexOutOfBounds { len, index },
+            });
+        }
+    }
+
     fn validate_match(&mut self, match_expr: ExprId, scrutinee_expr: ExprId, arms: &[MatchArm]) {
         let Some(scrut_ty) = self.infer.type_of_expr_with_adjust(scrutinee_expr) else {
             return;
@@ -215,6 +440,7 @@ impl<'db> ExprValidator<'db> {
         let pattern_arena = Arena::new();
         let mut m_arms = Vec::with_capacity(arms.len());
         let mut has_lowering_errors = false;
+        let mut has_int_literals = false;
         // Note: Skipping the entire diagnostic rather than just not including a faulty match arm is
         // preferred to avoid the chance of false positives.
         for arm in arms {
@@ -245,7 +471,12 @@ impl<'db> ExprValidator<'db> {
                 // If we had a NotUsefulMatchArm diagnostic, we could
                 // check the usefulness of each pattern as we added it
                 // to the matrix here.
-                let pat = self.lower_pattern(&cx, arm.pat, &mut has_lowering_errors);
+                let pat = self.lower_pattern(
+                    &cx,
+                    arm.pat,
+                    &mut has_lowering_errors,
+                    &mut has_int_literals,
+                );
                 let m_arm = pat_analysis::MatchArm {
                     pat: pattern_arena.alloc(pat),
                     has_guard: arm.guard.is_some(),
@@ -268,11 +499,16 @@ impl<'db> ExprValidator<'db> {
                 Err(()) => return,
             };
 
-        // FIXME Report unreachable arms
-        // https://github.com/rust-lang/rust/blob/f31622a50/compiler/rustc_mir_build/src/thir/pattern/check_match.rs#L200
+        // `arms` and `arm_usefulness` line up, as we bail out above if any arm is skipped.
+        for (arm, (_, usefulness)) in arms.iter().zip(&report.arm_usefulness) {
+            if matches!(usefulness, Usefulness::Redundant { .. }) {
+                self.diagnostics
+                    .push(BodyValidationDiagnostic::UnreachablePattern { pat: arm.pat });
+            }
+        }
 
         let witnesses = report.non_exhaustiveness_witnesses;
-        if !witnesses.is_empty() {
+        if !witnesses.is_empty() && !has_int_literals {
             self.diagnostics.push(BodyValidationDiagnostic::MissingMatchArms {
                 match_expr,
                 uncovered_patterns: missing_match_arms(
@@ -331,6 +567,12 @@ impl<'db> ExprValidator<'db> {
         else {
             return;
         };
+        for stmt in &**statements {
+            if let &Statement::Expr { expr, has_semi: true } = stmt {
+                self.check_for_unused_must_use(expr);
+            }
+        }
+
         let pattern_arena = Arena::new();
         let cx = MatchCheckCtx::new(self.owner.module(self.db()), &self.infcx, self.env);
         for stmt in &**statements {
@@ -347,10 +589,15 @@ impl<'db> ExprValidator<'db> {
             }
 
             let mut have_errors = false;
-            let deconstructed_pat = self.lower_pattern(&cx, pat, &mut have_errors);
+            let mut has_int_literals = false;
+            let deconstructed_pat =
+                self.lower_pattern(&cx, pat, &mut have_errors, &mut has_int_literals);
 
             // optimization, wildcard trivially hold
-            if have_errors || matches!(deconstructed_pat.ctor(), Constructor::Wildcard) {
+            if have_errors
+                || has_int_literals
+                || matches!(deconstructed_pat.ctor(), Constructor::Wildcard)
+            {
                 continue;
             }
 
@@ -387,6 +634,7 @@ impl<'db> ExprValidator<'db> {
         cx: &MatchCheckCtx<'a, 'db>,
         pat: PatId,
         have_errors: &mut bool,
+        has_int_literals: &mut bool,
     ) -> DeconstructedPat<'a, 'db> {
         let mut patcx = match_check::PatCtxt::new(self.db(), self.infer, &self.body);
         let pattern = patcx.lower_pattern(pat);
@@ -394,6 +642,7 @@ impl<'db> ExprValidator<'db> {
         if !patcx.errors.is_empty() {
             *have_errors = true;
         }
+        *has_int_literals |= patcx.has_int_literals;
         pattern
     }
 
@@ -655,6 +904,30 @@ fn types_of_subpatterns_do_match(pat: PatId, body: &Body, infer: &InferenceResul
     !has_type_mismatches
 }
 
+/// Collects the expressions making up the lengths of array repeat expressions in `body`.
+fn array_length_exprs(body: &Body) -> FxHashSet<ExprId> {
+    fn walk(body: &Body, expr: ExprId, acc: &mut FxHashSet<ExprId>) {
+        if acc.insert(expr) {
+            body.walk_child_exprs(expr, |child| walk(body, child, acc));
+        }
+    }
+
+    let mut acc = FxHashSet::default();
+    for (_, expr) in body.exprs() {
+        if let &Expr::Array(Array::Repeat { repeat, .. }) = expr {
+            walk(body, repeat, &mut acc);
+        }
+    }
+    acc
+}
+
+/// Whether `expr` or any of its subexpressions is one of `exprs`.
+fn contains_any_expr(body: &Body, expr: ExprId, exprs: &FxHashSet<ExprId>) -> bool {
+    let mut found = exprs.contains(&expr);
+    body.walk_child_exprs(expr, |child| found = found || contains_any_expr(body, child, exprs));
+    found
+}
+
 fn missing_match_arms<'a, 'db>(
     cx: &MatchCheckCtx<'a, 'db>,
     scrut_ty: Ty<'a>,
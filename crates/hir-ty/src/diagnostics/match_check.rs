@@ -82,11 +82,16 @@ pub(crate) enum PatKind<'db> {
         subpattern: Pat<'db>,
     },
 
-    // FIXME: for now, only bool literals are implemented
+    // FIXME: for now, only bool and integer literals are implemented
     LiteralBool {
         value: bool,
     },
 
+    /// An integer literal, stored as its two's complement bits for signed types.
+    LiteralInt {
+        value: u128,
+    },
+
     /// An or-pattern, e.g. `p | q`.
     /// Invariant: `pats.len() >= 2`.
     Or {
@@ -99,6 +104,9 @@ pub(crate) struct PatCtxt<'a, 'db> {
     infer: &'db InferenceResult,
     body: &'a Body,
     pub(crate) errors: Vec<PatternError>,
+    /// Integer literals are only lowered precisely enough to find redundant patterns, as their
+    /// types are not exhaustively listed.
+    pub(crate) has_int_literals: bool,
 }
 
 impl<'a, 'db> PatCtxt<'a, 'db> {
@@ -107,7 +115,7 @@ impl<'a, 'db> PatCtxt<'a, 'db> {
         infer: &'db InferenceResult,
         body: &'a Body,
     ) -> Self {
-        Self { db, infer, body, errors: Vec::new() }
+        Self { db, infer, body, errors: Vec::new(), has_int_literals: false }
     }
 
     pub(crate) fn lower_pattern(&mut self, pat: PatId) -> Pat<'db> {
@@ -290,10 +298,21 @@ impl<'a, 'db> PatCtxt<'a, 'db> {
     }
 
     fn lower_lit(&mut self, expr: hir_def::hir::ExprId) -> PatKind<'db> {
-        use hir_def::hir::{Expr, Literal::Bool};
+        use hir_def::hir::{
+            Expr,
+            Literal::{Bool, Int, Uint},
+        };
 
         match self.body[expr] {
             Expr::Literal(Bool(value)) => PatKind::LiteralBool { value },
+            Expr::Literal(Int(value, _)) => {
+                self.has_int_literals = true;
+                PatKind::LiteralInt { value: value as u128 }
+            }
+            Expr::Literal(Uint(value, _)) => {
+                self.has_int_literals = true;
+                PatKind::LiteralInt { value }
+            }
             _ => {
                 self.errors.push(PatternError::Unimplemented);
                 PatKind::Wild
@@ -418,6 +437,10 @@ impl<'db> HirDisplay<'db> for Pat<'db> {
                 subpattern.hir_fmt(f)
             }
             PatKind::LiteralBool { value } => write!(f, "{value}"),
+            PatKind::LiteralInt { value } => match self.ty.kind() {
+                TyKind::Int(_) => write!(f, "{}", *value as i128),
+                _ => write!(f, "{value}"),
+            },
             PatKind::Or { pats } => f.write_joined(pats.iter(), " | "),
         }
     }
@@ -8,7 +8,7 @@ use hir_def::{
 use intern::sym;
 use rustc_pattern_analysis::{
     IndexVec, PatCx, PrivateUninhabitedField,
-    constructor::{Constructor, ConstructorSet, VariantVisibility},
+    constructor::{Constructor, ConstructorSet, IntRange, MaybeInfiniteInt, VariantVisibility},
     usefulness::{PlaceValidity, UsefulnessReport, compute_match_usefulness},
 };
 use rustc_type_ir::inherent::{AdtDef, IntoKind};
@@ -231,6 +231,24 @@ impl<'a, 'db> MatchCheckCtx<'a, 'db> {
                 fields = Vec::new();
                 arity = 0;
             }
+            &PatKind::LiteralInt { value } => {
+                // Integers are unlistable, so only the order of the values matters and signed
+                // ones can be encoded as `i128`s.
+                ctor = match pat.ty.kind() {
+                    TyKind::Int(_) => IntRange(IntRange::from_singleton(
+                        MaybeInfiniteInt::new_finite_int(value, 128),
+                    )),
+                    TyKind::Uint(_) => {
+                        IntRange(IntRange::from_singleton(MaybeInfiniteInt::new_finite_uint(value)))
+                    }
+                    _ => {
+                        never!("int literal pattern has unexpected type: {:?}", &pat.ty);
+                        Wildcard
+                    }
+                };
+                fields = Vec::new();
+                arity = 0;
+            }
             PatKind::Never => {
                 ctor = Never;
                 fields = Vec::new();
@@ -253,7 +271,15 @@ impl<'a, 'db> MatchCheckCtx<'a, 'db> {
         let mut subpatterns = pat.iter_fields().map(|p| self.hoist_witness_pat(p));
         let kind = match pat.ctor() {
             &Bool(value) => PatKind::LiteralBool { value },
-            IntRange(_) => unimplemented!(),
+            // Ranges can't be written as patterns yet, so only the single values are hoisted.
+            IntRange(range) if range.is_singleton() => {
+                let value = match pat.ty().kind() {
+                    TyKind::Int(_) => range.lo.as_finite_int(128),
+                    _ => range.lo.as_finite_uint(),
+                };
+                value.map_or(PatKind::Wild, |value| PatKind::LiteralInt { value })
+            }
+            IntRange(_) => PatKind::Wild,
             Struct | Variant(_) | UnionField => match pat.ty().kind() {
                 TyKind::Tuple(..) => PatKind::Leaf {
                     subpatterns: subpatterns
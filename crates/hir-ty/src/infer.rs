@@ -297,6 +297,19 @@ pub enum InferenceDiagnostic {
         /// Whether the `GenericArgs` contains a `Self` arg.
         has_self_arg: bool,
     },
+    UnsatisfiedTraitBound {
+        id: ExprOrPatId,
+        trait_: TraitId,
+        /// The arguments of the unsatisfied bound, starting with its self type.
+        args: StoredGenericArgs,
+        /// The bounds whose impls required the unsatisfied bound, outermost first.
+        required_by: Vec<(TraitId, StoredGenericArgs)>,
+    },
+    ConstEvalFailed {
+        expr: ExprId,
+        /// The message of the panic that occurred during evaluation.
+        message: String,
+    },
 }
 
 /// A mismatch between an expected and an inferred type.
@@ -547,6 +560,9 @@ pub struct InferenceResult {
     pub mutated_bindings_in_closure: FxHashSet<BindingId>,
 
     pub(crate) coercion_casts: FxHashSet<ExprId>,
+    /// The first statement or tail expression of a block that follows a diverging statement,
+    /// along with the expression of that diverging statement.
+    pub(crate) unreachable_code: Vec<(ExprOrPatId, ExprId)>,
 }
 
 #[salsa::tracked]
@@ -580,6 +596,7 @@ impl InferenceResult {
             closure_info: Default::default(),
             mutated_bindings_in_closure: Default::default(),
             coercion_casts: Default::default(),
+            unreachable_code: Default::default(),
         }
     }
 
@@ -981,6 +998,7 @@ impl<'body, 'db> InferenceContext<'body, 'db> {
             mutated_bindings_in_closure: _,
             tuple_field_access_types: _,
             coercion_casts: _,
+            unreachable_code: _,
             diagnostics: _,
         } = &mut result;
 
@@ -1014,6 +1032,19 @@ impl<'body, 'db> InferenceContext<'body, 'db> {
             }
             type_mismatches.shrink_to_fit();
         }
+        diagnostics.extend(table.take_unsatisfied_trait_bounds().into_iter().map(
+            |(id, trait_ref, required_by)| {
+                InferenceDiagnostic::UnsatisfiedTraitBound {
+                    id,
+                    trait_: trait_ref.def_id.0,
+                    args: trait_ref.args.store(),
+                    required_by: required_by
+                        .into_iter()
+                        .map(|trait_ref| (trait_ref.def_id.0, trait_ref.args.store()))
+                        .collect(),
+                }
+            },
+        ));
         diagnostics.retain_mut(|diagnostic| {
             use InferenceDiagnostic::*;
             match diagnostic {
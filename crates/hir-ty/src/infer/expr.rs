@@ -1471,10 +1471,27 @@ impl<'db> InferenceContext<'_, 'db> {
     ) -> Ty<'db> {
         let coerce_ty = expected.coercion_target_type(&mut self.table);
         let g = self.resolver.update_to_inner_scope(self.db, self.owner, expr);
+        // If the block itself is unreachable, that is reported for the enclosing block.
+        let mut reported_unreachable = self.diverges.is_always();
+        // The last statement that could have diverged.
+        let mut prev_stmt = None;
 
         let (break_ty, ty) =
             self.with_breakable_ctx(BreakableKind::Block, Some(coerce_ty), label, |this| {
                 for stmt in statements {
+                    let (node, stmt_expr) = match *stmt {
+                        Statement::Let { pat, initializer, .. } => (Some(pat.into()), initializer),
+                        Statement::Expr { expr, .. } => (Some(expr.into()), Some(expr)),
+                        Statement::Item(_) => (None, None),
+                    };
+                    if !reported_unreachable
+                        && this.diverges.is_always()
+                        && let (Some(node), Some(cause)) = (node, prev_stmt)
+                    {
+                        this.result.unreachable_code.push((node, cause));
+                        reported_unreachable = true;
+                    }
+                    prev_stmt = stmt_expr.or(prev_stmt);
                     match stmt {
                         Statement::Let { pat, type_ref, initializer, else_branch } => {
                             let decl_ty = type_ref
@@ -1542,6 +1559,12 @@ impl<'db> InferenceContext<'_, 'db> {
 
                 // FIXME: This should make use of the breakable CoerceMany
                 if let Some(expr) = tail {
+                    if !reported_unreachable
+                        && this.diverges.is_always()
+                        && let Some(cause) = prev_stmt
+                    {
+                        this.result.unreachable_code.push((expr.into(), cause));
+                    }
                     this.infer_expr_coerce(expr, expected, ExprIsRead::Yes)
                 } else {
                     // Citing rustc: if there is no explicit tail expression,
@@ -34,7 +34,7 @@ impl<'db> InferenceContext<'_, 'db> {
         };
         let args = self.insert_type_vars(substs);
 
-        self.add_required_obligations_for_value_path(generic_def, args);
+        self.add_required_obligations_for_value_path(generic_def, args, id);
 
         let ty = self.db.value_ty(value_def)?.instantiate(self.interner(), args);
         let ty = self.process_remote_user_written_ty(ty);
@@ -222,13 +222,14 @@ impl<'db> InferenceContext<'_, 'db> {
         &mut self,
         def: GenericDefId,
         subst: GenericArgs<'db>,
+        id: ExprOrPatId,
     ) {
         let interner = self.interner();
         let predicates = GenericPredicates::query_all(self.db, def);
         let param_env = self.table.param_env;
         self.table.register_predicates(clauses_as_obligations(
             predicates.iter_instantiated_copied(interner, subst.as_slice()),
-            ObligationCause::new(),
+            ObligationCause::with_span(id),
             param_env,
         ));
 
@@ -245,7 +246,7 @@ impl<'db> InferenceContext<'_, 'db> {
             let trait_ref = TraitRef::new_from_args(interner, trait_.into(), parent_subst);
             self.table.register_predicate(Obligation::new(
                 interner,
-                ObligationCause::new(),
+                ObligationCause::with_span(id),
                 param_env,
                 trait_ref,
             ));
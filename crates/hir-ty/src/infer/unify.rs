@@ -3,12 +3,12 @@
 use std::fmt;
 
 use base_db::Crate;
-use hir_def::{AdtId, DefWithBodyId, GenericParamId};
+use hir_def::{AdtId, DefWithBodyId, GenericParamId, hir::ExprOrPatId};
 use hir_expand::name::Name;
 use intern::sym;
 use rustc_hash::FxHashSet;
 use rustc_type_ir::{
-    TyVid, TypeFoldable, TypeVisitableExt, UpcastFrom,
+    PredicatePolarity, TyVid, TypeFoldable, TypeVisitableExt, UpcastFrom,
     inherent::{Const as _, GenericArg as _, IntoKind, Ty as _},
     solve::Certainty,
 };
@@ -20,7 +20,7 @@ use crate::{
         AliasTy, Canonical, ClauseKind, Const, DbInterner, ErrorGuaranteed, GenericArg,
         GenericArgs, Goal, ParamEnv, Predicate, PredicateKind, Region, SolverDefId, Term, TraitRef,
         Ty, TyKind, TypingMode,
-        fulfill::{FulfillmentCtxt, NextSolverError},
+        fulfill::{FulfillmentCtxt, NextSolverError, find_unsatisfied_leaf_obligation},
         infer::{
             DbInternerInferExt, InferCtxt, InferOk, InferResult,
             at::{At, ToTrace},
@@ -29,6 +29,7 @@ use crate::{
         },
         inspect::{InspectConfig, InspectGoal, ProofTreeVisitor},
         obligation_ctxt::ObligationCtxt,
+        references_non_lt_error,
     },
     traits::{
         FnTrait, NextTraitSolveResult, ParamEnvAndCrate, next_trait_solve_canonical_in_ctxt,
@@ -133,11 +134,14 @@ pub(crate) struct InferenceTable<'db> {
     pub(crate) infer_ctxt: InferCtxt<'db>,
     pub(super) fulfillment_cx: FulfillmentCtxt<'db>,
     pub(super) diverging_type_vars: FxHashSet<Ty<'db>>,
+    /// Obligations with a known span that the solver proved to never hold.
+    pub(super) unsatisfied_obligations: Vec<PredicateObligation<'db>>,
 }
 
 pub(crate) struct InferenceTableSnapshot<'db> {
     ctxt_snapshot: CombinedSnapshot,
     obligations: FulfillmentCtxt<'db>,
+    unsatisfied_obligations_len: usize,
 }
 
 impl<'db> InferenceTable<'db> {
@@ -162,6 +166,7 @@ impl<'db> InferenceTable<'db> {
             fulfillment_cx: FulfillmentCtxt::new(&infer_ctxt),
             infer_ctxt,
             diverging_type_vars: FxHashSet::default(),
+            unsatisfied_obligations: Vec::new(),
         }
     }
 
@@ -384,13 +389,15 @@ impl<'db> InferenceTable<'db> {
     pub(crate) fn snapshot(&mut self) -> InferenceTableSnapshot<'db> {
         let ctxt_snapshot = self.infer_ctxt.start_snapshot();
         let obligations = self.fulfillment_cx.clone();
-        InferenceTableSnapshot { ctxt_snapshot, obligations }
+        let unsatisfied_obligations_len = self.unsatisfied_obligations.len();
+        InferenceTableSnapshot { ctxt_snapshot, obligations, unsatisfied_obligations_len }
     }
 
     #[tracing::instrument(skip_all)]
     pub(crate) fn rollback_to(&mut self, snapshot: InferenceTableSnapshot<'db>) {
         self.infer_ctxt.rollback_to(snapshot.ctxt_snapshot);
         self.fulfillment_cx = snapshot.obligations;
+        self.unsatisfied_obligations.truncate(snapshot.unsatisfied_obligations_len);
     }
 
     pub(crate) fn commit_if_ok<T, E>(
@@ -452,7 +459,57 @@ impl<'db> InferenceTable<'db> {
     }
 
     pub(crate) fn select_obligations_where_possible(&mut self) {
-        self.fulfillment_cx.try_evaluate_obligations(&self.infer_ctxt);
+        let errors = self.fulfillment_cx.try_evaluate_obligations(&self.infer_ctxt);
+        // Only keep the errors we can point at, the rest can't be reported anyway.
+        self.unsatisfied_obligations.extend(errors.into_iter().filter_map(|error| match error {
+            NextSolverError::TrueError(obligation) if obligation.cause.span().is_some() => {
+                Some(obligation)
+            }
+            _ => None,
+        }));
+    }
+
+    /// Takes the obligations that were proven to never hold, and resolves each of them to the
+    /// trait bound that is responsible for it, along with the bounds whose impls required it
+    /// (outermost first).
+    pub(super) fn take_unsatisfied_trait_bounds(
+        &mut self,
+    ) -> Vec<(ExprOrPatId, TraitRef<'db>, Vec<TraitRef<'db>>)> {
+        let obligations = std::mem::take(&mut self.unsatisfied_obligations);
+        let mut result: Vec<(ExprOrPatId, TraitRef<'db>, Vec<TraitRef<'db>>)> = Vec::new();
+        for obligation in obligations {
+            let Some(span) = obligation.cause.span() else { continue };
+            let (leaf, required_by) =
+                find_unsatisfied_leaf_obligation(&self.infer_ctxt, &obligation);
+            let Some(leaf) = leaf.predicate.as_trait_clause().and_then(|it| it.no_bound_vars())
+            else {
+                continue;
+            };
+            // `Sized` bounds are mostly implicit, and unsizing coercions aren't modelled precisely
+            // enough for us to trust their failures.
+            if leaf.polarity != PredicatePolarity::Positive
+                || Some(leaf.def_id().0) == self.interner().lang_items().Sized
+            {
+                continue;
+            }
+            let trait_ref = self.resolve_completely(leaf.trait_ref);
+            let required_by = required_by
+                .into_iter()
+                .filter_map(|it| Some(self.resolve_completely(it.no_bound_vars()?.trait_ref)))
+                .collect::<Vec<_>>();
+            // Bounds involving error types are most likely caused by other errors.
+            if references_non_lt_error(&trait_ref)
+                || required_by.iter().any(references_non_lt_error)
+            {
+                continue;
+            }
+            if result.iter().any(|&(other_span, other, _)| other_span == span && other == trait_ref)
+            {
+                continue;
+            }
+            result.push((span, trait_ref, required_by));
+        }
+        result
     }
 
     pub(super) fn register_predicate(&mut self, obligation: PredicateObligation<'db>) {
@@ -510,7 +510,7 @@ impl<'a, 'b, 'db> ConfirmContext<'a, 'b, 'db> {
         let method_predicates = clauses_as_obligations(
             GenericPredicates::query_all(self.db(), def_id.into())
                 .iter_instantiated_copied(self.interner(), all_args),
-            ObligationCause::new(),
+            ObligationCause::with_span(self.expr.into()),
             self.ctx.table.param_env,
         );
 
@@ -21,7 +21,7 @@ use crate::{
     consteval::usize_const,
     db::{HirDatabase, InternedClosureId},
     display::{DisplayTarget, HirDisplay},
-    infer::PointerCast,
+    infer::{AllowTwoPhase, AutoBorrowMutability, PointerCast},
     next_solver::{
         Const, DbInterner, ErrorGuaranteed, GenericArgs, ParamEnv, StoredConst, StoredGenericArgs,
         StoredTy, Ty, TyKind,
@@ -35,15 +35,21 @@ mod eval;
 mod lower;
 mod monomorphization;
 mod pretty;
+mod recursion;
 
-pub use borrowck::{BorrowckResult, MutabilityReason, borrowck_query};
+pub use borrowck::{
+    BorrowConflict, BorrowckResult, MovedValueUse, MutabilityReason, borrowck_query,
+};
 pub use eval::{
-    Evaluator, MirEvalError, VTableMap, interpret_mir, pad16, render_const_using_debug_impl,
+    DebugFrame, DebugHook, DebugLocal, Evaluator, MirEvalError, MirOutput, VTableMap,
+    interpret_mir, interpret_mir_with_debug_hook, mir_span_to_syntax, pad16,
+    render_const_using_debug_impl,
 };
 pub use lower::{MirLowerError, lower_to_mir, mir_body_for_closure_query, mir_body_query};
 pub use monomorphization::{
     monomorphized_mir_body_for_closure_query, monomorphized_mir_body_query,
 };
+pub use recursion::unconditional_recursion;
 
 pub(crate) use lower::mir_body_cycle_result;
 pub(crate) use monomorphization::monomorphized_mir_body_cycle_result;
@@ -716,6 +722,18 @@ impl BorrowKind {
             rustc_ast_ir::Mutability::Mut => BorrowKind::Mut { kind: MutBorrowKind::Default },
         }
     }
+
+    fn from_autoref(m: AutoBorrowMutability) -> Self {
+        match m {
+            AutoBorrowMutability::Not => BorrowKind::Shared,
+            AutoBorrowMutability::Mut { allow_two_phase_borrow: AllowTwoPhase::Yes } => {
+                BorrowKind::Mut { kind: MutBorrowKind::TwoPhasedBorrow }
+            }
+            AutoBorrowMutability::Mut { allow_two_phase_borrow: AllowTwoPhase::No } => {
+                BorrowKind::Mut { kind: MutBorrowKind::Default }
+            }
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
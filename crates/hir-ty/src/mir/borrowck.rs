@@ -1,14 +1,19 @@
-//! MIR borrow checker, which is used in diagnostics like `unused_mut`
+//! MIR borrow checker, which is used in diagnostics like `unused_mut`, use of moved values and
+//! conflicting borrows.
 
-// Currently it is an ad-hoc implementation, only useful for mutability analysis. Feel free to remove all of these
-// if needed for implementing a proper borrow checker.
+// Currently it is an ad-hoc implementation, useful for mutability analysis and for finding the most
+// common borrowck errors conservatively. Feel free to remove all of these if needed for implementing
+// a proper borrow checker.
 
 use std::iter;
 
-use hir_def::{DefWithBodyId, HasModule};
+use hir_def::{DefWithBodyId, HasModule, hir::Expr};
 use la_arena::ArenaMap;
-use rustc_hash::FxHashMap;
-use rustc_type_ir::inherent::GenericArgs as _;
+use rustc_hash::{FxHashMap, FxHashSet};
+use rustc_type_ir::{
+    GenericArgKind,
+    inherent::{GenericArgs as _, IntoKind},
+};
 use stdx::never;
 use triomphe::Arc;
 
@@ -18,14 +23,14 @@ use crate::{
     display::DisplayTarget,
     mir::OperandKind,
     next_solver::{
-        DbInterner, GenericArgs, ParamEnv, StoredTy, Ty, TypingMode,
+        DbInterner, GenericArg, GenericArgs, ParamEnv, StoredTy, Ty, TyKind, TypingMode,
         infer::{DbInternerInferExt, InferCtxt},
     },
 };
 
 use super::{
     BasicBlockId, BorrowKind, LocalId, MirBody, MirLowerError, MirSpan, MutBorrowKind, Operand,
-    Place, ProjectionElem, Rvalue, StatementKind, TerminatorKind,
+    Place, ProjectionElem, Rvalue, Statement, StatementKind, Terminator, TerminatorKind,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -56,6 +61,23 @@ pub struct BorrowRegion {
     pub places: Vec<MirSpan>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MovedValueUse {
+    pub local: LocalId,
+    /// The use of the value after it was moved.
+    pub span: MirSpan,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BorrowConflict {
+    pub local: LocalId,
+    pub kind: BorrowKind,
+    pub span: MirSpan,
+    /// The borrow which is still live at `span`.
+    pub live_kind: BorrowKind,
+    pub live_span: MirSpan,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BorrowckResult {
     pub mir_body: Arc<MirBody>,
@@ -63,6 +85,8 @@ pub struct BorrowckResult {
     pub moved_out_of_ref: Vec<MovedOutOfRef>,
     pub partially_moved: Vec<PartiallyMoved>,
     pub borrow_regions: Vec<BorrowRegion>,
+    pub moved_value_uses: Vec<MovedValueUse>,
+    pub borrow_conflicts: Vec<BorrowConflict>,
 }
 
 fn all_mir_bodies(
@@ -111,6 +135,8 @@ pub fn borrowck_query(
             moved_out_of_ref: moved_out_of_ref(&infcx, env, &body),
             partially_moved: partially_moved(&infcx, env, &body),
             borrow_regions: borrow_regions(db, &body),
+            moved_value_uses: moved_value_uses(&infcx, env, &body),
+            borrow_conflicts: borrow_conflicts(db, &body),
             mir_body: body,
         });
     })?;
@@ -365,6 +391,584 @@ fn borrow_regions(db: &dyn HirDatabase, body: &MirBody) -> Vec<BorrowRegion> {
     borrows.into_values().collect()
 }
 
+/// How a statement or a terminator reads a place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PlaceAccess {
+    /// The place is copied or moved out of.
+    Operand,
+    /// Only the discriminant or the length of the place is read.
+    Inspect,
+    Borrow(BorrowKind),
+}
+
+fn for_each_operand_place(
+    op: &Operand,
+    span: MirSpan,
+    f: &mut impl FnMut(&Place, PlaceAccess, MirSpan),
+) {
+    if let OperandKind::Copy(p) | OperandKind::Move(p) = &op.kind {
+        f(p, PlaceAccess::Operand, op.span.unwrap_or(span));
+    }
+}
+
+fn for_each_rvalue_place(
+    rvalue: &Rvalue,
+    span: MirSpan,
+    f: &mut impl FnMut(&Place, PlaceAccess, MirSpan),
+) {
+    match rvalue {
+        Rvalue::ShallowInitBox(o, _)
+        | Rvalue::UnaryOp(_, o)
+        | Rvalue::Cast(_, o, _)
+        | Rvalue::Repeat(o, _)
+        | Rvalue::Use(o) => for_each_operand_place(o, span, f),
+        Rvalue::CheckedBinaryOp(_, o1, o2) => {
+            for_each_operand_place(o1, span, f);
+            for_each_operand_place(o2, span, f);
+        }
+        Rvalue::Aggregate(_, ops) => {
+            for op in ops.iter() {
+                for_each_operand_place(op, span, f);
+            }
+        }
+        Rvalue::CopyForDeref(p) => f(p, PlaceAccess::Operand, span),
+        Rvalue::Discriminant(p) | Rvalue::Len(p) => f(p, PlaceAccess::Inspect, span),
+        Rvalue::Ref(kind, p) => f(p, PlaceAccess::Borrow(*kind), span),
+        Rvalue::ShallowInitBoxWithAlloc(_) => (),
+        Rvalue::ThreadLocalRef(n)
+        | Rvalue::AddressOf(n)
+        | Rvalue::BinaryOp(n)
+        | Rvalue::NullaryOp(n) => match *n {},
+    }
+}
+
+fn for_each_terminator_place(
+    terminator: &Terminator,
+    f: &mut impl FnMut(&Place, PlaceAccess, MirSpan),
+) {
+    let span = terminator.span;
+    match &terminator.kind {
+        TerminatorKind::SwitchInt { discr: value, .. }
+        | TerminatorKind::DropAndReplace { value, .. }
+        | TerminatorKind::Assert { cond: value, .. }
+        | TerminatorKind::Yield { value, .. } => for_each_operand_place(value, span, f),
+        TerminatorKind::Call { func, args, .. } => {
+            for_each_operand_place(func, span, f);
+            for arg in args.iter() {
+                for_each_operand_place(arg, span, f);
+            }
+        }
+        TerminatorKind::Goto { .. }
+        | TerminatorKind::UnwindResume
+        | TerminatorKind::Abort
+        | TerminatorKind::Return
+        | TerminatorKind::Unreachable
+        | TerminatorKind::Drop { .. }
+        | TerminatorKind::CoroutineDrop
+        | TerminatorKind::FalseEdge { .. }
+        | TerminatorKind::FalseUnwind { .. } => (),
+    }
+}
+
+fn successors(terminator: &TerminatorKind) -> Vec<BasicBlockId> {
+    match terminator {
+        TerminatorKind::Goto { target } => vec![*target],
+        TerminatorKind::SwitchInt { targets, .. } => targets.all_targets().to_vec(),
+        TerminatorKind::Call { target, cleanup, .. } => {
+            target.iter().chain(cleanup).copied().collect()
+        }
+        TerminatorKind::Assert { target, cleanup, .. } => {
+            iter::once(target).chain(cleanup).copied().collect()
+        }
+        TerminatorKind::Drop { target, unwind, .. }
+        | TerminatorKind::DropAndReplace { target, unwind, .. } => {
+            iter::once(target).chain(unwind).copied().collect()
+        }
+        TerminatorKind::Yield { resume, drop, .. } => {
+            iter::once(resume).chain(drop).copied().collect()
+        }
+        TerminatorKind::FalseEdge { real_target, imaginary_target } => {
+            vec![*real_target, *imaginary_target]
+        }
+        TerminatorKind::FalseUnwind { real_target, unwind } => {
+            iter::once(real_target).chain(unwind).copied().collect()
+        }
+        TerminatorKind::UnwindResume
+        | TerminatorKind::Abort
+        | TerminatorKind::Return
+        | TerminatorKind::Unreachable
+        | TerminatorKind::CoroutineDrop => vec![],
+    }
+}
+
+/// Runs a forward dataflow analysis until it reaches a fixpoint, and returns the state at the
+/// start of every reachable block. `join` merges a state into the start state of a successor and
+/// returns whether anything has changed.
+fn forward_fixpoint<S: Clone + Default>(
+    db: &dyn HirDatabase,
+    body: &MirBody,
+    transfer: impl Fn(BasicBlockId, &mut S),
+    join: impl Fn(&mut S, &S) -> bool,
+) -> ArenaMap<BasicBlockId, S> {
+    let mut entry_states = ArenaMap::default();
+    entry_states.insert(body.start_block, S::default());
+    let mut worklist = vec![body.start_block];
+    while let Some(block_id) = worklist.pop() {
+        db.unwind_if_revision_cancelled();
+        let mut state = entry_states[block_id].clone();
+        transfer(block_id, &mut state);
+        let Some(terminator) = &body.basic_blocks[block_id].terminator else {
+            never!("Terminator should be none only in construction");
+            continue;
+        };
+        for target in successors(&terminator.kind) {
+            let changed = match entry_states.get_mut(target) {
+                Some(entry) => join(entry, &state),
+                None => {
+                    entry_states.insert(target, state.clone());
+                    true
+                }
+            };
+            if changed {
+                worklist.push(target);
+            }
+        }
+    }
+    entry_states
+}
+
+fn has_deref(place: &Place, body: &MirBody) -> bool {
+    place.projection.lookup(&body.projection_store).contains(&ProjectionElem::Deref)
+}
+
+fn user_locals(body: &MirBody) -> FxHashSet<LocalId> {
+    body.binding_locals.iter().map(|(_, &local)| local).collect()
+}
+
+/// Places of user variables which may have been moved out of.
+type MovedPlaces = Vec<Place>;
+
+/// Finds uses of user variables after they (or a part of them) have been moved out of.
+///
+/// Only moves written as expressions are tracked, moves into pattern bindings are ignored, as
+/// bindings of a match arm are lowered before its guard is checked. Borrows inserted by autoref,
+/// e.g. for method receivers and overloaded operators, are not reported as uses either.
+fn moved_value_uses<'db>(
+    infcx: &InferCtxt<'db>,
+    env: ParamEnv<'db>,
+    body: &MirBody,
+) -> Vec<MovedValueUse> {
+    let db = infcx.interner.db;
+    let store = &body.projection_store;
+    let user_locals = user_locals(body);
+    let hir_body = db.body(body.owner);
+    let is_move = |place: &Place| {
+        if !user_locals.contains(&place.local) {
+            return false;
+        }
+        let mut ty: Ty<'db> = body.locals[place.local].ty.as_ref();
+        for proj in place.projection.lookup(store) {
+            match proj {
+                // Moving out of these is an error on its own, or is done by `Box` magic.
+                ProjectionElem::Deref
+                | ProjectionElem::Index(_)
+                | ProjectionElem::ConstantIndex { .. }
+                | ProjectionElem::Subslice { .. } => return false,
+                ProjectionElem::Field(_)
+                | ProjectionElem::ClosureField(_)
+                | ProjectionElem::OpaqueCast(_) => (),
+            }
+            ty = proj.projected_ty(
+                infcx,
+                env,
+                ty,
+                make_fetch_closure_field(db),
+                body.owner.module(db).krate(db),
+            );
+        }
+        // Mutable references are reborrowed implicitly in most places, and we don't lower those
+        // reborrows precisely enough to tell them apart from moves.
+        ty.as_reference().is_none()
+            && !ty.references_non_lt_error()
+            && !ty.is_trivially_pure_clone_copy()
+            && !infcx.type_is_copy_modulo_regions(env, ty)
+    };
+    let check_use = |moved: &MovedPlaces,
+                     place: &Place,
+                     access: PlaceAccess,
+                     span: MirSpan,
+                     result: &mut Vec<MovedValueUse>| {
+        let MirSpan::ExprId(expr) = span else { return };
+        if matches!(access, PlaceAccess::Borrow(_)) && !matches!(hir_body[expr], Expr::Ref { .. }) {
+            return;
+        }
+        let conflict = moved.iter().any(|it| {
+            it.is_parent(place, store)
+                || access != PlaceAccess::Inspect && place.is_parent(it, store)
+        });
+        if conflict {
+            let it = MovedValueUse { local: place.local, span };
+            if !result.contains(&it) {
+                result.push(it);
+            }
+        }
+    };
+    let reinit = |moved: &mut MovedPlaces, place: &Place| {
+        if !has_deref(place, body) {
+            moved.retain(|it| !place.is_parent(it, store));
+        }
+    };
+    let visit = |moved: &mut MovedPlaces,
+                 result: &mut Vec<MovedValueUse>,
+                 place: &Place,
+                 access: PlaceAccess,
+                 span: MirSpan| {
+        check_use(moved, place, access, span, result);
+        if access == PlaceAccess::Operand
+            && matches!(span, MirSpan::ExprId(_))
+            && !moved.contains(place)
+            && is_move(place)
+        {
+            moved.push(*place);
+        }
+    };
+    let transfer = |block_id: BasicBlockId, moved: &mut MovedPlaces, result: &mut Vec<_>| {
+        let block = &body.basic_blocks[block_id];
+        for statement in &block.statements {
+            match &statement.kind {
+                StatementKind::Assign(place, rvalue) => {
+                    for_each_rvalue_place(rvalue, statement.span, &mut |p, access, span| {
+                        visit(moved, result, p, access, span)
+                    });
+                    if has_deref(place, body) {
+                        visit(
+                            moved,
+                            result,
+                            &place.local.into(),
+                            PlaceAccess::Inspect,
+                            statement.span,
+                        );
+                    }
+                    reinit(moved, place);
+                }
+                StatementKind::StorageLive(local) | StatementKind::StorageDead(local) => {
+                    moved.retain(|it| it.local != *local);
+                }
+                StatementKind::FakeRead(_) | StatementKind::Deinit(_) | StatementKind::Nop => (),
+            }
+        }
+        if let Some(terminator) = &block.terminator {
+            for_each_terminator_place(terminator, &mut |p, access, span| {
+                visit(moved, result, p, access, span)
+            });
+            if let TerminatorKind::Call { destination, .. } = &terminator.kind {
+                reinit(moved, destination);
+            }
+        }
+    };
+    let join = |entry: &mut MovedPlaces, moved: &MovedPlaces| {
+        let len = entry.len();
+        for it in moved {
+            if !entry.contains(it) {
+                entry.push(*it);
+            }
+        }
+        entry.len() != len
+    };
+    let entry_states =
+        forward_fixpoint(db, body, |block_id, moved| transfer(block_id, moved, &mut vec![]), join);
+    let mut result = vec![];
+    for (block_id, moved) in entry_states.iter() {
+        transfer(block_id, &mut moved.clone(), &mut result);
+    }
+    result
+}
+
+/// A borrow of a user variable.
+struct Loan {
+    place: Place,
+    kind: BorrowKind,
+    span: MirSpan,
+    /// The local the borrow is stored into.
+    holder: LocalId,
+}
+
+/// The loans which may be (transitively) stored in each local.
+type HeldLoans = FxHashMap<LocalId, FxHashSet<usize>>;
+
+/// Whether a value of type `ty` may contain a borrow. `'static` borrows never conflict with
+/// borrows of locals.
+fn may_hold_borrow(ty: Ty<'_>) -> bool {
+    ty.walk().any(|arg| matches!(arg.kind(), GenericArgKind::Lifetime(lt) if !lt.is_static()))
+}
+
+/// Returns for each of the `arg_count` arguments of a call of `func` whether the result may hold
+/// the borrows the argument holds, judging from the lifetimes in the signature of `func`.
+///
+/// A reference argument is borrowed by the result if its lifetime appears in the return type, so
+/// `fn next(&mut self) -> &'a str` in `impl<'a> Lexer<'a>` does not keep `self` borrowed. Other
+/// arguments are borrowed if they share a lifetime or a type parameter with the return type.
+/// Calls whose signature is unknown, like those of closures, are assumed to borrow nothing.
+fn args_borrowed_by_result(db: &dyn HirDatabase, func: &Operand, arg_count: usize) -> Vec<bool> {
+    let OperandKind::Constant { ty, .. } = &func.kind else {
+        return vec![false; arg_count];
+    };
+    let TyKind::FnDef(def, _) = ty.as_ref().kind() else {
+        return vec![false; arg_count];
+    };
+    let sig = db.callable_item_signature(def.0).instantiate_identity().skip_binder();
+    let is_generic = |arg: GenericArg<'_>| match arg.kind() {
+        GenericArgKind::Lifetime(lt) => !lt.is_static(),
+        GenericArgKind::Type(ty) => matches!(ty.kind(), TyKind::Param(_)),
+        GenericArgKind::Const(_) => false,
+    };
+    let output: FxHashSet<_> = sig.output().walk().filter(|&it| is_generic(it)).collect();
+    let mut borrowed: Vec<_> = sig
+        .inputs()
+        .iter()
+        .map(|input| match input.kind() {
+            TyKind::Ref(lt, _, _) => output.contains(&lt.into()),
+            _ => input.walk().any(|it| is_generic(it) && output.contains(&it)),
+        })
+        .collect();
+    borrowed.resize(arg_count, false);
+    borrowed
+}
+
+fn store_loans(held: &mut HeldLoans, place: &Place, loans: FxHashSet<usize>, body: &MirBody) {
+    if has_deref(place, body) {
+        // FIXME: Track borrows which escape behind references.
+        return;
+    }
+    if place.projection.lookup(&body.projection_store).is_empty() {
+        if loans.is_empty() {
+            held.remove(&place.local);
+        } else {
+            held.insert(place.local, loans);
+        }
+    } else if !loans.is_empty() {
+        held.entry(place.local).or_default().extend(loans);
+    }
+}
+
+/// Updates `live` from the locals live after `statement` to those live before it.
+fn statement_liveness(statement: &Statement, live: &mut FxHashSet<LocalId>, body: &MirBody) {
+    match &statement.kind {
+        StatementKind::Assign(place, rvalue) => {
+            if place.projection.lookup(&body.projection_store).is_empty() {
+                live.remove(&place.local);
+            } else if has_deref(place, body) {
+                live.insert(place.local);
+            }
+            for_each_rvalue_place(rvalue, statement.span, &mut |p, _, _| {
+                live.insert(p.local);
+            });
+        }
+        StatementKind::StorageLive(local) | StatementKind::StorageDead(local) => {
+            live.remove(local);
+        }
+        StatementKind::FakeRead(_) | StatementKind::Deinit(_) | StatementKind::Nop => (),
+    }
+}
+
+/// Updates `live` from the locals live after `terminator` to those live before it.
+fn terminator_liveness(terminator: &Terminator, live: &mut FxHashSet<LocalId>, body: &MirBody) {
+    if let TerminatorKind::Call { destination, .. } = &terminator.kind {
+        if destination.projection.lookup(&body.projection_store).is_empty() {
+            live.remove(&destination.local);
+        } else if has_deref(destination, body) {
+            live.insert(destination.local);
+        }
+    }
+    for_each_terminator_place(terminator, &mut |p, _, _| {
+        live.insert(p.local);
+    });
+}
+
+fn live_locals_on_entry(
+    db: &dyn HirDatabase,
+    body: &MirBody,
+) -> ArenaMap<BasicBlockId, FxHashSet<LocalId>> {
+    let mut live_on_entry: ArenaMap<BasicBlockId, FxHashSet<LocalId>> =
+        body.basic_blocks.iter().map(|(id, _)| (id, FxHashSet::default())).collect();
+    let mut changed = true;
+    while changed {
+        db.unwind_if_revision_cancelled();
+        changed = false;
+        for (block_id, block) in body.basic_blocks.iter().rev() {
+            let Some(terminator) = &block.terminator else { continue };
+            let mut live = live_on_exit(&live_on_entry, terminator);
+            terminator_liveness(terminator, &mut live, body);
+            for statement in block.statements.iter().rev() {
+                statement_liveness(statement, &mut live, body);
+            }
+            if live != live_on_entry[block_id] {
+                live_on_entry.insert(block_id, live);
+                changed = true;
+            }
+        }
+    }
+    live_on_entry
+}
+
+fn live_on_exit(
+    live_on_entry: &ArenaMap<BasicBlockId, FxHashSet<LocalId>>,
+    terminator: &Terminator,
+) -> FxHashSet<LocalId> {
+    successors(&terminator.kind)
+        .into_iter()
+        .filter_map(|it| live_on_entry.get(it))
+        .flatten()
+        .copied()
+        .collect()
+}
+
+/// Finds borrows of user variables which conflict with another borrow that is still live, i.e.
+/// stored in a local which is used later.
+///
+/// Borrows flow from the arguments of a call into its result only when the signature of the callee
+/// ties them together, see [`args_borrowed_by_result`].
+fn borrow_conflicts(db: &dyn HirDatabase, body: &MirBody) -> Vec<BorrowConflict> {
+    let store = &body.projection_store;
+    let user_locals = user_locals(body);
+    let mut loans = vec![];
+    let mut loan_at = FxHashMap::default();
+    for (block_id, block) in body.basic_blocks.iter() {
+        for (index, statement) in block.statements.iter().enumerate() {
+            if let StatementKind::Assign(holder, Rvalue::Ref(kind, place)) = &statement.kind
+                && let BorrowKind::Shared
+                | BorrowKind::Mut {
+                    kind: MutBorrowKind::Default | MutBorrowKind::TwoPhasedBorrow,
+                } = kind
+                && user_locals.contains(&place.local)
+                && statement.span != MirSpan::Unknown
+            {
+                loan_at.insert((block_id, index), loans.len());
+                loans.push(Loan {
+                    place: *place,
+                    kind: *kind,
+                    span: statement.span,
+                    holder: holder.local,
+                });
+            }
+        }
+    }
+    if loans.is_empty() {
+        return vec![];
+    }
+
+    let find_conflict = |held: &HeldLoans, new: &Loan, live: &FxHashSet<LocalId>| {
+        loans.iter().enumerate().find(|&(index, loan)| {
+            let conflicting_kinds =
+                !(loan.kind == BorrowKind::Shared && new.kind == BorrowKind::Shared);
+            let overlapping =
+                loan.place.is_parent(&new.place, store) || new.place.is_parent(&loan.place, store);
+            conflicting_kinds
+                && overlapping
+                && held.iter().any(|(holder, loans)| {
+                    // A two-phase borrow may be shared until it is used by its holder.
+                    let reserved = *holder == loan.holder
+                        && loan.kind == BorrowKind::Mut { kind: MutBorrowKind::TwoPhasedBorrow }
+                        && new.kind == BorrowKind::Shared;
+                    loans.contains(&index) && live.contains(holder) && !reserved
+                })
+        })
+    };
+    let live_on_entry = live_locals_on_entry(db, body);
+    let transfer = |block_id: BasicBlockId,
+                    held: &mut HeldLoans,
+                    result: Option<&mut Vec<BorrowConflict>>| {
+        let block = &body.basic_blocks[block_id];
+        let Some(terminator) = &block.terminator else { return };
+        // Locals live after each statement, only needed when reporting.
+        let live_after = result.as_ref().map(|_| {
+            let mut live = live_on_exit(&live_on_entry, terminator);
+            terminator_liveness(terminator, &mut live, body);
+            let mut live_after = vec![FxHashSet::default(); block.statements.len()];
+            for (index, statement) in block.statements.iter().enumerate().rev() {
+                live_after[index] = live.clone();
+                statement_liveness(statement, &mut live, body);
+            }
+            live_after
+        });
+        let mut result = result;
+        for (index, statement) in block.statements.iter().enumerate() {
+            match &statement.kind {
+                StatementKind::Assign(place, rvalue) => {
+                    let new_loan = loan_at.get(&(block_id, index)).copied();
+                    if let (Some(new_loan), Some(live_after), Some(result)) =
+                        (new_loan, &live_after, result.as_deref_mut())
+                    {
+                        let mut live = live_after[index].clone();
+                        if place.projection.lookup(store).is_empty() {
+                            // The old value of the destination is overwritten.
+                            live.remove(&place.local);
+                        }
+                        let new = &loans[new_loan];
+                        if let Some((_, loan)) = find_conflict(&*held, new, &live) {
+                            result.push(BorrowConflict {
+                                local: new.place.local,
+                                kind: new.kind,
+                                span: new.span,
+                                live_kind: loan.kind,
+                                live_span: loan.span,
+                            });
+                        }
+                    }
+                    let mut incoming: FxHashSet<usize> = new_loan.into_iter().collect();
+                    for_each_rvalue_place(rvalue, statement.span, &mut |p, access, _| {
+                        if access != PlaceAccess::Inspect
+                            && let Some(loans) = held.get(&p.local)
+                        {
+                            incoming.extend(loans);
+                        }
+                    });
+                    store_loans(held, place, incoming, body);
+                }
+                StatementKind::StorageLive(local) | StatementKind::StorageDead(local) => {
+                    held.remove(local);
+                }
+                StatementKind::FakeRead(_) | StatementKind::Deinit(_) | StatementKind::Nop => (),
+            }
+        }
+        if let TerminatorKind::Call { func, args, destination, .. } = &terminator.kind {
+            let incoming = if may_hold_borrow(body.locals[destination.local].ty.as_ref()) {
+                let borrowed_args = args_borrowed_by_result(db, func, args.len());
+                args.iter()
+                    .zip(borrowed_args)
+                    .filter(|&(_, borrowed)| borrowed)
+                    .filter_map(|(arg, _)| match &arg.kind {
+                        OperandKind::Copy(p) | OperandKind::Move(p) => held.get(&p.local),
+                        OperandKind::Constant { .. } | OperandKind::Static(_) => None,
+                    })
+                    .flatten()
+                    .copied()
+                    .collect()
+            } else {
+                FxHashSet::default()
+            };
+            store_loans(held, destination, incoming, body);
+        }
+    };
+    let join = |entry: &mut HeldLoans, held: &HeldLoans| {
+        let mut changed = false;
+        for (local, loans) in held {
+            let entry = entry.entry(*local).or_default();
+            let len = entry.len();
+            entry.extend(loans);
+            changed |= entry.len() != len;
+        }
+        changed
+    };
+    let entry_states =
+        forward_fixpoint(db, body, |block_id, held| transfer(block_id, held, None), join);
+    let mut result = vec![];
+    for (block_id, held) in entry_states.iter() {
+        transfer(block_id, &mut held.clone(), Some(&mut result));
+    }
+    result
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ProjectionCase {
     /// Projection is a local
@@ -58,10 +58,14 @@ use super::{
     TerminatorKind, UnOp, return_slot,
 };
 
+mod debug;
 mod shim;
 #[cfg(test)]
 mod tests;
 
+use debug::DebugStackFrame;
+pub use debug::{DebugFrame, DebugHook, DebugLocal};
+
 macro_rules! from_bytes {
     ($ty:tt, $value:expr) => {
         ($ty::from_le_bytes(match ($value).try_into() {
@@ -205,6 +209,11 @@ pub struct Evaluator<'db> {
     stack_depth_limit: usize,
     /// Maximum count of bytes that heap and stack can grow
     memory_limit: usize,
+    /// Called before every statement and terminator, used by the interactive debugger.
+    debug_hook: Option<Box<dyn DebugHook<'db> + 'db>>,
+    /// Every frame of the call stack while a debug hook is set, the innermost one last. Unlike
+    /// `code_stack`, this isn't reset by nested calls to `interpret_mir`.
+    debug_stack: Vec<DebugStackFrame>,
     infcx: InferCtxt<'db>,
 }
 
@@ -363,6 +372,8 @@ pub enum MirEvalError {
     ),
     ExecutionLimitExceeded,
     StackOverflow,
+    /// Execution was stopped by a [`DebugHook`].
+    Interrupted,
     /// FIXME: Fold this into InternalError
     InvalidVTableId(usize),
     /// ?
@@ -398,31 +409,8 @@ impl MirEvalError {
                         writeln!(f, "In {closure:?}")?;
                     }
                 }
-                let source_map = db.body_with_source_map(*def).1;
-                let span: InFile<SyntaxNodePtr> = match span {
-                    MirSpan::ExprId(e) => match source_map.expr_syntax(*e) {
-                        Ok(s) => s.map(|it| it.into()),
-                        Err(_) => continue,
-                    },
-                    MirSpan::PatId(p) => match source_map.pat_syntax(*p) {
-                        Ok(s) => s.map(|it| it.syntax_node_ptr()),
-                        Err(_) => continue,
-                    },
-                    MirSpan::BindingId(b) => {
-                        match source_map
-                            .patterns_for_binding(*b)
-                            .iter()
-                            .find_map(|p| source_map.pat_syntax(*p).ok())
-                        {
-                            Some(s) => s.map(|it| it.syntax_node_ptr()),
-                            None => continue,
-                        }
-                    }
-                    MirSpan::SelfParam => match source_map.self_param_syntax() {
-                        Some(s) => s.map(|it| it.syntax_node_ptr()),
-                        None => continue,
-                    },
-                    MirSpan::Unknown => continue,
+                let Some(span) = mir_span_to_syntax(db, *def, *span) else {
+                    continue;
                 };
                 let file_id = span.file_id.original_file(db);
                 let text_range = span.value.text_range();
@@ -481,6 +469,7 @@ impl MirEvalError {
             | MirEvalError::InvalidConst(_)
             | MirEvalError::ExecutionLimitExceeded
             | MirEvalError::StackOverflow
+            | MirEvalError::Interrupted
             | MirEvalError::CoerceUnsizedError(_)
             | MirEvalError::InternalError(_)
             | MirEvalError::InvalidVTableId(_) => writeln!(f, "{err:?}")?,
@@ -500,6 +489,30 @@ impl MirEvalError {
     }
 }
 
+/// Maps a [`MirSpan`] of the body of `def` back to the syntax node it was lowered from.
+pub fn mir_span_to_syntax(
+    db: &dyn HirDatabase,
+    def: DefWithBodyId,
+    span: MirSpan,
+) -> Option<InFile<SyntaxNodePtr>> {
+    let source_map = db.body_with_source_map(def).1;
+    match span {
+        MirSpan::ExprId(e) => source_map.expr_syntax(e).ok().map(|s| s.map(|it| it.into())),
+        MirSpan::PatId(p) => {
+            source_map.pat_syntax(p).ok().map(|s| s.map(|it| it.syntax_node_ptr()))
+        }
+        MirSpan::BindingId(b) => source_map
+            .patterns_for_binding(b)
+            .iter()
+            .find_map(|p| source_map.pat_syntax(*p).ok())
+            .map(|s| s.map(|it| it.syntax_node_ptr())),
+        MirSpan::SelfParam => {
+            source_map.self_param_syntax().map(|s| s.map(|it| it.syntax_node_ptr()))
+        }
+        MirSpan::Unknown => None,
+    }
+}
+
 impl std::fmt::Debug for MirEvalError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -519,6 +532,7 @@ impl std::fmt::Debug for MirEvalError {
             Self::TypeIsUnsized(ty, it) => write!(f, "{ty:?} is unsized. {it} should be sized."),
             Self::ExecutionLimitExceeded => write!(f, "execution limit exceeded"),
             Self::StackOverflow => write!(f, "stack overflow"),
+            Self::Interrupted => write!(f, "interrupted"),
             Self::MirLowerError(arg0, arg1) => {
                 f.debug_tuple("MirLowerError").field(arg0).field(arg1).finish()
             }
@@ -541,7 +555,7 @@ impl std::fmt::Debug for MirEvalError {
 
 type Result<'db, T> = std::result::Result<T, MirEvalError>;
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 struct DropFlags {
     need_drop: FxHashSet<Place>,
 }
@@ -569,7 +583,7 @@ impl DropFlags {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Locals {
     ptr: ArenaMap<LocalId, Interval>,
     body: Arc<MirBody>,
@@ -601,30 +615,20 @@ pub fn interpret_mir<'db>(
     assert_placeholder_ty_is_unused: bool,
     trait_env: Option<ParamEnvAndCrate<'db>>,
 ) -> Result<'db, (Result<'db, Const<'db>>, MirOutput)> {
-    let ty = body.locals[return_slot()].ty.as_ref();
-    let mut evaluator = Evaluator::new(db, body.owner, assert_placeholder_ty_is_unused, trait_env)?;
-    let it: Result<'db, Const<'db>> = (|| {
-        if evaluator.ptr_size() != size_of::<usize>() {
-            not_supported!("targets with different pointer size from host");
-        }
-        let interval = evaluator.interpret_mir(body.clone(), None.into_iter())?;
-        let bytes = interval.get(&evaluator)?;
-        let mut memory_map = evaluator.create_memory_map(
-            bytes,
-            ty,
-            &Locals { ptr: ArenaMap::new(), body, drop_flags: DropFlags::default() },
-        )?;
-        let bytes = bytes.into();
-        let memory_map = if memory_map.memory.is_empty() && evaluator.vtable_map.is_empty() {
-            MemoryMap::Empty
-        } else {
-            memory_map.vtable = mem::take(&mut evaluator.vtable_map);
-            memory_map.vtable.shrink_to_fit();
-            MemoryMap::Complex(Box::new(memory_map))
-        };
-        Ok(Const::new_valtree(evaluator.interner(), ty, bytes, memory_map))
-    })();
-    Ok((it, MirOutput { stdout: evaluator.stdout, stderr: evaluator.stderr }))
+    let evaluator = Evaluator::new(db, body.owner, assert_placeholder_ty_is_unused, trait_env)?;
+    Ok(evaluator.run_to_const(body))
+}
+
+/// Like [`interpret_mir`], but calls `hook` before every statement and terminator is executed,
+/// which allows pausing the evaluation and inspecting the current stack frames.
+pub fn interpret_mir_with_debug_hook<'db>(
+    db: &'db dyn HirDatabase,
+    body: Arc<MirBody>,
+    hook: Box<dyn DebugHook<'db> + 'db>,
+) -> Result<'db, (Result<'db, Const<'db>>, MirOutput)> {
+    let mut evaluator = Evaluator::new(db, body.owner, false, None)?;
+    evaluator.debug_hook = Some(hook);
+    Ok(evaluator.run_to_const(body))
 }
 
 #[cfg(test)]
@@ -670,6 +674,8 @@ impl<'db> Evaluator<'db> {
             stack_depth_limit: 100,
             execution_limit: EXECUTION_LIMIT,
             memory_limit: 1_000_000_000, // 2GB, 1GB for stack and 1GB for heap
+            debug_hook: None,
+            debug_stack: vec![],
             layout_cache: RefCell::new(Default::default()),
             projected_ty_cache: RefCell::new(Default::default()),
             not_special_fn_cache: RefCell::new(Default::default()),
@@ -689,6 +695,32 @@ impl<'db> Evaluator<'db> {
         })
     }
 
+    fn run_to_const(mut self, body: Arc<MirBody>) -> (Result<'db, Const<'db>>, MirOutput) {
+        let ty = body.locals[return_slot()].ty.as_ref();
+        let it: Result<'db, Const<'db>> = (|| {
+            if self.ptr_size() != size_of::<usize>() {
+                not_supported!("targets with different pointer size from host");
+            }
+            let interval = self.interpret_mir(body.clone(), None.into_iter())?;
+            let bytes = interval.get(&self)?;
+            let mut memory_map = self.create_memory_map(
+                bytes,
+                ty,
+                &Locals { ptr: ArenaMap::new(), body, drop_flags: DropFlags::default() },
+            )?;
+            let bytes = bytes.into();
+            let memory_map = if memory_map.memory.is_empty() && self.vtable_map.is_empty() {
+                MemoryMap::Empty
+            } else {
+                memory_map.vtable = mem::take(&mut self.vtable_map);
+                memory_map.vtable.shrink_to_fit();
+                MemoryMap::Complex(Box::new(memory_map))
+            };
+            Ok(Const::new_valtree(self.interner(), ty, bytes, memory_map))
+        })();
+        (it, MirOutput { stdout: self.stdout, stderr: self.stderr })
+    }
+
     #[inline]
     fn interner(&self) -> DbInterner<'db> {
         self.infcx.interner
@@ -776,6 +808,16 @@ impl<'db> Evaluator<'db> {
                         usize,
                         self.read_memory(locals.ptr[*op].addr, self.ptr_size())?
                     );
+                    // Slice metadata isn't always the element count (e.g. for C string
+                    // literals), so only arrays are checked.
+                    if let TyKind::Array(_, c) = prev_ty.kind()
+                        && let Some(len) = try_const_usize(self.db, c)
+                        && offset as u128 >= len
+                    {
+                        return Err(MirEvalError::Panic(format!(
+                            "index out of bounds: the length is {len} but the index is {offset}"
+                        )));
+                    }
                     metadata = None; // Result of index is always sized
                     let ty_size =
                         self.size_of_sized(ty, locals, "array inner type should be sized")?;
@@ -929,6 +971,8 @@ impl<'db> Evaluator<'db> {
         let mut current_block_idx = body.start_block;
         let (mut locals, prev_stack_ptr) = self.create_locals_for_body(&body, None)?;
         self.fill_locals_for_body(&body, &mut locals, args)?;
+        let debug_stack_len = self.debug_stack.len();
+        self.debug_push_frame(&locals);
         let prev_code_stack = mem::take(&mut self.code_stack);
         let span = (MirSpan::Unknown, body.owner);
         self.code_stack.push(StackFrame { locals, destination: None, prev_stack_ptr, span });
@@ -947,23 +991,26 @@ impl<'db> Evaluator<'db> {
                         return Err(MirEvalError::ExecutionLimitExceeded);
                     }
                     for statement in &current_block.statements {
+                        self.debug_step(statement.span, locals)?;
                         match &statement.kind {
                             StatementKind::Assign(l, r) => {
                                 let addr = self.place_addr(l, locals)?;
                                 let result = self.eval_rvalue(r, locals)?;
                                 self.copy_from_interval_or_owned(addr, result)?;
                                 locals.drop_flags.add_place(*l, &locals.body.projection_store);
+                                self.debug_set_initialized(l, true);
                             }
                             StatementKind::Deinit(_) => not_supported!("de-init statement"),
-                            StatementKind::StorageLive(_)
-                            | StatementKind::FakeRead(_)
-                            | StatementKind::StorageDead(_)
-                            | StatementKind::Nop => (),
+                            StatementKind::StorageLive(l) | StatementKind::StorageDead(l) => {
+                                self.debug_set_initialized(&(*l).into(), false);
+                            }
+                            StatementKind::FakeRead(_) | StatementKind::Nop => (),
                         }
                     }
                     let Some(terminator) = current_block.terminator.as_ref() else {
                         not_supported!("block without terminator");
                     };
+                    self.debug_step(terminator.span, locals)?;
                     match &terminator.kind {
                         TerminatorKind::Goto { target } => {
                             current_block_idx = *target;
@@ -1009,11 +1056,14 @@ impl<'db> Evaluator<'db> {
                                 .drop_flags
                                 .add_place(*destination, &locals.body.projection_store);
                             if let Some(stack_frame) = stack_frame {
+                                self.debug_call(*destination);
                                 self.code_stack.push(my_stack_frame);
                                 current_block_idx = stack_frame.locals.body.start_block;
+                                self.debug_push_frame(&stack_frame.locals);
                                 self.code_stack.push(stack_frame);
                                 return Ok(None);
                             } else {
+                                self.debug_set_initialized(destination, true);
                                 current_block_idx =
                                     target.ok_or(MirEvalError::UndefinedBehavior(
                                         "Diverging function returned".to_owned(),
@@ -1037,6 +1087,7 @@ impl<'db> Evaluator<'db> {
                         }
                         TerminatorKind::Drop { place, target, unwind: _ } => {
                             self.drop_place(place, locals, terminator.span)?;
+                            self.debug_set_initialized(place, false);
                             current_block_idx = *target;
                         }
                         _ => not_supported!("unknown terminator"),
@@ -1048,6 +1099,7 @@ impl<'db> Evaluator<'db> {
                 Ok(None) => continue 'stack,
                 Ok(Some(x)) => x,
                 Err(e) => {
+                    self.debug_stack.truncate(debug_stack_len);
                     let my_code_stack = mem::replace(&mut self.code_stack, prev_code_stack);
                     let mut error_stack = vec![];
                     for frame in my_code_stack.into_iter().rev() {
@@ -1064,6 +1116,7 @@ impl<'db> Evaluator<'db> {
                 .entry(my_stack_frame.locals.body.owner)
                 .or_default()
                 .push(my_stack_frame.locals);
+            self.debug_pop_frame();
             match my_stack_frame.destination {
                 None => {
                     self.code_stack = prev_code_stack;
@@ -1912,6 +1965,9 @@ impl<'db> Evaluator<'db> {
         Ok(match &it.kind {
             OperandKind::Copy(p) | OperandKind::Move(p) => {
                 locals.drop_flags.remove_place(p, &locals.body.projection_store);
+                if let OperandKind::Move(p) = &it.kind {
+                    self.debug_set_initialized(p, false);
+                }
                 self.eval_place(p, locals)?
             }
             OperandKind::Static(st) => {
@@ -0,0 +1,200 @@
+//! Hooks for stepping through the evaluation of a MIR body, used by the interactive debugger.
+
+use std::ops::ControlFlow;
+
+use hir_def::DefWithBodyId;
+use hir_expand::{InFile, name::Name};
+use rustc_hash::FxHashSet;
+use syntax::SyntaxNodePtr;
+
+use crate::{
+    MemoryMap,
+    db::HirDatabase,
+    display::{DisplayTarget, HirDisplay},
+    mir::{LocalId, MirSpan, Place},
+    next_solver::Const,
+};
+
+use super::{Evaluator, Locals, MirEvalError, Result, mir_span_to_syntax};
+
+/// Decides whether the evaluation continues after each step. Returning [`ControlFlow::Break`]
+/// aborts the evaluation with [`MirEvalError::Interrupted`].
+pub trait DebugHook<'db> {
+    fn on_step(&mut self, frame: &DebugFrame<'_, 'db>) -> ControlFlow<()>;
+}
+
+impl<'db, F> DebugHook<'db> for F
+where
+    F: FnMut(&DebugFrame<'_, 'db>) -> ControlFlow<()>,
+{
+    fn on_step(&mut self, frame: &DebugFrame<'_, 'db>) -> ControlFlow<()> {
+        self(frame)
+    }
+}
+
+/// The state of the evaluator right before a statement or a terminator is executed.
+pub struct DebugFrame<'a, 'db> {
+    evaluator: &'a Evaluator<'db>,
+    locals: &'a Locals,
+    span: MirSpan,
+}
+
+/// A frame of [`Evaluator::debug_stack`].
+pub(super) struct DebugStackFrame {
+    locals: Locals,
+    span: MirSpan,
+    /// The locals that hold a value, they are assigned to and not moved out of or dead.
+    initialized: FxHashSet<LocalId>,
+    /// The destination of the call the frame is waiting for, initialized when the callee returns.
+    call_destination: Option<Place>,
+}
+
+/// A named local of a stack frame, with its type and value rendered for display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugLocal {
+    pub name: Name,
+    pub ty: String,
+    /// `None` if the value can not be read, e.g. because it is not initialized yet.
+    pub value: Option<String>,
+}
+
+impl<'a, 'db> DebugFrame<'a, 'db> {
+    pub fn db(&self) -> &'db dyn HirDatabase {
+        self.evaluator.db
+    }
+
+    /// The body that is currently executing.
+    pub fn owner(&self) -> DefWithBodyId {
+        self.locals.body.owner
+    }
+
+    /// The span of the statement or terminator that is about to be executed.
+    pub fn span(&self) -> MirSpan {
+        self.span
+    }
+
+    pub fn source(&self) -> Option<InFile<SyntaxNodePtr>> {
+        mir_span_to_syntax(self.db(), self.owner(), self.span)
+    }
+
+    /// Number of frames below the current one.
+    pub fn depth(&self) -> usize {
+        self.evaluator.debug_stack.len().saturating_sub(1)
+    }
+
+    /// Returns the owner and the current span of every frame in the call stack, starting
+    /// with the innermost one.
+    pub fn stack(&self) -> Vec<(DefWithBodyId, MirSpan)> {
+        let mut result = vec![(self.owner(), self.span)];
+        let callers = self.callers().map(|frame| (frame.locals.body.owner, frame.span));
+        result.extend(callers);
+        result
+    }
+
+    fn callers(&self) -> impl Iterator<Item = &'a DebugStackFrame> {
+        self.evaluator.debug_stack.iter().rev().skip(1)
+    }
+
+    /// Renders the named locals of the frame at `frame` in [`Self::stack`].
+    pub fn locals(&self, frame: usize, display_target: DisplayTarget) -> Vec<DebugLocal> {
+        let Some(debug_frame) = self.evaluator.debug_stack.iter().rev().nth(frame) else {
+            return vec![];
+        };
+        let locals = if frame == 0 { self.locals } else { &debug_frame.locals };
+        let db = self.db();
+        let body = db.body(locals.body.owner);
+        let mut result: Vec<_> = locals
+            .body
+            .binding_locals
+            .iter()
+            .map(|(binding, &local)| DebugLocal {
+                name: body[binding].name.clone(),
+                ty: locals.body.locals[local].ty.as_ref().display(db, display_target).to_string(),
+                value: debug_frame
+                    .initialized
+                    .contains(&local)
+                    .then(|| self.render_local(locals, local).ok())
+                    .flatten()
+                    .map(|it| it.display(db, display_target).to_string()),
+            })
+            .collect();
+        result.sort_by(|a, b| a.name.as_str().cmp(b.name.as_str()));
+        result
+    }
+
+    fn render_local(&self, locals: &Locals, local: LocalId) -> Result<'db, Const<'db>> {
+        let Some(interval) = locals.ptr.get(local) else {
+            return Err(MirEvalError::InternalError("local is not allocated".into()));
+        };
+        let ty = locals.body.locals[local].ty.as_ref();
+        let bytes = interval.get(self.evaluator)?;
+        let mut memory_map = self.evaluator.create_memory_map(bytes, ty, locals)?;
+        let memory_map = if memory_map.memory.is_empty() {
+            MemoryMap::Empty
+        } else {
+            memory_map.vtable = self.evaluator.vtable_map.clone();
+            MemoryMap::Complex(Box::new(memory_map))
+        };
+        Ok(Const::new_valtree(self.evaluator.interner(), ty, bytes.into(), memory_map))
+    }
+}
+
+impl<'db> Evaluator<'db> {
+    /// Starts a new frame in [`Evaluator::debug_stack`], if there is a debug hook.
+    pub(super) fn debug_push_frame(&mut self, locals: &Locals) {
+        if self.debug_hook.is_some() {
+            self.debug_stack.push(DebugStackFrame {
+                locals: locals.clone(),
+                span: MirSpan::Unknown,
+                initialized: locals.body.param_locals.iter().copied().collect(),
+                call_destination: None,
+            });
+        }
+    }
+
+    /// Ends the innermost frame of [`Evaluator::debug_stack`], which returns to its caller.
+    pub(super) fn debug_pop_frame(&mut self) {
+        self.debug_stack.pop();
+        let destination = self.debug_stack.last_mut().and_then(|it| it.call_destination.take());
+        if let Some(destination) = destination {
+            self.debug_set_initialized(&destination, true);
+        }
+    }
+
+    /// Records whether `place` of the innermost frame holds a value now.
+    pub(super) fn debug_set_initialized(&mut self, place: &Place, initialized: bool) {
+        // Only whole locals are tracked, assigning a part of a local doesn't make it readable.
+        let Some(frame) = self.debug_stack.last_mut() else { return };
+        if !place.projection.is_empty() {
+            return;
+        }
+        if initialized {
+            frame.initialized.insert(place.local);
+        } else {
+            frame.initialized.remove(&place.local);
+        }
+    }
+
+    /// Makes the innermost frame wait for a call that initializes `destination` on return.
+    pub(super) fn debug_call(&mut self, destination: Place) {
+        if let Some(frame) = self.debug_stack.last_mut() {
+            frame.call_destination = Some(destination);
+        }
+    }
+
+    #[inline]
+    pub(super) fn debug_step(&mut self, span: MirSpan, locals: &Locals) -> Result<'db, ()> {
+        let Some(mut hook) = self.debug_hook.take() else {
+            return Ok(());
+        };
+        if let Some(frame) = self.debug_stack.last_mut() {
+            frame.span = span;
+        }
+        let flow = hook.on_step(&DebugFrame { evaluator: self, locals, span });
+        self.debug_hook = Some(hook);
+        match flow {
+            ControlFlow::Continue(()) => Ok(()),
+            ControlFlow::Break(()) => Err(MirEvalError::Interrupted),
+        }
+    }
+}
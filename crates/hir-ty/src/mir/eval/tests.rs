@@ -1,53 +1,57 @@
+use std::ops::ControlFlow;
+
 use hir_def::{HasModule, db::DefDatabase};
 use hir_expand::EditionedFileId;
 use span::Edition;
 use syntax::{TextRange, TextSize};
 use test_fixture::WithFixture;
+use triomphe::Arc;
 
 use crate::{
     db::HirDatabase,
     display::DisplayTarget,
-    mir::MirLowerError,
+    mir::{MirBody, MirLowerError},
     next_solver::{DbInterner, GenericArgs},
     setup_tracing,
     test_db::TestDB,
 };
 
-use super::{MirEvalError, interpret_mir};
+use super::{DebugFrame, MirEvalError, interpret_mir, interpret_mir_with_debug_hook};
+
+fn main_mir_body(db: &TestDB, file_id: EditionedFileId) -> Result<Arc<MirBody>, MirEvalError> {
+    let interner = DbInterner::new_no_crate(db);
+    let module_id = db.module_for_file(file_id.file_id(db));
+    let def_map = module_id.def_map(db);
+    let scope = &def_map[module_id].scope;
+    let func_id = scope
+        .declarations()
+        .find_map(|x| match x {
+            hir_def::ModuleDefId::FunctionId(x) => {
+                if db.function_signature(x).name.display(db, Edition::CURRENT).to_string() == "main"
+                {
+                    Some(x)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        })
+        .expect("no main function found");
+    db.monomorphized_mir_body(
+        func_id.into(),
+        GenericArgs::empty(interner).store(),
+        crate::ParamEnvAndCrate {
+            param_env: db.trait_environment(func_id.into()),
+            krate: func_id.krate(db),
+        }
+        .store(),
+    )
+    .map_err(|e| MirEvalError::MirLowerError(func_id, e))
+}
 
 fn eval_main(db: &TestDB, file_id: EditionedFileId) -> Result<(String, String), MirEvalError> {
     crate::attach_db(db, || {
-        let interner = DbInterner::new_no_crate(db);
-        let module_id = db.module_for_file(file_id.file_id(db));
-        let def_map = module_id.def_map(db);
-        let scope = &def_map[module_id].scope;
-        let func_id = scope
-            .declarations()
-            .find_map(|x| match x {
-                hir_def::ModuleDefId::FunctionId(x) => {
-                    if db.function_signature(x).name.display(db, Edition::CURRENT).to_string()
-                        == "main"
-                    {
-                        Some(x)
-                    } else {
-                        None
-                    }
-                }
-                _ => None,
-            })
-            .expect("no main function found");
-        let body = db
-            .monomorphized_mir_body(
-                func_id.into(),
-                GenericArgs::empty(interner).store(),
-                crate::ParamEnvAndCrate {
-                    param_env: db.trait_environment(func_id.into()),
-                    krate: func_id.krate(db),
-                }
-                .store(),
-            )
-            .map_err(|e| MirEvalError::MirLowerError(func_id, e))?;
-
+        let body = main_mir_body(db, file_id)?;
         let (result, output) = interpret_mir(db, body, false, None)?;
         result?;
         Ok((output.stdout().into_owned(), output.stderr().into_owned()))
@@ -133,6 +137,109 @@ fn check_error_with(
     })
 }
 
+#[test]
+fn debug_hook_inspects_locals() {
+    let (db, file_ids) = TestDB::with_many_files(
+        r#"
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+fn main() {
+    let x = 2;
+    let y = add(x, 3);
+    let z = y * 2;
+}
+"#,
+    );
+    crate::attach_db(&db, || {
+        let file_id = *file_ids.last().unwrap();
+        let body = main_mir_body(&db, file_id).unwrap();
+        let krate = db.module_for_file(file_id.file_id(&db)).krate(&db);
+        let display_target = DisplayTarget::from_crate(&db, krate);
+        let mut max_depth = 0;
+        let mut main_locals = vec![];
+        let hook = |frame: &DebugFrame<'_, '_>| -> ControlFlow<()> {
+            max_depth = max_depth.max(frame.depth());
+            // Remember the last step where all the locals are initialized, before they are
+            // dropped at the end of `main`.
+            let locals = frame.locals(0, display_target);
+            if frame.depth() == 0 && locals.iter().all(|it| it.value.is_some()) {
+                main_locals = locals
+                    .into_iter()
+                    .map(|it| format!("{}: {} = {}", it.name.as_str(), it.ty, it.value.unwrap()))
+                    .collect();
+            }
+            ControlFlow::Continue(())
+        };
+        let (result, _) = interpret_mir_with_debug_hook(&db, body, Box::new(hook)).unwrap();
+        result.unwrap();
+        assert_eq!(max_depth, 1);
+        assert_eq!(main_locals, ["x: i32 = 2", "y: i32 = 5", "z: i32 = 10"]);
+    })
+}
+
+#[test]
+fn debug_hook_sees_callers_of_closures() {
+    let (db, file_ids) = TestDB::with_many_files(
+        r#"
+//- minicore: fn
+fn main() {
+    let x = 2;
+    let f = |a: i32| a + 1;
+    let y = f(x);
+}
+"#,
+    );
+    crate::attach_db(&db, || {
+        let file_id = *file_ids.last().unwrap();
+        let body = main_mir_body(&db, file_id).unwrap();
+        let krate = db.module_for_file(file_id.file_id(&db)).krate(&db);
+        let display_target = DisplayTarget::from_crate(&db, krate);
+        let mut caller_locals = vec![];
+        let hook = |frame: &DebugFrame<'_, '_>| -> ControlFlow<()> {
+            if frame.depth() == 1 {
+                assert_eq!(frame.stack().len(), 2);
+                caller_locals = frame
+                    .locals(1, display_target)
+                    .into_iter()
+                    .filter(|it| it.name.as_str() != "f")
+                    .map(|it| format!("{} = {:?}", it.name.as_str(), it.value))
+                    .collect();
+            }
+            ControlFlow::Continue(())
+        };
+        let (result, _) = interpret_mir_with_debug_hook(&db, body, Box::new(hook)).unwrap();
+        result.unwrap();
+        assert_eq!(caller_locals, ["x = Some(\"2\")", "y = None"]);
+    })
+}
+
+#[test]
+fn debug_hook_interrupts() {
+    let (db, file_ids) = TestDB::with_many_files(
+        r#"
+fn main() {
+    loop {}
+}
+"#,
+    );
+    crate::attach_db(&db, || {
+        let file_id = *file_ids.last().unwrap();
+        let body = main_mir_body(&db, file_id).unwrap();
+        let mut steps = 0;
+        let hook = |_: &DebugFrame<'_, '_>| -> ControlFlow<()> {
+            steps += 1;
+            if steps == 10 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+        };
+        let (result, _) = interpret_mir_with_debug_hook(&db, body, Box::new(hook)).unwrap();
+        let mut err = result.unwrap_err();
+        while let MirEvalError::InFunction(e, _) = err {
+            err = *e;
+        }
+        assert_eq!(err, MirEvalError::Interrupted);
+    })
+}
+
 #[test]
 fn function_with_extern_c_abi() {
     check_pass(
@@ -397,11 +397,16 @@ impl<'a, 'db> MirLowerCtx<'a, 'db> {
                     place,
                     current,
                     rest,
-                    (*m).into(),
+                    BorrowKind::from_autoref(*m),
                 ),
-                Adjust::Borrow(AutoBorrow::RawPtr(m)) => {
-                    self.lower_expr_to_place_with_borrow_adjust(expr_id, place, current, rest, *m)
-                }
+                Adjust::Borrow(AutoBorrow::RawPtr(m)) => self
+                    .lower_expr_to_place_with_borrow_adjust(
+                        expr_id,
+                        place,
+                        current,
+                        rest,
+                        BorrowKind::from_rustc(*m),
+                    ),
                 Adjust::Pointer(cast) => {
                     let Some((p, current)) =
                         self.lower_expr_as_place_with_adjust(current, expr_id, true, rest)?
@@ -431,14 +436,13 @@ impl<'a, 'db> MirLowerCtx<'a, 'db> {
         place: Place,
         current: BasicBlockId,
         rest: &[Adjustment],
-        m: Mutability,
+        bk: BorrowKind,
     ) -> Result<'db, Option<BasicBlockId>> {
         let Some((p, current)) =
             self.lower_expr_as_place_with_adjust(current, expr_id, true, rest)?
         else {
             return Ok(None);
         };
-        let bk = BorrowKind::from_rustc(m);
         self.push_assignment(current, place, Rvalue::Ref(bk, p), expr_id.into());
         Ok(Some(current))
     }
@@ -0,0 +1,110 @@
+//! Detection of functions that cannot return without calling themselves, used by the
+//! `unconditional_recursion` diagnostic.
+
+use either::Either;
+use hir_def::{FunctionId, HasModule};
+use rustc_hash::FxHashSet;
+use rustc_type_ir::inherent::IntoKind;
+
+use crate::{
+    CallableDefId, InferenceResult, ParamEnvAndCrate,
+    db::HirDatabase,
+    method_resolution::is_dyn_method,
+    next_solver::{DbInterner, TyKind},
+};
+
+use super::{BasicBlockId, MirSpan, Operand, OperandKind, TerminatorKind};
+
+/// Returns the spans of the calls `func` makes to itself if every path from its entry reaches
+/// one of them, and an empty list otherwise.
+///
+/// Calls of trait methods count as recursive calls when they resolve to `func`, which catches
+/// things like a `PartialEq` impl comparing `self` with `==`.
+pub fn unconditional_recursion(db: &dyn HirDatabase, func: FunctionId) -> Vec<MirSpan> {
+    if db.function_signature(func).is_async() {
+        return Vec::new();
+    }
+    // Calls in erroneous code, like ones with the wrong number of arguments, are not reported.
+    let infer = InferenceResult::for_body(db, func.into());
+    if infer.has_errors || !infer.diagnostics().is_empty() {
+        return Vec::new();
+    }
+    let Ok(body) = db.mir_body(func.into()) else {
+        return Vec::new();
+    };
+    let env = ParamEnvAndCrate {
+        param_env: db.trait_environment_for_body(func.into()),
+        krate: func.krate(db),
+    };
+
+    let mut call_sites = Vec::new();
+    let mut visited = FxHashSet::default();
+    let mut stack = vec![body.start_block];
+    while let Some(block) = stack.pop() {
+        if !visited.insert(block) {
+            continue;
+        }
+        let Some(terminator) = &body.basic_blocks[block].terminator else {
+            return Vec::new();
+        };
+        if let TerminatorKind::Call { func: callee, .. } = &terminator.kind
+            && is_call_of(db, env, func, callee)
+        {
+            call_sites.push(terminator.span);
+            continue;
+        }
+        let Some(successors) = normal_successors(&terminator.kind) else {
+            // The function can return, or diverge without recursing, on this path.
+            return Vec::new();
+        };
+        stack.extend(successors);
+    }
+    call_sites
+}
+
+/// Whether `callee` is `func` itself, or a trait method that resolves to it.
+fn is_call_of<'db>(
+    db: &'db dyn HirDatabase,
+    env: ParamEnvAndCrate<'db>,
+    func: FunctionId,
+    callee: &Operand,
+) -> bool {
+    let OperandKind::Constant { ty, .. } = &callee.kind else {
+        return false;
+    };
+    let TyKind::FnDef(def, args) = ty.as_ref().kind() else {
+        return false;
+    };
+    let CallableDefId::FunctionId(callee) = def.0 else {
+        return false;
+    };
+    if callee == func {
+        return true;
+    }
+    let interner = DbInterner::new_with(db, env.krate);
+    if is_dyn_method(interner, env.param_env, callee, args).is_some() {
+        return false;
+    }
+    matches!(db.lookup_impl_method(env, callee, args).0, Either::Left(it) if it == func)
+}
+
+/// Returns the blocks execution can continue with after `terminator` when nothing unwinds, or
+/// `None` when the function returns or diverges after it.
+fn normal_successors(terminator: &TerminatorKind) -> Option<Vec<BasicBlockId>> {
+    Some(match terminator {
+        TerminatorKind::Goto { target }
+        | TerminatorKind::Assert { target, .. }
+        | TerminatorKind::Drop { target, .. }
+        | TerminatorKind::DropAndReplace { target, .. }
+        | TerminatorKind::FalseEdge { real_target: target, .. }
+        | TerminatorKind::FalseUnwind { real_target: target, .. } => vec![*target],
+        TerminatorKind::SwitchInt { targets, .. } => targets.all_targets().to_vec(),
+        TerminatorKind::Call { target, .. } => vec![(*target)?],
+        TerminatorKind::Return
+        | TerminatorKind::Unreachable
+        | TerminatorKind::UnwindResume
+        | TerminatorKind::Abort
+        | TerminatorKind::Yield { .. }
+        | TerminatorKind::CoroutineDrop => return None,
+    })
+}
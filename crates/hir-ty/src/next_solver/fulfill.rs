@@ -24,6 +24,8 @@ use crate::next_solver::{
     inspect::ProofTreeVisitor,
 };
 
+pub use errors::find_unsatisfied_leaf_obligation;
+
 type PendingObligations<'db> =
     Vec<(PredicateObligation<'db>, Option<GoalStalledOn<DbInterner<'db>>>)>;
 
@@ -1,7 +1,8 @@
 //! Trait solving error diagnosis and reporting.
 //!
-//! This code isn't used by rust-analyzer (it should, but then it'll probably be better to re-port it from rustc).
-//! It's only there because without it, debugging trait solver errors is a nightmare.
+//! Most of this code isn't used by rust-analyzer (it should, but then it'll probably be better to re-port it
+//! from rustc). It's only there because without it, debugging trait solver errors is a nightmare. The exception
+//! is [`find_unsatisfied_leaf_obligation`], which inference uses to report unsatisfied trait bounds.
 
 use std::{fmt::Debug, ops::ControlFlow};
 
@@ -212,35 +213,59 @@ pub(super) fn fulfillment_error_for_overflow<'db>(
     }
 }
 
-#[instrument(level = "debug", skip(infcx), ret)]
 fn find_best_leaf_obligation<'db>(
     infcx: &InferCtxt<'db>,
     obligation: &PredicateObligation<'db>,
     consider_ambiguities: bool,
 ) -> PredicateObligation<'db> {
+    find_best_leaf_obligation_with_requirements(infcx, obligation, consider_ambiguities).0
+}
+
+/// Finds the obligation that is responsible for `obligation` never holding, together with the
+/// trait predicates whose impls required it, outermost first.
+pub fn find_unsatisfied_leaf_obligation<'db>(
+    infcx: &InferCtxt<'db>,
+    obligation: &PredicateObligation<'db>,
+) -> (PredicateObligation<'db>, Vec<PolyTraitPredicate<'db>>) {
+    find_best_leaf_obligation_with_requirements(infcx, obligation, false)
+}
+
+#[instrument(level = "debug", skip(infcx), ret)]
+fn find_best_leaf_obligation_with_requirements<'db>(
+    infcx: &InferCtxt<'db>,
+    obligation: &PredicateObligation<'db>,
+    consider_ambiguities: bool,
+) -> (PredicateObligation<'db>, Vec<PolyTraitPredicate<'db>>) {
     let obligation = infcx.resolve_vars_if_possible(obligation.clone());
     // FIXME: we use a probe here as the `BestObligation` visitor does not
     // check whether it uses candidates which get shadowed by where-bounds.
     //
     // We should probably fix the visitor to not do so instead, as this also
     // means the leaf obligation may be incorrect.
-    let obligation = infcx
+    let (obligation, required_by) = infcx
         .fudge_inference_if_ok(|| {
-            infcx
-                .visit_proof_tree(
-                    obligation.as_goal(),
-                    &mut BestObligation { obligation: obligation.clone(), consider_ambiguities },
-                )
+            let mut visitor = BestObligation {
+                obligation: obligation.clone(),
+                consider_ambiguities,
+                required_by: Vec::new(),
+            };
+            let leaf = infcx
+                .visit_proof_tree(obligation.as_goal(), &mut visitor)
                 .break_value()
-                .ok_or(())
+                .ok_or(())?;
+            Ok((leaf, visitor.required_by))
         })
-        .unwrap_or(obligation);
-    deeply_normalize_for_diagnostics(infcx, obligation.param_env, obligation)
+        .unwrap_or_else(|()| (obligation, Vec::new()));
+    let obligation = deeply_normalize_for_diagnostics(infcx, obligation.param_env, obligation);
+    (obligation, required_by)
 }
 
 struct BestObligation<'db> {
     obligation: PredicateObligation<'db>,
     consider_ambiguities: bool,
+    /// The trait predicates whose impl where-clauses led to `obligation`. This is left
+    /// untouched when we break out of the visitor, so it describes the path to the leaf.
+    required_by: Vec<PolyTraitPredicate<'db>>,
 }
 
 impl<'db> BestObligation<'db> {
@@ -540,27 +565,31 @@ impl<'db> ProofTreeVisitor<'db> for BestObligation<'db> {
                 recursion_depth: self.obligation.recursion_depth + 1,
             };
 
-            let obligation = match (child_mode, nested_goal.source()) {
+            let (obligation, required_by) = match (child_mode, nested_goal.source()) {
                 (
                     ChildMode::Trait(_) | ChildMode::Host(_),
                     GoalSource::Misc | GoalSource::TypeRelating | GoalSource::NormalizeGoal(_),
                 ) => {
                     continue;
                 }
-                (ChildMode::Trait(_parent_trait_pred), GoalSource::ImplWhereBound) => {
-                    make_obligation()
+                (ChildMode::Trait(parent_trait_pred), GoalSource::ImplWhereBound) => {
+                    (make_obligation(), Some(parent_trait_pred))
                 }
                 (
                     ChildMode::Host(_parent_host_pred),
                     GoalSource::ImplWhereBound | GoalSource::AliasBoundConstCondition,
-                ) => make_obligation(),
+                ) => (make_obligation(), None),
                 (ChildMode::PassThrough, _)
                 | (_, GoalSource::AliasWellFormed | GoalSource::AliasBoundConstCondition) => {
-                    make_obligation()
+                    (make_obligation(), None)
                 }
             };
 
+            self.required_by.extend(required_by);
             self.with_derived_obligation(obligation, |this| nested_goal.visit_with(this))?;
+            if required_by.is_some() {
+                self.required_by.pop();
+            }
         }
 
         // alias-relate may fail because the lhs or rhs can't be normalized,
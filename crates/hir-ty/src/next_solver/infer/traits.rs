@@ -7,7 +7,7 @@ use std::{
     hash::{Hash, Hasher},
 };
 
-use hir_def::TraitId;
+use hir_def::{TraitId, hir::ExprOrPatId};
 use macros::{TypeFoldable, TypeVisitable};
 use rustc_type_ir::elaborate::Elaboratable;
 use rustc_type_ir::{
@@ -33,15 +33,26 @@ use super::InferCtxt;
 /// only live for a short period of time.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ObligationCause {
-    // FIXME: This should contain an `ExprId`/`PatId` etc., and a cause code. But for now we
-    // don't report trait solving diagnostics, so this is irrelevant.
-    _private: (),
+    // FIXME: This should also contain a cause code. For now we only track the expression or
+    // pattern that required the obligation, to be able to report unsatisfied trait bounds.
+    span: Option<ExprOrPatId>,
 }
 
 impl ObligationCause {
     #[inline]
     pub fn new() -> ObligationCause {
-        ObligationCause { _private: () }
+        ObligationCause { span: None }
+    }
+
+    #[inline]
+    pub fn with_span(span: ExprOrPatId) -> ObligationCause {
+        ObligationCause { span: Some(span) }
+    }
+
+    /// The expression or pattern that required this obligation, if known.
+    #[inline]
+    pub fn span(&self) -> Option<ExprOrPatId> {
+        self.span
     }
 
     #[inline]
@@ -5,7 +5,7 @@ use either::Either;
 use hir_def::{
     AssocItemId, AttrDefId, FieldId, GenericDefId, ItemContainerId, LifetimeParamId, ModuleDefId,
     TraitId, TypeOrConstParamId,
-    attrs::{AttrFlags, Docs, IsInnerDoc},
+    attrs::{AttrFlags, Deprecation, Docs, IsInnerDoc},
     expr_store::path::Path,
     item_scope::ItemInNs,
     per_ns::Namespace,
@@ -104,6 +104,32 @@ impl AttrsWithOwner {
         self.attrs.contains(AttrFlags::IS_DEPRECATED)
     }
 
+    /// Returns the `since` and `note` of `#[deprecated]`, or `None` if the item is not deprecated.
+    #[inline]
+    pub fn deprecation<'db>(&self, db: &'db dyn HirDatabase) -> Option<&'db Deprecation> {
+        let owner = match self.owner {
+            AttrsOwner::AttrDef(it) => Either::Left(it),
+            AttrsOwner::Field(it) => Either::Right(it),
+            AttrsOwner::LifetimeParam(_) | AttrsOwner::TypeOrConstParam(_) | AttrsOwner::Dummy => {
+                return None;
+            }
+        };
+        self.attrs.deprecation(db, owner)
+    }
+
+    /// Returns the feature given by `#[unstable(feature = "name")]`.
+    #[inline]
+    pub fn unstable_feature<'db>(&self, db: &'db dyn HirDatabase) -> Option<&'db Symbol> {
+        let owner = match self.owner {
+            AttrsOwner::AttrDef(it) => Either::Left(it),
+            AttrsOwner::Field(it) => Either::Right(it),
+            AttrsOwner::LifetimeParam(_) | AttrsOwner::TypeOrConstParam(_) | AttrsOwner::Dummy => {
+                return None;
+            }
+        };
+        self.attrs.unstable_feature(db, owner)
+    }
+
     #[inline]
     pub fn is_non_exhaustive(&self) -> bool {
         self.attrs.contains(AttrFlags::NON_EXHAUSTIVE)
@@ -0,0 +1,134 @@
+//! Reachability analysis for private items, used to report dead code.
+//!
+//! An item is live if it is a root, like an exported function or a test, or if a live item uses
+//! it. Finding the uses of an item needs a reference search, which is left to the caller.
+
+use std::collections::VecDeque;
+
+use hir_def::attrs::AttrFlags;
+use rustc_hash::{FxHashMap, FxHashSet};
+use stdx::impl_from;
+
+use crate::{
+    AsAssocItem, Const, Field, Function, HasVisibility, Static, Variant, Visibility,
+    db::HirDatabase,
+};
+
+/// How many private items we follow through before giving up and treating an item as used.
+const MAX_DEPTH: usize = 16;
+
+/// An item that is dead code unless it is live.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeadCodeItem {
+    Function(Function),
+    Const(Const),
+    Static(Static),
+    Field(Field),
+    Variant(Variant),
+}
+impl_from!(Function, Const, Static, Field, Variant for DeadCodeItem);
+
+impl DeadCodeItem {
+    /// Whether the item is used regardless of any references to it, e.g. because it is exported
+    /// or called by the test harness.
+    pub fn is_root(self, db: &dyn HirDatabase) -> bool {
+        let visibility = match self {
+            DeadCodeItem::Function(it) => it.visibility(db),
+            DeadCodeItem::Const(it) => it.visibility(db),
+            DeadCodeItem::Static(it) => it.visibility(db),
+            DeadCodeItem::Field(it) => it.visibility(db),
+            DeadCodeItem::Variant(it) => it.visibility(db),
+        };
+        if visibility == Visibility::Public {
+            return true;
+        }
+        let (assoc_item, attrs) = match self {
+            DeadCodeItem::Function(func) => {
+                if func.is_main(db) || func.is_test(db) || func.is_bench(db) {
+                    return true;
+                }
+                (func.as_assoc_item(db), func.attrs(db))
+            }
+            DeadCodeItem::Const(konst) => (konst.as_assoc_item(db), AttrFlags::empty()),
+            DeadCodeItem::Static(it) => (None, AttrFlags::query(db, it.id.into())),
+            DeadCodeItem::Field(_) | DeadCodeItem::Variant(_) => return false,
+        };
+        if assoc_item.is_some_and(|it| it.container_or_implemented_trait(db).is_some()) {
+            return true;
+        }
+        // Symbols kept for the linker may be used from anywhere.
+        attrs.intersects(AttrFlags::NO_MANGLE | AttrFlags::HAS_EXPORT_NAME | AttrFlags::IS_USED)
+    }
+}
+
+/// Decides which items are live, remembering the answer for every item it had to look at.
+pub struct Liveness<'db, F> {
+    db: &'db dyn HirDatabase,
+    /// Returns the items whose definitions use the given item, or `None` if it has a use that
+    /// keeps it alive on its own, like a re-export.
+    users: F,
+    live: FxHashMap<DeadCodeItem, bool>,
+}
+
+impl<'db, F> Liveness<'db, F>
+where
+    F: FnMut(DeadCodeItem) -> Option<Vec<DeadCodeItem>>,
+{
+    pub fn new(db: &'db dyn HirDatabase, users: F) -> Self {
+        Liveness { db, users, live: FxHashMap::default() }
+    }
+
+    pub fn is_live(&mut self, item: DeadCodeItem) -> bool {
+        if let Some(&live) = self.live.get(&item) {
+            return live;
+        }
+
+        // Collect everything `item` could be kept alive by first, as the users of an item can
+        // form cycles.
+        let mut users = FxHashMap::default();
+        let mut live = FxHashSet::default();
+        let mut queue = VecDeque::from([(item, 0)]);
+        while let Some((current, depth)) = queue.pop_front() {
+            if users.contains_key(&current) {
+                continue;
+            }
+            let current_users = match self.live.get(&current) {
+                Some(&known) => {
+                    if known {
+                        live.insert(current);
+                    }
+                    Vec::new()
+                }
+                None if depth > MAX_DEPTH || current.is_root(self.db) => {
+                    live.insert(current);
+                    Vec::new()
+                }
+                None => (self.users)(current).unwrap_or_else(|| {
+                    live.insert(current);
+                    Vec::new()
+                }),
+            };
+            queue.extend(current_users.iter().map(|&user| (user, depth + 1)));
+            users.insert(current, current_users);
+        }
+
+        // Then let liveness flow from the users to the items they use.
+        loop {
+            let newly_live = users
+                .iter()
+                .filter(|&(item, item_users)| {
+                    !live.contains(item) && item_users.iter().any(|user| live.contains(user))
+                })
+                .map(|(&item, _)| item)
+                .collect::<Vec<_>>();
+            if newly_live.is_empty() {
+                break;
+            }
+            live.extend(newly_live);
+        }
+        for item in users.into_keys() {
+            self.live.insert(item, live.contains(&item));
+        }
+        self.live[&item]
+    }
+}
@@ -6,7 +6,8 @@
 use cfg::{CfgExpr, CfgOptions};
 use either::Either;
 use hir_def::{
-    DefWithBodyId, GenericParamId, SyntheticSyntax,
+    DefWithBodyId, GenericParamId, SyntheticSyntax, TraitId,
+    attrs::AttrFlags,
     expr_store::{
         ExprOrPatPtr, ExpressionStoreSourceMap, hir_assoc_type_binding_to_ast,
         hir_generic_arg_to_ast, hir_segment_to_ast_segment,
@@ -19,20 +20,22 @@ use hir_ty::{
     PathLoweringDiagnostic, TyLoweringDiagnostic, TyLoweringDiagnosticKind,
     db::HirDatabase,
     diagnostics::{BodyValidationDiagnostic, UnsafetyReason},
+    next_solver::{DbInterner, StoredGenericArgs},
 };
+use intern::Symbol;
 use syntax::{
     AstNode, AstPtr, SyntaxError, SyntaxNodePtr, TextRange,
-    ast::{self, HasGenericArgs},
+    ast::{self, ArithOp, HasGenericArgs},
     match_ast,
 };
 use triomphe::Arc;
 
-use crate::{AssocItem, Field, Function, GenericDef, Local, Trait, Type};
+use crate::{Adt, AssocItem, Field, Function, GenericDef, Local, Trait, TraitRef, Type};
 
 pub use hir_def::VariantId;
 pub use hir_ty::{
     GenericArgsProhibitedReason, IncorrectGenericsLenKind,
-    diagnostics::{CaseType, IncorrectCase},
+    diagnostics::{CaseType, IncorrectCase, UnconditionalPanicKind},
 };
 
 macro_rules! diagnostics {
@@ -67,9 +70,12 @@ macro_rules! diagnostics {
 // ]
 
 diagnostics![AnyDiagnostic<'db> ->
+    ArithmeticOverflow,
     AwaitOutsideOfAsync,
     BreakOutsideOfLoop,
     CastToUnsized<'db>,
+    ConflictingBorrow,
+    ConstEvalFailed,
     ExpectedFunction<'db>,
     InactiveCode,
     IncoherentImpl,
@@ -100,9 +106,13 @@ diagnostics![AnyDiagnostic<'db> ->
     TraitImplRedundantAssocItems,
     TypedHole<'db>,
     TypeMismatch<'db>,
+    UnconditionalPanic,
+    UnconditionalRecursion,
     UndeclaredLabel,
     UnimplementedBuiltinMacro,
+    UnreachableCode,
     UnreachableLabel,
+    UnreachablePattern,
     UnresolvedAssocItem,
     UnresolvedExternCrate,
     UnresolvedField<'db>,
@@ -111,8 +121,11 @@ diagnostics![AnyDiagnostic<'db> ->
     UnresolvedMethodCall<'db>,
     UnresolvedModule,
     UnresolvedIdent,
+    UnsatisfiedTraitBound<'db>,
+    UnusedMustUse,
     UnusedMut,
     UnusedVariable,
+    UseOfMovedValue,
     GenericArgsProhibited,
     ParenthesizedGenericArgsWithoutFnTrait,
     BadRtn,
@@ -271,6 +284,15 @@ pub struct UnresolvedIdent {
     pub node: InFile<(ExprOrPatPtr, Option<TextRange>)>,
 }
 
+#[derive(Debug)]
+pub struct UnsatisfiedTraitBound<'db> {
+    pub expr_or_pat: InFile<ExprOrPatPtr>,
+    /// The bound that doesn't hold.
+    pub trait_ref: TraitRef<'db>,
+    /// The bounds whose impls required `trait_ref`, outermost first.
+    pub required_by: Vec<TraitRef<'db>>,
+}
+
 #[derive(Debug)]
 pub struct PrivateField {
     pub expr: InFile<ExprOrPatPtr>,
@@ -354,6 +376,21 @@ pub struct MovedOutOfRef<'db> {
     pub span: InFile<SyntaxNodePtr>,
 }
 
+#[derive(Debug)]
+pub struct UseOfMovedValue {
+    pub local: Local,
+    pub span: InFile<SyntaxNodePtr>,
+}
+
+#[derive(Debug)]
+pub struct ConflictingBorrow {
+    pub local: Local,
+    pub span: InFile<SyntaxNodePtr>,
+    pub is_mut: bool,
+    /// Whether the borrow that is still live is mutable.
+    pub live_is_mut: bool,
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct IncoherentImpl {
     pub file_id: HirFileId,
@@ -399,6 +436,61 @@ pub struct RemoveUnnecessaryElse {
     pub if_expr: InFile<AstPtr<ast::IfExpr>>,
 }
 
+#[derive(Debug)]
+pub struct UnreachableCode {
+    /// The first statement or tail expression that is never executed.
+    pub node: InFile<SyntaxNodePtr>,
+}
+
+#[derive(Debug)]
+pub struct UnreachablePattern {
+    pub pat: InFile<AstPtr<ast::Pat>>,
+}
+
+#[derive(Debug)]
+pub struct UnusedMustUse {
+    pub expr: InFile<ExprOrPatPtr>,
+    pub kind: UnusedMustUseKind,
+    /// The reason given by `#[must_use = "reason"]`.
+    pub reason: Option<Symbol>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnusedMustUseKind {
+    /// The type of the value is `#[must_use]`.
+    Type(Adt),
+    /// The value is returned by a `#[must_use]` function.
+    Function(Function),
+    /// The value is a future that is never awaited.
+    Future,
+}
+
+#[derive(Debug)]
+pub struct ArithmeticOverflow {
+    pub expr: InFile<ExprOrPatPtr>,
+    pub op: ArithOp,
+}
+
+#[derive(Debug)]
+pub struct UnconditionalPanic {
+    pub expr: InFile<ExprOrPatPtr>,
+    pub kind: UnconditionalPanicKind,
+}
+
+#[derive(Debug)]
+pub struct UnconditionalRecursion {
+    pub func: InFile<AstPtr<ast::Fn>>,
+    /// The calls through which the function recurses.
+    pub call_sites: Vec<InFile<SyntaxNodePtr>>,
+}
+
+#[derive(Debug)]
+pub struct ConstEvalFailed {
+    pub expr: InFile<ExprOrPatPtr>,
+    /// The message of the panic that occurred during evaluation.
+    pub message: String,
+}
+
 #[derive(Debug)]
 pub struct CastToUnsized<'db> {
     pub expr: InFile<ExprOrPatPtr>,
@@ -610,6 +702,57 @@ impl<'db> AnyDiagnostic<'db> {
                     );
                 }
             }
+            BodyValidationDiagnostic::UnreachableCode { unreachable } => {
+                let source_ptr =
+                    source_map.expr_or_pat_syntax(unreachable).ok()?.map(|it| it.syntax_node_ptr());
+                let root = source_ptr.file_syntax(db);
+                let node = source_ptr.value.to_node(&root);
+                // Point at the whole statement rather than at its expression or pattern.
+                let node = node
+                    .ancestors()
+                    .take_while(|it| !ast::StmtList::can_cast(it.kind()))
+                    .find(|it| ast::Stmt::can_cast(it.kind()))
+                    .unwrap_or(node);
+                return Some(
+                    UnreachableCode {
+                        node: InFile::new(source_ptr.file_id, SyntaxNodePtr::new(&node)),
+                    }
+                    .into(),
+                );
+            }
+            BodyValidationDiagnostic::UnreachablePattern { pat } => {
+                let source_ptr = source_map.pat_syntax(pat).ok()?;
+                if let Some(ptr) = source_ptr.value.cast::<ast::Pat>() {
+                    return Some(
+                        UnreachablePattern { pat: InFile::new(source_ptr.file_id, ptr) }.into(),
+                    );
+                }
+            }
+            BodyValidationDiagnostic::UnusedMustUse { expr, kind } => {
+                let expr = source_map.expr_syntax(expr).ok()?;
+                let (kind, reason) = match kind {
+                    hir_ty::diagnostics::UnusedMustUseKind::Type(adt) => (
+                        UnusedMustUseKind::Type(adt.into()),
+                        AttrFlags::must_use_reason(db, adt.into()),
+                    ),
+                    hir_ty::diagnostics::UnusedMustUseKind::Function(func) => (
+                        UnusedMustUseKind::Function(func.into()),
+                        AttrFlags::must_use_reason(db, func.into()),
+                    ),
+                    hir_ty::diagnostics::UnusedMustUseKind::Future => {
+                        (UnusedMustUseKind::Future, None)
+                    }
+                };
+                return Some(UnusedMustUse { expr, kind, reason }.into());
+            }
+            BodyValidationDiagnostic::ArithmeticOverflow { expr, op } => {
+                let expr = source_map.expr_syntax(expr).ok()?;
+                return Some(ArithmeticOverflow { expr, op }.into());
+            }
+            BodyValidationDiagnostic::UnconditionalPanic { expr, kind } => {
+                let expr = source_map.expr_syntax(expr).ok()?;
+                return Some(UnconditionalPanic { expr, kind }.into());
+            }
         }
         None
     }
@@ -810,6 +953,40 @@ impl<'db> AnyDiagnostic<'db> {
                 let expected_kind = GenericArgKind::from_id(param_id);
                 IncorrectGenericsOrder { provided_arg, expected_kind }.into()
             }
+            InferenceDiagnostic::UnsatisfiedTraitBound { id, trait_, args, required_by } => {
+                // Obligations of desugared and macro-generated expressions can't be pointed at
+                // precisely, and are more often than not caused by an incomplete std.
+                let expr_or_pat = match *id {
+                    ExprOrPatId::ExprId(expr) if source_map.is_desugared_expr(expr) => return None,
+                    ExprOrPatId::ExprId(expr) => source_map.expr_syntax(expr).ok()?,
+                    ExprOrPatId::PatId(pat) => source_map.pat_syntax(pat).ok()?,
+                };
+                if expr_or_pat.file_id.is_macro() {
+                    return None;
+                }
+                let trait_ref = |trait_: TraitId, args: &StoredGenericArgs| {
+                    let interner = DbInterner::new_no_crate(db);
+                    let trait_ref = hir_ty::next_solver::TraitRef::new_from_args(
+                        interner,
+                        trait_.into(),
+                        args.as_ref(),
+                    );
+                    TraitRef::new(db, def, trait_ref)
+                };
+                UnsatisfiedTraitBound {
+                    expr_or_pat,
+                    trait_ref: trait_ref(*trait_, args),
+                    required_by: required_by
+                        .iter()
+                        .map(|(trait_, args)| trait_ref(*trait_, args))
+                        .collect(),
+                }
+                .into()
+            }
+            InferenceDiagnostic::ConstEvalFailed { expr, message } => {
+                let expr = expr_syntax(*expr)?;
+                ConstEvalFailed { expr, message: message.clone() }.into()
+            }
         })
     }
 
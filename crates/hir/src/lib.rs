@@ -29,6 +29,7 @@ mod semantics;
 mod source_analyzer;
 
 pub mod db;
+pub mod dead_code;
 pub mod diagnostics;
 pub mod symbols;
 pub mod term_search;
@@ -41,7 +42,7 @@ pub use hir_def::ModuleId;
 use std::{
     fmt,
     mem::discriminant,
-    ops::{ControlFlow, Not},
+    ops::{ControlFlow, Not, RangeInclusive},
 };
 
 use arrayvec::ArrayVec;
@@ -60,6 +61,7 @@ use hir_def::{
         BindingAnnotation, BindingId, Expr, ExprId, ExprOrPatId, LabelId, Pat,
         generics::{LifetimeParamData, TypeOrConstParamData, TypeParamProvenance},
     },
+    item_scope::{ImportOrExternCrate, UseOrImportId},
     item_tree::ImportAlias,
     lang_item::LangItemTarget,
     layout::{self, ReprOptions, TargetDataLayout},
@@ -88,7 +90,7 @@ use hir_ty::{
     method_resolution::{
         self, InherentImpls, MethodResolutionContext, MethodResolutionUnstableFeatures,
     },
-    mir::{MutBorrowKind, interpret_mir},
+    mir::{MutBorrowKind, interpret_mir, interpret_mir_with_debug_hook},
     next_solver::{
         AliasTy, AnyImplId, ClauseKind, ConstKind, DbInterner, EarlyBinder, EarlyParamRegion,
         ErrorGuaranteed, GenericArg, GenericArgs, ParamConst, ParamEnv, PolyFnSig, Region,
@@ -140,7 +142,7 @@ pub use {
     hir_def::{
         Complete,
         FindPathConfig,
-        attrs::{Docs, IsInnerDoc},
+        attrs::{Deprecation, Docs, IsInnerDoc},
         find_path::PrefixKind,
         import_map,
         lang_item::{LangItemEnum as LangItem, crate_lang_items},
@@ -177,7 +179,7 @@ pub use {
         drop::DropGlue,
         dyn_compatibility::{DynCompatibilityViolation, MethodViolationCode},
         layout::LayoutError,
-        mir::{MirEvalError, MirLowerError},
+        mir::{DebugFrame, DebugHook, DebugLocal, MirEvalError, MirLowerError, MirOutput},
         next_solver::abi::Safety,
         next_solver::{clear_tls_solver_cache, collect_ty_garbage},
     },
@@ -279,6 +281,11 @@ impl Crate {
         self.id.data(db).edition
     }
 
+    /// Whether the crate enables `feature` with `#![feature(...)]`.
+    pub fn is_unstable_feature_enabled(self, db: &dyn HirDatabase, feature: &Symbol) -> bool {
+        crate_def_map(db, self.id).is_unstable_feature_enabled(feature)
+    }
+
     pub fn version(self, db: &dyn HirDatabase) -> Option<String> {
         self.id.extra_data(db).version.clone()
     }
@@ -650,6 +657,27 @@ impl Module {
             .collect()
     }
 
+    /// Returns the `#[deprecated]` attribute of the `use` item that brings `name` into the scope
+    /// of this module, if there is one.
+    pub fn import_deprecation<'db>(
+        self,
+        db: &'db dyn HirDatabase,
+        name: &Name,
+    ) -> Option<&'db Deprecation> {
+        let item = self.id.def_map(db)[self.id].scope.get(name);
+        let imports = [
+            item.types.and_then(|it| it.import),
+            item.values.and_then(|it| it.import).map(ImportOrExternCrate::from),
+            item.macros.and_then(|it| it.import),
+        ];
+        imports.into_iter().flatten().find_map(|import| match import.use_()? {
+            UseOrImportId::Use(id) => {
+                AttrFlags::query(db, id.into()).deprecation(db, Either::Left(id.into()))
+            }
+            UseOrImportId::Include(_) | UseOrImportId::Import(_) => None,
+        })
+    }
+
     pub fn resolve_mod_path(
         &self,
         db: &dyn HirDatabase,
@@ -2124,39 +2152,40 @@ impl DefWithBody {
         }
 
         if let Ok(borrowck_results) = db.borrowck(id) {
+            let span_syntax = |span| mir::mir_span_to_syntax(db, id, span);
             for borrowck_result in borrowck_results.iter() {
                 let mir_body = &borrowck_result.mir_body;
                 for moof in &borrowck_result.moved_out_of_ref {
-                    let span: InFile<SyntaxNodePtr> = match moof.span {
-                        mir::MirSpan::ExprId(e) => match source_map.expr_syntax(e) {
-                            Ok(s) => s.map(|it| it.into()),
-                            Err(_) => continue,
-                        },
-                        mir::MirSpan::PatId(p) => match source_map.pat_syntax(p) {
-                            Ok(s) => s.map(|it| it.into()),
-                            Err(_) => continue,
-                        },
-                        mir::MirSpan::SelfParam => match source_map.self_param_syntax() {
-                            Some(s) => s.map(|it| it.into()),
-                            None => continue,
-                        },
-                        mir::MirSpan::BindingId(b) => {
-                            match source_map
-                                .patterns_for_binding(b)
-                                .iter()
-                                .find_map(|p| source_map.pat_syntax(*p).ok())
-                            {
-                                Some(s) => s.map(|it| it.into()),
-                                None => continue,
-                            }
-                        }
-                        mir::MirSpan::Unknown => continue,
-                    };
+                    let Some(span) = span_syntax(moof.span) else { continue };
                     acc.push(
                         MovedOutOfRef { ty: Type::new_for_crate(krate, moof.ty.as_ref()), span }
                             .into(),
                     )
                 }
+                let local_to_binding = mir_body.local_to_binding_map();
+                for moved_use in &borrowck_result.moved_value_uses {
+                    let Some(&binding_id) = local_to_binding.get(moved_use.local) else {
+                        continue;
+                    };
+                    let Some(span) = span_syntax(moved_use.span) else { continue };
+                    let local = Local { parent: id, binding_id };
+                    acc.push(UseOfMovedValue { local, span }.into());
+                }
+                for conflict in &borrowck_result.borrow_conflicts {
+                    let Some(&binding_id) = local_to_binding.get(conflict.local) else {
+                        continue;
+                    };
+                    let Some(span) = span_syntax(conflict.span) else { continue };
+                    acc.push(
+                        ConflictingBorrow {
+                            local: Local { parent: id, binding_id },
+                            span,
+                            is_mut: matches!(conflict.kind, mir::BorrowKind::Mut { .. }),
+                            live_is_mut: matches!(conflict.live_kind, mir::BorrowKind::Mut { .. }),
+                        }
+                        .into(),
+                    );
+                }
                 let mol = &borrowck_result.mutability_of_locals;
                 for (binding_id, binding_data) in body.bindings() {
                     if binding_data.problems.is_some() {
@@ -2193,33 +2222,8 @@ impl DefWithBody {
                         (mir::MutabilityReason::Mut { .. }, true)
                         | (mir::MutabilityReason::Not, false) => (),
                         (mir::MutabilityReason::Mut { spans }, false) => {
-                            for span in spans {
-                                let span: InFile<SyntaxNodePtr> = match span {
-                                    mir::MirSpan::ExprId(e) => match source_map.expr_syntax(*e) {
-                                        Ok(s) => s.map(|it| it.into()),
-                                        Err(_) => continue,
-                                    },
-                                    mir::MirSpan::PatId(p) => match source_map.pat_syntax(*p) {
-                                        Ok(s) => s.map(|it| it.into()),
-                                        Err(_) => continue,
-                                    },
-                                    mir::MirSpan::BindingId(b) => {
-                                        match source_map
-                                            .patterns_for_binding(*b)
-                                            .iter()
-                                            .find_map(|p| source_map.pat_syntax(*p).ok())
-                                        {
-                                            Some(s) => s.map(|it| it.into()),
-                                            None => continue,
-                                        }
-                                    }
-                                    mir::MirSpan::SelfParam => match source_map.self_param_syntax()
-                                    {
-                                        Some(s) => s.map(|it| it.into()),
-                                        None => continue,
-                                    },
-                                    mir::MirSpan::Unknown => continue,
-                                };
+                            for &span in spans {
+                                let Some(span) = span_syntax(span) else { continue };
                                 acc.push(NeedMut { local, span }.into());
                             }
                         }
@@ -2240,6 +2244,33 @@ impl DefWithBody {
             acc.extend(AnyDiagnostic::body_validation_diagnostic(db, diagnostic, &source_map));
         }
 
+        let eval_error = match self {
+            DefWithBody::Const(it) => it.eval(db).err(),
+            DefWithBody::Static(it) => it.eval(db).err(),
+            _ => None,
+        };
+        if let Some(ConstEvalError::MirEvalError(e)) = eval_error
+            && let Some(message) = e.is_panic()
+            && let Ok(expr) = source_map.expr_syntax(body.body_expr)
+        {
+            acc.push(ConstEvalFailed { expr, message: message.to_owned() }.into());
+        }
+
+        if let DefWithBody::Function(Function { id: AnyFunctionId::FunctionId(func) }) = self {
+            let mut call_sites: Vec<_> = mir::unconditional_recursion(db, func)
+                .into_iter()
+                .filter_map(|span| mir::mir_span_to_syntax(db, id, span))
+                .collect();
+            call_sites.sort_by_key(|it| it.value.text_range().start());
+            if !call_sites.is_empty() {
+                let source = func.lookup(db).source(db);
+                acc.push(
+                    UnconditionalRecursion { func: source.map(|it| AstPtr::new(&it)), call_sites }
+                        .into(),
+                );
+            }
+        }
+
         for diag in hir_ty::diagnostics::incorrect_case(db, id.into()) {
             acc.push(diag.into())
         }
@@ -2677,6 +2708,11 @@ impl Function {
         self.attrs(db).contains(AttrFlags::IS_IGNORE)
     }
 
+    /// Does this function have the `should_panic` attribute?
+    pub fn is_should_panic(self, db: &dyn HirDatabase) -> bool {
+        self.attrs(db).contains(AttrFlags::IS_SHOULD_PANIC)
+    }
+
     /// Does this function have `#[bench]` attribute?
     pub fn is_bench(self, db: &dyn HirDatabase) -> bool {
         self.attrs(db).contains(AttrFlags::IS_BENCH)
@@ -2749,24 +2785,38 @@ impl Function {
         db: &dyn HirDatabase,
         span_formatter: impl Fn(FileId, TextRange) -> String,
     ) -> Result<String, ConstEvalError> {
-        let AnyFunctionId::FunctionId(id) = self.id else {
-            return Err(ConstEvalError::MirEvalError(MirEvalError::NotSupported(
-                "evaluation of builtin derive impl methods is not supported".to_owned(),
-            )));
-        };
-        let interner = DbInterner::new_no_crate(db);
-        let body = db.monomorphized_mir_body(
-            id.into(),
-            GenericArgs::empty(interner).store(),
-            ParamEnvAndCrate {
-                param_env: db.trait_environment(id.into()),
-                krate: id.module(db).krate(db),
-            }
-            .store(),
-        )?;
-        let (result, output) = interpret_mir(db, body, false, None)?;
+        self.eval_inner(db, None, span_formatter)
+    }
+
+    /// Like [`Function::eval`], but calls `hook` before every MIR statement and terminator is
+    /// executed, which allows stepping through the function and inspecting its locals.
+    pub fn eval_with_debug_hook<'db>(
+        self,
+        db: &'db dyn HirDatabase,
+        hook: Box<dyn DebugHook<'db> + 'db>,
+        span_formatter: impl Fn(FileId, TextRange) -> String,
+    ) -> Result<String, ConstEvalError> {
+        self.eval_inner(db, Some(hook), span_formatter)
+    }
+
+    /// Evaluates the function with the mir interpreter, returning the evaluation error, if any,
+    /// together with everything the function wrote to stdout and stderr.
+    pub fn eval_with_output(
+        self,
+        db: &dyn HirDatabase,
+    ) -> Result<(Result<(), MirEvalError>, MirOutput), ConstEvalError> {
+        self.interpret(db, None)
+    }
+
+    fn eval_inner<'db>(
+        self,
+        db: &'db dyn HirDatabase,
+        hook: Option<Box<dyn DebugHook<'db> + 'db>>,
+        span_formatter: impl Fn(FileId, TextRange) -> String,
+    ) -> Result<String, ConstEvalError> {
+        let (result, output) = self.interpret(db, hook)?;
         let mut text = match result {
-            Ok(_) => "pass".to_owned(),
+            Ok(()) => "pass".to_owned(),
             Err(e) => {
                 let mut r = String::new();
                 _ = e.pretty_print(
@@ -2790,6 +2840,33 @@ impl Function {
         }
         Ok(text)
     }
+
+    fn interpret<'db>(
+        self,
+        db: &'db dyn HirDatabase,
+        hook: Option<Box<dyn DebugHook<'db> + 'db>>,
+    ) -> Result<(Result<(), MirEvalError>, MirOutput), ConstEvalError> {
+        let AnyFunctionId::FunctionId(id) = self.id else {
+            return Err(ConstEvalError::MirEvalError(MirEvalError::NotSupported(
+                "evaluation of builtin derive impl methods is not supported".to_owned(),
+            )));
+        };
+        let interner = DbInterner::new_no_crate(db);
+        let body = db.monomorphized_mir_body(
+            id.into(),
+            GenericArgs::empty(interner).store(),
+            ParamEnvAndCrate {
+                param_env: db.trait_environment(id.into()),
+                krate: id.module(db).krate(db),
+            }
+            .store(),
+        )?;
+        let (result, output) = match hook {
+            Some(hook) => interpret_mir_with_debug_hook(db, body, hook)?,
+            None => interpret_mir(db, body, false, None)?,
+        };
+        Ok((result.map(drop), output))
+    }
 }
 
 // Note: logically, this belongs to `hir_ty`, but we are not using it there yet.
@@ -4911,6 +4988,15 @@ impl<'db> TraitRef<'db> {
         TraitRef { env, trait_ref }
     }
 
+    pub(crate) fn new(
+        db: &'db dyn HirDatabase,
+        lexical_env: impl HasResolver,
+        trait_ref: hir_ty::next_solver::TraitRef<'db>,
+    ) -> Self {
+        let resolver = lexical_env.resolver(db);
+        TraitRef::new_with_resolver(db, &resolver, trait_ref)
+    }
+
     pub fn trait_(&self) -> Trait {
         Trait { id: self.trait_ref.def_id.0 }
     }
@@ -6537,6 +6623,66 @@ impl Layout {
             };
         Some(tag_size)
     }
+
+    /// The largest niche of the type, if it has any invalid values left.
+    pub fn niche(&self) -> Option<LayoutNiche> {
+        let niche = self.0.largest_niche?;
+        Some(LayoutNiche {
+            offset: niche.offset.bytes(),
+            size: niche.value.size(&*self.1).bytes(),
+            valid_range: (niche.valid_range.start, niche.valid_range.end),
+            available: niche.available(&*self.1),
+        })
+    }
+
+    /// How the discriminant of a multi-variant type is stored.
+    pub fn tag(&self) -> Option<LayoutTag> {
+        let layout::Variants::Multiple { tag, tag_encoding, .. } = &self.0.variants else {
+            return None;
+        };
+        // The only field on enum variants is the tag field
+        let offset = self.0.fields.offset(0).bytes();
+        let size = tag.size(&*self.1).bytes();
+        Some(match tag_encoding {
+            TagEncoding::Direct => LayoutTag::Direct { offset, size },
+            TagEncoding::Niche { untagged_variant, niche_variants, niche_start } => {
+                LayoutTag::Niche {
+                    offset,
+                    size,
+                    untagged_variant: untagged_variant.0,
+                    niche_variants: niche_variants.start().0..=niche_variants.end().0,
+                    niche_start: *niche_start,
+                }
+            }
+        })
+    }
+}
+
+/// A range of invalid values of a scalar inside a type, which enclosing enums can use to store
+/// their discriminant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutNiche {
+    pub offset: u64,
+    pub size: u64,
+    /// The valid values of the scalar, as an inclusive range that may wrap around.
+    pub valid_range: (u128, u128),
+    /// Number of invalid values of the scalar.
+    pub available: u128,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutTag {
+    /// The discriminant is stored as is.
+    Direct { offset: u64, size: u64 },
+    /// The discriminant is stored in the niche of the `untagged_variant`: the variants in
+    /// `niche_variants` are encoded as consecutive values starting with `niche_start`.
+    Niche {
+        offset: u64,
+        size: u64,
+        untagged_variant: usize,
+        niche_variants: RangeInclusive<usize>,
+        niche_start: u128,
+    },
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
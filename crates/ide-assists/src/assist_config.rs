@@ -29,6 +29,8 @@ pub struct AssistConfig {
     pub expr_fill_default: ExprFillDefaultMode,
     pub prefer_self_ty: bool,
     pub show_rename_conflicts: bool,
+    pub generate_builder_with_prefix: bool,
+    pub generate_builder_into_setters: bool,
 }
 
 impl AssistConfig {
@@ -0,0 +1,205 @@
+use ide_db::{
+    change_signature::{
+        NewParam, change_signature as change_fn_signature, check_can_change_signature,
+    },
+    source_change::ChangeAnnotation,
+};
+use itertools::Itertools;
+use stdx::never;
+use syntax::{AstNode, ast};
+
+use crate::{AssistContext, AssistId, Assists, utils::location};
+
+// Assist: change_signature
+//
+// Moves a function parameter one position to the left or to the right, updating all the callers,
+// the trait declaration and the implementations.
+//
+// ```
+// fn frobnicate(x: i32, $0y: bool) {}
+//
+// fn main() {
+//     frobnicate(92, true);
+// }
+// ```
+// ->
+// ```
+// fn frobnicate(y: bool, x: i32) {}
+//
+// fn main() {
+//     frobnicate(true, 92);
+// }
+// ```
+pub(crate) fn change_signature(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let param: ast::Param = ctx.find_node_at_offset()?;
+    let param_list = ast::ParamList::cast(param.syntax().parent()?)?;
+    let func = ast::Fn::cast(param_list.syntax().parent()?)?;
+    let func = ctx.sema.to_def(&func)?;
+    check_can_change_signature(&ctx.sema, func).ok()?;
+    let param_count = param_list.params().count();
+    let index = param_list.params().position(|it| it == param)?;
+
+    let target = param.syntax().text_range();
+    for (label, other) in [
+        ("Move parameter left", index.checked_sub(1)),
+        ("Move parameter right", Some(index + 1).filter(|&it| it < param_count)),
+    ] {
+        let Some(other) = other else { continue };
+        acc.add(AssistId::refactor("change_signature"), label, target, |builder| {
+            let mut order: Vec<_> = (0..param_count).collect();
+            order.swap(index, other);
+            let new_params: Vec<_> =
+                order.into_iter().map(|index| NewParam::Existing { index, ty: None }).collect();
+            let edit = match change_fn_signature(&ctx.sema, func, &new_params) {
+                Ok(it) => it,
+                Err(err) => {
+                    never!("failed to move a parameter: {}", err);
+                    return;
+                }
+            };
+            if !edit.unrewritten.is_empty() {
+                builder.annotate(ChangeAnnotation {
+                    label: "This change leaves uses of the function as they are".to_owned(),
+                    needs_confirmation: true,
+                    description: Some(format!(
+                        "These uses are not updated:\n{}",
+                        edit.unrewritten
+                            .iter()
+                            .map(|site| {
+                                let file_id = ctx.sema.attach_first_edition(site.range.file_id);
+                                let location = location(ctx.db(), file_id, site.range.range);
+                                format!("{location}: {}", site.reason)
+                            })
+                            .format("\n")
+                    )),
+                });
+            }
+            edit.apply(builder);
+        });
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use crate::tests::{
+        check_assist_annotations, check_assist_by_label, check_assist_not_applicable,
+    };
+
+    use super::*;
+
+    #[test]
+    fn move_param_right() {
+        check_assist_by_label(
+            change_signature,
+            r#"
+fn foo($0a: u32, b: bool, c: char) {}
+
+fn main() {
+    foo(1, true, 'c');
+    let f: fn(u32, bool, char) = foo;
+}
+"#,
+            r#"
+fn foo(b: bool, a: u32, c: char) {}
+
+fn main() {
+    foo(true, 1, 'c');
+    let f: fn(u32, bool, char) = |a, b, c| foo(b, a, c);
+}
+"#,
+            "Move parameter right",
+        );
+    }
+
+    #[test]
+    fn move_param_left_in_trait_impl() {
+        check_assist_by_label(
+            change_signature,
+            r#"
+trait Tr {
+    fn f(&self, a: u32, b: bool);
+}
+
+struct S;
+
+impl Tr for S {
+    fn f(&self, a: u32, b$0: bool) {}
+}
+
+fn main() {
+    S.f(1, true);
+    Tr::f(&S, 2, false);
+}
+"#,
+            r#"
+trait Tr {
+    fn f(&self, b: bool, a: u32);
+}
+
+struct S;
+
+impl Tr for S {
+    fn f(&self, b: bool, a: u32) {}
+}
+
+fn main() {
+    S.f(true, 1);
+    Tr::f(&S, false, 2);
+}
+"#,
+            "Move parameter left",
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_single_param_or_closures() {
+        check_assist_not_applicable(change_signature, r#"fn foo(&self, $0a: u32) {}"#);
+        check_assist_not_applicable(
+            change_signature,
+            r#"
+fn main() {
+    let f = |$0a: u32, b: u32| a + b;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn reports_unrewritten_calls() {
+        check_assist_annotations(
+            change_signature,
+            r#"
+fn foo($0a: u32, b: bool) {}
+
+fn main() {
+    foo(1, true);
+    foo(2);
+}
+"#,
+            "Move parameter right",
+            expect![[r#"
+                This change leaves uses of the function as they are
+                These uses are not updated:
+                /main.rs:5:5: the call has the wrong number of arguments
+            "#]],
+        );
+    }
+
+    #[test]
+    fn not_applicable_outside_the_workspace() {
+        check_assist_not_applicable(
+            change_signature,
+            r#"
+//- /main.rs crate:main deps:dep
+fn main() {
+    dep::foo(1, true);
+}
+//- /dep.rs crate:dep library
+pub fn foo($0a: u32, b: bool) {}
+"#,
+        );
+    }
+}
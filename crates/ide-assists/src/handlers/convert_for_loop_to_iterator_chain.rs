@@ -0,0 +1,1250 @@
+use hir::{Access, HirDisplay, Local, Semantics};
+use ide_db::{
+    RootDatabase,
+    defs::{Definition, NameRefClass},
+    famous_defs::FamousDefs,
+};
+use itertools::Itertools;
+use syntax::{
+    AstNode, Direction, SyntaxKind, SyntaxNode, TextRange,
+    ast::{self, HasArgList, HasLoopBody, HasName, edit::IndentLevel},
+};
+
+use crate::{AssistContext, AssistId, Assists, utils::invert_boolean_expression_legacy};
+
+use super::convert_iter_for_each_to_for::iterator_receiver;
+
+// Assist: convert_for_loop_to_iterator_chain
+//
+// Converts a for loop that accumulates into a local or that searches for an
+// element into a chain of iterator adapters.
+//
+// ```
+// # //- minicore: iterator, copy
+// fn total(prices: [u32; 3]) -> u32 {
+//     let mut total = 0;
+//     for$0 price in prices {
+//         if price > 10 {
+//             total += price;
+//         }
+//     }
+//     total
+// }
+// ```
+// ->
+// ```
+// fn total(prices: [u32; 3]) -> u32 {
+//     let total: u32 = prices.into_iter().filter(|&price| price > 10).sum();
+//     total
+// }
+// ```
+pub(crate) fn convert_for_loop_to_iterator_chain(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let for_loop = ctx.find_node_at_offset::<ast::ForExpr>()?;
+    let body = for_loop.loop_body()?;
+    if body.syntax().text_range().start() < ctx.offset() || for_loop.label().is_some() {
+        return None;
+    }
+    let (filters, leaf) = split_filters(&body)?;
+    if !filters.iter().all(has_no_jumps) {
+        return None;
+    }
+    let parts = LoopParts {
+        ctx,
+        pat: for_loop.pat()?,
+        iterable: for_loop.iterable()?,
+        filters,
+        // The statement the loop is in, if it's not the tail of its block.
+        node: for_loop
+            .syntax()
+            .parent()
+            .filter(|it| it.kind() == SyntaxKind::EXPR_STMT)
+            .unwrap_or_else(|| for_loop.syntax().clone()),
+    };
+
+    let (range, replacement) = match leaf.as_slice() {
+        [ast::Expr::MethodCallExpr(call)] => parts.collect(call)?,
+        [ast::Expr::BinExpr(assignment)] => parts.fold(assignment)?,
+        [ast::Expr::ReturnExpr(ret)] => parts.return_search(ret)?,
+        [ast::Expr::BreakExpr(brk)] => parts.break_search(brk)?,
+        [ast::Expr::BinExpr(assignment), ast::Expr::BreakExpr(brk)] => {
+            parts.flag_search(assignment, brk)?
+        }
+        _ => return None,
+    };
+
+    acc.add(
+        AssistId::refactor_rewrite("convert_for_loop_to_iterator_chain"),
+        "Replace this for loop with an iterator chain",
+        for_loop.syntax().text_range(),
+        |builder| builder.replace(range, replacement),
+    )
+}
+
+// Assist: convert_iterator_chain_to_for_loop
+//
+// Unrolls a chain of iterator adapters assigned to a local into a for loop.
+//
+// ```
+// # //- minicore: iterator, copy
+// fn total(prices: [u32; 3]) -> u32 {
+//     let total: u32 = prices.into_iter().filter(|&price| price > 10).$0sum();
+//     total
+// }
+// ```
+// ->
+// ```
+// fn total(prices: [u32; 3]) -> u32 {
+//     let mut total: u32 = 0;
+//     for price in prices {
+//         if price > 10 {
+//             total += price;
+//         }
+//     }
+//     total
+// }
+// ```
+pub(crate) fn convert_iterator_chain_to_for_loop(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let call = ctx.find_node_at_offset::<ast::MethodCallExpr>()?;
+    let name_ref = call.name_ref()?;
+    if !name_ref.syntax().text_range().contains_range(ctx.selection_trimmed()) {
+        return None;
+    }
+    let let_stmt = call.syntax().parent().and_then(ast::LetStmt::cast)?;
+    if let_stmt.let_else().is_some() {
+        return None;
+    }
+    let ast::Pat::IdentPat(binding) = let_stmt.pat()? else { return None };
+    if binding.ref_token().is_some() || binding.pat().is_some() {
+        return None;
+    }
+    let name = binding.name()?.to_string();
+    // `let total = total.iter().sum();` would refer to the new local once unrolled.
+    if call.syntax().descendants().filter_map(ast::NameRef::cast).any(|it| it.text() == name) {
+        return None;
+    }
+
+    let terminal = Terminal::new(&call)?;
+    let position = matches!(terminal, Terminal::Search(Search::Position, ..));
+    let mut adapters = Vec::new();
+    let mut base = call.receiver()?;
+    while let ast::Expr::MethodCallExpr(call) = &base {
+        let adapter = match call.name_ref()?.text().as_str() {
+            "filter" => Adapter::Filter,
+            "map" => Adapter::Map,
+            _ => break,
+        };
+        let (param, body) = closure_parts(call)?;
+        adapters.push((adapter, param, body));
+        base = call.receiver()?;
+    }
+    adapters.reverse();
+
+    let db = ctx.db();
+    let ty = ctx.sema.type_of_expr(&base)?.adjusted();
+    let krate = ctx.sema.scope(base.syntax())?.krate();
+    if !ty.impls_trait(db, FamousDefs(&ctx.sema, krate).core_iter_Iterator()?, &[]) {
+        return None;
+    }
+    let iterable = match &base {
+        ast::Expr::MethodCallExpr(call)
+            if call.name_ref()?.text() == "into_iter"
+                && call.arg_list()?.args().next().is_none() =>
+        {
+            call.receiver()?
+        }
+        _ => base.clone(),
+    };
+
+    let local_ty = ctx.sema.type_of_pat(&binding.clone().into())?.original;
+    let mut unrolled =
+        Unrolled { sema: &ctx.sema, loop_pat: None, value: None, lines: Vec::new(), depth: 0 };
+    for (adapter, param, body) in adapters {
+        match adapter {
+            Adapter::Filter => {
+                unrolled.bind(&param, Some(&body))?;
+                unrolled.push(format!("if {body} {{"));
+                unrolled.depth += 1;
+            }
+            Adapter::Map => {
+                unrolled.bind(&param, None)?;
+                unrolled.value = Some(Value::Expr(body.to_string()));
+            }
+        }
+    }
+
+    let annotation = let_stmt.ty().map(|it| it.to_string());
+    let init = match terminal {
+        Terminal::Collect => {
+            let adt = local_ty.as_adt()?;
+            let push = match adt.name(db).as_str() {
+                "Vec" => "push",
+                "VecDeque" => "push_back",
+                "HashSet" | "BTreeSet" => "insert",
+                _ => return None,
+            };
+            let value = unrolled.value()?;
+            unrolled.push(format!("{name}.{push}({value});"));
+            let ty = match annotation {
+                Some(it) => it,
+                None => display_type(&ctx.sema, &local_ty, let_stmt.syntax())?,
+            };
+            format!(": {ty} = {}::new()", adt.name(db).display(db, krate.edition(db)))
+        }
+        Terminal::Sum | Terminal::Product | Terminal::Count => {
+            if !local_ty.is_int_or_uint() && !local_ty.is_float() {
+                return None;
+            }
+            let (op, neutral, value) = match terminal {
+                Terminal::Sum => ("+=", 0, unrolled.value()?),
+                Terminal::Product => ("*=", 1, unrolled.value()?),
+                _ => ("+=", 0, "1".to_owned()),
+            };
+            unrolled.push(format!("{name} {op} {value};"));
+            let neutral =
+                if local_ty.is_float() { format!("{neutral}.0") } else { neutral.to_string() };
+            format!(": {} = {neutral}", display_type(&ctx.sema, &local_ty, let_stmt.syntax())?)
+        }
+        Terminal::Search(search, param, cond) => {
+            let (cond, found, init) = match search {
+                Search::Any => {
+                    unrolled.bind(&param, None)?;
+                    (cond.to_string(), "true".to_owned(), "false")
+                }
+                Search::All => {
+                    unrolled.bind(&param, None)?;
+                    let cond = invert_boolean_expression_legacy(cond).to_string();
+                    (cond, "false".to_owned(), "true")
+                }
+                Search::Find => {
+                    unrolled.bind(&param, Some(&cond))?;
+                    (cond.to_string(), format!("Some({})", unrolled.value()?), "None")
+                }
+                Search::Position => {
+                    // The index has to count the elements before any of the adapters.
+                    if unrolled.loop_pat.is_some() {
+                        return None;
+                    }
+                    let index = ["i", "index", "idx"].into_iter().find(|index| {
+                        *index != name
+                            && !param.syntax().descendants().chain(cond.syntax().descendants()).any(
+                                |it| {
+                                    matches!(it.kind(), SyntaxKind::NAME | SyntaxKind::NAME_REF)
+                                        && it.text() == *index
+                                },
+                            )
+                    })?;
+                    unrolled.loop_pat = Some(format!("({index}, {param})"));
+                    (cond.to_string(), format!("Some({index})"), "None")
+                }
+            };
+            unrolled.push(format!("if {cond} {{"));
+            unrolled.push(format!("    {name} = {found};"));
+            unrolled.push("    break;".to_owned());
+            unrolled.push("}".to_owned());
+            let annotation = annotation.map(|it| format!(": {it}")).unwrap_or_default();
+            format!("{annotation} = {init}")
+        }
+    };
+    while unrolled.depth > 0 {
+        unrolled.depth -= 1;
+        unrolled.push("}".to_owned());
+    }
+
+    let iterable = if position { format!("{base}.enumerate()") } else { iterable.to_string() };
+    let indent = IndentLevel::from_node(let_stmt.syntax());
+    // Only `count` doesn't look at the elements.
+    let loop_pat = unrolled.loop_pat.unwrap_or_else(|| "_".to_owned());
+    let body = unrolled.lines.iter().map(|line| format!("\n{}    {line}", indent)).join("");
+    let replacement =
+        format!("let mut {name}{init};\n{indent}for {loop_pat} in {iterable} {{{body}\n{indent}}}");
+
+    acc.add(
+        AssistId::refactor_rewrite("convert_iterator_chain_to_for_loop"),
+        "Replace this iterator chain with a for loop",
+        let_stmt.syntax().text_range(),
+        |builder| builder.replace(let_stmt.syntax().text_range(), replacement),
+    )
+}
+
+struct LoopParts<'a, 'db> {
+    ctx: &'a AssistContext<'db>,
+    pat: ast::Pat,
+    iterable: ast::Expr,
+    filters: Vec<ast::Expr>,
+    node: SyntaxNode,
+}
+
+impl<'db> LoopParts<'_, 'db> {
+    /// `let mut v = Vec::new(); for x in it { v.push(x) }`
+    fn collect(&self, call: &ast::MethodCallExpr) -> Option<(TextRange, String)> {
+        if !matches!(call.name_ref()?.text().as_str(), "push" | "push_back" | "insert") {
+            return None;
+        }
+        let value = call.arg_list()?.args().exactly_one().ok()?;
+        let acc = self.accumulator(&call.receiver()?)?;
+        let is_empty_collection = match &acc.init {
+            ast::Expr::CallExpr(call) => {
+                let ast::Expr::PathExpr(callee) = call.expr()? else { return None };
+                let name = callee.path()?.segment()?.name_ref()?;
+                call.arg_list()?.args().next().is_none()
+                    && matches!(name.text().as_str(), "new" | "default")
+            }
+            ast::Expr::MacroExpr(mac) => {
+                let mac = mac.macro_call()?;
+                mac.path()?.segment()?.name_ref()?.text() == "vec"
+                    && mac.token_tree()?.token_trees_and_tokens().count() == 2
+            }
+            _ => false,
+        };
+        if !is_empty_collection || !has_no_jumps(&value) {
+            return None;
+        }
+
+        let sema = &self.ctx.sema;
+        let value_ty = sema.type_of_expr(&value)?.original;
+        let krate = sema.scope(&self.node)?.krate();
+        let from_iter = FamousDefs(sema, krate).core_iter_FromIterator()?;
+        if !acc.ty.impls_trait(sema.db, from_iter, &[value_ty]) {
+            return None;
+        }
+        let chain = format!("{}{}.collect()", self.filtered()?, self.map(&value));
+        self.replace_accumulator(&acc, true, chain)
+    }
+
+    /// `let mut total = 0; for x in it { total += x }`
+    fn fold(&self, assignment: &ast::BinExpr) -> Option<(TextRange, String)> {
+        let ast::BinaryOp::Assignment { op: Some(op) } = assignment.op_kind()? else {
+            return None;
+        };
+        let neutral = match op {
+            ast::ArithOp::Add => 0.0,
+            ast::ArithOp::Mul => 1.0,
+            _ => return None,
+        };
+        let value = assignment.rhs()?;
+        let acc = self.accumulator(&assignment.lhs()?)?;
+        if !acc.ty.is_int_or_uint() && !acc.ty.is_float() {
+            return None;
+        }
+        if number_literal(&acc.init)? != neutral || !has_no_jumps(&value) {
+            return None;
+        }
+        let value_ty = self.ctx.sema.type_of_expr(&value)?.original;
+        if value_ty.strip_references() != acc.ty {
+            return None;
+        }
+
+        let filtered = self.filtered()?;
+        let chain = match op {
+            ast::ArithOp::Add if acc.ty.is_usize() && number_literal(&value) == Some(1.0) => {
+                format!("{filtered}.count()")
+            }
+            ast::ArithOp::Add => format!("{filtered}{}.sum()", self.map(&value)),
+            _ => format!("{filtered}{}.product()", self.map(&value)),
+        };
+        self.replace_accumulator(&acc, true, chain)
+    }
+
+    /// `let mut found = false; for x in it { if p(x) { found = true; break; } }`
+    fn flag_search(
+        &self,
+        assignment: &ast::BinExpr,
+        brk: &ast::BreakExpr,
+    ) -> Option<(TextRange, String)> {
+        if assignment.op_kind()? != (ast::BinaryOp::Assignment { op: None })
+            || brk.expr().is_some()
+            || brk.lifetime().is_some()
+        {
+            return None;
+        }
+        let acc = self.accumulator(&assignment.lhs()?)?;
+        let chain = self.search(&assignment.rhs()?, &acc.init)?;
+        self.replace_accumulator(&acc, false, chain)
+    }
+
+    /// `for x in it { if p(x) { return true; } } false` in the body of a function.
+    fn return_search(&self, ret: &ast::ReturnExpr) -> Option<(TextRange, String)> {
+        let stmt_list = self.node.parent().and_then(ast::StmtList::cast)?;
+        let tail = stmt_list.tail_expr()?;
+        let block = stmt_list.syntax().parent().and_then(ast::BlockExpr::cast)?;
+        if self.node.next_sibling().as_ref() != Some(tail.syntax())
+            || block.syntax().parent().and_then(ast::Fn::cast).is_none()
+        {
+            return None;
+        }
+        let chain = self.search(&ret.expr()?, &tail)?;
+        Some((
+            TextRange::new(self.node.text_range().start(), tail.syntax().text_range().end()),
+            chain,
+        ))
+    }
+
+    /// `'search: { for x in it { if p(x) { break 'search Some(x); } } None }`
+    fn break_search(&self, brk: &ast::BreakExpr) -> Option<(TextRange, String)> {
+        let stmt_list = self.node.parent().and_then(ast::StmtList::cast)?;
+        let tail = stmt_list.tail_expr()?;
+        let block = stmt_list.syntax().parent().and_then(ast::BlockExpr::cast)?;
+        let label = block.label()?.lifetime()?;
+        if brk.lifetime()?.text() != label.text()
+            || stmt_list.statements().exactly_one().ok()?.syntax() != &self.node
+        {
+            return None;
+        }
+        let chain = self.search(&brk.expr()?, &tail)?;
+        Some((block.syntax().text_range(), chain))
+    }
+
+    /// The iterator chain for a loop that stops at the first element matching the
+    /// filters, with `found` being the result in that case and `default` the one
+    /// when there's no such element.
+    fn search(&self, found: &ast::Expr, default: &ast::Expr) -> Option<String> {
+        if self.filters.is_empty() {
+            return None;
+        }
+        let cond = self.filters.iter().map(|it| match it {
+            ast::Expr::BinExpr(bin)
+                if bin.op_kind() == Some(ast::BinaryOp::LogicOp(ast::LogicOp::Or))
+                    && self.filters.len() > 1 =>
+            {
+                format!("({it})")
+            }
+            _ => it.to_string(),
+        });
+        let cond = cond.format(" && ").to_string();
+        let receiver = || iterator_receiver(self.ctx, &self.iterable);
+
+        match (bool_literal(found), bool_literal(default)) {
+            (Some(true), Some(false)) => {
+                return Some(format!("{}.any(|{}| {cond})", receiver(), self.pat));
+            }
+            (Some(false), Some(true)) => {
+                let cond = match self.filters.as_slice() {
+                    [filter] => invert_boolean_expression_legacy(filter.clone()).to_string(),
+                    _ => format!("!({cond})"),
+                };
+                return Some(format!("{}.all(|{}| {cond})", receiver(), self.pat));
+            }
+            _ => (),
+        }
+
+        let ast::Expr::PathExpr(none) = default else { return None };
+        let ast::Expr::CallExpr(some) = found else { return None };
+        let ast::Expr::PathExpr(callee) = some.expr()? else { return None };
+        let ast::Expr::PathExpr(value) = some.arg_list()?.args().exactly_one().ok()? else {
+            return None;
+        };
+        if none.path()?.as_single_name_ref()?.text() != "None"
+            || callee.path()?.as_single_name_ref()?.text() != "Some"
+        {
+            return None;
+        }
+        let value = value.path()?.as_single_name_ref()?;
+
+        match &self.pat {
+            ast::Pat::IdentPat(pat) if pat.name()?.text() == value.text() => {
+                Some(format!("{}.find(|{}| {cond})", receiver(), self.by_ref_param(&self.pat)?))
+            }
+            // `for (i, x) in it.enumerate() { .. Some(i) .. }`
+            ast::Pat::TuplePat(pat) => {
+                let (index, element) = pat.fields().collect_tuple()?;
+                let ast::Pat::IdentPat(index) = index else { return None };
+                let ast::Expr::MethodCallExpr(enumerate) = &self.iterable else { return None };
+                if index.name()?.text() != value.text()
+                    || enumerate.name_ref()?.text() != "enumerate"
+                    || enumerate.arg_list()?.args().next().is_some()
+                {
+                    return None;
+                }
+                let index = self.ctx.sema.to_def(&index)?;
+                if self
+                    .filters
+                    .iter()
+                    .any(|it| local_refs(&self.ctx.sema, index, it.syntax()).next().is_some())
+                {
+                    return None;
+                }
+                Some(format!("{}.position(|{element}| {cond})", enumerate.receiver()?))
+            }
+            _ => None,
+        }
+    }
+
+    /// The local declared right before the loop which `expr` refers to.
+    fn accumulator(&self, expr: &ast::Expr) -> Option<Accumulator<'db>> {
+        let ast::Expr::PathExpr(path) = expr else { return None };
+        let stmt = self.node.prev_sibling().and_then(ast::LetStmt::cast)?;
+        let ast::Pat::IdentPat(pat) = stmt.pat()? else { return None };
+        let init = stmt.initializer()?;
+        if stmt.let_else().is_some() || pat.ref_token().is_some() || pat.pat().is_some() {
+            return None;
+        }
+        let sema = &self.ctx.sema;
+        let local = sema.to_def(&pat)?;
+        match sema.resolve_path(&path.path()?)? {
+            hir::PathResolution::Local(it) if it == local => (),
+            _ => return None,
+        }
+        // The loop must not read the local anywhere else.
+        if local_refs(sema, local, &self.node).count() != 1 {
+            return None;
+        }
+        let ty = sema.type_of_pat(&pat.clone().into())?.original;
+        Some(Accumulator { stmt, pat, init, local, ty })
+    }
+
+    /// Replaces the declaration of `acc` and the loop with `let acc = chain;`.
+    fn replace_accumulator(
+        &self,
+        acc: &Accumulator<'db>,
+        with_type: bool,
+        chain: String,
+    ) -> Option<(TextRange, String)> {
+        let sema = &self.ctx.sema;
+        let later_uses = self
+            .node
+            .siblings(Direction::Next)
+            .skip(1)
+            .flat_map(|it| local_refs(sema, acc.local, &it).collect_vec());
+        let mutated = later_uses.into_iter().any(|name_ref| is_mutable_use(sema, &name_ref));
+        let mut_ = if mutated { "mut " } else { "" };
+        let name = acc.pat.name()?;
+        let ty = match (acc.stmt.ty(), with_type) {
+            (Some(ty), _) => format!(": {ty}"),
+            (None, true) => format!(": {}", display_type(sema, &acc.ty, acc.stmt.syntax())?),
+            (None, false) => String::new(),
+        };
+        let range =
+            TextRange::new(acc.stmt.syntax().text_range().start(), self.node.text_range().end());
+        Some((range, format!("let {mut_}{name}{ty} = {chain};")))
+    }
+
+    /// The iterator with the filters of the loop applied.
+    fn filtered(&self) -> Option<String> {
+        let mut chain = iterator_receiver(self.ctx, &self.iterable);
+        if !self.filters.is_empty() {
+            let param = self.by_ref_param(&self.pat)?;
+            for filter in &self.filters {
+                chain.push_str(&format!(".filter(|{param}| {filter})"));
+            }
+        }
+        Some(chain)
+    }
+
+    /// The `map` turning the elements into `value`, if they aren't `value` already.
+    fn map(&self, value: &ast::Expr) -> String {
+        let is_element = match (&self.pat, value) {
+            (ast::Pat::IdentPat(pat), ast::Expr::PathExpr(path)) => {
+                path.path().and_then(|it| it.as_single_name_ref()).map(|it| it.to_string())
+                    == pat.name().map(|it| it.to_string())
+            }
+            _ => false,
+        };
+        if is_element { String::new() } else { format!(".map(|{}| {value})", self.pat) }
+    }
+
+    /// The parameter of a closure taking the elements by reference, which binds
+    /// the same names as `pat` does in the loop.
+    fn by_ref_param(&self, pat: &ast::Pat) -> Option<String> {
+        let has_binding_mode = pat
+            .syntax()
+            .descendants()
+            .filter_map(ast::IdentPat::cast)
+            .any(|it| it.mut_token().is_some() || it.ref_token().is_some());
+        if has_binding_mode {
+            return None;
+        }
+        let ty = self.ctx.sema.type_of_pat(pat)?.original;
+        if ty.is_copy(self.ctx.db()) {
+            return Some(format!("&{pat}"));
+        }
+        let ast::Pat::IdentPat(binding) = pat else { return None };
+        let local = self.ctx.sema.to_def(binding)?;
+        self.filters
+            .iter()
+            .all(|filter| {
+                local_refs(&self.ctx.sema, local, filter.syntax()).all(|it| is_auto_deref_use(&it))
+            })
+            .then(|| pat.to_string())
+    }
+}
+
+struct Accumulator<'db> {
+    stmt: ast::LetStmt,
+    pat: ast::IdentPat,
+    init: ast::Expr,
+    local: Local,
+    ty: hir::Type<'db>,
+}
+
+#[derive(Clone, Copy)]
+enum Adapter {
+    Filter,
+    Map,
+}
+
+#[derive(Clone, Copy)]
+enum Search {
+    Any,
+    All,
+    Find,
+    Position,
+}
+
+/// The method consuming an iterator chain.
+enum Terminal {
+    Collect,
+    Sum,
+    Product,
+    Count,
+    Search(Search, ast::Pat, ast::Expr),
+}
+
+impl Terminal {
+    fn new(call: &ast::MethodCallExpr) -> Option<Terminal> {
+        let no_args = call.arg_list()?.args().next().is_none();
+        let search = match call.name_ref()?.text().as_str() {
+            "collect" if no_args => return Some(Terminal::Collect),
+            "sum" if no_args => return Some(Terminal::Sum),
+            "product" if no_args => return Some(Terminal::Product),
+            "count" if no_args => return Some(Terminal::Count),
+            "any" => Search::Any,
+            "all" => Search::All,
+            "find" => Search::Find,
+            "position" => Search::Position,
+            _ => return None,
+        };
+        let (param, body) = closure_parts(call)?;
+        Some(Terminal::Search(search, param, body))
+    }
+}
+
+/// The element flowing through an unrolled iterator chain.
+enum Value {
+    /// The element is bound to a local.
+    Binding(String),
+    /// The element is the result of a `map`.
+    Expr(String),
+    /// The element was destructured by a pattern.
+    Destructured,
+}
+
+/// The body of a for loop an iterator chain is unrolled into.
+struct Unrolled<'a, 'db> {
+    sema: &'a Semantics<'db, RootDatabase>,
+    loop_pat: Option<String>,
+    /// `None` until the loop pattern is known.
+    value: Option<Value>,
+    lines: Vec<String>,
+    depth: usize,
+}
+
+impl Unrolled<'_, '_> {
+    fn push(&mut self, line: String) {
+        self.lines.push(format!("{}{line}", "    ".repeat(self.depth)));
+    }
+
+    /// The expression of the current element.
+    fn value(&mut self) -> Option<String> {
+        let value = self.value.get_or_insert_with(|| {
+            self.loop_pat = Some("item".to_owned());
+            Value::Binding("item".to_owned())
+        });
+        match value {
+            Value::Binding(it) | Value::Expr(it) => Some(it.clone()),
+            Value::Destructured => None,
+        }
+    }
+
+    /// Binds the current element to the parameter of a closure. `by_ref_body` is
+    /// the body of the closure if it takes the elements by reference.
+    fn bind(&mut self, param: &ast::Pat, by_ref_body: Option<&ast::Expr>) -> Option<()> {
+        let pat = match (param, by_ref_body) {
+            (ast::Pat::RefPat(pat), Some(_)) => match pat.pat()? {
+                ast::Pat::IdentPat(it) if it.mut_token().is_none() && it.ref_token().is_none() => {
+                    it
+                }
+                _ => return None,
+            },
+            (ast::Pat::IdentPat(pat), Some(body)) => {
+                let local = self.sema.to_def(pat)?;
+                if pat.mut_token().is_some()
+                    || pat.ref_token().is_some()
+                    || !local_refs(self.sema, local, body.syntax()).all(|it| is_auto_deref_use(&it))
+                {
+                    return None;
+                }
+                pat.clone()
+            }
+            (_, Some(_)) => return None,
+            (ast::Pat::IdentPat(pat), None) if pat.pat().is_none() => pat.clone(),
+            (pat, None) => {
+                self.bind_pat(pat.to_string())?;
+                self.value = Some(Value::Destructured);
+                return Some(());
+            }
+        };
+        self.bind_pat(pat.to_string())?;
+        self.value = Some(Value::Binding(pat.name()?.to_string()));
+        Some(())
+    }
+
+    fn bind_pat(&mut self, pat: String) -> Option<()> {
+        match &self.value {
+            None => self.loop_pat = Some(pat),
+            Some(Value::Binding(it)) if *it == pat => (),
+            Some(Value::Binding(it) | Value::Expr(it)) => {
+                let line = format!("let {pat} = {it};");
+                self.push(line);
+            }
+            Some(Value::Destructured) => return None,
+        }
+        Some(())
+    }
+}
+
+/// The pattern and the body of the closure `call` takes.
+fn closure_parts(call: &ast::MethodCallExpr) -> Option<(ast::Pat, ast::Expr)> {
+    let ast::Expr::ClosureExpr(closure) = call.arg_list()?.args().exactly_one().ok()? else {
+        return None;
+    };
+    let param = closure.param_list()?.params().exactly_one().ok()?;
+    let body = closure.body()?;
+    has_no_jumps(&body).then_some((param.pat()?, body))
+}
+
+/// Splits the body of a loop into the conditions of the `if`s it's nested in and
+/// the expressions of the innermost block.
+fn split_filters(body: &ast::BlockExpr) -> Option<(Vec<ast::Expr>, Vec<ast::Expr>)> {
+    let mut filters = Vec::new();
+    let mut exprs = block_exprs(body)?;
+    while let [ast::Expr::IfExpr(if_expr)] = exprs.as_slice() {
+        let if_expr = if_expr.clone();
+        let cond = if_expr.condition()?;
+        if if_expr.else_branch().is_some()
+            || cond.syntax().descendants().any(|it| it.kind() == SyntaxKind::LET_EXPR)
+        {
+            return None;
+        }
+        exprs = block_exprs(&if_expr.then_branch()?)?;
+        filters.push(cond);
+    }
+    Some((filters, exprs))
+}
+
+fn block_exprs(block: &ast::BlockExpr) -> Option<Vec<ast::Expr>> {
+    let stmt_list = block.stmt_list()?;
+    stmt_list
+        .statements()
+        .map(|stmt| match stmt {
+            ast::Stmt::ExprStmt(it) => it.expr(),
+            _ => None,
+        })
+        .chain(stmt_list.tail_expr().map(Some))
+        .collect()
+}
+
+/// Whether `expr` keeps doing the same when moved into or out of a closure.
+fn has_no_jumps(expr: &ast::Expr) -> bool {
+    !expr.syntax().descendants().any(|it| {
+        matches!(
+            it.kind(),
+            SyntaxKind::RETURN_EXPR
+                | SyntaxKind::BREAK_EXPR
+                | SyntaxKind::CONTINUE_EXPR
+                | SyntaxKind::TRY_EXPR
+                | SyntaxKind::AWAIT_EXPR
+                | SyntaxKind::YIELD_EXPR
+                | SyntaxKind::BECOME_EXPR
+        )
+    })
+}
+
+fn number_literal(expr: &ast::Expr) -> Option<f64> {
+    let ast::Expr::Literal(lit) = expr else { return None };
+    match lit.kind() {
+        ast::LiteralKind::IntNumber(it) => Some(it.value().ok()? as f64),
+        ast::LiteralKind::FloatNumber(it) => it.value_string().parse().ok(),
+        _ => None,
+    }
+}
+
+fn bool_literal(expr: &ast::Expr) -> Option<bool> {
+    let ast::Expr::Literal(lit) = expr else { return None };
+    match lit.kind() {
+        ast::LiteralKind::Bool(it) => Some(it),
+        _ => None,
+    }
+}
+
+fn display_type(
+    sema: &Semantics<'_, RootDatabase>,
+    ty: &hir::Type<'_>,
+    node: &SyntaxNode,
+) -> Option<String> {
+    let module = sema.scope(node)?.module();
+    ty.display_source_code(sema.db, module.into(), true).ok()
+}
+
+/// The references to `local` in `node`.
+fn local_refs<'a>(
+    sema: &'a Semantics<'_, RootDatabase>,
+    local: Local,
+    node: &SyntaxNode,
+) -> impl Iterator<Item = ast::NameRef> + 'a {
+    node.descendants().filter_map(ast::NameRef::cast).filter(move |name_ref| {
+        match NameRefClass::classify(sema, name_ref) {
+            Some(NameRefClass::Definition(Definition::Local(it), _)) => it == local,
+            Some(NameRefClass::FieldShorthand { local_ref, .. }) => local_ref == local,
+            _ => false,
+        }
+    })
+}
+
+/// Whether the local `name_ref` refers to is used in a way that works for a
+/// reference to it as well, thanks to auto-deref.
+fn is_auto_deref_use(name_ref: &ast::NameRef) -> bool {
+    let Some(expr) = name_ref.syntax().ancestors().find_map(ast::PathExpr::cast) else {
+        return false;
+    };
+    let expr = ast::Expr::from(expr);
+    match expr.syntax().parent().and_then(ast::Expr::cast) {
+        Some(ast::Expr::MethodCallExpr(call)) => call.receiver() == Some(expr),
+        Some(ast::Expr::FieldExpr(field)) => field.expr() == Some(expr),
+        _ => false,
+    }
+}
+
+/// Whether the local `name_ref` refers to has to be mutable for this use.
+fn is_mutable_use(sema: &Semantics<'_, RootDatabase>, name_ref: &ast::NameRef) -> bool {
+    let Some(expr) = name_ref.syntax().ancestors().find_map(ast::PathExpr::cast) else {
+        return true;
+    };
+    let expr = ast::Expr::from(expr);
+    match expr.syntax().parent().and_then(ast::Expr::cast) {
+        Some(ast::Expr::MethodCallExpr(call)) if call.receiver().as_ref() == Some(&expr) => sema
+            .resolve_method_call(&call)
+            .and_then(|it| it.self_param(sema.db))
+            .is_none_or(|it| it.access(sema.db) == Access::Exclusive),
+        Some(ast::Expr::RefExpr(it)) => it.mut_token().is_some(),
+        Some(ast::Expr::BinExpr(it)) => {
+            matches!(it.op_kind(), Some(ast::BinaryOp::Assignment { .. }))
+                && it.lhs().as_ref() == Some(&expr)
+        }
+        Some(ast::Expr::FieldExpr(_) | ast::Expr::IndexExpr(_)) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn collect_with_filter_and_map() {
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            r#"
+//- minicore: iterator, copy
+struct Vec<T>(T);
+impl<T> Vec<T> {
+    fn new() -> Self {
+        loop {}
+    }
+    fn push(&mut self, _value: T) {}
+}
+impl<T> core::iter::FromIterator<T> for Vec<T> {
+    fn from_iter<I: core::iter::IntoIterator<Item = T>>(_iter: I) -> Self {
+        loop {}
+    }
+}
+fn doubled_evens(xs: [u32; 4]) -> Vec<u32> {
+    let mut evens = Vec::new();
+    for$0 x in xs {
+        if x % 2 == 0 {
+            evens.push(x * 2);
+        }
+    }
+    evens
+}
+"#,
+            r#"
+struct Vec<T>(T);
+impl<T> Vec<T> {
+    fn new() -> Self {
+        loop {}
+    }
+    fn push(&mut self, _value: T) {}
+}
+impl<T> core::iter::FromIterator<T> for Vec<T> {
+    fn from_iter<I: core::iter::IntoIterator<Item = T>>(_iter: I) -> Self {
+        loop {}
+    }
+}
+fn doubled_evens(xs: [u32; 4]) -> Vec<u32> {
+    let evens: Vec<u32> = xs.into_iter().filter(|&x| x % 2 == 0).map(|x| x * 2).collect();
+    evens
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn count() {
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            r#"
+//- minicore: iterator, copy
+fn count_big(xs: [u32; 3]) -> usize {
+    let mut n = 0;
+    for$0 x in xs {
+        if x > 1 {
+            n += 1;
+        }
+    }
+    n
+}
+"#,
+            r#"
+fn count_big(xs: [u32; 3]) -> usize {
+    let n: usize = xs.into_iter().filter(|&x| x > 1).count();
+    n
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn keep_mut_when_mutated_later() {
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            r#"
+//- minicore: iterator, copy
+fn total(xs: [u32; 3]) -> u32 {
+    let mut total = 0;
+    for$0 x in xs {
+        total += x;
+    }
+    total += 1;
+    total
+}
+"#,
+            r#"
+fn total(xs: [u32; 3]) -> u32 {
+    let mut total: u32 = xs.into_iter().sum();
+    total += 1;
+    total
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn any_with_return() {
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            r#"
+//- minicore: iterator, copy
+fn has_even(xs: [u32; 3]) -> bool {
+    for$0 x in xs {
+        if x % 2 == 0 {
+            return true;
+        }
+    }
+    false
+}
+"#,
+            r#"
+fn has_even(xs: [u32; 3]) -> bool {
+    xs.into_iter().any(|x| x % 2 == 0)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn all_with_flag() {
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            r#"
+//- minicore: iterator, copy
+fn all_small(xs: [u32; 3]) -> bool {
+    let mut small = true;
+    for$0 x in xs {
+        if x >= 10 {
+            small = false;
+            break;
+        }
+    }
+    small
+}
+"#,
+            r#"
+fn all_small(xs: [u32; 3]) -> bool {
+    let small = xs.into_iter().all(|x| x < 10);
+    small
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn find_with_break_value() {
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            r#"
+//- minicore: iterator, copy
+fn first_big(xs: [u32; 3]) -> Option<u32> {
+    let found = 'search: {
+        for$0 x in xs {
+            if x > 10 {
+                break 'search Some(x);
+            }
+        }
+        None
+    };
+    found
+}
+"#,
+            r#"
+fn first_big(xs: [u32; 3]) -> Option<u32> {
+    let found = xs.into_iter().find(|&x| x > 10);
+    found
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn position_with_enumerate() {
+        check_assist(
+            convert_for_loop_to_iterator_chain,
+            r#"
+//- minicore: iterator, copy
+fn index_of_zero(xs: [u32; 3]) -> Option<usize> {
+    for$0 (i, x) in xs.into_iter().enumerate() {
+        if x == 0 {
+            return Some(i);
+        }
+    }
+    None
+}
+"#,
+            r#"
+fn index_of_zero(xs: [u32; 3]) -> Option<usize> {
+    xs.into_iter().position(|x| x == 0)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_when_accumulator_is_read_in_loop() {
+        check_assist_not_applicable(
+            convert_for_loop_to_iterator_chain,
+            r#"
+//- minicore: iterator, copy
+fn increasing(xs: [u32; 3]) -> u32 {
+    let mut total = 0;
+    for$0 x in xs {
+        if x > total {
+            total += x;
+        }
+    }
+    total
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_body() {
+        check_assist_not_applicable(
+            convert_for_loop_to_iterator_chain,
+            r#"
+//- minicore: iterator, copy
+fn total(xs: [u32; 3]) -> u32 {
+    let mut total = 0;
+    for x in xs {
+        total $0+= x;
+    }
+    total
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unroll_collect() {
+        check_assist(
+            convert_iterator_chain_to_for_loop,
+            r#"
+//- minicore: iterator, copy
+struct Vec<T>(T);
+impl<T> Vec<T> {
+    fn new() -> Self {
+        loop {}
+    }
+    fn push(&mut self, _value: T) {}
+}
+impl<T> core::iter::FromIterator<T> for Vec<T> {
+    fn from_iter<I: core::iter::IntoIterator<Item = T>>(_iter: I) -> Self {
+        loop {}
+    }
+}
+fn doubled_evens(xs: [u32; 4]) -> Vec<u32> {
+    let evens: Vec<u32> = xs.into_iter().filter(|&x| x % 2 == 0).map(|x| x * 2).$0collect();
+    evens
+}
+"#,
+            r#"
+struct Vec<T>(T);
+impl<T> Vec<T> {
+    fn new() -> Self {
+        loop {}
+    }
+    fn push(&mut self, _value: T) {}
+}
+impl<T> core::iter::FromIterator<T> for Vec<T> {
+    fn from_iter<I: core::iter::IntoIterator<Item = T>>(_iter: I) -> Self {
+        loop {}
+    }
+}
+fn doubled_evens(xs: [u32; 4]) -> Vec<u32> {
+    let mut evens: Vec<u32> = Vec::new();
+    for x in xs {
+        if x % 2 == 0 {
+            evens.push(x * 2);
+        }
+    }
+    evens
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unroll_map_into_other_binding() {
+        check_assist(
+            convert_iterator_chain_to_for_loop,
+            r#"
+//- minicore: iterator, copy
+fn count_big(xs: [u32; 3]) -> usize {
+    let n: usize = xs.into_iter().map(|x| x + 1).filter(|&y| y > 2).$0count();
+    n
+}
+"#,
+            r#"
+fn count_big(xs: [u32; 3]) -> usize {
+    let mut n: usize = 0;
+    for x in xs {
+        let y = x + 1;
+        if y > 2 {
+            n += 1;
+        }
+    }
+    n
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unroll_any() {
+        check_assist(
+            convert_iterator_chain_to_for_loop,
+            r#"
+//- minicore: iterator, copy
+fn has_three(xs: [u32; 3]) -> bool {
+    let found = xs.into_iter().$0any(|x| x == 3);
+    found
+}
+"#,
+            r#"
+fn has_three(xs: [u32; 3]) -> bool {
+    let mut found = false;
+    for x in xs {
+        if x == 3 {
+            found = true;
+            break;
+        }
+    }
+    found
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unroll_find() {
+        check_assist(
+            convert_iterator_chain_to_for_loop,
+            r#"
+//- minicore: iterator, copy
+fn first_big(xs: [u32; 3]) -> Option<u32> {
+    let found = xs.into_iter().$0find(|&x| x > 10);
+    found
+}
+"#,
+            r#"
+fn first_big(xs: [u32; 3]) -> Option<u32> {
+    let mut found = None;
+    for x in xs {
+        if x > 10 {
+            found = Some(x);
+            break;
+        }
+    }
+    found
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unroll_position() {
+        check_assist(
+            convert_iterator_chain_to_for_loop,
+            r#"
+//- minicore: iterator, copy
+fn index_of_zero(xs: [u32; 3]) -> Option<usize> {
+    let pos = xs.into_iter().$0position(|x| x == 0);
+    pos
+}
+"#,
+            r#"
+fn index_of_zero(xs: [u32; 3]) -> Option<usize> {
+    let mut pos = None;
+    for (i, x) in xs.into_iter().enumerate() {
+        if x == 0 {
+            pos = Some(i);
+            break;
+        }
+    }
+    pos
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unroll_not_applicable_when_shadowing() {
+        check_assist_not_applicable(
+            convert_iterator_chain_to_for_loop,
+            r#"
+//- minicore: iterator, copy
+fn total(xs: [u32; 3]) -> u32 {
+    let xs: u32 = xs.into_iter().$0sum();
+    xs
+}
+"#,
+        );
+    }
+}
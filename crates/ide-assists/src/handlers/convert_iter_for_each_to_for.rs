@@ -116,29 +116,7 @@ pub(crate) fn convert_for_loop_with_for_each(
         "Replace this for loop with `Iterator::for_each`",
         for_loop.syntax().text_range(),
         |builder| {
-            let mut buf = String::new();
-
-            if let Some((expr_behind_ref, method, krate)) =
-                is_ref_and_impls_iter_method(&ctx.sema, &iterable)
-            {
-                // We have either "for x in &col" and col implements a method called iter
-                //             or "for x in &mut col" and col implements a method called iter_mut
-                format_to!(
-                    buf,
-                    "{expr_behind_ref}.{}()",
-                    method.display(ctx.db(), krate.edition(ctx.db()))
-                );
-            } else if let ast::Expr::RangeExpr(..) = iterable {
-                // range expressions need to be parenthesized for the syntax to be correct
-                format_to!(buf, "({iterable})");
-            } else if impls_core_iter(&ctx.sema, &iterable) {
-                format_to!(buf, "{iterable}");
-            } else if let ast::Expr::RefExpr(_) = iterable {
-                format_to!(buf, "({iterable}).into_iter()");
-            } else {
-                format_to!(buf, "{iterable}.into_iter()");
-            }
-
+            let mut buf = iterator_receiver(ctx, &iterable);
             format_to!(buf, ".for_each(|{pat}| {body});");
 
             builder.replace(for_loop.syntax().text_range(), buf)
@@ -146,6 +124,27 @@ pub(crate) fn convert_for_loop_with_for_each(
     )
 }
 
+/// The iterator a for loop over `iterable` goes through, written so that it can be
+/// the receiver of a method call.
+pub(super) fn iterator_receiver(ctx: &AssistContext<'_>, iterable: &ast::Expr) -> String {
+    if let Some((expr_behind_ref, method, krate)) =
+        is_ref_and_impls_iter_method(&ctx.sema, iterable)
+    {
+        // We have either "for x in &col" and col implements a method called iter
+        //             or "for x in &mut col" and col implements a method called iter_mut
+        format!("{expr_behind_ref}.{}()", method.display(ctx.db(), krate.edition(ctx.db())))
+    } else if let ast::Expr::RangeExpr(..) = iterable {
+        // range expressions need to be parenthesized for the syntax to be correct
+        format!("({iterable})")
+    } else if impls_core_iter(&ctx.sema, iterable) {
+        format!("{iterable}")
+    } else if let ast::Expr::RefExpr(_) = iterable {
+        format!("({iterable}).into_iter()")
+    } else {
+        format!("{iterable}.into_iter()")
+    }
+}
+
 /// If iterable is a reference where the expression behind the reference implements a method
 /// returning an Iterator called iter or iter_mut (depending on the type of reference) then return
 /// the expression behind the reference and the method name
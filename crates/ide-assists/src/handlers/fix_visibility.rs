@@ -1,7 +1,7 @@
-use hir::{HasSource, HasVisibility, ModuleDef, PathResolution, ScopeDef, db::HirDatabase};
-use ide_db::FileId;
+use hir::{HasVisibility, ModuleDef, PathResolution, ScopeDef};
+use ide_db::helpers::visibility_owner_for_def;
 use syntax::{
-    AstNode, TextRange,
+    AstNode,
     ast::{self, HasVisibility as _, edit_in_place::HasVisibilityEdit, make},
 };
 
@@ -57,7 +57,7 @@ fn add_vis_to_referenced_module_def(acc: &mut Assists, ctx: &AssistContext<'_>)
         return None;
     };
 
-    let (vis_owner, target, target_file, target_name) = target_data_for_def(ctx.db(), def)?;
+    let (vis_owner, target, target_file, target_name) = visibility_owner_for_def(ctx.db(), def)?;
 
     let missing_visibility = if current_module.krate(ctx.db()) == target_module.krate(ctx.db()) {
         make::visibility_pub_crate()
@@ -87,75 +87,6 @@ fn add_vis_to_referenced_module_def(acc: &mut Assists, ctx: &AssistContext<'_>)
     })
 }
 
-fn target_data_for_def(
-    db: &dyn HirDatabase,
-    def: hir::ModuleDef,
-) -> Option<(ast::AnyHasVisibility, TextRange, FileId, Option<hir::Name>)> {
-    fn offset_target_and_file_id<S, Ast>(
-        db: &dyn HirDatabase,
-        x: S,
-    ) -> Option<(ast::AnyHasVisibility, TextRange, FileId)>
-    where
-        S: HasSource<Ast = Ast>,
-        Ast: AstNode + ast::HasVisibility,
-    {
-        let source = x.source(db)?;
-        let in_file_syntax = source.syntax();
-        let file_id = in_file_syntax.file_id;
-        let range = in_file_syntax.value.text_range();
-        Some((
-            ast::AnyHasVisibility::new(source.value),
-            range,
-            file_id.original_file(db).file_id(db),
-        ))
-    }
-
-    let target_name;
-    let (offset, target, target_file) = match def {
-        hir::ModuleDef::Function(f) => {
-            target_name = Some(f.name(db));
-            offset_target_and_file_id(db, f)?
-        }
-        hir::ModuleDef::Adt(adt) => {
-            target_name = Some(adt.name(db));
-            match adt {
-                hir::Adt::Struct(s) => offset_target_and_file_id(db, s)?,
-                hir::Adt::Union(u) => offset_target_and_file_id(db, u)?,
-                hir::Adt::Enum(e) => offset_target_and_file_id(db, e)?,
-            }
-        }
-        hir::ModuleDef::Const(c) => {
-            target_name = c.name(db);
-            offset_target_and_file_id(db, c)?
-        }
-        hir::ModuleDef::Static(s) => {
-            target_name = Some(s.name(db));
-            offset_target_and_file_id(db, s)?
-        }
-        hir::ModuleDef::Trait(t) => {
-            target_name = Some(t.name(db));
-            offset_target_and_file_id(db, t)?
-        }
-        hir::ModuleDef::TypeAlias(t) => {
-            target_name = Some(t.name(db));
-            offset_target_and_file_id(db, t)?
-        }
-        hir::ModuleDef::Module(m) => {
-            target_name = m.name(db);
-            let in_file_source = m.declaration_source(db)?;
-            let file_id = in_file_source.file_id.original_file(db);
-            let range = in_file_source.value.syntax().text_range();
-            (ast::AnyHasVisibility::new(in_file_source.value), range, file_id.file_id(db))
-        }
-        // FIXME
-        hir::ModuleDef::Macro(_) => return None,
-        // Enum variants can't be private, we can't modify builtin types
-        hir::ModuleDef::Variant(_) | hir::ModuleDef::BuiltinType(_) => return None,
-    };
-
-    Some((offset, target, target_file, target_name))
-}
-
 #[cfg(test)]
 mod tests {
     use crate::tests::{check_assist, check_assist_not_applicable};
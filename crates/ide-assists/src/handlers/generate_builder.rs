@@ -0,0 +1,478 @@
+use ide_db::famous_defs::FamousDefs;
+use itertools::Itertools;
+use stdx::format_to;
+use syntax::{
+    AstNode, SyntaxKind,
+    ast::{
+        self, HasAttrs, HasGenericArgs, HasGenericParams, HasName, HasVisibility, edit::IndentLevel,
+    },
+};
+
+use crate::{
+    AssistContext, AssistId, Assists,
+    utils::{add_method_to_adt, find_struct_impl, generate_impl},
+};
+
+// Assist: generate_builder
+//
+// Generates a builder for a struct, with a setter for each field. Fields of type `Option`
+// may be left unset, `build` fails when any other field is missing.
+//
+// ```
+// //- minicore: option
+// struct Person$0 {
+//     name: String,
+//     email: Option<String>,
+// }
+// ```
+// ->
+// ```
+// struct Person {
+//     name: String,
+//     email: Option<String>,
+// }
+//
+// impl Person {
+//     fn builder() -> PersonBuilder {
+//         PersonBuilder { name: None, email: None }
+//     }
+// }
+//
+// struct PersonBuilder {
+//     name: Option<String>,
+//     email: Option<String>,
+// }
+//
+// impl PersonBuilder {
+//     fn name(mut self, name: String) -> Self {
+//         self.name = Some(name);
+//         self
+//     }
+//
+//     fn email(mut self, email: String) -> Self {
+//         self.email = Some(email);
+//         self
+//     }
+//
+//     fn build(self) -> Result<Person, &'static str> {
+//         Ok(Person {
+//             name: self.name.ok_or("missing field `name`")?,
+//             email: self.email,
+//         })
+//     }
+// }
+// ```
+pub(crate) fn generate_builder(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let strukt = ctx.find_node_at_offset::<ast::Struct>()?;
+    let ast::FieldList::RecordFieldList(field_list) = strukt.field_list()? else { return None };
+    // The fields have assists of their own, only offer this on the struct's header.
+    if field_list.syntax().text_range().contains(ctx.offset()) {
+        return None;
+    }
+    let name = strukt.name()?;
+    let builder_name = format!("{name}Builder");
+
+    let mut name_taken = false;
+    ctx.sema.scope(strukt.syntax())?.process_all_names(&mut |name, _| {
+        name_taken |= name.as_str() == builder_name;
+    });
+    if name_taken {
+        return None;
+    }
+    let adt = ast::Adt::Struct(strukt.clone());
+    let impl_def = find_struct_impl(ctx, &adt, &["builder".to_owned()])?;
+
+    let krate = ctx.sema.scope(strukt.syntax())?.krate();
+    let option = FamousDefs(&ctx.sema, krate).core_option_Option();
+    let fields = field_list
+        .fields()
+        .map(|field| {
+            let ty = field.ty()?;
+            let is_option = ctx
+                .sema
+                .resolve_type(&ty)
+                .and_then(|it| it.as_adt())
+                .is_some_and(|it| Some(it) == option.map(hir::Adt::Enum));
+            let optional_ty = option_inner_type(&ty).filter(|_| is_option);
+            Some(Field {
+                name: field.name()?.to_string(),
+                is_optional: optional_ty.is_some(),
+                ty: optional_ty.unwrap_or(ty).to_string(),
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if fields.is_empty() {
+        return None;
+    }
+    // With `#[derive(Default)]` the missing fields get their default values instead.
+    let derives_default = strukt
+        .attrs()
+        .filter_map(|attr| attr.as_simple_call())
+        .filter(|(name, _)| name == "derive")
+        .any(|(_, tt)| {
+            tt.syntax()
+                .children_with_tokens()
+                .any(|it| it.kind() == SyntaxKind::IDENT && it.to_string() == "Default")
+        });
+
+    let target = strukt.syntax().text_range();
+    acc.add(
+        AssistId::generate("generate_builder"),
+        format!("Generate `{builder_name}`"),
+        target,
+        |builder| {
+            let vis = strukt.visibility().map(|it| format!("{it} ")).unwrap_or_default();
+            let generic_params =
+                strukt.generic_param_list().map(|it| it.to_string()).unwrap_or_default();
+            let impl_params = generate_impl(&adt)
+                .generic_param_list()
+                .map(|it| it.to_string())
+                .unwrap_or_default();
+            let generic_args = strukt
+                .generic_param_list()
+                .map(|it| it.to_generic_args().to_string())
+                .unwrap_or_default();
+            let where_clause = strukt
+                .where_clause()
+                .map(|it| format!("\n{it}\n"))
+                .unwrap_or_else(|| " ".to_owned());
+            let builder_ty = format!("{builder_name}{generic_args}");
+            let struct_ty = format!("{name}{generic_args}");
+
+            let builder_fn = format!(
+                "{vis}fn builder() -> {builder_ty} {{\n    {builder_name} {{ {} }}\n}}",
+                fields.iter().map(|it| format!("{}: None", it.name)).join(", ")
+            );
+
+            let mut buf = String::new();
+            if impl_def.is_none() {
+                format_to!(
+                    buf,
+                    "\n\nimpl{impl_params} {struct_ty}{where_clause}{{\n{}\n}}",
+                    indent(&builder_fn, 1)
+                );
+            }
+
+            format_to!(buf, "\n\n{vis}struct {builder_name}{generic_params}{where_clause}{{\n");
+            for field in &fields {
+                format_to!(buf, "    {}: Option<{}>,\n", field.name, field.ty);
+            }
+            buf.push('}');
+
+            let mut methods = Vec::new();
+            for field in &fields {
+                let Field { name, ty, .. } = field;
+                let setter = if ctx.config.generate_builder_with_prefix {
+                    format!("with_{}", name.trim_start_matches("r#"))
+                } else {
+                    name.clone()
+                };
+                let (param_ty, value) = if ctx.config.generate_builder_into_setters {
+                    (format!("impl Into<{ty}>"), format!("{name}.into()"))
+                } else {
+                    (ty.clone(), name.clone())
+                };
+                methods.push(format!(
+                    "{vis}fn {setter}(mut self, {name}: {param_ty}) -> Self {{\n    \
+                     self.{name} = Some({value});\n    \
+                     self\n}}"
+                ));
+            }
+            let inits = fields
+                .iter()
+                .map(|Field { name, is_optional, .. }| {
+                    if *is_optional {
+                        format!("        {name}: self.{name},\n")
+                    } else if derives_default {
+                        format!("        {name}: self.{name}.unwrap_or_default(),\n")
+                    } else {
+                        format!(
+                            "        {name}: self.{name}.ok_or(\"missing field `{}`\")?,\n",
+                            name.trim_start_matches("r#")
+                        )
+                    }
+                })
+                .join("");
+            let is_fallible = !derives_default && fields.iter().any(|it| !it.is_optional);
+            methods.push(if is_fallible {
+                format!(
+                    "{vis}fn build(self) -> Result<{struct_ty}, &'static str> {{\n    \
+                     Ok({name} {{\n{inits}    }})\n}}"
+                )
+            } else {
+                format!("{vis}fn build(self) -> {struct_ty} {{\n    {name} {{\n{inits}    }}\n}}")
+            });
+            format_to!(
+                buf,
+                "\n\nimpl{impl_params} {builder_ty}{where_clause}{{\n{}\n}}",
+                indent(&methods.join("\n\n"), 1)
+            );
+
+            let indent_level = IndentLevel::from_node(strukt.syntax());
+            builder.insert(strukt.syntax().text_range().end(), indent(&buf, indent_level.0));
+            if impl_def.is_some() {
+                let method = indent(&builder_fn, indent_level.0 + 1);
+                add_method_to_adt(builder, &adt, impl_def, &method);
+            }
+        },
+    )
+}
+
+struct Field {
+    name: String,
+    /// The type of the setter argument, the inner type for `Option` fields.
+    ty: String,
+    is_optional: bool,
+}
+
+/// The `T` of an `Option<T>` written as a path.
+fn option_inner_type(ty: &ast::Type) -> Option<ast::Type> {
+    let ast::Type::PathType(path_ty) = ty else { return None };
+    let segment = path_ty.path()?.segment()?;
+    let ast::GenericArg::TypeArg(arg) =
+        segment.generic_arg_list()?.generic_args().exactly_one().ok()?
+    else {
+        return None;
+    };
+    arg.ty()
+}
+
+/// Indents every non-empty line of `text` by `level` levels.
+fn indent(text: &str, level: u8) -> String {
+    let indent = IndentLevel(level);
+    text.split('\n')
+        .map(|line| if line.is_empty() { String::new() } else { format!("{indent}{line}") })
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        AssistConfig,
+        tests::{TEST_CONFIG, check_assist, check_assist_not_applicable, check_assist_with_config},
+    };
+
+    use super::*;
+
+    #[test]
+    fn generic_struct_in_module() {
+        check_assist(
+            generate_builder,
+            r#"
+//- minicore: option
+mod config {
+    pub struct $0Config<'a, T: Clone = u8> {
+        pub name: &'a str,
+        pub value: T,
+        pub retries: Option<u32>,
+    }
+}
+"#,
+            r#"
+mod config {
+    pub struct Config<'a, T: Clone = u8> {
+        pub name: &'a str,
+        pub value: T,
+        pub retries: Option<u32>,
+    }
+
+    impl<'a, T: Clone> Config<'a, T> {
+        pub fn builder() -> ConfigBuilder<'a, T> {
+            ConfigBuilder { name: None, value: None, retries: None }
+        }
+    }
+
+    pub struct ConfigBuilder<'a, T: Clone = u8> {
+        name: Option<&'a str>,
+        value: Option<T>,
+        retries: Option<u32>,
+    }
+
+    impl<'a, T: Clone> ConfigBuilder<'a, T> {
+        pub fn name(mut self, name: &'a str) -> Self {
+            self.name = Some(name);
+            self
+        }
+
+        pub fn value(mut self, value: T) -> Self {
+            self.value = Some(value);
+            self
+        }
+
+        pub fn retries(mut self, retries: u32) -> Self {
+            self.retries = Some(retries);
+            self
+        }
+
+        pub fn build(self) -> Result<Config<'a, T>, &'static str> {
+            Ok(Config {
+                name: self.name.ok_or("missing field `name`")?,
+                value: self.value.ok_or("missing field `value`")?,
+                retries: self.retries,
+            })
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn derive_default_makes_build_infallible() {
+        check_assist(
+            generate_builder,
+            r#"
+//- minicore: option, default, derive
+#[derive(Default)]
+struct Options$0 {
+    verbose: bool,
+    level: Option<u8>,
+}
+
+impl Options {
+    fn new() -> Self {
+        Self::default()
+    }
+}
+"#,
+            r#"
+#[derive(Default)]
+struct Options {
+    verbose: bool,
+    level: Option<u8>,
+}
+
+struct OptionsBuilder {
+    verbose: Option<bool>,
+    level: Option<u8>,
+}
+
+impl OptionsBuilder {
+    fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = Some(verbose);
+        self
+    }
+
+    fn level(mut self, level: u8) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    fn build(self) -> Options {
+        Options {
+            verbose: self.verbose.unwrap_or_default(),
+            level: self.level,
+        }
+    }
+}
+
+impl Options {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn builder() -> OptionsBuilder {
+        OptionsBuilder { verbose: None, level: None }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn with_prefix_and_into_setters() {
+        check_assist_with_config(
+            generate_builder,
+            AssistConfig {
+                generate_builder_with_prefix: true,
+                generate_builder_into_setters: true,
+                ..TEST_CONFIG
+            },
+            r#"
+struct Request$0<B>
+where
+    B: AsRef<[u8]>,
+{
+    url: String,
+    body: B,
+}
+"#,
+            r#"
+struct Request<B>
+where
+    B: AsRef<[u8]>,
+{
+    url: String,
+    body: B,
+}
+
+impl<B> Request<B>
+where
+    B: AsRef<[u8]>,
+{
+    fn builder() -> RequestBuilder<B> {
+        RequestBuilder { url: None, body: None }
+    }
+}
+
+struct RequestBuilder<B>
+where
+    B: AsRef<[u8]>,
+{
+    url: Option<String>,
+    body: Option<B>,
+}
+
+impl<B> RequestBuilder<B>
+where
+    B: AsRef<[u8]>,
+{
+    fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    fn with_body(mut self, body: impl Into<B>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    fn build(self) -> Result<Request<B>, &'static str> {
+        Ok(Request {
+            url: self.url.ok_or("missing field `url`")?,
+            body: self.body.ok_or("missing field `body`")?,
+        })
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(generate_builder, r#"struct Point$0(i32, i32);"#);
+        check_assist_not_applicable(generate_builder, r#"struct Point { $0x: i32 }"#);
+        check_assist_not_applicable(
+            generate_builder,
+            r#"
+struct Point$0 {
+    x: i32,
+}
+
+struct PointBuilder;
+"#,
+        );
+        check_assist_not_applicable(
+            generate_builder,
+            r#"
+struct Point$0 {
+    x: i32,
+}
+
+impl Point {
+    fn builder() {}
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,1070 @@
+use hir::{AsAssocItem, AssocItemContainer, ModuleDef};
+use ide_db::{
+    EditionedFileId, FxHashMap, FxHashSet,
+    defs::Definition,
+    famous_defs::FamousDefs,
+    helpers::mod_path_to_ast,
+    syntax_helpers::node_ext::{for_each_tail_expr, walk_expr},
+};
+use itertools::Itertools;
+use stdx::{format_to, to_camel_case};
+use syntax::{
+    AstNode, TextRange, TextSize,
+    ast::{self, HasArgList, HasGenericParams, HasName, HasVisibility, edit::IndentLevel},
+    match_ast,
+};
+
+use crate::{
+    AssistContext, AssistId, Assists, GroupLabel,
+    handlers::{propagate_to_callers::call_expr, wrap_return_type::tail_cb_impl},
+};
+
+// Assist: introduce_newtype
+//
+// Turns a type alias or the type of a struct field into a newtype, wrapping the values that
+// flow into it and unwrapping them where the wrapped type is needed.
+//
+// ```
+// # //- minicore: from
+// type UserId$0 = u64;
+//
+// fn next(id: UserId) -> UserId {
+//     id + 1
+// }
+//
+// fn main() {
+//     let id = next(1);
+// }
+// ```
+// ->
+// ```
+// #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+// struct UserId(u64);
+//
+// impl From<u64> for UserId {
+//     fn from(value: u64) -> Self {
+//         Self(value)
+//     }
+// }
+//
+// impl From<UserId> for u64 {
+//     fn from(value: UserId) -> Self {
+//         value.0
+//     }
+// }
+//
+// fn next(id: UserId) -> UserId {
+//     UserId(id.0 + 1)
+// }
+//
+// fn main() {
+//     let id = next(UserId(1));
+// }
+// ```
+pub(crate) fn introduce_newtype(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let introduction = match ctx.find_node_at_offset::<ast::TypeAlias>() {
+        Some(alias) => from_alias(ctx, &alias)?,
+        None => from_field(ctx, &ctx.find_node_at_offset::<ast::RecordField>()?)?,
+    };
+
+    let name = &introduction.newtype.name;
+    let group = GroupLabel(format!("Introduce newtype `{name}`..."));
+    for (deref, display) in [(false, false), (true, false), (false, true), (true, true)] {
+        let impls = Impls { deref, display };
+        let label = match (deref, display) {
+            (false, false) => format!("Introduce newtype `{name}`"),
+            (true, false) => format!("Introduce newtype `{name}` implementing `Deref`"),
+            (false, true) => format!("Introduce newtype `{name}` implementing `Display`"),
+            (true, true) => {
+                format!("Introduce newtype `{name}` implementing `Deref` and `Display`")
+            }
+        };
+        acc.add_group(
+            &group,
+            AssistId::refactor_rewrite("introduce_newtype"),
+            label,
+            introduction.target,
+            |builder| {
+                let mut edits = introduction.declaration_edits(ctx, impls);
+                Flow::new(ctx, introduction.def).collect(ctx).adjust(
+                    ctx,
+                    &introduction.newtype,
+                    impls,
+                    &mut edits,
+                );
+                for (file_id, mut edits) in edits.0 {
+                    builder.edit_file(file_id.file_id(ctx.db()));
+                    edits.sort_by_key(|&(range, order, _)| (range.start(), order));
+                    for (range, _, text) in edits {
+                        builder.replace(range, text);
+                    }
+                }
+            },
+        );
+    }
+    Some(())
+}
+
+/// The impls generated on top of the derives and the `From` conversions.
+#[derive(Clone, Copy)]
+struct Impls {
+    deref: bool,
+    display: bool,
+}
+
+/// Where the newtype is declared, and the alias or field it comes from.
+struct Introduction<'db> {
+    newtype: Newtype<'db>,
+    def: Definition,
+    vis: String,
+    indent: IndentLevel,
+    site: Site,
+    target: TextRange,
+}
+
+enum Site {
+    /// The range of the type alias the newtype replaces.
+    Alias(TextRange),
+    /// The offset of the struct to insert the newtype before, and the range of the field's type.
+    Field(TextSize, TextRange),
+}
+
+impl Introduction<'_> {
+    fn declaration_edits(&self, ctx: &AssistContext<'_>, impls: Impls) -> Edits {
+        let Introduction { newtype, vis, indent, .. } = self;
+        let declaration = newtype.declaration(ctx, vis, *indent, impls);
+        let mut edits = Edits::default();
+        match self.site {
+            Site::Alias(range) => edits.push(ctx.file_id(), range, Order::Prefix, declaration),
+            Site::Field(offset, ty) => {
+                let range = TextRange::empty(offset);
+                edits.push(
+                    ctx.file_id(),
+                    range,
+                    Order::Prefix,
+                    format!("{declaration}\n\n{indent}"),
+                );
+                edits.push(ctx.file_id(), ty, Order::Prefix, newtype.name.clone());
+            }
+        }
+        edits
+    }
+}
+
+fn from_alias<'db>(ctx: &AssistContext<'db>, alias: &ast::TypeAlias) -> Option<Introduction<'db>> {
+    if alias.generic_param_list().is_some() {
+        return None;
+    }
+    let def = ctx.sema.to_def(alias)?;
+    if def.as_assoc_item(ctx.db()).is_some() {
+        return None;
+    }
+    let ty = alias.ty()?;
+    let newtype = Newtype::new(ctx, def.module(ctx.db()), alias.name()?.text().to_string(), &ty)?;
+
+    let start = match alias.visibility() {
+        Some(vis) => vis.syntax().text_range().start(),
+        None => alias.type_token()?.text_range().start(),
+    };
+    Some(Introduction {
+        newtype,
+        def: Definition::TypeAlias(def),
+        vis: alias.visibility().map(|it| format!("{it} ")).unwrap_or_default(),
+        indent: IndentLevel::from_node(alias.syntax()),
+        site: Site::Alias(TextRange::new(start, alias.syntax().text_range().end())),
+        target: alias.syntax().text_range(),
+    })
+}
+
+fn from_field<'db>(
+    ctx: &AssistContext<'db>,
+    field: &ast::RecordField,
+) -> Option<Introduction<'db>> {
+    let strukt = field.syntax().parent()?.parent().and_then(ast::Struct::cast)?;
+    let ty = field.ty()?;
+    // The field's name is the target of many other assists, only offer this on its type.
+    if !ty.syntax().text_range().contains_inclusive(ctx.offset()) {
+        return None;
+    }
+    let def = ctx.sema.to_def(field)?;
+    let module = ctx.sema.scope(strukt.syntax())?.module();
+    let name = to_camel_case(&field.name()?.to_string());
+    if module.scope(ctx.db(), None).iter().any(|(it, _)| it.as_str() == name) {
+        return None;
+    }
+    let newtype = Newtype::new(ctx, module, name, &ty)?;
+
+    Some(Introduction {
+        newtype,
+        def: Definition::Field(def),
+        vis: strukt.visibility().map(|it| format!("{it} ")).unwrap_or_default(),
+        indent: IndentLevel::from_node(strukt.syntax()),
+        site: Site::Field(strukt.syntax().text_range().start(), ty.syntax().text_range()),
+        target: field.syntax().text_range(),
+    })
+}
+
+struct Newtype<'db> {
+    name: String,
+    module: hir::Module,
+    ty: ast::Type,
+    wrapped: hir::Type<'db>,
+}
+
+impl<'db> Newtype<'db> {
+    fn new(
+        ctx: &AssistContext<'db>,
+        module: hir::Module,
+        name: String,
+        ty: &ast::Type,
+    ) -> Option<Self> {
+        let wrapped = ctx.sema.resolve_type(ty)?;
+        // A newtype over a borrowed or generic type would need parameters of its own.
+        if wrapped.contains_reference(ctx.db()) || !wrapped.generic_params(ctx.db()).is_empty() {
+            return None;
+        }
+        Some(Newtype { name, module, ty: ty.clone(), wrapped })
+    }
+
+    /// The newtype with its derives, its `From` conversions and `impls`.
+    fn declaration(
+        &self,
+        ctx: &AssistContext<'_>,
+        vis: &str,
+        indent: IndentLevel,
+        impls: Impls,
+    ) -> String {
+        let db = ctx.db();
+        let Newtype { name, module, ty, .. } = self;
+        let krate = module.krate(db);
+        let famous_defs = FamousDefs(&ctx.sema, krate);
+        let cfg = ctx.config.find_path_config(ctx.sema.is_nightly(krate));
+        let path = |def: ModuleDef| {
+            module.find_path(db, def, cfg).map(|it| mod_path_to_ast(&it, krate.edition(db)))
+        };
+
+        let mut buf = format!(
+            "#[derive({})]\n{vis}struct {name}({vis}{ty});",
+            self.derives(ctx, &famous_defs).join(", ")
+        );
+        if let Some(from) = famous_defs.core_convert_From().and_then(|it| path(it.into())) {
+            format_to!(
+                buf,
+                "\n\nimpl {from}<{ty}> for {name} {{
+    fn from(value: {ty}) -> Self {{
+        Self(value)
+    }}
+}}
+
+impl {from}<{name}> for {ty} {{
+    fn from(value: {name}) -> Self {{
+        value.0
+    }}
+}}"
+            );
+        }
+        if impls.deref
+            && let Some(deref) = famous_defs.core_ops_Deref().and_then(|it| path(it.into()))
+        {
+            format_to!(
+                buf,
+                "\n\nimpl {deref} for {name} {{
+    type Target = {ty};
+
+    fn deref(&self) -> &Self::Target {{
+        &self.0
+    }}
+}}"
+            );
+        }
+        if impls.display
+            && let Some(display) = famous_defs.core_fmt_Display()
+            && let Some(fmt) = path(display.module(db).into())
+        {
+            format_to!(
+                buf,
+                "\n\nimpl {fmt}::Display for {name} {{
+    fn fmt(&self, f: &mut {fmt}::Formatter<'_>) -> {fmt}::Result {{
+        {fmt}::Display::fmt(&self.0, f)
+    }}
+}}"
+            );
+        }
+        buf.lines()
+            .enumerate()
+            .map(|(idx, line)| match idx {
+                0 => line.to_owned(),
+                _ if line.is_empty() => String::new(),
+                _ => format!("{indent}{line}"),
+            })
+            .join("\n")
+    }
+
+    fn derives(
+        &self,
+        ctx: &AssistContext<'_>,
+        famous_defs: &FamousDefs<'_, '_>,
+    ) -> Vec<&'static str> {
+        let db = ctx.db();
+        let ty = &self.wrapped;
+        let mut derives = vec!["Debug", "Clone"];
+        if ty.is_scalar() || ty.is_copy(db) {
+            derives.push("Copy");
+        }
+        derives.push("PartialEq");
+        if ty.is_float() {
+            derives.push("PartialOrd");
+        } else if ty.is_scalar()
+            || famous_defs.core_cmp_Ord().is_some_and(|ord| ty.impls_trait(db, ord, &[]))
+        {
+            derives.extend(["Eq", "PartialOrd", "Ord", "Hash"]);
+        }
+        derives
+    }
+}
+
+/// Where values of the newtype live and how they get there.
+#[derive(Default)]
+struct Flow {
+    queue: Vec<Definition>,
+    /// Locals, fields, consts, statics and functions (for their return value) holding the
+    /// newtype.
+    holders: FxHashSet<Definition>,
+    /// Functions with parameters among the holders.
+    param_fns: FxHashSet<hir::Function>,
+    /// Expressions whose value ends up in a holder.
+    inflows: Vec<(EditionedFileId, ast::Expr)>,
+    /// Expressions reading a holder.
+    values: Vec<(EditionedFileId, ast::Expr)>,
+}
+
+impl Flow {
+    /// Starts from the holders of `def`, a type alias or a field.
+    fn new(ctx: &AssistContext<'_>, def: Definition) -> Self {
+        let mut flow = Flow::default();
+        match def {
+            Definition::TypeAlias(alias) => flow.holders_of_alias(ctx, alias),
+            def => flow.queue.push(def),
+        }
+        flow
+    }
+
+    /// Queues everything declared with the type alias as its whole type.
+    fn holders_of_alias(&mut self, ctx: &AssistContext<'_>, alias: hir::TypeAlias) {
+        for (file_id, references) in Definition::TypeAlias(alias).usages(&ctx.sema).all() {
+            for reference in references {
+                let Some(name_ref) = reference.name.as_name_ref() else { continue };
+                let Some(path_ty) = name_ref
+                    .syntax()
+                    .parent()
+                    .and_then(ast::PathSegment::cast)
+                    .and_then(|it| it.parent_path().syntax().parent())
+                    .and_then(ast::PathType::cast)
+                else {
+                    continue;
+                };
+                let Some(parent) = path_ty.syntax().parent() else { continue };
+                match_ast! {
+                    match parent {
+                        ast::Param(param) => {
+                            let func = param.syntax().parent().and_then(|it| it.parent());
+                            if let Some(ast::Pat::IdentPat(pat)) = param.pat()
+                                && let Some(local) = ctx.sema.to_def(&pat)
+                                && let Some(func) = func.and_then(ast::Fn::cast)
+                                && let Some(func) = ctx.sema.to_def(&func)
+                            {
+                                self.queue.push(Definition::Local(local));
+                                self.param_fns.insert(func);
+                            }
+                        },
+                        ast::RetType(ret) => {
+                            if let Some(func) = ret.syntax().parent().and_then(ast::Fn::cast)
+                                && let Some(func) = ctx.sema.to_def(&func)
+                            {
+                                self.queue.push(Definition::Function(func));
+                            }
+                        },
+                        ast::RecordField(field) => {
+                            if let Some(field) = ctx.sema.to_def(&field) {
+                                self.queue.push(Definition::Field(field));
+                            }
+                        },
+                        ast::LetStmt(let_) => {
+                            if let Some(init) = let_.initializer() {
+                                self.inflows.push((file_id, init));
+                            }
+                            if let Some(ast::Pat::IdentPat(pat)) = let_.pat()
+                                && let Some(local) = ctx.sema.to_def(&pat)
+                            {
+                                self.queue.push(Definition::Local(local));
+                            }
+                        },
+                        ast::Const(it) => {
+                            if let Some(it) = ctx.sema.to_def(&it) {
+                                self.queue.push(Definition::Const(it));
+                            }
+                        },
+                        ast::Static(it) => {
+                            if let Some(it) = ctx.sema.to_def(&it) {
+                                self.queue.push(Definition::Static(it));
+                            }
+                        },
+                        _ => (),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Follows the holders to the expressions reading and writing them, picking up the locals
+    /// that inference makes hold the newtype too.
+    fn collect(mut self, ctx: &AssistContext<'_>) -> Self {
+        while let Some(def) = self.queue.pop() {
+            if !self.holders.insert(def) {
+                continue;
+            }
+            match def {
+                Definition::Function(func) => {
+                    let Some(source) = ctx.sema.source(func) else { continue };
+                    let Some(file_id) = source.file_id.file_id() else { continue };
+                    let Some(body) = source.value.body() else { continue };
+                    let body = ast::Expr::BlockExpr(body);
+                    let mut exprs = Vec::new();
+                    walk_expr(&body, &mut |expr| {
+                        if let ast::Expr::ReturnExpr(ret_expr) = expr
+                            && let Some(arg) = ret_expr.expr()
+                        {
+                            for_each_tail_expr(&arg, &mut |e| tail_cb_impl(&mut exprs, e));
+                        }
+                    });
+                    for_each_tail_expr(&body, &mut |e| tail_cb_impl(&mut exprs, e));
+                    self.inflows.extend(exprs.into_iter().map(|expr| (file_id, expr)));
+                }
+                Definition::Const(it) => {
+                    if let Some(source) = ctx.sema.source(it)
+                        && let Some(file_id) = source.file_id.file_id()
+                    {
+                        self.inflows.extend(source.value.body().map(|it| (file_id, it)));
+                    }
+                }
+                Definition::Static(it) => {
+                    if let Some(source) = ctx.sema.source(it)
+                        && let Some(file_id) = source.file_id.file_id()
+                    {
+                        self.inflows.extend(source.value.body().map(|it| (file_id, it)));
+                    }
+                }
+                _ => (),
+            }
+
+            for (file_id, references) in def.usages(&ctx.sema).all() {
+                for reference in references {
+                    let Some(name_ref) = reference.name.as_name_ref() else { continue };
+                    if ctx.sema.hir_file_for(name_ref.syntax()).is_macro() {
+                        continue;
+                    }
+                    let value = match def {
+                        Definition::Function(_) => call_expr(name_ref),
+                        Definition::Field(_) => {
+                            if let Some(field) = ast::RecordExprField::for_field_name(name_ref) {
+                                self.inflows.extend(field.expr().map(|it| (file_id, it)));
+                                continue;
+                            }
+                            name_ref
+                                .syntax()
+                                .parent()
+                                .and_then(ast::FieldExpr::cast)
+                                .map(Into::into)
+                        }
+                        _ => name_ref
+                            .syntax()
+                            .parent()
+                            .and_then(ast::PathSegment::cast)
+                            .and_then(|it| it.parent_path().syntax().parent())
+                            .and_then(ast::PathExpr::cast)
+                            .map(Into::into),
+                    };
+                    let Some(value) = value else { continue };
+
+                    let outer = outermost_parens(&value);
+                    match outer.syntax().parent().and_then(ValueParent::cast) {
+                        // `let x = value;` makes `x` hold the newtype as well.
+                        Some(ValueParent::Let(let_)) if let_.ty().is_none() => {
+                            if let Some(ast::Pat::IdentPat(pat)) = let_.pat()
+                                && let Some(local) = ctx.sema.to_def(&pat)
+                            {
+                                self.queue.push(Definition::Local(local));
+                            }
+                        }
+                        Some(ValueParent::Bin(bin))
+                            if bin.op_kind() == Some(ast::BinaryOp::Assignment { op: None })
+                                && bin.lhs().as_ref() == Some(&outer) =>
+                        {
+                            self.inflows.extend(bin.rhs().map(|it| (file_id, it)));
+                        }
+                        _ => (),
+                    }
+                    self.values.push((file_id, value));
+                }
+            }
+        }
+
+        for func in self.param_fns.clone() {
+            for (file_id, references) in Definition::Function(func).usages(&ctx.sema).all() {
+                for reference in references {
+                    let Some(call) = reference.name.as_name_ref().and_then(call_expr) else {
+                        continue;
+                    };
+                    let (callable, args) = match &call {
+                        ast::Expr::CallExpr(call) => (
+                            call.expr().and_then(|it| ctx.sema.resolve_expr_as_callable(&it)),
+                            call.arg_list(),
+                        ),
+                        ast::Expr::MethodCallExpr(call) => {
+                            (ctx.sema.resolve_method_call_as_callable(call), call.arg_list())
+                        }
+                        _ => continue,
+                    };
+                    let (Some(callable), Some(args)) = (callable, args) else { continue };
+                    for (arg, param) in args.args().zip(callable.params()) {
+                        if param
+                            .as_local(ctx.db())
+                            .is_some_and(|local| self.holders.contains(&Definition::Local(local)))
+                        {
+                            self.inflows.push((file_id, arg));
+                        }
+                    }
+                }
+            }
+        }
+        self
+    }
+
+    /// Wraps the values flowing into holders, and unwraps those read from holders where the
+    /// wrapped type is expected.
+    fn adjust(
+        &self,
+        ctx: &AssistContext<'_>,
+        newtype: &Newtype<'_>,
+        impls: Impls,
+        edits: &mut Edits,
+    ) {
+        let inflows: FxHashSet<_> =
+            self.inflows.iter().map(|(file_id, it)| (*file_id, it.syntax().text_range())).collect();
+        let values: FxHashSet<_> =
+            self.values.iter().map(|(file_id, it)| (*file_id, it.syntax().text_range())).collect();
+
+        for (file_id, expr) in &self.inflows {
+            if !values.contains(&(*file_id, expr.syntax().text_range())) {
+                edits.wrap(*file_id, expr, &newtype.name);
+            }
+        }
+        for (file_id, value) in &self.values {
+            if inflows.contains(&(*file_id, value.syntax().text_range())) {
+                continue;
+            }
+            match self.expected_use(ctx, newtype, impls, &outermost_parens(value), |other| {
+                values.contains(&(*file_id, other.syntax().text_range()))
+            }) {
+                Use::Newtype => (),
+                Use::Wrapped => edits.unwrap(*file_id, value),
+                Use::WrapOther(other) => edits.wrap(*file_id, &other, &newtype.name),
+            }
+        }
+    }
+
+    /// How the context of a value read from a holder uses it.
+    fn expected_use(
+        &self,
+        ctx: &AssistContext<'_>,
+        newtype: &Newtype<'_>,
+        impls: Impls,
+        expr: &ast::Expr,
+        is_value: impl Fn(&ast::Expr) -> bool,
+    ) -> Use {
+        let db = ctx.db();
+        let expects_wrapped = |ty: &hir::Type<'_>| ty.could_unify_with(db, &newtype.wrapped);
+        let returns_wrapped = |func: Option<ast::Fn>| {
+            let Some(func) = func.and_then(|it| ctx.sema.to_def(&it)) else { return false };
+            !self.holders.contains(&Definition::Function(func))
+                && expects_wrapped(&func.ret_type(db))
+        };
+        let wrapped_if = |it: bool| if it { Use::Wrapped } else { Use::Newtype };
+
+        let Some(parent) = expr.syntax().parent() else { return Use::Newtype };
+        match_ast! {
+            match parent {
+                ast::BinExpr(bin) => {
+                    let other = if bin.lhs().as_ref() == Some(expr) { bin.rhs() } else { bin.lhs() };
+                    match bin.op_kind() {
+                        Some(ast::BinaryOp::ArithOp(_) | ast::BinaryOp::Assignment { op: Some(_) }) => {
+                            Use::Wrapped
+                        }
+                        Some(ast::BinaryOp::Assignment { op: None }) => {
+                            wrapped_if(bin.rhs().as_ref() == Some(expr) && !other.is_some_and(|it| is_value(&it)))
+                        }
+                        Some(ast::BinaryOp::CmpOp(_)) => match other {
+                            Some(other) if is_value(&other) => Use::Newtype,
+                            Some(ast::Expr::Literal(lit)) => Use::WrapOther(lit.into()),
+                            _ => Use::Wrapped,
+                        },
+                        _ => Use::Newtype,
+                    }
+                },
+                ast::PrefixExpr(prefix) => wrapped_if(prefix.op_kind() != Some(ast::UnaryOp::Deref)),
+                ast::CastExpr(_) => Use::Wrapped,
+                ast::RangeExpr(_) => Use::Wrapped,
+                ast::IndexExpr(index) => wrapped_if(index.index().as_ref() == Some(expr)),
+                ast::ArgList(args) => {
+                    let Some(idx) = args.args().position(|it| &it == expr) else { return Use::Newtype };
+                    let callable = args.syntax().parent().and_then(|call| {
+                        match_ast! {
+                            match call {
+                                ast::CallExpr(call) => ctx.sema.resolve_expr_as_callable(&call.expr()?),
+                                ast::MethodCallExpr(call) => ctx.sema.resolve_method_call_as_callable(&call),
+                                _ => None,
+                            }
+                        }
+                    });
+                    let Some(param) = callable.and_then(|it| it.params().into_iter().nth(idx)) else {
+                        return Use::Newtype;
+                    };
+                    let is_holder = param
+                        .as_local(db)
+                        .is_some_and(|local| self.holders.contains(&Definition::Local(local)));
+                    wrapped_if(!is_holder && expects_wrapped(param.ty()))
+                },
+                ast::RecordExprField(field) => {
+                    let Some((field, _, ty)) = ctx.sema.resolve_record_field(&field) else {
+                        return Use::Newtype;
+                    };
+                    wrapped_if(!self.holders.contains(&Definition::Field(field)) && expects_wrapped(&ty))
+                },
+                ast::LetStmt(let_) => {
+                    let ty = let_.ty().and_then(|it| ctx.sema.resolve_type(&it));
+                    wrapped_if(ty.is_some_and(|it| expects_wrapped(&it)))
+                },
+                ast::ReturnExpr(ret) => {
+                    wrapped_if(returns_wrapped(ret.syntax().ancestors().find_map(ast::Fn::cast)))
+                },
+                ast::StmtList(list) => {
+                    let func = list.syntax().parent().and_then(|it| it.parent()).and_then(ast::Fn::cast);
+                    wrapped_if(list.tail_expr().as_ref() == Some(expr) && returns_wrapped(func))
+                },
+                ast::MethodCallExpr(call) => {
+                    // Inherent methods of the wrapped type aren't available on the newtype unless
+                    // it derefs to it, trait methods are mostly covered by the derives.
+                    if impls.deref || call.receiver().as_ref() != Some(expr) {
+                        return Use::Newtype;
+                    }
+                    let container = ctx
+                        .sema
+                        .resolve_method_call(&call)
+                        .and_then(|it| it.as_assoc_item(db))
+                        .map(|it| it.container(db));
+                    wrapped_if(matches!(container, Some(AssocItemContainer::Impl(it)) if it.trait_(db).is_none()))
+                },
+                _ => Use::Newtype,
+            }
+        }
+    }
+}
+
+enum Use {
+    /// The newtype itself fits.
+    Newtype,
+    /// The wrapped value is needed.
+    Wrapped,
+    /// The value is compared with a literal, which needs wrapping instead.
+    WrapOther(ast::Expr),
+}
+
+/// The parents of a value [`Flow::collect`] cares about.
+enum ValueParent {
+    Let(ast::LetStmt),
+    Bin(ast::BinExpr),
+}
+
+impl ValueParent {
+    fn cast(node: syntax::SyntaxNode) -> Option<Self> {
+        match_ast! {
+            match node {
+                ast::LetStmt(it) => Some(ValueParent::Let(it)),
+                ast::BinExpr(it) => Some(ValueParent::Bin(it)),
+                _ => None,
+            }
+        }
+    }
+}
+
+fn outermost_parens(expr: &ast::Expr) -> ast::Expr {
+    let mut expr = expr.clone();
+    while let Some(paren) = expr.syntax().parent().and_then(ast::ParenExpr::cast) {
+        expr = paren.into();
+    }
+    expr
+}
+
+/// The order of edits starting at the same offset.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Order {
+    Postfix,
+    Suffix,
+    Prefix,
+}
+
+#[derive(Default)]
+struct Edits(FxHashMap<EditionedFileId, Vec<(TextRange, Order, String)>>);
+
+impl Edits {
+    fn push(&mut self, file_id: EditionedFileId, range: TextRange, order: Order, text: String) {
+        self.0.entry(file_id).or_default().push((range, order, text));
+    }
+
+    fn wrap(&mut self, file_id: EditionedFileId, expr: &ast::Expr, name: &str) {
+        let range = expr.syntax().text_range();
+        let field = shorthand_field(expr).map(|it| format!("{it}: ")).unwrap_or_default();
+        self.push(
+            file_id,
+            TextRange::empty(range.start()),
+            Order::Prefix,
+            format!("{field}{name}("),
+        );
+        self.push(file_id, TextRange::empty(range.end()), Order::Suffix, ")".to_owned());
+    }
+
+    fn unwrap(&mut self, file_id: EditionedFileId, expr: &ast::Expr) {
+        let range = expr.syntax().text_range();
+        if let Some(field) = shorthand_field(expr) {
+            self.push(
+                file_id,
+                TextRange::empty(range.start()),
+                Order::Prefix,
+                format!("{field}: "),
+            );
+        }
+        self.push(file_id, TextRange::empty(range.end()), Order::Postfix, ".0".to_owned());
+    }
+}
+
+/// The field name to spell out when `expr` is the value of a shorthand record field.
+fn shorthand_field(expr: &ast::Expr) -> Option<ast::NameRef> {
+    let field = ast::RecordExprField::cast(expr.syntax().parent()?)?;
+    if field.name_ref().is_some() {
+        return None;
+    }
+    field.field_name()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn alias_flows_through_lets_and_calls() {
+        check_assist(
+            introduce_newtype,
+            r#"
+type Id$0 = u32;
+
+fn lookup(id: Id) -> bool {
+    id > 10
+}
+
+fn fetch() -> Id {
+    7
+}
+
+fn run() {
+    let a = fetch();
+    let b: Id = 3;
+    lookup(a);
+    lookup(4);
+    let c = a + b;
+    let v: u32 = b;
+}
+"#,
+            r#"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Id(u32);
+
+fn lookup(id: Id) -> bool {
+    id > Id(10)
+}
+
+fn fetch() -> Id {
+    Id(7)
+}
+
+fn run() {
+    let a = fetch();
+    let b: Id = Id(3);
+    lookup(a);
+    lookup(Id(4));
+    let c = a.0 + b.0;
+    let v: u32 = b.0;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn alias_with_all_impls() {
+        check_assist_by_label(
+            introduce_newtype,
+            r#"
+//- minicore: from, deref, fmt
+mod units {
+    /// A length.
+    pub type Meters$0 = f64;
+
+    pub fn double(m: Meters) -> Meters {
+        m * 2.0
+    }
+}
+"#,
+            r#"
+mod units {
+    /// A length.
+    #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+    pub struct Meters(pub f64);
+
+    impl From<f64> for Meters {
+        fn from(value: f64) -> Self {
+            Self(value)
+        }
+    }
+
+    impl From<Meters> for f64 {
+        fn from(value: Meters) -> Self {
+            value.0
+        }
+    }
+
+    impl core::ops::Deref for Meters {
+        type Target = f64;
+
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+
+    impl core::fmt::Display for Meters {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            core::fmt::Display::fmt(&self.0, f)
+        }
+    }
+
+    pub fn double(m: Meters) -> Meters {
+        Meters(m.0 * 2.0)
+    }
+}
+"#,
+            "Introduce newtype `Meters` implementing `Deref` and `Display`",
+        );
+    }
+
+    #[test]
+    fn deref_keeps_inherent_method_calls() {
+        let before = r#"
+//- minicore: deref
+struct Inner;
+impl Inner {
+    fn get(&self) -> u32 { 0 }
+}
+
+type Outer$0 = Inner;
+
+fn get(o: Outer) -> u32 {
+    o.get()
+}
+"#;
+        check_assist_by_label(
+            introduce_newtype,
+            before,
+            r#"
+struct Inner;
+impl Inner {
+    fn get(&self) -> u32 { 0 }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Outer(Inner);
+
+fn get(o: Outer) -> u32 {
+    o.0.get()
+}
+"#,
+            "Introduce newtype `Outer`",
+        );
+        check_assist_by_label(
+            introduce_newtype,
+            before,
+            r#"
+struct Inner;
+impl Inner {
+    fn get(&self) -> u32 { 0 }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Outer(Inner);
+
+impl core::ops::Deref for Outer {
+    type Target = Inner;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+fn get(o: Outer) -> u32 {
+    o.get()
+}
+"#,
+            "Introduce newtype `Outer` implementing `Deref`",
+        );
+    }
+
+    #[test]
+    fn field_construction_and_usage_sites() {
+        check_assist(
+            introduce_newtype,
+            r#"
+struct User {
+    user_id: $0u64,
+    admin: bool,
+}
+
+fn make(user_id: u64) -> User {
+    User { user_id, admin: false }
+}
+
+fn next(u: &User) -> u64 {
+    u.user_id + 1
+}
+
+fn same(a: &User, b: &User) -> bool {
+    a.user_id == b.user_id
+}
+
+fn is_root(u: &User) -> bool {
+    u.user_id == 0
+}
+
+fn raw(u: &User) -> u64 {
+    u.user_id
+}
+
+fn set(u: &mut User) {
+    u.user_id = 5;
+    u.user_id += 1;
+}
+"#,
+            r#"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct UserId(u64);
+
+struct User {
+    user_id: UserId,
+    admin: bool,
+}
+
+fn make(user_id: u64) -> User {
+    User { user_id: UserId(user_id), admin: false }
+}
+
+fn next(u: &User) -> u64 {
+    u.user_id.0 + 1
+}
+
+fn same(a: &User, b: &User) -> bool {
+    a.user_id == b.user_id
+}
+
+fn is_root(u: &User) -> bool {
+    u.user_id == UserId(0)
+}
+
+fn raw(u: &User) -> u64 {
+    u.user_id.0
+}
+
+fn set(u: &mut User) {
+    u.user_id = UserId(5);
+    u.user_id.0 += 1;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn field_flows_into_other_struct() {
+        check_assist(
+            introduce_newtype,
+            r#"
+pub struct Order {
+    pub amount: $0u64,
+}
+
+struct Line {
+    amount: u64,
+}
+
+fn line(o: &Order) -> Line {
+    let amount = o.amount;
+    Line { amount }
+}
+
+fn order(amount: u64) -> Order {
+    Order { amount: amount * 2 }
+}
+"#,
+            r#"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Amount(pub u64);
+
+pub struct Order {
+    pub amount: Amount,
+}
+
+struct Line {
+    amount: u64,
+}
+
+fn line(o: &Order) -> Line {
+    let amount = o.amount;
+    Line { amount: amount.0 }
+}
+
+fn order(amount: u64) -> Order {
+    Order { amount: Amount(amount * 2) }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable() {
+        check_assist_not_applicable(introduce_newtype, r#"type Ref$0<'a> = &'a str;"#);
+        check_assist_not_applicable(introduce_newtype, r#"type Id$0<T> = T;"#);
+        check_assist_not_applicable(
+            introduce_newtype,
+            r#"
+trait Tr { type Out; }
+struct S;
+impl Tr for S { type Out$0 = u32; }
+"#,
+        );
+        check_assist_not_applicable(
+            introduce_newtype,
+            r#"
+struct Id;
+struct User { id: $0u64 }
+"#,
+        );
+        check_assist_not_applicable(introduce_newtype, r#"struct User<'a> { name: &'a $0str }"#);
+        check_assist_not_applicable(introduce_newtype, r#"struct User { $0id: u64 }"#);
+    }
+}
@@ -0,0 +1,578 @@
+use hir::{AsAssocItem, FileRange, ModuleDef};
+use ide_db::{
+    FxHashSet,
+    assists::{AssistId, GroupLabel},
+    change_signature::{NewParam, change_signature, check_can_change_signature},
+    defs::Definition,
+    helpers::mod_path_to_ast,
+    imports::insert_use::{ImportScope, insert_use},
+    search::{FileReferenceNode, ReferenceCategory, SearchScope},
+    source_change::ChangeAnnotation,
+};
+use itertools::Itertools;
+use stdx::{never, to_camel_case};
+use syntax::{
+    AstNode, SyntaxKind, T, TextRange,
+    ast::{self, HasGenericParams, HasName, HasVisibility, edit::IndentLevel, make},
+};
+
+use crate::{
+    assist_context::{AssistContext, Assists},
+    utils::location,
+};
+
+// Assist: introduce_parameter_object
+//
+// Replaces the selected parameters of a function with a struct holding them,
+// which the callers construct from their arguments.
+//
+// ```
+// fn draw($0x: i32, y: i32, color: u32$0) {
+//     let _ = (x, y, color);
+// }
+//
+// fn main() {
+//     draw(1, 2, 0xff);
+// }
+// ```
+// ->
+// ```
+// struct DrawParams {
+//     x: i32,
+//     y: i32,
+//     color: u32,
+// }
+//
+// fn draw(params: DrawParams) {
+//     let _ = (params.x, params.y, params.color);
+// }
+//
+// fn main() {
+//     draw(DrawParams { x: 1, y: 2, color: 0xff });
+// }
+// ```
+pub(crate) fn introduce_parameter_object(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let param_list = ctx.find_node_at_offset::<ast::ParamList>()?;
+    let fn_ = param_list.syntax().parent().and_then(ast::Fn::cast)?;
+    let params: Vec<_> = param_list.params().collect();
+    // Without a selection all the parameters go into the struct.
+    let selection = ctx.selection_trimmed();
+    let indices: Vec<_> = params
+        .iter()
+        .positions(|param| {
+            selection.is_empty()
+                || param.syntax().text_range().intersect(selection).is_some_and(|it| !it.is_empty())
+        })
+        .collect();
+    if indices.is_empty() || (selection.is_empty() && indices.len() < 2) {
+        return None;
+    }
+
+    let db = ctx.db();
+    let func = ctx.sema.to_def(&fn_)?;
+    if func.as_assoc_item(db).and_then(|it| it.container_or_implemented_trait(db)).is_some()
+        || check_can_change_signature(&ctx.sema, func).is_err()
+    {
+        return None;
+    }
+    let generic_params: Vec<_> = fn_
+        .generic_param_list()
+        .into_iter()
+        .flat_map(|it| it.generic_params())
+        .filter_map(|param| match param {
+            ast::GenericParam::TypeParam(it) => it.name(),
+            ast::GenericParam::ConstParam(it) => it.name(),
+            ast::GenericParam::LifetimeParam(_) => None,
+        })
+        .map(|it| it.to_string())
+        .collect();
+
+    let mut fields = Vec::new();
+    let mut has_lifetime = false;
+    for &index in &indices {
+        let param = &params[index];
+        let Some(ast::Pat::IdentPat(pat)) = param.pat() else { return None };
+        if pat.ref_token().is_some() || pat.pat().is_some() {
+            return None;
+        }
+        let (ty, elided) = field_type(&param.ty()?, &generic_params)?;
+        has_lifetime |= elided;
+        fields.push(Field {
+            name: pat.name()?.to_string(),
+            ty,
+            local: ctx.sema.to_def(&pat)?,
+            mutable: pat.mut_token().is_some(),
+        });
+    }
+
+    let struct_name = format!("{}Params", to_camel_case(&fn_.name()?.text()));
+    let mut name_taken = false;
+    ctx.sema.scope(fn_.syntax())?.process_all_names(&mut |name, _| {
+        name_taken |= name.as_str() == struct_name;
+    });
+    let is_used_in_fn = |name: &str| {
+        fn_.syntax().descendants().any(|it| {
+            matches!(it.kind(), SyntaxKind::NAME | SyntaxKind::NAME_REF) && it.text() == name
+        })
+    };
+    let pattern = ["params", "args", "options"].into_iter().find(|it| !is_used_in_fn(it))?;
+    if name_taken {
+        return None;
+    }
+
+    // A recursive call would be rewritten along with the arguments it passes.
+    let fn_scope = SearchScope::file_range(FileRange {
+        file_id: ctx.file_id(),
+        range: fn_.syntax().text_range(),
+    });
+    if Definition::Function(func).usages(&ctx.sema).in_scope(&fn_scope).at_least_one() {
+        return None;
+    }
+    // Without a way to show the uses left as they are, the assist is only offered when every
+    // use of the parameters can be rewritten, so they have to be found before offering it.
+    // Otherwise they are found on demand.
+    let body_uses = if ctx.config.show_rename_conflicts {
+        None
+    } else {
+        let body_uses = BodyUses::collect(ctx, &fields);
+        if !body_uses.blocked.is_empty() {
+            return None;
+        }
+        Some(body_uses)
+    };
+
+    let target_module = ctx.sema.scope(fn_.syntax())?.module().nearest_non_block_module(db);
+    // Structs can't be declared in an `impl`, so it goes before it.
+    let anchor = fn_
+        .syntax()
+        .ancestors()
+        .find(|it| it.kind() == SyntaxKind::IMPL)
+        .unwrap_or_else(|| fn_.syntax().clone());
+    let indent = IndentLevel::from_node(&anchor);
+    let vis = fn_.visibility().map(|it| format!("{it} ")).unwrap_or_default();
+    let generics = if has_lifetime { "<'a>" } else { "" };
+    let param_ty = if has_lifetime { format!("{struct_name}<'_>") } else { struct_name.clone() };
+    let pattern = if fields.iter().any(|it| it.mutable) {
+        format!("mut {pattern}")
+    } else {
+        pattern.to_owned()
+    };
+
+    let group = GroupLabel("Introduce parameter object".to_owned());
+    let target =
+        indices.iter().map(|&it| params[it].syntax().text_range()).reduce(TextRange::cover)?;
+    for named in [true, false] {
+        let label =
+            if named { "Introduce parameter struct" } else { "Introduce parameter tuple struct" };
+        acc.add_group(
+            &group,
+            AssistId::refactor_extract("introduce_parameter_object"),
+            label,
+            target,
+            |builder| {
+                let body_uses =
+                    body_uses.clone().unwrap_or_else(|| BodyUses::collect(ctx, &fields));
+                if !body_uses.blocked.is_empty() {
+                    builder.annotate(ChangeAnnotation {
+                        label: format!(
+                            "This leaves {} use(s) of the parameters as they are",
+                            body_uses.blocked.len()
+                        ),
+                        needs_confirmation: true,
+                        description: Some(format!(
+                            "Uses in format strings:\n{}",
+                            body_uses
+                                .blocked
+                                .iter()
+                                .map(|&range| location(db, ctx.file_id(), range))
+                                .format("\n")
+                        )),
+                    });
+                }
+                let grouped = NewParam::Grouped {
+                    pattern: pattern.clone(),
+                    ty: param_ty.clone(),
+                    struct_name: struct_name.clone(),
+                    indices: indices.clone(),
+                    field_names: named.then(|| fields.iter().map(|it| it.name.clone()).collect()),
+                };
+                let new_params: Vec<_> = (0..params.len())
+                    .filter_map(|index| match index {
+                        _ if index == indices[0] => Some(grouped.clone()),
+                        _ if indices.contains(&index) => None,
+                        _ => Some(NewParam::Existing { index, ty: None }),
+                    })
+                    .collect();
+                match change_signature(&ctx.sema, func, &new_params) {
+                    Ok(edit) => edit.apply(builder),
+                    Err(err) => {
+                        never!("failed to introduce a parameter object: {}", err);
+                        return;
+                    }
+                }
+
+                builder.edit_file(ctx.vfs_file_id());
+                let struct_def = if named {
+                    let fields = fields
+                        .iter()
+                        .map(|it| format!("\n{indent}    {vis}{}: {},", it.name, it.ty))
+                        .join("");
+                    format!("{vis}struct {struct_name}{generics} {{{fields}\n{indent}}}")
+                } else {
+                    let fields = fields.iter().map(|it| format!("{vis}{}", it.ty)).join(", ");
+                    format!("{vis}struct {struct_name}{generics}({fields});")
+                };
+                let pattern = pattern.trim_start_matches("mut ");
+                builder.insert(anchor.text_range().start(), format!("{struct_def}\n\n{indent}"));
+                for &(idx, range, is_shorthand) in &body_uses.uses {
+                    let field = if named { fields[idx].name.clone() } else { idx.to_string() };
+                    let access = format!("{pattern}.{field}");
+                    let replacement = if is_shorthand {
+                        format!("{}: {access}", fields[idx].name)
+                    } else {
+                        access
+                    };
+                    builder.replace(range, replacement);
+                }
+
+                // The callers in other modules need to import the struct.
+                let mut visited_modules = FxHashSet::default();
+                for (file_id, refs) in Definition::Function(func).usages(&ctx.sema).all().iter() {
+                    builder.edit_file(file_id.file_id(db));
+                    for reference in refs {
+                        let Some(name_ref) = reference.name.as_name_ref() else { continue };
+                        let Some(scope) = ctx.sema.scope(name_ref.syntax()) else { continue };
+                        let module = scope.module();
+                        if reference.category.contains(ReferenceCategory::IMPORT)
+                            || module.nearest_non_block_module(db) == target_module
+                            || !visited_modules.insert(module)
+                        {
+                            continue;
+                        }
+                        let cfg =
+                            ctx.config.find_path_config(ctx.sema.is_nightly(module.krate(db)));
+                        let path = module.find_use_path(
+                            db,
+                            ModuleDef::Module(target_module),
+                            ctx.config.insert_use.prefix_kind,
+                            cfg,
+                        );
+                        let Some(path) = path else { continue };
+                        let path = make::path_concat(
+                            mod_path_to_ast(&path, target_module.krate(db).edition(db)),
+                            make::path_from_text(&struct_name),
+                        );
+                        let Some(import_scope) =
+                            ImportScope::find_insert_use_container(name_ref.syntax(), &ctx.sema)
+                        else {
+                            continue;
+                        };
+                        let import_scope = builder.make_import_scope_mut(import_scope);
+                        insert_use(&import_scope, path, &ctx.config.insert_use);
+                    }
+                }
+            },
+        );
+    }
+    Some(())
+}
+
+struct Field {
+    name: String,
+    ty: String,
+    local: hir::Local,
+    mutable: bool,
+}
+
+/// The uses of the grouped parameters in the body of the function, as the index of the field,
+/// the range and whether it's a shorthand field of a record expression, and the uses in format
+/// strings that can't be rewritten.
+#[derive(Clone)]
+struct BodyUses {
+    uses: Vec<(usize, TextRange, bool)>,
+    blocked: Vec<TextRange>,
+}
+
+impl BodyUses {
+    fn collect(ctx: &AssistContext<'_>, fields: &[Field]) -> BodyUses {
+        let mut body_uses = BodyUses { uses: Vec::new(), blocked: Vec::new() };
+        for (idx, field) in fields.iter().enumerate() {
+            for (_, refs) in Definition::Local(field.local).usages(&ctx.sema).all() {
+                for reference in refs {
+                    let FileReferenceNode::NameRef(name_ref) = reference.name else {
+                        body_uses.blocked.push(reference.range);
+                        continue;
+                    };
+                    let is_shorthand = ast::RecordExprField::for_field_name(&name_ref)
+                        .is_some_and(|it| it.name_ref().is_none());
+                    body_uses.uses.push((idx, reference.range, is_shorthand));
+                }
+            }
+        }
+        body_uses.blocked.sort_by_key(|it| it.start());
+        body_uses
+    }
+}
+
+/// The type of a field of the struct for a parameter of type `ty`, with the elided
+/// lifetimes replaced by `'a`, and whether there were any. Types that depend on the
+/// generic parameters of the function aren't supported.
+fn field_type(ty: &ast::Type, generic_params: &[String]) -> Option<(String, bool)> {
+    let mut edits = Vec::new();
+    for node in ty.syntax().descendants() {
+        match node.kind() {
+            SyntaxKind::IMPL_TRAIT_TYPE => return None,
+            SyntaxKind::NAME_REF if generic_params.iter().any(|it| node.text() == it.as_str()) => {
+                return None;
+            }
+            SyntaxKind::REF_TYPE => {
+                let ref_ty = ast::RefType::cast(node)?;
+                if ref_ty.lifetime().is_none() {
+                    edits.push((TextRange::empty(ref_ty.amp_token()?.text_range().end()), "'a "));
+                }
+            }
+            SyntaxKind::LIFETIME => match node.text().to_string().as_str() {
+                "'_" => edits.push((node.text_range(), "'a")),
+                "'static" => (),
+                _ => return None,
+            },
+            _ => (),
+        }
+    }
+    if ty.syntax().descendants_with_tokens().any(|it| it.kind() == T![Self]) {
+        return None;
+    }
+
+    let start = ty.syntax().text_range().start();
+    let mut text = ty.to_string();
+    for (range, insert) in edits.iter().rev() {
+        let range = range - start;
+        text.replace_range(std::ops::Range::<usize>::from(range), insert);
+    }
+    Some((text, !edits.is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use crate::{
+        AssistConfig,
+        tests::{
+            TEST_CONFIG, check_assist, check_assist_annotations, check_assist_by_label,
+            check_assist_not_applicable, check_assist_not_applicable_with_config,
+        },
+    };
+
+    use super::*;
+
+    #[test]
+    fn group_all_params() {
+        check_assist(
+            introduce_parameter_object,
+            r#"
+fn draw(x: i32, y$0: i32) -> i32 {
+    x + y
+}
+
+fn main() {
+    let x = 1;
+    draw(x, 2);
+    let f: fn(i32, i32) -> i32 = draw;
+}
+"#,
+            r#"
+struct DrawParams {
+    x: i32,
+    y: i32,
+}
+
+fn draw(params: DrawParams) -> i32 {
+    params.x + params.y
+}
+
+fn main() {
+    let x = 1;
+    draw(DrawParams { x, y: 2 });
+    let f: fn(i32, i32) -> i32 = |x, y| draw(DrawParams { x, y });
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn group_selected_params_into_tuple_struct() {
+        check_assist_by_label(
+            introduce_parameter_object,
+            r#"
+fn scale(factor: u32, $0mut x: u32, y: u32$0) -> (u32, u32) {
+    x *= factor;
+    (x, y * factor)
+}
+
+fn main() {
+    scale(2, 3, 4);
+}
+"#,
+            r#"
+struct ScaleParams(u32, u32);
+
+fn scale(factor: u32, mut params: ScaleParams) -> (u32, u32) {
+    params.0 *= factor;
+    (params.0, params.1 * factor)
+}
+
+fn main() {
+    scale(2, ScaleParams(3, 4));
+}
+"#,
+            "Introduce parameter tuple struct",
+        );
+    }
+
+    #[test]
+    fn method_with_callers_in_other_module() {
+        check_assist(
+            introduce_parameter_object,
+            r#"
+mod geometry {
+    pub struct Canvas;
+
+    impl Canvas {
+        pub fn rect(&self, $0width: u32, height: u32) -> u32 {
+            let area = Area { width };
+            area.width * height
+        }
+    }
+
+    struct Area {
+        width: u32,
+    }
+}
+
+fn main() {
+    let _ = geometry::Canvas.rect(2, 3);
+}
+"#,
+            r#"
+use geometry::RectParams;
+
+mod geometry {
+    pub struct Canvas;
+
+    pub struct RectParams {
+        pub width: u32,
+        pub height: u32,
+    }
+
+    impl Canvas {
+        pub fn rect(&self, params: RectParams) -> u32 {
+            let area = Area { width: params.width };
+            area.width * params.height
+        }
+    }
+
+    struct Area {
+        width: u32,
+    }
+}
+
+fn main() {
+    let _ = geometry::Canvas.rect(RectParams { width: 2, height: 3 });
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn elided_lifetimes() {
+        check_assist(
+            introduce_parameter_object,
+            r#"
+fn greet($0greeting: &str, name: &'static str) -> usize {
+    greeting.len() + name.len()
+}
+"#,
+            r#"
+struct GreetParams<'a> {
+    greeting: &'a str,
+    name: &'static str,
+}
+
+fn greet(params: GreetParams<'_>) -> usize {
+    params.greeting.len() + params.name.len()
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_trait_methods_and_generic_params() {
+        check_assist_not_applicable(
+            introduce_parameter_object,
+            r#"
+trait Tr {
+    fn f(&self, $0a: u32, b: u32);
+}
+"#,
+        );
+        check_assist_not_applicable(
+            introduce_parameter_object,
+            r#"
+fn f<T>($0a: T, b: u32) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_recursive_functions() {
+        check_assist_not_applicable(
+            introduce_parameter_object,
+            r#"
+fn count($0n: u32, acc: u32) -> u32 {
+    if n == 0 { acc } else { count(n - 1, acc + 1) }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_outside_the_workspace() {
+        check_assist_not_applicable(
+            introduce_parameter_object,
+            r#"
+//- /main.rs crate:main deps:dep
+fn main() {
+    dep::draw(1, 2);
+}
+//- /dep.rs crate:dep library
+pub fn draw($0x: i32, y: i32) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn reports_uses_in_format_strings() {
+        let fixture = r#"
+//- minicore: fmt
+fn draw(x: i32, y$0: i32) {
+    format_args!("{x}, {}", y);
+}
+"#;
+        check_assist_annotations(
+            introduce_parameter_object,
+            fixture,
+            "Introduce parameter struct",
+            expect![[r#"
+                This leaves 1 use(s) of the parameters as they are
+                Uses in format strings:
+                /main.rs:2:20
+            "#]],
+        );
+        check_assist_not_applicable_with_config(
+            introduce_parameter_object,
+            AssistConfig { show_rename_conflicts: false, ..TEST_CONFIG },
+            fixture,
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use hir::ModuleDef;
+use ide_db::{
+    assists::GroupLabel,
+    move_to_module::{ItemMove, MoveToModuleConfig, items_in_range},
+};
+use syntax::{AstNode, SyntaxKind, TextRange};
+
+use crate::{AssistContext, AssistId, Assists};
+
+// Assist: move_item_to_module
+//
+// Moves the selected items into the parent module or one of the child modules,
+// fixing up the paths that refer to them.
+//
+// ```
+// mod util {}
+//
+// fn helper$0() -> u32 { 1 }
+//
+// fn main() {
+//     helper();
+// }
+// ```
+// ->
+// ```
+// use util::helper;
+//
+// mod util {
+//     pub(crate) fn helper() -> u32 { 1 }
+// }
+//
+// fn main() {
+//     helper();
+// }
+// ```
+pub(crate) fn move_item_to_module(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let selection = ctx.selection_trimmed();
+    let items = items_in_range(ctx.source_file(), selection);
+    let first = items.first()?;
+    if selection.is_empty() {
+        // Don't offer to move a whole function when the cursor is in its body.
+        let body = first.syntax().children().find(|it| {
+            matches!(
+                it.kind(),
+                SyntaxKind::BLOCK_EXPR
+                    | SyntaxKind::ASSOC_ITEM_LIST
+                    | SyntaxKind::RECORD_FIELD_LIST
+                    | SyntaxKind::VARIANT_LIST
+            )
+        });
+        if body.is_some_and(|it| it.text_range().start() < ctx.offset()) {
+            return None;
+        }
+    }
+
+    let db = ctx.db();
+    let source = ctx.sema.scope(first.syntax())?.module();
+    let krate = source.krate(db);
+    let edition = krate.edition(db);
+    let config = MoveToModuleConfig {
+        insert_use: ctx.config.insert_use,
+        find_path: ctx.config.find_path_config(ctx.sema.is_nightly(krate)),
+    };
+    let target_range = items.iter().map(|it| it.syntax().text_range()).reduce(TextRange::cover)?;
+
+    let group = GroupLabel("Move item to module".to_owned());
+    for target in source.parent(db).into_iter().chain(source.children(db)) {
+        let Ok(mv) = ItemMove::new(&ctx.sema, ctx.file_id(), items.clone(), target) else {
+            continue;
+        };
+        let Some(path) = source.find_path(db, ModuleDef::Module(target), config.find_path) else {
+            continue;
+        };
+        acc.add_group(
+            &group,
+            AssistId::refactor("move_item_to_module"),
+            format!("Move to module `{}`", path.display(db, edition)),
+            target_range,
+            |builder| {
+                mv.apply(&ctx.sema, &config, builder);
+            },
+        );
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn move_to_child_module() {
+        check_assist(
+            move_item_to_module,
+            r#"
+mod util {
+    pub fn zero() -> u32 {
+        0
+    }
+}
+
+fn one$0() -> u32 {
+    util::zero() + 1
+}
+
+fn main() {
+    let _ = one();
+}
+"#,
+            r#"
+use util::one;
+
+mod util {
+    pub fn zero() -> u32 {
+        0
+    }
+
+    pub(crate) fn one() -> u32 {
+        self::zero() + 1
+    }
+}
+
+fn main() {
+    let _ = one();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn move_to_parent_module() {
+        check_assist_by_label(
+            move_item_to_module,
+            r#"
+mod a {
+    mod b {}
+
+    pub struct Foo$0;
+}
+
+fn main() {
+    let _ = a::Foo;
+}
+"#,
+            r#"
+mod a {
+    mod b {}
+}
+
+fn main() {
+    let _ = Foo;
+}
+
+pub struct Foo;
+"#,
+            "Move to module `crate`",
+        );
+    }
+
+    #[test]
+    fn move_selected_items() {
+        check_assist(
+            move_item_to_module,
+            r#"
+mod shapes {}
+
+$0struct Square(u32);
+
+impl Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+}$0
+"#,
+            r#"
+mod shapes {
+    struct Square(u32);
+
+    impl Square {
+        fn area(&self) -> u32 {
+            self.0 * self.0
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_in_body() {
+        check_assist_not_applicable(
+            move_item_to_module,
+            r#"
+mod util {}
+
+fn one() -> u32 {
+    $01
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_without_other_module() {
+        check_assist_not_applicable(
+            move_item_to_module,
+            r#"
+fn one$0() -> u32 {
+    1
+}
+"#,
+        );
+    }
+}
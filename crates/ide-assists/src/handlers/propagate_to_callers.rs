@@ -0,0 +1,785 @@
+use hir::{AsAssocItem, HasCrate, HirDisplay};
+use ide_db::{
+    EditionedFileId, FxHashMap, FxHashSet,
+    defs::Definition,
+    famous_defs::FamousDefs,
+    source_change::ChangeAnnotation,
+    syntax_helpers::node_ext::{for_each_tail_expr, walk_expr},
+};
+use itertools::Itertools;
+use stdx::format_to;
+use syntax::{
+    AstNode, TextRange, TextSize,
+    ast::{self, HasName, edit::IndentLevel},
+    match_ast,
+};
+
+use crate::{
+    AssistContext, AssistId, Assists, handlers::wrap_return_type::tail_cb_impl, utils::location,
+};
+
+// Assist: make_async_and_propagate
+//
+// Makes the function `async` and awaits it at every call site, making the callers `async` in
+// turn until reaching ones that already are.
+//
+// ```
+// fn read_config() -> u32$0 { 0 }
+// fn load() -> u32 { read_config() + 1 }
+// async fn run() { load(); }
+// ```
+// ->
+// ```
+// async fn read_config() -> u32 { 0 }
+// async fn load() -> u32 { read_config().await + 1 }
+// async fn run() { load().await; }
+// ```
+pub(crate) fn make_async_and_propagate(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    propagate(acc, ctx, Effect::Async)
+}
+
+// Assist: make_fallible_and_propagate
+//
+// Makes the function return a `Result` and applies `?` at every call site, converting the
+// callers in turn until reaching ones that already return a `Result`, whose error type is used.
+//
+// ```
+// # //- minicore: result
+// struct Error;
+// fn parse$0(s: &str) -> u32 { 0 }
+// fn load(s: &str) -> u32 {
+//     parse(s) + 1
+// }
+// fn run() -> Result<(), Error> {
+//     load("1");
+//     Ok(())
+// }
+// ```
+// ->
+// ```
+// struct Error;
+// fn parse(s: &str) -> Result<u32, Error> { Ok(0) }
+// fn load(s: &str) -> Result<u32, Error> {
+//     Ok(parse(s)? + 1)
+// }
+// fn run() -> Result<(), Error> {
+//     load("1")?;
+//     Ok(())
+// }
+// ```
+pub(crate) fn make_fallible_and_propagate(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    propagate(acc, ctx, Effect::Fallible)
+}
+
+fn propagate(acc: &mut Assists, ctx: &AssistContext<'_>, effect: Effect) -> Option<()> {
+    let fn_ = ctx.find_node_at_offset::<ast::Fn>()?;
+    if fn_.body().is_some_and(|body| body.syntax().text_range().contains(ctx.offset())) {
+        return None;
+    }
+    let name = fn_.name()?;
+    let func = ctx.sema.to_def(&fn_)?;
+    if effect.is_applied(ctx, func) || !effect.can_change(ctx, func, &fn_) {
+        return None;
+    }
+
+    // Without a way to show the calls left as they are, the assist is only offered when every
+    // call can be adjusted, so the callers have to be found before offering it. Otherwise they
+    // are found on demand.
+    let propagation = if ctx.config.show_rename_conflicts {
+        None
+    } else {
+        let propagation = Propagation::collect(ctx, func, fn_.clone(), effect);
+        if !propagation.blocked.is_empty() {
+            return None;
+        }
+        Some(propagation)
+    };
+
+    acc.add(
+        effect.assist_id(),
+        format!("Make `{name}` {} and propagate to callers", effect.adjective()),
+        name.syntax().text_range(),
+        |builder| {
+            let db = ctx.db();
+            let propagation =
+                propagation.unwrap_or_else(|| Propagation::collect(ctx, func, fn_, effect));
+            let callers = propagation.changed.len() - 1;
+            if callers > 0 || !propagation.blocked.is_empty() {
+                let label = if propagation.blocked.is_empty() {
+                    format!("This also makes {callers} caller(s) {}", effect.adjective())
+                } else {
+                    format!(
+                        "This leaves {} call(s) that can't be adjusted",
+                        propagation.blocked.len()
+                    )
+                };
+                let mut description = format!(
+                    "Changed functions:\n{}",
+                    propagation
+                        .changed
+                        .iter()
+                        .filter_map(|(_, file_id, fn_)| {
+                            let name = fn_.name()?;
+                            let range = name.syntax().text_range();
+                            Some(format!("`{name}` at {}", location(db, *file_id, range)))
+                        })
+                        .format("\n")
+                );
+                if !propagation.blocked.is_empty() {
+                    format_to!(
+                        description,
+                        "\n\nCalls that are left as they are:\n{}",
+                        propagation
+                            .blocked
+                            .iter()
+                            .map(|(file_id, range, reason)| {
+                                format!("{}: {reason}", location(db, *file_id, *range))
+                            })
+                            .format("\n")
+                    );
+                }
+                builder.annotate(ChangeAnnotation {
+                    label,
+                    needs_confirmation: true,
+                    description: Some(description),
+                });
+            }
+
+            for (file_id, mut insertions) in propagation.insertions(ctx) {
+                builder.edit_file(file_id.file_id(db));
+                // Sorting by `Order` keeps an `.await` or `?` inside an `Ok(…)` wrapping the call.
+                insertions.sort_by_key(|&(offset, order, _)| (offset, order));
+                for (offset, _, text) in insertions {
+                    builder.insert(offset, text);
+                }
+            }
+        },
+    )
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Effect {
+    Async,
+    Fallible,
+}
+
+impl Effect {
+    fn assist_id(self) -> AssistId {
+        match self {
+            Effect::Async => AssistId::refactor_rewrite("make_async_and_propagate"),
+            Effect::Fallible => AssistId::refactor_rewrite("make_fallible_and_propagate"),
+        }
+    }
+
+    fn adjective(self) -> &'static str {
+        match self {
+            Effect::Async => "async",
+            Effect::Fallible => "fallible",
+        }
+    }
+
+    fn postfix(self) -> &'static str {
+        match self {
+            Effect::Async => ".await",
+            Effect::Fallible => "?",
+        }
+    }
+
+    /// Whether calls to `func` can already be awaited or propagated with `?`, which is where the
+    /// propagation stops.
+    fn is_applied(self, ctx: &AssistContext<'_>, func: hir::Function) -> bool {
+        let db = ctx.db();
+        match self {
+            Effect::Async => func.is_async(db),
+            Effect::Fallible => {
+                let result = FamousDefs(&ctx.sema, func.krate(db)).core_result_Result();
+                result.is_some_and(|result| {
+                    func.ret_type(db).as_adt() == Some(hir::Adt::Enum(result))
+                })
+            }
+        }
+    }
+
+    fn can_change(self, ctx: &AssistContext<'_>, func: hir::Function, fn_: &ast::Fn) -> bool {
+        let db = ctx.db();
+        // Changing a trait method would mean changing every implementation, and a `const fn`
+        // can neither be `async` nor use `?`.
+        if fn_.body().is_none()
+            || func.as_assoc_item(db).and_then(|it| it.container_or_implemented_trait(db)).is_some()
+            || func.is_const(db)
+        {
+            return false;
+        }
+        match self {
+            Effect::Async => fn_.abi().is_none() && !func.is_main(db) && !func.is_test(db),
+            Effect::Fallible => true,
+        }
+    }
+}
+
+struct Propagation<'db> {
+    effect: Effect,
+    /// The functions whose signature changes, starting with the one under the cursor.
+    changed: Vec<(hir::Function, EditionedFileId, ast::Fn)>,
+    /// The calls to await or to apply `?` to.
+    calls: Vec<(EditionedFileId, ast::Expr)>,
+    /// The uses of changed functions that can't be adjusted, and why.
+    blocked: Vec<(EditionedFileId, TextRange, &'static str)>,
+    /// The error type of the first caller found that already returns a `Result`.
+    error_ty: Option<hir::Type<'db>>,
+}
+
+impl<'db> Propagation<'db> {
+    /// Walks the incoming calls of `func` the way the call hierarchy does, converting callers
+    /// until reaching ones that already have the effect. Uses that can't be adjusted, e.g. when
+    /// the function is used as a value or called from a closure, are left as they are.
+    fn collect(
+        ctx: &AssistContext<'db>,
+        func: hir::Function,
+        fn_: ast::Fn,
+        effect: Effect,
+    ) -> Self {
+        let db = ctx.db();
+        let mut propagation = Propagation {
+            effect,
+            changed: Vec::new(),
+            calls: Vec::new(),
+            blocked: Vec::new(),
+            error_ty: None,
+        };
+        let mut seen = FxHashSet::default();
+        seen.insert(func);
+        let mut queue = vec![(func, ctx.file_id(), fn_)];
+        while let Some((func, file_id, fn_)) = queue.pop() {
+            propagation.changed.push((func, file_id, fn_));
+
+            for (file_id, references) in Definition::Function(func).usages(&ctx.sema).all() {
+                for reference in references {
+                    let mut block =
+                        |reason| propagation.blocked.push((file_id, reference.range, reason));
+                    let Some(name_ref) = reference.name.as_name_ref() else {
+                        block("the function is not called");
+                        continue;
+                    };
+                    if name_ref.syntax().ancestors().any(|it| ast::UseTree::can_cast(it.kind())) {
+                        continue;
+                    }
+                    let Some(call) = call_expr(name_ref) else {
+                        block("the function is not called");
+                        continue;
+                    };
+                    if ctx.sema.hir_file_for(call.syntax()).is_macro() {
+                        block("the call is inside a macro call");
+                        continue;
+                    }
+                    let caller = match enclosing_fn(&call, effect) {
+                        Some(Some(caller)) => match ctx.sema.to_def(&caller) {
+                            Some(def) => Some((def, caller)),
+                            None => {
+                                block("the caller can't be changed");
+                                continue;
+                            }
+                        },
+                        Some(None) => None,
+                        None => {
+                            block("the call is in a closure or block the effect can't reach");
+                            continue;
+                        }
+                    };
+                    let Some((caller, caller_fn)) = caller else {
+                        propagation.calls.push((file_id, call));
+                        continue;
+                    };
+                    if effect.is_applied(ctx, caller) {
+                        if propagation.error_ty.is_none() {
+                            propagation.error_ty = caller.ret_type(db).type_arguments().nth(1);
+                        }
+                    } else if !effect.can_change(ctx, caller, &caller_fn) {
+                        block("the caller can't be changed");
+                        continue;
+                    } else if seen.insert(caller) {
+                        queue.push((caller, file_id, caller_fn));
+                    }
+                    propagation.calls.push((file_id, call));
+                }
+            }
+        }
+        propagation.blocked.sort_by_key(|&(file_id, range, _)| (file_id, range.start()));
+        propagation
+    }
+
+    fn insertions(
+        &self,
+        ctx: &AssistContext<'_>,
+    ) -> FxHashMap<EditionedFileId, Vec<(TextSize, Order, String)>> {
+        let db = ctx.db();
+        let mut insertions: FxHashMap<_, Vec<_>> = FxHashMap::default();
+        for (file_id, call) in &self.calls {
+            let offset = call.syntax().text_range().end();
+            let postfix = self.effect.postfix().to_owned();
+            insertions.entry(*file_id).or_default().push((offset, Order::Postfix, postfix));
+        }
+        for (func, file_id, fn_) in &self.changed {
+            let acc = insertions.entry(*file_id).or_default();
+            match self.effect {
+                Effect::Async => {
+                    if let Some(token) = fn_.unsafe_token().or_else(|| fn_.fn_token()) {
+                        acc.push((token.text_range().start(), Order::Prefix, "async ".to_owned()));
+                    }
+                }
+                Effect::Fallible => {
+                    let error = self
+                        .error_ty
+                        .as_ref()
+                        .and_then(|ty| {
+                            ty.display_source_code(db, func.module(db).into(), true).ok()
+                        })
+                        .unwrap_or_else(|| "Box<dyn std::error::Error>".to_owned());
+                    make_fallible(acc, fn_, func.ret_type(db).is_unit(), &error);
+                }
+            }
+        }
+        insertions
+    }
+}
+
+/// The order of insertions at the same offset: a postfix belongs to the expression ending there,
+/// before anything closing an enclosing construct.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Order {
+    Postfix,
+    Suffix,
+    Prefix,
+}
+
+/// Returns the call expression `name_ref` is the callee of.
+pub(crate) fn call_expr(name_ref: &ast::NameRef) -> Option<ast::Expr> {
+    let parent = name_ref.syntax().parent()?;
+    if let Some(method_call) = ast::MethodCallExpr::cast(parent.clone()) {
+        return Some(method_call.into());
+    }
+    let path = ast::PathSegment::cast(parent)?.parent_path();
+    let path_expr = ast::PathExpr::cast(path.syntax().parent()?)?;
+    let call = ast::CallExpr::cast(path_expr.syntax().parent()?)?;
+    (call.expr()?.syntax() == path_expr.syntax()).then(|| call.into())
+}
+
+/// Returns the function whose body `call` is in, `Some(None)` if it is in an `async` block or
+/// closure that can await it directly, and `None` if the effect can't reach the call.
+fn enclosing_fn(call: &ast::Expr, effect: Effect) -> Option<Option<ast::Fn>> {
+    for node in call.syntax().ancestors().skip(1) {
+        match_ast! {
+            match node {
+                ast::Fn(it) => return Some(Some(it)),
+                ast::ClosureExpr(it) => {
+                    return (effect == Effect::Async && it.async_token().is_some()).then_some(None);
+                },
+                ast::BlockExpr(it) => match it.modifier() {
+                    Some(ast::BlockModifier::Async(_)) => {
+                        return (effect == Effect::Async).then_some(None);
+                    }
+                    Some(
+                        ast::BlockModifier::AsyncGen(_)
+                        | ast::BlockModifier::Gen(_)
+                        | ast::BlockModifier::Try(_)
+                        | ast::BlockModifier::Const(_),
+                    ) => return None,
+                    _ => (),
+                },
+                ast::Const(_) => return None,
+                ast::Static(_) => return None,
+                _ => (),
+            }
+        }
+    }
+    None
+}
+
+/// Wraps the return type of `fn_` in `Result` and its returned values in `Ok`.
+fn make_fallible(
+    acc: &mut Vec<(TextSize, Order, String)>,
+    fn_: &ast::Fn,
+    returns_unit: bool,
+    error: &str,
+) {
+    let Some(body) = fn_.body() else { return };
+    match fn_.ret_type().and_then(|it| it.ty()) {
+        Some(ty) => {
+            let range = ty.syntax().text_range();
+            acc.push((range.start(), Order::Prefix, "Result<".to_owned()));
+            acc.push((range.end(), Order::Suffix, format!(", {error}>")));
+        }
+        None => {
+            let Some(params) = fn_.param_list() else { return };
+            let offset = params.syntax().text_range().end();
+            acc.push((offset, Order::Suffix, format!(" -> Result<(), {error}>")));
+        }
+    }
+
+    let body = ast::Expr::BlockExpr(body);
+    let mut exprs_to_wrap = Vec::new();
+    walk_expr(&body, &mut |expr| {
+        if let ast::Expr::ReturnExpr(ret_expr) = expr {
+            match ret_expr.expr() {
+                Some(arg) => for_each_tail_expr(&arg, &mut |e| tail_cb_impl(&mut exprs_to_wrap, e)),
+                None => {
+                    let offset = ret_expr.syntax().text_range().end();
+                    acc.push((offset, Order::Suffix, " Ok(())".to_owned()));
+                }
+            }
+        }
+    });
+
+    let ast::Expr::BlockExpr(block) = &body else { return };
+    let Some(stmt_list) = block.stmt_list() else { return };
+    if !returns_unit {
+        for_each_tail_expr(&body, &mut |e| tail_cb_impl(&mut exprs_to_wrap, e));
+    } else if let Some(tail) = stmt_list.tail_expr() {
+        if tail.is_block_like() {
+            let indent = IndentLevel::from_node(tail.syntax());
+            acc.push((
+                tail.syntax().text_range().end(),
+                Order::Suffix,
+                format!("\n{indent}Ok(())"),
+            ));
+        } else {
+            exprs_to_wrap.push(tail);
+        }
+    } else if let Some(stmt) = stmt_list.statements().last() {
+        let indent = IndentLevel::from_node(stmt.syntax());
+        acc.push((stmt.syntax().text_range().end(), Order::Suffix, format!("\n{indent}Ok(())")));
+    } else if let Some(l_curly) = stmt_list.l_curly_token() {
+        acc.push((l_curly.text_range().end(), Order::Suffix, " Ok(())".to_owned()));
+    }
+
+    for expr in exprs_to_wrap {
+        let range = expr.syntax().text_range();
+        acc.push((range.start(), Order::Prefix, "Ok(".to_owned()));
+        acc.push((range.end(), Order::Suffix, ")".to_owned()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use crate::{
+        AssistConfig,
+        tests::{
+            TEST_CONFIG, check_assist, check_assist_annotations, check_assist_not_applicable,
+            check_assist_not_applicable_with_config,
+        },
+    };
+
+    use super::*;
+
+    #[test]
+    fn async_propagates_to_callers() {
+        check_assist(
+            make_async_and_propagate,
+            r#"
+fn fetch$0() -> u32 { 0 }
+fn load() -> u32 {
+    fetch() + fetch()
+}
+fn run() {
+    let x = load();
+}
+async fn main_loop() {
+    run();
+}
+"#,
+            r#"
+async fn fetch() -> u32 { 0 }
+async fn load() -> u32 {
+    fetch().await + fetch().await
+}
+async fn run() {
+    let x = load().await;
+}
+async fn main_loop() {
+    run().await;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn async_awaits_method_calls() {
+        check_assist(
+            make_async_and_propagate,
+            r#"
+struct Client;
+impl Client {
+    pub unsafe fn $0get(&self) -> u32 { 0 }
+}
+async fn use_client(c: &Client) -> u32 {
+    unsafe { c.get() }
+}
+"#,
+            r#"
+struct Client;
+impl Client {
+    pub async unsafe fn get(&self) -> u32 { 0 }
+}
+async fn use_client(c: &Client) -> u32 {
+    unsafe { c.get().await }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn async_stops_at_async_blocks() {
+        check_assist(
+            make_async_and_propagate,
+            r#"
+fn fetch$0() -> u32 { 0 }
+fn spawn() {
+    let fut = async { fetch() };
+    let fut = async || fetch();
+}
+"#,
+            r#"
+async fn fetch() -> u32 { 0 }
+fn spawn() {
+    let fut = async { fetch().await };
+    let fut = async || fetch().await;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn async_across_files() {
+        check_assist(
+            make_async_and_propagate,
+            r#"
+//- /main.rs
+mod net;
+async fn run() {
+    net::fetch();
+}
+//- /net.rs
+pub fn fetch$0() {}
+"#,
+            r#"
+//- /main.rs
+mod net;
+async fn run() {
+    net::fetch().await;
+}
+//- /net.rs
+pub async fn fetch() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn async_not_applicable() {
+        check_assist_not_applicable(make_async_and_propagate, r#"async fn fetch$0() {}"#);
+        check_assist_not_applicable(make_async_and_propagate, r#"fn fetch() { $0 }"#);
+        let config = AssistConfig { show_rename_conflicts: false, ..TEST_CONFIG };
+        check_assist_not_applicable_with_config(
+            make_async_and_propagate,
+            config.clone(),
+            r#"
+fn fetch$0() {}
+fn main() { fetch(); }
+"#,
+        );
+        check_assist_not_applicable_with_config(
+            make_async_and_propagate,
+            config.clone(),
+            r#"
+fn fetch$0() {}
+fn run() { let f = || fetch(); }
+"#,
+        );
+        check_assist_not_applicable_with_config(
+            make_async_and_propagate,
+            config,
+            r#"
+fn fetch$0() {}
+fn run() { let f = fetch; }
+"#,
+        );
+        check_assist_not_applicable(
+            make_async_and_propagate,
+            r#"
+trait Source { fn fetch(&self); }
+struct S;
+impl Source for S { fn fetch$0(&self) {} }
+"#,
+        );
+        check_assist_not_applicable(make_async_and_propagate, r#"const fn fetch$0() {}"#);
+    }
+
+    #[test]
+    fn fallible_propagates_to_callers() {
+        check_assist(
+            make_fallible_and_propagate,
+            r#"
+//- minicore: result
+struct ParseError;
+fn parse$0(s: &str) -> u32 {
+    if s.is_empty() {
+        return 0;
+    }
+    1
+}
+fn load() -> u32 {
+    let n = parse("1");
+    parse("2")
+}
+fn run() -> Result<(), ParseError> {
+    load();
+    Ok(())
+}
+"#,
+            r#"
+struct ParseError;
+fn parse(s: &str) -> Result<u32, ParseError> {
+    if s.is_empty() {
+        return Ok(0);
+    }
+    Ok(1)
+}
+fn load() -> Result<u32, ParseError> {
+    let n = parse("1")?;
+    Ok(parse("2")?)
+}
+fn run() -> Result<(), ParseError> {
+    load()?;
+    Ok(())
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fallible_unit_functions() {
+        check_assist(
+            make_fallible_and_propagate,
+            r#"
+//- minicore: result
+fn check$0(x: u32) {
+    if x == 0 {
+        return;
+    }
+}
+fn validate() {
+    check(1);
+    check(2)
+}
+fn main() {
+    for i in 0..3 {
+        validate();
+    }
+}
+fn empty() {}
+"#,
+            r#"
+fn check(x: u32) -> Result<(), Box<dyn std::error::Error>> {
+    if x == 0 {
+        return Ok(());
+    }
+    Ok(())
+}
+fn validate() -> Result<(), Box<dyn std::error::Error>> {
+    check(1)?;
+    Ok(check(2)?)
+}
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    for i in 0..3 {
+        validate()?;
+    }
+    Ok(())
+}
+fn empty() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn fallible_not_applicable() {
+        check_assist_not_applicable(
+            make_fallible_and_propagate,
+            r#"
+//- minicore: result
+fn parse$0() -> Result<u32, ()> { Ok(0) }
+"#,
+        );
+        let config = AssistConfig { show_rename_conflicts: false, ..TEST_CONFIG };
+        check_assist_not_applicable_with_config(
+            make_fallible_and_propagate,
+            config.clone(),
+            r#"
+//- minicore: result
+fn parse$0() -> u32 { 0 }
+fn run() { let f = || parse(); }
+"#,
+        );
+        check_assist_not_applicable_with_config(
+            make_fallible_and_propagate,
+            config,
+            r#"
+//- minicore: result
+fn parse$0() -> u32 { 0 }
+async fn run() { let f = async { parse() }; }
+"#,
+        );
+    }
+
+    #[test]
+    fn reports_calls_left_as_they_are() {
+        check_assist_annotations(
+            make_async_and_propagate,
+            r#"
+fn fetch$0() {}
+fn load() { fetch(); }
+fn main() {
+    load();
+    let f = || fetch();
+}
+"#,
+            "Make `fetch` async and propagate to callers",
+            expect![[r#"
+                This leaves 2 call(s) that can't be adjusted
+                Changed functions:
+                `fetch` at /main.rs:1:4
+                `load` at /main.rs:2:4
+
+                Calls that are left as they are:
+                /main.rs:4:5: the caller can't be changed
+                /main.rs:5:16: the call is in a closure or block the effect can't reach
+            "#]],
+        );
+    }
+
+    #[test]
+    fn adjusts_the_calls_it_can() {
+        check_assist(
+            make_async_and_propagate,
+            r#"
+fn fetch$0() {}
+fn load() { fetch(); }
+fn main() {
+    load();
+    let f = || fetch();
+}
+"#,
+            r#"
+async fn fetch() {}
+async fn load() { fetch().await; }
+fn main() {
+    load();
+    let f = || fetch();
+}
+"#,
+        );
+    }
+}
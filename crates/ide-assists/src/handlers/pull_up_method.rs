@@ -0,0 +1,656 @@
+use hir::{InFile, ModuleDef};
+use ide_db::{
+    FileId, FxHashMap, FxHashSet, RootDatabase,
+    assists::{AssistId, GroupLabel},
+    defs::Definition,
+    helpers::{mod_path_to_ast, removal_ranges},
+    imports::insert_use::{ImportScope, insert_use},
+    source_change::SourceChangeBuilder,
+};
+use itertools::Itertools;
+use syntax::{
+    AstNode, SyntaxKind, SyntaxNode, TextRange, TextSize,
+    ast::{self, HasGenericParams, HasVisibility, edit::IndentLevel},
+};
+
+use crate::assist_context::{AssistContext, Assists};
+
+// Assist: pull_up_method
+//
+// Moves a method of an inherent impl into an implementation of a trait, adding its
+// signature to the trait and a `todo!()` stub to the other implementations.
+//
+// ```
+// trait Shape {
+//     fn area(&self) -> f64;
+// }
+//
+// struct Square(f64);
+//
+// impl Square {
+//     fn $0perimeter(&self) -> f64 {
+//         4.0 * self.0
+//     }
+// }
+//
+// impl Shape for Square {
+//     fn area(&self) -> f64 {
+//         self.0 * self.0
+//     }
+// }
+//
+// struct Circle(f64);
+//
+// impl Shape for Circle {
+//     fn area(&self) -> f64 {
+//         3.0 * self.0 * self.0
+//     }
+// }
+// ```
+// ->
+// ```
+// trait Shape {
+//     fn area(&self) -> f64;
+//
+//     fn perimeter(&self) -> f64;
+// }
+//
+// struct Square(f64);
+//
+// impl Shape for Square {
+//     fn area(&self) -> f64 {
+//         self.0 * self.0
+//     }
+//
+//     fn perimeter(&self) -> f64 {
+//         4.0 * self.0
+//     }
+// }
+//
+// struct Circle(f64);
+//
+// impl Shape for Circle {
+//     fn area(&self) -> f64 {
+//         3.0 * self.0 * self.0
+//     }
+//
+//     fn perimeter(&self) -> f64 {
+//         todo!()
+//     }
+// }
+// ```
+pub(crate) fn pull_up_method(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let (fn_, impl_) = method_at_cursor::<ast::Impl>(ctx)?;
+    if impl_.trait_().is_some()
+        || impl_.generic_param_list().is_some()
+        || fn_.const_token().is_some()
+    {
+        return None;
+    }
+    let db = ctx.db();
+    let func = ctx.sema.to_def(&fn_)?;
+    let impl_def = ctx.sema.to_def(&impl_)?;
+    let name = func.name(db);
+    let self_ty = impl_def.self_ty(db);
+
+    // The body of the method only resolves the same way when it stays in the same module.
+    let candidates: Vec<_> = hir::Impl::all_for_type(db, self_ty.clone())
+        .into_iter()
+        .filter_map(|trait_impl| {
+            let trait_ = trait_impl.trait_(db)?;
+            let is_candidate = trait_.module(db).krate(db).origin(db).is_local()
+                && trait_impl.module(db) == impl_def.module(db)
+                && trait_.items(db).iter().all(|it| it.name(db).as_ref() != Some(&name));
+            is_candidate.then_some((trait_impl, trait_))
+        })
+        .collect();
+    if candidates.is_empty() {
+        return None;
+    }
+
+    let signature = signature(ctx, &fn_, &self_ty);
+    let stub = format!("{} {{\n    todo!()\n}}", signature.trim_end_matches(';'));
+    let method = item_text(&fn_, fn_.syntax().text_range().end(), Vec::new());
+    let removed = if impl_.assoc_item_list()?.assoc_items().count() == 1 {
+        impl_.syntax().clone()
+    } else {
+        fn_.syntax().clone()
+    };
+
+    let group = GroupLabel("Pull method up into a trait".to_owned());
+    for (trait_impl, trait_) in candidates {
+        let mut edits = Edits::default();
+        edits.append_item(db, ctx.sema.source(trait_)?.syntax(), &signature)?;
+        edits.append_item(db, ctx.sema.source(trait_impl)?.syntax(), &method)?;
+        for other in hir::Impl::all_for_trait(db, trait_) {
+            if other != trait_impl {
+                edits.append_item(db, ctx.sema.source(other)?.syntax(), &stub)?;
+            }
+        }
+        for range in removal_ranges(std::slice::from_ref(&removed)) {
+            edits.add(ctx.vfs_file_id(), range, String::new());
+        }
+
+        acc.add_group(
+            &group,
+            AssistId::refactor_rewrite("pull_up_method"),
+            format!(
+                "Pull `{}` up into `{}`",
+                name.display(db, ctx.edition()),
+                trait_.name(db).display(db, ctx.edition())
+            ),
+            fn_.syntax().text_range(),
+            |builder| {
+                // The callers of the method need the trait in scope now.
+                let mut visited_modules = FxHashSet::default();
+                for (file_id, refs) in Definition::Function(func).usages(&ctx.sema).all() {
+                    for reference in refs {
+                        let Some(name_ref) = reference.name.as_name_ref() else { continue };
+                        edits.import_trait(
+                            ctx,
+                            file_id.file_id(db),
+                            name_ref.syntax(),
+                            trait_,
+                            &mut visited_modules,
+                        );
+                    }
+                }
+                edits.apply(builder, ctx)
+            },
+        );
+    }
+    Some(())
+}
+
+// Assist: push_down_method
+//
+// Moves the default implementation of a trait method into the implementations of the
+// trait that don't override it.
+//
+// ```
+// trait Animal {
+//     fn name(&self) -> &'static str;
+//
+//     fn $0describe(&self) -> usize {
+//         self.name().len()
+//     }
+// }
+//
+// struct Dog;
+//
+// impl Animal for Dog {
+//     fn name(&self) -> &'static str {
+//         "dog"
+//     }
+// }
+// ```
+// ->
+// ```
+// trait Animal {
+//     fn name(&self) -> &'static str;
+//
+//     fn describe(&self) -> usize;
+// }
+//
+// struct Dog;
+//
+// impl Animal for Dog {
+//     fn name(&self) -> &'static str {
+//         "dog"
+//     }
+//
+//     fn describe(&self) -> usize {
+//         self.name().len()
+//     }
+// }
+// ```
+pub(crate) fn push_down_method(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let (fn_, trait_ast) = method_at_cursor::<ast::Trait>(ctx)?;
+    let body = fn_.body()?;
+    if trait_ast.generic_param_list().is_some() {
+        return None;
+    }
+    let db = ctx.db();
+    let func = ctx.sema.to_def(&fn_)?;
+    let trait_ = ctx.sema.to_def(&trait_ast)?;
+    let name = func.name(db);
+
+    let mut edits = Edits::default();
+    let method = item_text(&fn_, fn_.syntax().text_range().end(), Vec::new());
+    for impl_def in hir::Impl::all_for_trait(db, trait_) {
+        if impl_def.items(db).iter().any(|it| it.name(db).as_ref() == Some(&name)) {
+            continue;
+        }
+        // The body only resolves the same way when it stays in the same module.
+        if impl_def.module(db) != trait_.module(db) {
+            return None;
+        }
+        edits.append_item(db, ctx.sema.source(impl_def)?.syntax(), &method)?;
+    }
+
+    let body_start = body
+        .syntax()
+        .prev_sibling_or_token()
+        .filter(|it| it.kind() == SyntaxKind::WHITESPACE)
+        .map_or(body.syntax().text_range().start(), |it| it.text_range().start());
+    edits.add(
+        ctx.vfs_file_id(),
+        TextRange::new(body_start, body.syntax().text_range().end()),
+        ";".to_owned(),
+    );
+
+    acc.add(
+        AssistId::refactor_rewrite("push_down_method"),
+        format!("Push `{}` down into the implementations", name.display(db, ctx.edition())),
+        fn_.syntax().text_range(),
+        |builder| edits.apply(builder, ctx),
+    )
+}
+
+/// The method whose signature is at the cursor, along with the impl or trait holding it.
+fn method_at_cursor<N: AstNode>(ctx: &AssistContext<'_>) -> Option<(ast::Fn, N)> {
+    let fn_ = ctx.find_node_at_offset::<ast::Fn>()?;
+    if fn_.body().is_some_and(|it| it.syntax().text_range().contains(ctx.offset())) {
+        return None;
+    }
+    let holder = fn_.syntax().parent().filter(|it| ast::AssocItemList::can_cast(it.kind()))?;
+    let holder = N::cast(holder.parent()?)?;
+    Some((fn_, holder))
+}
+
+/// The text of `fn_` up to `end` with `replacements` applied, without its visibility and
+/// indented at level zero.
+fn item_text(fn_: &ast::Fn, end: TextSize, mut replacements: Vec<(TextRange, &str)>) -> String {
+    if let Some(vis) = fn_.visibility() {
+        let mut range = vis.syntax().text_range();
+        if let Some(ws) = vis.syntax().next_sibling_or_token()
+            && ws.kind() == SyntaxKind::WHITESPACE
+        {
+            range = range.cover(ws.text_range());
+        }
+        replacements.push((range, ""));
+    }
+    replacements.sort_by_key(|(range, _)| range.start());
+
+    let start = fn_.syntax().text_range().start();
+    let mut text = fn_.syntax().to_string();
+    text.truncate((end - start).into());
+    for (range, replacement) in replacements.into_iter().rev() {
+        text.replace_range(std::ops::Range::<usize>::from(range - start), replacement);
+    }
+    let indent = IndentLevel::from_node(fn_.syntax()).to_string();
+    text.split('\n').map(|line| line.strip_prefix(indent.as_str()).unwrap_or(line)).join("\n")
+}
+
+/// The declaration of `fn_` in a trait, with the mentions of `self_ty` replaced by `Self`.
+fn signature(ctx: &AssistContext<'_>, fn_: &ast::Fn, self_ty: &hir::Type<'_>) -> String {
+    let end = match fn_.body() {
+        Some(body) => body
+            .syntax()
+            .prev_sibling_or_token()
+            .filter(|it| it.kind() == SyntaxKind::WHITESPACE)
+            .map_or(body.syntax().text_range().start(), |it| it.text_range().start()),
+        None => fn_.syntax().text_range().end(),
+    };
+    let mut replacements: Vec<(TextRange, &str)> = Vec::new();
+    for ty in fn_.syntax().descendants().filter_map(ast::PathType::cast) {
+        let range = ty.syntax().text_range();
+        if range.end() > end || replacements.iter().any(|(it, _)| it.contains_range(range)) {
+            continue;
+        }
+        let Some(ty) = ctx.sema.resolve_type(&ast::Type::PathType(ty)) else { continue };
+        if ty.as_adt().is_some()
+            && ty.as_adt() == self_ty.as_adt()
+            && ty.could_unify_with(ctx.db(), self_ty)
+        {
+            replacements.push((range, "Self"));
+        }
+    }
+    format!("{};", item_text(fn_, end, replacements))
+}
+
+/// The text edits and imports of a pull up or push down, over several files.
+#[derive(Default)]
+struct Edits {
+    text: FxHashMap<FileId, Vec<(TextRange, String)>>,
+    imports: FxHashMap<FileId, Vec<(ImportScope, ast::Path)>>,
+}
+
+impl Edits {
+    fn add(&mut self, file_id: FileId, range: TextRange, text: String) {
+        self.text.entry(file_id).or_default().push((range, text));
+    }
+
+    /// Adds `item`, indented at level zero, as the last item of the impl or trait `holder`,
+    /// returning the file of `holder`.
+    fn append_item(
+        &mut self,
+        db: &RootDatabase,
+        holder: InFile<&SyntaxNode>,
+        item: &str,
+    ) -> Option<FileId> {
+        let file_id = holder.file_id.file_id()?.file_id(db);
+        let list = holder.value.children().find_map(ast::AssocItemList::cast)?;
+        let indent = IndentLevel::from_node(holder.value);
+        let item = item.replace('\n', &format!("\n{}", indent + 1));
+        match list.assoc_items().last() {
+            Some(last) => {
+                let offset = last.syntax().text_range().end();
+                self.add(file_id, TextRange::empty(offset), format!("\n\n{}{item}", indent + 1));
+            }
+            None => {
+                let range = TextRange::new(
+                    list.l_curly_token()?.text_range().end(),
+                    list.r_curly_token()?.text_range().start(),
+                );
+                self.add(file_id, range, format!("\n{}{item}\n{indent}", indent + 1));
+            }
+        }
+        Some(file_id)
+    }
+
+    /// Imports `trait_` into the module of `node`, unless it's already in scope there.
+    fn import_trait(
+        &mut self,
+        ctx: &AssistContext<'_>,
+        file_id: FileId,
+        node: &SyntaxNode,
+        trait_: hir::Trait,
+        visited_modules: &mut FxHashSet<hir::Module>,
+    ) {
+        let db = ctx.db();
+        let Some(scope) = ctx.sema.scope(node) else { return };
+        let module = scope.module();
+        if scope.visible_traits().contains(&trait_.into()) || !visited_modules.insert(module) {
+            return;
+        }
+        let cfg = ctx.config.find_path_config(ctx.sema.is_nightly(module.krate(db)));
+        let Some(path) = module.find_use_path(
+            db,
+            ModuleDef::Trait(trait_),
+            ctx.config.insert_use.prefix_kind,
+            cfg,
+        ) else {
+            return;
+        };
+        let Some(import_scope) = ImportScope::find_insert_use_container(node, &ctx.sema) else {
+            return;
+        };
+        let path = mod_path_to_ast(&path, module.krate(db).edition(db));
+        self.imports.entry(file_id).or_default().push((import_scope, path));
+    }
+
+    fn apply(&self, builder: &mut SourceChangeBuilder, ctx: &AssistContext<'_>) {
+        let files: FxHashSet<_> = self.text.keys().chain(self.imports.keys()).collect();
+        for &file_id in files {
+            builder.edit_file(file_id);
+            for (range, text) in self.text.get(&file_id).into_iter().flatten() {
+                builder.replace(*range, text);
+            }
+            for (scope, path) in self.imports.get(&file_id).into_iter().flatten() {
+                let scope = builder.make_import_scope_mut(scope.clone());
+                insert_use(&scope, path.clone(), &ctx.config.insert_use);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_assist, check_assist_by_label, check_assist_not_applicable};
+
+    use super::*;
+
+    #[test]
+    fn pull_up_imports_trait_for_callers() {
+        check_assist(
+            pull_up_method,
+            r#"
+mod shapes {
+    pub trait Shape {
+        fn area(&self) -> u32;
+    }
+
+    pub struct Rect(pub u32, pub u32);
+
+    impl Rect {
+        pub fn new(w: u32, h: u32) -> Rect {
+            Rect(w, h)
+        }
+
+        pub fn $0scaled(&self, k: u32) -> Rect {
+            Rect(self.0 * k, self.1 * k)
+        }
+    }
+
+    impl Shape for Rect {
+        fn area(&self) -> u32 {
+            self.0 * self.1
+        }
+    }
+}
+
+fn main() {
+    let r = shapes::Rect::new(1, 2).scaled(2);
+}
+"#,
+            r#"
+use shapes::Shape;
+
+mod shapes {
+    pub trait Shape {
+        fn area(&self) -> u32;
+
+        fn scaled(&self, k: u32) -> Self;
+    }
+
+    pub struct Rect(pub u32, pub u32);
+
+    impl Rect {
+        pub fn new(w: u32, h: u32) -> Rect {
+            Rect(w, h)
+        }
+    }
+
+    impl Shape for Rect {
+        fn area(&self) -> u32 {
+            self.0 * self.1
+        }
+
+        fn scaled(&self, k: u32) -> Rect {
+            Rect(self.0 * k, self.1 * k)
+        }
+    }
+}
+
+fn main() {
+    let r = shapes::Rect::new(1, 2).scaled(2);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn pull_up_into_chosen_trait() {
+        check_assist_by_label(
+            pull_up_method,
+            r#"
+trait Named {}
+trait Sized2 {}
+
+struct S;
+
+impl S {
+    fn $0size(&self) -> usize {
+        0
+    }
+}
+
+impl Named for S {}
+impl Sized2 for S {}
+"#,
+            r#"
+trait Named {}
+trait Sized2 {
+    fn size(&self) -> usize;
+}
+
+struct S;
+
+impl Named for S {}
+impl Sized2 for S {
+    fn size(&self) -> usize {
+        0
+    }
+}
+"#,
+            "Pull `size` up into `Sized2`",
+        );
+    }
+
+    #[test]
+    fn pull_up_not_applicable() {
+        // The trait already has an item with the same name.
+        check_assist_not_applicable(
+            pull_up_method,
+            r#"
+trait Tr {
+    fn f(&self) {}
+}
+struct S;
+impl S {
+    fn $0f(&self) {}
+}
+impl Tr for S {}
+"#,
+        );
+        // The trait impl is in another module.
+        check_assist_not_applicable(
+            pull_up_method,
+            r#"
+trait Tr {}
+struct S;
+impl S {
+    fn $0f(&self) {}
+}
+mod m {
+    impl super::Tr for super::S {}
+}
+"#,
+        );
+        // The cursor is in the body.
+        check_assist_not_applicable(
+            pull_up_method,
+            r#"
+trait Tr {}
+struct S;
+impl S {
+    fn f(&self) { $0 }
+}
+impl Tr for S {}
+"#,
+        );
+    }
+
+    #[test]
+    fn push_down_into_impls_without_override() {
+        check_assist(
+            push_down_method,
+            r#"
+trait Animal {
+    fn name(&self) -> &'static str {
+        "animal"
+    }
+
+    fn $0describe(&self) -> usize {
+        self.name().len()
+    }
+}
+
+struct Dog;
+
+impl Animal for Dog {
+    fn describe(&self) -> usize {
+        3
+    }
+}
+
+struct Cat;
+
+impl Animal for Cat {}
+"#,
+            r#"
+trait Animal {
+    fn name(&self) -> &'static str {
+        "animal"
+    }
+
+    fn describe(&self) -> usize;
+}
+
+struct Dog;
+
+impl Animal for Dog {
+    fn describe(&self) -> usize {
+        3
+    }
+}
+
+struct Cat;
+
+impl Animal for Cat {
+    fn describe(&self) -> usize {
+        self.name().len()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn push_down_not_applicable() {
+        // The method has no default implementation.
+        check_assist_not_applicable(
+            push_down_method,
+            r#"
+trait Tr {
+    fn $0f(&self);
+}
+"#,
+        );
+        // An implementation in another module may not see what the body uses.
+        check_assist_not_applicable(
+            push_down_method,
+            r#"
+struct Helper;
+
+trait Tr {
+    fn $0f(&self) -> Helper {
+        Helper
+    }
+}
+
+mod m {
+    pub struct S;
+
+    impl crate::Tr for S {}
+}
+"#,
+        );
+        // The body may use the parameters of the trait.
+        check_assist_not_applicable(
+            push_down_method,
+            r#"
+trait Tr<T: Default> {
+    fn $0f(&self) -> T {
+        T::default()
+    }
+}
+
+struct S;
+
+impl Tr<u32> for S {}
+"#,
+        );
+    }
+}
@@ -1,19 +1,5 @@
-use std::collections::hash_map::Entry;
-
-use hir::{
-    FileRange, InFile, InRealFile, Module, ModuleDef, ModuleSource, PathResolution,
-    PathResolutionPerNs,
-};
-use ide_db::text_edit::TextRange;
-use ide_db::{
-    FxHashMap, RootDatabase,
-    defs::Definition,
-    search::{FileReference, ReferenceCategory, SearchScope},
-};
-use syntax::{
-    AstNode,
-    ast::{self, Rename},
-};
+use ide_db::imports::unused_imports::unused_use_trees;
+use syntax::{AstNode, ast};
 
 use crate::{AssistContext, AssistId, Assists};
 
@@ -48,70 +34,8 @@ pub(crate) fn remove_unused_imports(acc: &mut Assists, ctx: &AssistContext<'_>)
         .filter_map(ast::Use::cast);
     let uses = uses_up.chain(uses_down).collect::<Vec<_>>();
 
-    // Maps use nodes to the scope that we should search through to find
-    let mut search_scopes = FxHashMap::<Module, Vec<SearchScope>>::default();
-
     // iterator over all unused use trees
-    let mut unused = uses
-        .into_iter()
-        .flat_map(|u| u.syntax().descendants().filter_map(ast::UseTree::cast))
-        .filter(|u| u.use_tree_list().is_none())
-        .filter_map(|u| {
-            // Find any uses trees that are unused
-
-            let use_module = ctx.sema.scope(u.syntax()).map(|s| s.module())?;
-            let scope = match search_scopes.entry(use_module) {
-                Entry::Occupied(o) => o.into_mut(),
-                Entry::Vacant(v) => v.insert(module_search_scope(ctx.db(), use_module)),
-            };
-
-            // Gets the path associated with this use tree. If there isn't one, then ignore this use tree.
-            let path = if let Some(path) = u.path() {
-                path
-            } else if u.star_token().is_some() {
-                // This case maps to the situation where the * token is braced.
-                // In this case, the parent use tree's path is the one we should use to resolve the glob.
-                match u.syntax().ancestors().skip(1).find_map(ast::UseTree::cast) {
-                    Some(parent_u) if parent_u.path().is_some() => parent_u.path()?,
-                    _ => return None,
-                }
-            } else {
-                return None;
-            };
-
-            // Get the actual definition associated with this use item.
-            let res = match ctx.sema.resolve_path_per_ns(&path) {
-                Some(x) if x.any().is_some() => x,
-                Some(_) | None => {
-                    return None;
-                }
-            };
-
-            if u.star_token().is_some() {
-                // Check if any of the children of this module are used
-                let def_mod = match res.type_ns {
-                    Some(PathResolution::Def(ModuleDef::Module(module))) => module,
-                    _ => return None,
-                };
-
-                if !def_mod
-                    .scope(ctx.db(), Some(use_module))
-                    .iter()
-                    .filter_map(|(_, x)| match x {
-                        hir::ScopeDef::ModuleDef(d) => Some(Definition::from(*d)),
-                        _ => None,
-                    })
-                    .any(|d| used_once_in_scope(ctx, d, u.rename(), scope))
-                {
-                    Some(u)
-                } else {
-                    None
-                }
-            } else {
-                is_path_per_ns_unused_in_scope(ctx, &u, scope, &res).then_some(u)
-            }
-        })
-        .peekable();
+    let mut unused = unused_use_trees(&ctx.sema, uses).peekable();
 
     // Peek so we terminate early if an unused use is found. Only do the rest of the work if the user selects the assist.
     if unused.peek().is_some() {
@@ -131,145 +55,6 @@ pub(crate) fn remove_unused_imports(acc: &mut Assists, ctx: &AssistContext<'_>)
     }
 }
 
-fn is_path_per_ns_unused_in_scope(
-    ctx: &AssistContext<'_>,
-    u: &ast::UseTree,
-    scope: &mut Vec<SearchScope>,
-    path: &PathResolutionPerNs,
-) -> bool {
-    if let Some(PathResolution::Def(ModuleDef::Trait(ref t))) = path.type_ns {
-        if is_trait_unused_in_scope(ctx, u, scope, t) {
-            let path = [path.value_ns, path.macro_ns];
-            is_path_unused_in_scope(ctx, u, scope, &path)
-        } else {
-            false
-        }
-    } else {
-        let path = [path.type_ns, path.value_ns, path.macro_ns];
-        is_path_unused_in_scope(ctx, u, scope, &path)
-    }
-}
-
-fn is_path_unused_in_scope(
-    ctx: &AssistContext<'_>,
-    u: &ast::UseTree,
-    scope: &mut Vec<SearchScope>,
-    path: &[Option<PathResolution>],
-) -> bool {
-    !path
-        .iter()
-        .filter_map(|path| *path)
-        .filter_map(|res| match res {
-            PathResolution::Def(d) => Some(Definition::from(d)),
-            _ => None,
-        })
-        .any(|def| used_once_in_scope(ctx, def, u.rename(), scope))
-}
-
-fn is_trait_unused_in_scope(
-    ctx: &AssistContext<'_>,
-    u: &ast::UseTree,
-    scope: &mut Vec<SearchScope>,
-    t: &hir::Trait,
-) -> bool {
-    !std::iter::once((Definition::Trait(*t), u.rename()))
-        .chain(t.items(ctx.db()).into_iter().map(|item| (item.into(), None)))
-        .any(|(d, rename)| used_once_in_scope(ctx, d, rename, scope))
-}
-
-fn used_once_in_scope(
-    ctx: &AssistContext<'_>,
-    def: Definition,
-    rename: Option<Rename>,
-    scopes: &Vec<SearchScope>,
-) -> bool {
-    let mut found = false;
-
-    for scope in scopes {
-        let mut search_non_import = |_, r: FileReference| {
-            // The import itself is a use; we must skip that.
-            if !r.category.contains(ReferenceCategory::IMPORT) {
-                found = true;
-                true
-            } else {
-                false
-            }
-        };
-        def.usages(&ctx.sema)
-            .in_scope(scope)
-            .with_rename(rename.as_ref())
-            .search(&mut search_non_import);
-        if found {
-            break;
-        }
-    }
-
-    found
-}
-
-/// Build a search scope spanning the given module but none of its submodules.
-fn module_search_scope(db: &RootDatabase, module: hir::Module) -> Vec<SearchScope> {
-    let (file_id, range) = {
-        let InFile { file_id, value } = module.definition_source(db);
-        if let Some(InRealFile { file_id, value: call_source }) = file_id.original_call_node(db) {
-            (file_id, Some(call_source.text_range()))
-        } else {
-            (
-                file_id.original_file(db),
-                match value {
-                    ModuleSource::SourceFile(_) => None,
-                    ModuleSource::Module(it) => Some(it.syntax().text_range()),
-                    ModuleSource::BlockExpr(it) => Some(it.syntax().text_range()),
-                },
-            )
-        }
-    };
-
-    fn split_at_subrange(first: TextRange, second: TextRange) -> (TextRange, Option<TextRange>) {
-        let intersect = first.intersect(second);
-        if let Some(intersect) = intersect {
-            let start_range = TextRange::new(first.start(), intersect.start());
-
-            if intersect.end() < first.end() {
-                (start_range, Some(TextRange::new(intersect.end(), first.end())))
-            } else {
-                (start_range, None)
-            }
-        } else {
-            (first, None)
-        }
-    }
-
-    let mut scopes = Vec::new();
-    if let Some(range) = range {
-        let mut ranges = vec![range];
-
-        for child in module.children(db) {
-            let rng = match child.definition_source(db).value {
-                ModuleSource::SourceFile(_) => continue,
-                ModuleSource::Module(it) => it.syntax().text_range(),
-                ModuleSource::BlockExpr(_) => continue,
-            };
-            let mut new_ranges = Vec::new();
-            for old_range in ranges.iter_mut() {
-                let split = split_at_subrange(*old_range, rng);
-                *old_range = split.0;
-                new_ranges.extend(split.1);
-            }
-
-            ranges.append(&mut new_ranges);
-        }
-
-        for range in ranges {
-            scopes.push(SearchScope::file_range(FileRange { file_id, range }));
-        }
-    } else {
-        scopes.push(SearchScope::single_file(file_id));
-    }
-
-    scopes
-}
-
 #[cfg(test)]
 mod tests {
     use crate::tests::{check_assist, check_assist_not_applicable};
@@ -0,0 +1,864 @@
+use hir::{AsAssocItem, EditionedFileId, ModuleDef, PathResolution};
+use ide_db::{
+    FileId, FxHashMap,
+    assists::AssistId,
+    defs::Definition,
+    search::FileReferenceNode,
+    source_change::{ChangeAnnotation, SourceChangeBuilder},
+    syntax_helpers::suggest_name::NameGenerator,
+};
+use itertools::Itertools;
+use syntax::{
+    AstNode, Direction, SyntaxElement, SyntaxKind, SyntaxNode, T, TextRange, TextSize,
+    ast::{self, HasGenericArgs, HasGenericParams, HasName, HasTypeBounds},
+};
+
+use crate::{AssistContext, Assists, utils::location};
+
+// Assist: replace_dyn_trait_with_generic
+//
+// Replaces a `dyn Trait` in the type of a function parameter or a struct field with a new
+// type parameter. For a struct the parameter is added to its impls and uses too.
+//
+// ```
+// trait Draw {}
+//
+// fn render(shape: &dyn $0Draw) {}
+// ```
+// ->
+// ```
+// trait Draw {}
+//
+// fn render<D: Draw + ?Sized>(shape: &D) {}
+// ```
+pub(crate) fn replace_dyn_trait_with_generic(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let dyn_ty = ctx.find_node_at_offset::<ast::DynTraitType>()?;
+    let bounds = dyn_ty.type_bound_list()?;
+    let mut replaced = dyn_ty.syntax().clone();
+    if let Some(paren) = replaced.parent().filter(|it| it.kind() == SyntaxKind::PAREN_TYPE) {
+        replaced = paren;
+    }
+    // `dyn Trait` is unsized, so it can only be used behind a pointer of some kind.
+    if !matches!(
+        replaced.parent()?.kind(),
+        SyntaxKind::REF_TYPE | SyntaxKind::PTR_TYPE | SyntaxKind::TYPE_ARG
+    ) {
+        return None;
+    }
+    let field_or_param = replaced.ancestors().find(|it| {
+        matches!(it.kind(), SyntaxKind::PARAM | SyntaxKind::RECORD_FIELD | SyntaxKind::TUPLE_FIELD)
+    })?;
+    let owner = field_or_param.parent()?.parent()?;
+    let first_bound = bounds.bounds().find_map(|it| it.ty())?.syntax().first_token()?;
+    let name_hint = first_bound.text().chars().next()?.to_string();
+
+    let db = ctx.db();
+    let mut edits = Edits::default();
+    let file_id = ctx.vfs_file_id();
+    let name;
+    let owner_def;
+    if let Some(fn_) = ast::Fn::cast(owner.clone()) {
+        let func = ctx.sema.to_def(&fn_)?;
+        if func.as_assoc_item(db).and_then(|it| it.container_or_implemented_trait(db)).is_some() {
+            return None;
+        }
+        name = fresh_name(fn_.generic_param_list(), &name_hint);
+        edits.add(file_id, replaced.text_range(), name.clone());
+        let (range, text) = add_generic_param(
+            fn_.generic_param_list(),
+            fn_.name()?.syntax().text_range().end(),
+            &format!("{name}: {bounds} + ?Sized"),
+        );
+        edits.add(file_id, range, text);
+        owner_def = Owner::Fn(func);
+    } else {
+        let strukt = ast::Struct::cast(owner)?;
+        let generic_params = strukt.generic_param_list();
+        // A parameter without a default can't follow the ones with defaults.
+        if generic_params.iter().flat_map(|it| it.type_or_const_params()).any(|it| match it {
+            ast::TypeOrConstParam::Type(it) => it.default_type().is_some(),
+            ast::TypeOrConstParam::Const(it) => it.default_val().is_some(),
+        }) {
+            return None;
+        }
+        name = fresh_name(generic_params.clone(), &name_hint);
+        edits.add(file_id, replaced.text_range(), name.clone());
+        let (range, text) = add_generic_param(
+            generic_params,
+            strukt.name()?.syntax().text_range().end(),
+            &format!("{name}: {bounds} + ?Sized"),
+        );
+        edits.add(file_id, range, text);
+        owner_def = Owner::Struct(ctx.sema.to_def(&strukt)?, strukt);
+    }
+
+    acc.add(
+        AssistId::refactor_rewrite("replace_dyn_trait_with_generic"),
+        format!("Replace `dyn {bounds}` with type parameter `{name}`"),
+        dyn_ty.syntax().text_range(),
+        |builder| {
+            match owner_def {
+                // The new parameter is inferred where the function is called with a turbofish.
+                Owner::Fn(func) => {
+                    for (file_id, arg_list) in turbofishes(ctx, func) {
+                        if let Some(last) = arg_list.generic_args().last() {
+                            let offset = last.syntax().text_range().end();
+                            edits.add(file_id, TextRange::empty(offset), ", _".to_owned());
+                        }
+                    }
+                }
+                Owner::Struct(adt, strukt) => {
+                    add_struct_args(ctx, adt, &strukt, &name, &bounds, &name_hint, &mut edits)
+                }
+            }
+            edits.apply(builder)
+        },
+    )
+}
+
+// Assist: replace_generic_with_dyn_trait
+//
+// Replaces a type parameter of a function or a struct that is only used behind pointers with
+// a `dyn Trait`. Only offered when the trait is dyn compatible.
+//
+// ```
+// trait Draw {}
+//
+// fn render<$0D: Draw + ?Sized>(shape: &D) {}
+// ```
+// ->
+// ```
+// trait Draw {}
+//
+// fn render(shape: &dyn Draw) {}
+// ```
+pub(crate) fn replace_generic_with_dyn_trait(
+    acc: &mut Assists,
+    ctx: &AssistContext<'_>,
+) -> Option<()> {
+    let type_param = ctx.find_node_at_offset::<ast::TypeParam>()?;
+    let param_list = type_param.syntax().parent().and_then(ast::GenericParamList::cast)?;
+    let owner = param_list.syntax().parent()?;
+    let db = ctx.db();
+
+    let mut principal = None;
+    let mut dyn_bounds = Vec::new();
+    for bound in type_param.type_bound_list()?.bounds() {
+        if bound.question_mark_token().is_some() {
+            continue;
+        }
+        if bound.lifetime().is_none() {
+            let Some(ast::Type::PathType(ty)) = bound.ty() else { return None };
+            let Some(PathResolution::Def(ModuleDef::Trait(trait_))) =
+                ctx.sema.resolve_path(&ty.path()?)
+            else {
+                return None;
+            };
+            if !trait_.is_auto(db) && principal.replace(trait_).is_some() {
+                return None;
+            }
+        }
+        dyn_bounds.push(bound.to_string());
+    }
+    if principal?.dyn_compatibility(db).is_some() {
+        return None;
+    }
+    let dyn_ty = format!("dyn {}", dyn_bounds.join(" + "));
+
+    // All the uses of the parameter have to be in the signature, behind a pointer.
+    let (fn_, strukt) = (ast::Fn::cast(owner.clone()), ast::Struct::cast(owner));
+    let allowed_range = match (&fn_, &strukt) {
+        (Some(fn_), _) => fn_.param_list()?.syntax().text_range(),
+        (_, Some(strukt)) => strukt.field_list()?.syntax().text_range(),
+        _ => return None,
+    };
+    let param_def = ctx.sema.to_def(&type_param)?;
+    let usages = Definition::GenericParam(param_def.into()).usages(&ctx.sema).all();
+    let mut edits = Edits::default();
+    let file_id = ctx.vfs_file_id();
+    for reference in usages.iter().flat_map(|(_, refs)| refs) {
+        let FileReferenceNode::NameRef(name_ref) = &reference.name else { return None };
+        let path = type_path(name_ref).filter(|it| it.qualifier().is_none())?;
+        let path_ty = path.syntax().parent()?;
+        if !allowed_range.contains_range(path_ty.text_range()) {
+            return None;
+        }
+        let parent = path_ty.parent()?;
+        let text = match parent.kind() {
+            SyntaxKind::REF_TYPE | SyntaxKind::PTR_TYPE if dyn_bounds.len() > 1 => {
+                format!("({dyn_ty})")
+            }
+            SyntaxKind::REF_TYPE | SyntaxKind::PTR_TYPE => dyn_ty.clone(),
+            SyntaxKind::TYPE_ARG => {
+                let segment = parent.ancestors().find_map(ast::PathSegment::cast)?;
+                let pointer = segment.name_ref()?;
+                if !["Box", "Rc", "Arc"].contains(&pointer.text().as_str()) {
+                    return None;
+                }
+                dyn_ty.clone()
+            }
+            _ => return None,
+        };
+        edits.add(file_id, path_ty.text_range(), text);
+    }
+    if usages.is_empty() {
+        return None;
+    }
+    edits.add(file_id, remove_generic_param(&param_list, type_param.syntax()), String::new());
+
+    // The argument for the parameter goes away from all the uses of the function or the struct,
+    // and for a struct so does the matching parameter of its impls.
+    let index =
+        param_list.type_or_const_params().position(|it| it.syntax() == type_param.syntax())?;
+    let owner_def = match (&fn_, strukt) {
+        (Some(fn_), _) => {
+            let func = ctx.sema.to_def(fn_)?;
+            if func.as_assoc_item(db).and_then(|it| it.container_or_implemented_trait(db)).is_some()
+            {
+                return None;
+            }
+            Owner::Fn(func)
+        }
+        (_, Some(strukt)) => Owner::Struct(ctx.sema.to_def(&strukt)?, strukt),
+        _ => return None,
+    };
+    // Without a way to show the impls left as they are, the assist is only offered when every
+    // impl can lose the parameter, so the uses have to be found before offering it. Otherwise
+    // they are found on demand.
+    let uses = if ctx.config.show_rename_conflicts {
+        None
+    } else {
+        let uses = remove_args(ctx, &owner_def, index);
+        if !uses.blocked.is_empty() {
+            return None;
+        }
+        Some(uses)
+    };
+
+    acc.add(
+        AssistId::refactor_rewrite("replace_generic_with_dyn_trait"),
+        format!("Replace type parameter `{}` with `{dyn_ty}`", type_param.name()?),
+        type_param.syntax().text_range(),
+        |builder| {
+            let uses = uses.unwrap_or_else(|| remove_args(ctx, &owner_def, index));
+            if !uses.blocked.is_empty() {
+                builder.annotate(ChangeAnnotation {
+                    label: format!(
+                        "This leaves {} impl(s) that can't be adjusted",
+                        uses.blocked.len()
+                    ),
+                    needs_confirmation: true,
+                    description: Some(format!(
+                        "Impls that use the parameter elsewhere:\n{}",
+                        uses.blocked
+                            .iter()
+                            .map(|&(file_id, range)| location(db, file_id, range))
+                            .format("\n")
+                    )),
+                });
+            }
+            edits.apply(builder);
+            uses.edits.apply(builder);
+        },
+    )
+}
+
+/// The function or the struct whose generic parameters an assist changes.
+enum Owner {
+    Fn(hir::Function),
+    Struct(hir::Struct, ast::Struct),
+}
+
+/// The edits to the uses of a function or a struct, and the impls of the struct that can't
+/// lose a parameter.
+struct Uses {
+    edits: Edits,
+    blocked: Vec<(EditionedFileId, TextRange)>,
+}
+
+/// The generic argument lists of the calls of `func` that use a turbofish.
+fn turbofishes(ctx: &AssistContext<'_>, func: hir::Function) -> Vec<(FileId, ast::GenericArgList)> {
+    let db = ctx.db();
+    let usages = Definition::Function(func).usages(&ctx.sema).all();
+    usages
+        .iter()
+        .flat_map(|(file_id, refs)| refs.iter().map(move |it| (file_id.file_id(db), it)))
+        .filter_map(|(file_id, reference)| {
+            let FileReferenceNode::NameRef(name_ref) = &reference.name else { return None };
+            let parent = name_ref.syntax().parent()?;
+            let arg_list = match ast::PathSegment::cast(parent.clone()) {
+                Some(segment) => segment.generic_arg_list(),
+                None => ast::MethodCallExpr::cast(parent)?.generic_arg_list(),
+            };
+            Some((file_id, arg_list?))
+        })
+        .collect()
+}
+
+/// Adds an argument for the new parameter `name` of `strukt` to its uses. The impls of the
+/// struct get a parameter of their own, the other uses keep the `dyn Trait` as an argument.
+fn add_struct_args(
+    ctx: &AssistContext<'_>,
+    adt: hir::Struct,
+    strukt: &ast::Struct,
+    name: &str,
+    bounds: &ast::TypeBoundList,
+    name_hint: &str,
+    edits: &mut Edits,
+) {
+    let db = ctx.db();
+    let usages = Definition::Adt(adt.into()).usages(&ctx.sema).all();
+    let mut impl_params = FxHashMap::default();
+    let mut type_uses = Vec::new();
+    for (file_id, refs) in usages.iter() {
+        let file_id = file_id.file_id(db);
+        for reference in refs {
+            let FileReferenceNode::NameRef(name_ref) = &reference.name else { continue };
+            let Some(path) = type_path(name_ref) else { continue };
+            let Some(impl_) = impl_of_self_ty(name_ref) else {
+                type_uses.push((file_id, path));
+                continue;
+            };
+            let impl_name = fresh_name(impl_.generic_param_list(), name_hint);
+            let (Some(impl_token), Some((arg_range, arg_text))) =
+                (impl_.impl_token(), add_generic_arg(&path, &impl_name))
+            else {
+                continue;
+            };
+            let (range, text) = add_generic_param(
+                impl_.generic_param_list(),
+                impl_token.text_range().end(),
+                &format!("{impl_name}: {bounds} + ?Sized"),
+            );
+            edits.add(file_id, range, text);
+            edits.add(file_id, arg_range, arg_text);
+            impl_params.insert(impl_.syntax().clone(), impl_name);
+        }
+    }
+    for (file_id, path) in type_uses {
+        let arg = if path.syntax().ancestors().any(|it| it == *strukt.syntax()) {
+            name.to_owned()
+        } else {
+            match path.syntax().ancestors().find_map(|it| impl_params.get(&it)) {
+                Some(impl_name) => impl_name.clone(),
+                None => format!("dyn {bounds}"),
+            }
+        };
+        if let Some((range, text)) = add_generic_arg(&path, &arg) {
+            edits.add(file_id, range, text);
+        }
+    }
+}
+
+/// Removes the argument for the parameter at `index` from the uses of `owner`, and the
+/// matching parameter from the impls of a struct.
+fn remove_args(ctx: &AssistContext<'_>, owner: &Owner, index: usize) -> Uses {
+    let db = ctx.db();
+    let mut uses = Uses { edits: Edits::default(), blocked: Vec::new() };
+    match owner {
+        Owner::Fn(func) => {
+            for (file_id, arg_list) in turbofishes(ctx, *func) {
+                if let Some((range, _)) = generic_arg_removal(&arg_list, index) {
+                    uses.edits.add(file_id, range, String::new());
+                }
+            }
+        }
+        Owner::Struct(adt, _) => {
+            for (file_id, refs) in Definition::Adt((*adt).into()).usages(&ctx.sema).all() {
+                for reference in refs {
+                    let FileReferenceNode::NameRef(name_ref) = reference.name else { continue };
+                    let Some(arg_list) = name_ref
+                        .syntax()
+                        .parent()
+                        .and_then(ast::PathSegment::cast)
+                        .and_then(|it| it.generic_arg_list())
+                    else {
+                        continue;
+                    };
+                    let Some((range, arg)) = generic_arg_removal(&arg_list, index) else {
+                        continue;
+                    };
+                    match impl_of_self_ty(&name_ref).map(|it| impl_param_removal(ctx, &it, &arg)) {
+                        Some(None) => uses.blocked.push((file_id, reference.range)),
+                        Some(Some(param_range)) => {
+                            uses.edits.add(file_id.file_id(db), range, String::new());
+                            if let Some(param_range) = param_range {
+                                uses.edits.add(file_id.file_id(db), param_range, String::new());
+                            }
+                        }
+                        None => uses.edits.add(file_id.file_id(db), range, String::new()),
+                    }
+                }
+            }
+        }
+    }
+    uses.blocked.sort_by_key(|&(file_id, range)| (file_id, range.start()));
+    uses
+}
+
+/// The range to delete to remove the argument at `index` among the type and const arguments
+/// of `arg_list`, along with that argument.
+fn generic_arg_removal(
+    arg_list: &ast::GenericArgList,
+    index: usize,
+) -> Option<(TextRange, ast::GenericArg)> {
+    let args: Vec<_> = arg_list.generic_args().collect();
+    let arg =
+        args.iter().filter(|it| !matches!(it, ast::GenericArg::LifetimeArg(_))).nth(index)?.clone();
+    let range = if args.len() == 1 {
+        arg_list.syntax().text_range()
+    } else {
+        list_item_removal(arg.syntax())
+    };
+    Some((range, arg))
+}
+
+/// The path of the type `name_ref` is the last segment of.
+fn type_path(name_ref: &ast::NameRef) -> Option<ast::Path> {
+    let segment = name_ref.syntax().parent().and_then(ast::PathSegment::cast)?;
+    let path = segment.parent_path();
+    path.syntax().parent().filter(|it| it.kind() == SyntaxKind::PATH_TYPE)?;
+    Some(path)
+}
+
+/// The impl whose self type is the path of `name_ref`.
+fn impl_of_self_ty(name_ref: &ast::NameRef) -> Option<ast::Impl> {
+    let path_ty = type_path(name_ref)?.syntax().parent()?;
+    let impl_ = path_ty.parent().and_then(ast::Impl::cast)?;
+    (impl_.self_ty()?.syntax() == &path_ty).then_some(impl_)
+}
+
+/// The range to delete to remove the parameter of `impl_` that `arg` of its self type names,
+/// if it names one. Fails when that parameter is used anywhere else in the impl.
+fn impl_param_removal(
+    ctx: &AssistContext<'_>,
+    impl_: &ast::Impl,
+    arg: &ast::GenericArg,
+) -> Option<Option<TextRange>> {
+    let param = match arg {
+        ast::GenericArg::TypeArg(arg) => match arg.ty()? {
+            ast::Type::PathType(ty) => match ctx.sema.resolve_path(&ty.path()?) {
+                Some(PathResolution::TypeParam(param)) => param,
+                _ => return Some(None),
+            },
+            _ => return Some(None),
+        },
+        _ => return None,
+    };
+    let param_list = impl_.generic_param_list()?;
+    let param_ast = param_list.type_or_const_params().find_map(|it| match it {
+        ast::TypeOrConstParam::Type(it) if ctx.sema.to_def(&it) == Some(param) => Some(it),
+        _ => None,
+    })?;
+    let usages = Definition::GenericParam(param.into()).usages(&ctx.sema).all();
+    let arg_range = arg.syntax().text_range();
+    if usages.iter().flat_map(|(_, refs)| refs).any(|it| !arg_range.contains_range(it.range)) {
+        return None;
+    }
+    Some(Some(remove_generic_param(&param_list, param_ast.syntax())))
+}
+
+/// A name for a new type parameter starting like `hint`, that isn't used in `list` yet.
+fn fresh_name(list: Option<ast::GenericParamList>, hint: &str) -> String {
+    let existing: Vec<_> = list
+        .iter()
+        .flat_map(|it| it.generic_params())
+        .filter_map(|it| match it {
+            ast::GenericParam::TypeParam(it) => it.name(),
+            ast::GenericParam::ConstParam(it) => it.name(),
+            ast::GenericParam::LifetimeParam(_) => None,
+        })
+        .map(|it| it.to_string())
+        .collect();
+    NameGenerator::new_with_names(existing.iter().map(|it| it.as_str()))
+        .suggest_name(hint)
+        .to_string()
+}
+
+/// The edit adding `param` to `list`, or creating the list at `offset` when there is none.
+fn add_generic_param(
+    list: Option<ast::GenericParamList>,
+    offset: TextSize,
+    param: &str,
+) -> (TextRange, String) {
+    match list {
+        Some(list) => match list.generic_params().last() {
+            Some(last) => {
+                (TextRange::empty(last.syntax().text_range().end()), format!(", {param}"))
+            }
+            None => (list.syntax().text_range(), format!("<{param}>")),
+        },
+        None => (TextRange::empty(offset), format!("<{param}>")),
+    }
+}
+
+/// The edit adding `arg` to the generic arguments of the last segment of `path`.
+fn add_generic_arg(path: &ast::Path, arg: &str) -> Option<(TextRange, String)> {
+    let segment = path.segment()?;
+    let edit = match segment.generic_arg_list() {
+        Some(list) => match list.generic_args().last() {
+            Some(last) => (TextRange::empty(last.syntax().text_range().end()), format!(", {arg}")),
+            None => (list.syntax().text_range(), format!("<{arg}>")),
+        },
+        None => {
+            (TextRange::empty(segment.name_ref()?.syntax().text_range().end()), format!("<{arg}>"))
+        }
+    };
+    Some(edit)
+}
+
+/// The range to delete to remove `param` from `list`.
+fn remove_generic_param(list: &ast::GenericParamList, param: &SyntaxNode) -> TextRange {
+    if list.generic_params().count() == 1 {
+        list.syntax().text_range()
+    } else {
+        list_item_removal(param)
+    }
+}
+
+/// The range of `node`, an item of a comma separated list, along with one of its commas.
+fn list_item_removal(node: &SyntaxNode) -> TextRange {
+    let non_trivia = |it: &SyntaxElement| !it.kind().is_trivia();
+    let next = node.siblings_with_tokens(Direction::Next).skip(1).find(non_trivia);
+    if let Some(comma) = next.filter(|it| it.kind() == T![,]) {
+        let end =
+            std::iter::successors(comma.next_sibling_or_token(), |it| it.next_sibling_or_token())
+                .find(non_trivia)
+                .map_or(comma.text_range().end(), |it| it.text_range().start());
+        return TextRange::new(node.text_range().start(), end);
+    }
+    let prev = node.siblings_with_tokens(Direction::Prev).skip(1).find(non_trivia);
+    match prev.filter(|it| it.kind() == T![,]) {
+        Some(comma) => TextRange::new(comma.text_range().start(), node.text_range().end()),
+        None => node.text_range(),
+    }
+}
+
+/// The text edits of the assists, over several files.
+#[derive(Default)]
+struct Edits(FxHashMap<FileId, Vec<(TextRange, String)>>);
+
+impl Edits {
+    fn add(&mut self, file_id: FileId, range: TextRange, text: String) {
+        self.0.entry(file_id).or_default().push((range, text));
+    }
+
+    fn apply(&self, builder: &mut SourceChangeBuilder) {
+        for (&file_id, edits) in &self.0 {
+            builder.edit_file(file_id);
+            for (range, text) in edits {
+                builder.replace(*range, text);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use crate::{
+        AssistConfig,
+        tests::{
+            TEST_CONFIG, check_assist, check_assist_annotations, check_assist_not_applicable,
+            check_assist_not_applicable_with_config,
+        },
+    };
+
+    use super::*;
+
+    #[test]
+    fn dyn_field_to_generic_struct() {
+        check_assist(
+            replace_dyn_trait_with_generic,
+            r#"
+trait Draw {}
+trait Named {}
+
+struct Canvas {
+    shape: Box<dyn $0Draw + Send>,
+    next: Option<Box<Canvas>>,
+}
+
+impl Canvas {
+    fn take(self) -> Canvas {
+        self
+    }
+}
+
+impl Named for Canvas {}
+
+fn paint(canvas: &Canvas) {}
+"#,
+            r#"
+trait Draw {}
+trait Named {}
+
+struct Canvas<D: Draw + Send + ?Sized> {
+    shape: Box<D>,
+    next: Option<Box<Canvas<D>>>,
+}
+
+impl<D: Draw + Send + ?Sized> Canvas<D> {
+    fn take(self) -> Canvas<D> {
+        self
+    }
+}
+
+impl<D: Draw + Send + ?Sized> Named for Canvas<D> {}
+
+fn paint(canvas: &Canvas<dyn Draw + Send>) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn dyn_param_to_generic_fn() {
+        check_assist(
+            replace_dyn_trait_with_generic,
+            r#"
+trait Draw {}
+
+fn render<'a, T>(items: &[T], shape: &'a mut (dyn $0Draw + 'a)) {}
+"#,
+            r#"
+trait Draw {}
+
+fn render<'a, T, D: Draw + 'a + ?Sized>(items: &[T], shape: &'a mut D) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn dyn_to_generic_infers_the_parameter_at_turbofish_calls() {
+        check_assist(
+            replace_dyn_trait_with_generic,
+            r#"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+struct Painter;
+impl Painter {
+    fn paint<T>(&self, items: &[T], shape: &dyn $0Draw) {}
+}
+
+fn main() {
+    Painter.paint::<u32>(&[], &Circle);
+    Painter.paint(&[0], &Circle);
+}
+"#,
+            r#"
+trait Draw {}
+struct Circle;
+impl Draw for Circle {}
+struct Painter;
+impl Painter {
+    fn paint<T, D: Draw + ?Sized>(&self, items: &[T], shape: &D) {}
+}
+
+fn main() {
+    Painter.paint::<u32, _>(&[], &Circle);
+    Painter.paint(&[0], &Circle);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn dyn_to_generic_not_applicable_in_trait_impls() {
+        check_assist_not_applicable(
+            replace_dyn_trait_with_generic,
+            r#"
+trait Draw {}
+trait Render {
+    fn render(&self, shape: &dyn Draw);
+}
+struct S;
+impl Render for S {
+    fn render(&self, shape: &dyn $0Draw) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generic_fn_to_dyn() {
+        check_assist(
+            replace_generic_with_dyn_trait,
+            r#"
+//- minicore: send
+trait Draw {}
+struct Box<T: ?Sized>(*const T);
+
+fn render<'a, $0D: Draw + Send, T>(first: &D, rest: Box<D>, items: &'a [T]) {}
+"#,
+            r#"
+trait Draw {}
+struct Box<T: ?Sized>(*const T);
+
+fn render<'a, T>(first: &(dyn Draw + Send), rest: Box<dyn Draw + Send>, items: &'a [T]) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn generic_struct_to_dyn() {
+        check_assist(
+            replace_generic_with_dyn_trait,
+            r#"
+trait Draw {}
+struct Box<T: ?Sized>(*const T);
+struct Circle;
+impl Draw for Circle {}
+
+struct Canvas<$0D: Draw> {
+    shape: Box<D>,
+    size: u32,
+}
+
+impl<D: Draw> Canvas<D> {
+    fn size(&self) -> u32 {
+        self.size
+    }
+}
+
+fn paint(canvas: &Canvas<Circle>) {}
+"#,
+            r#"
+trait Draw {}
+struct Box<T: ?Sized>(*const T);
+struct Circle;
+impl Draw for Circle {}
+
+struct Canvas {
+    shape: Box<dyn Draw>,
+    size: u32,
+}
+
+impl Canvas {
+    fn size(&self) -> u32 {
+        self.size
+    }
+}
+
+fn paint(canvas: &Canvas) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn generic_to_dyn_removes_turbofish_args() {
+        check_assist(
+            replace_generic_with_dyn_trait,
+            r#"
+//- /main.rs
+mod shapes;
+trait Draw {}
+pub fn render<'a, T, $0D: Draw + ?Sized>(items: &'a [T], shape: &D) {}
+
+fn main() {
+    render::<u32, shapes::Circle>(&[], &shapes::Circle);
+    render(&[0], &shapes::Circle);
+}
+//- /shapes.rs
+pub struct Circle;
+impl crate::Draw for Circle {}
+fn draw() {
+    let f = crate::render::<u8, Circle>;
+}
+"#,
+            r#"
+//- /main.rs
+mod shapes;
+trait Draw {}
+pub fn render<'a, T>(items: &'a [T], shape: &dyn Draw) {}
+
+fn main() {
+    render::<u32>(&[], &shapes::Circle);
+    render(&[0], &shapes::Circle);
+}
+//- /shapes.rs
+pub struct Circle;
+impl crate::Draw for Circle {}
+fn draw() {
+    let f = crate::render::<u8>;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn generic_to_dyn_reports_impls_left_as_they_are() {
+        check_assist_annotations(
+            replace_generic_with_dyn_trait,
+            r#"
+trait Draw {}
+struct Canvas<$0D: Draw> {
+    shape: &'static D,
+}
+impl<D: Draw> Canvas<D> {
+    fn shape(&self) -> &D {
+        self.shape
+    }
+}
+"#,
+            "Replace type parameter `D` with `dyn Draw`",
+            expect![[r#"
+                This leaves 1 impl(s) that can't be adjusted
+                Impls that use the parameter elsewhere:
+                /main.rs:5:15
+            "#]],
+        );
+    }
+
+    #[test]
+    fn generic_to_dyn_not_applicable() {
+        // The trait isn't dyn compatible.
+        check_assist_not_applicable(
+            replace_generic_with_dyn_trait,
+            r#"
+trait Make {
+    fn make() -> Self;
+}
+fn f<$0M: Make>(m: &M) {}
+"#,
+        );
+        // The parameter is used by value.
+        check_assist_not_applicable(
+            replace_generic_with_dyn_trait,
+            r#"
+trait Draw {}
+fn f<$0D: Draw>(a: &D, b: D) {}
+"#,
+        );
+        // The parameter is used outside of the signature.
+        check_assist_not_applicable(
+            replace_generic_with_dyn_trait,
+            r#"
+trait Draw {}
+fn f<$0D: Draw>(a: &D) -> &D {
+    a
+}
+"#,
+        );
+        // The impl uses its parameter in a method.
+        check_assist_not_applicable_with_config(
+            replace_generic_with_dyn_trait,
+            AssistConfig { show_rename_conflicts: false, ..TEST_CONFIG },
+            r#"
+trait Draw {}
+struct Canvas<$0D: Draw> {
+    shape: &'static D,
+}
+impl<D: Draw> Canvas<D> {
+    fn shape(&self) -> &D {
+        self.shape
+    }
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,685 @@
+use hir::{AsAssocItem, AssocItemContainer, PathResolution, Semantics};
+use ide_db::{
+    EditionedFileId, FxHashMap, FxHashSet, RootDatabase,
+    defs::{Definition, NameRefClass},
+    helpers::removal_ranges,
+    search::{FileReference, FileReferenceNode, ReferenceCategory, SearchScope},
+    source_change::ChangeAnnotation,
+};
+use itertools::Itertools;
+use syntax::{
+    AstNode, Direction, SyntaxKind, SyntaxNode, T, TextRange, algo::non_trivia_sibling, ast,
+    match_ast,
+};
+
+use crate::{AssistContext, AssistId, Assists, utils::location};
+
+// Assist: safe_delete
+//
+// Deletes the item under the cursor when nothing uses it anymore, along with its
+// imports, the `impl`s of a deleted type and the imports only it needed.
+//
+// ```
+// struct Meters(u32);
+//
+// impl Meters {
+//     fn get(&self) -> u32 { self.0 }
+// }
+//
+// struct Feet$0(u32);
+//
+// impl Feet {
+//     fn get(&self) -> u32 { self.0 }
+// }
+// ```
+// ->
+// ```
+// struct Meters(u32);
+//
+// impl Meters {
+//     fn get(&self) -> u32 { self.0 }
+// }
+// ```
+pub(crate) fn safe_delete(acc: &mut Assists, ctx: &AssistContext<'_>) -> Option<()> {
+    let name = ctx.find_node_at_offset::<ast::Name>()?;
+    let (def, node) = deleted_item(&ctx.sema, &name)?;
+    let in_list = matches!(node.kind(), SyntaxKind::RECORD_FIELD | SyntaxKind::VARIANT);
+    // Deleting the last field or variant empties the type, delete the type itself instead.
+    if in_list
+        && !node
+            .siblings(Direction::Next)
+            .chain(node.siblings(Direction::Prev))
+            .any(|it| it != node && it.kind() == node.kind())
+    {
+        return None;
+    }
+
+    // Without a way to show the references left behind, used items are not deleted, so the
+    // usages have to be found before offering the assist. Otherwise they are found on demand.
+    let deletion = if ctx.config.show_rename_conflicts {
+        None
+    } else {
+        let deletion = Deletion::new(ctx, def, &node, in_list);
+        if !deletion.remaining.is_empty() {
+            return None;
+        }
+        Some(deletion)
+    };
+
+    let label = format!("Delete `{}`", name.text());
+    acc.add(AssistId::refactor("safe_delete"), label, name.syntax().text_range(), |builder| {
+        let Deletion { deleted, mut imports, remaining } =
+            deletion.unwrap_or_else(|| Deletion::new(ctx, def, &node, in_list));
+        let is_deleted = |file_id, range| is_deleted(ctx, &node, &deleted, file_id, range);
+
+        let mut ranges: FxHashMap<EditionedFileId, Vec<TextRange>> = FxHashMap::default();
+        if in_list {
+            ranges.entry(ctx.file_id()).or_default().push(list_element_range(&node));
+        }
+        for (&file_id, nodes) in &deleted {
+            let unused = unused_imports(&ctx.sema, file_id, nodes, &is_deleted);
+            imports.entry(file_id).or_default().extend(unused);
+        }
+        let mut items = deleted.clone();
+        for (file_id, trees) in imports {
+            for tree in top_removed_trees(trees) {
+                match tree.syntax().parent().and_then(ast::Use::cast) {
+                    Some(use_) => items.entry(file_id).or_default().push(use_.syntax().clone()),
+                    None => {
+                        ranges.entry(file_id).or_default().push(list_element_range(tree.syntax()))
+                    }
+                }
+            }
+        }
+        for (file_id, nodes) in items {
+            ranges.entry(file_id).or_default().extend(removal_ranges(&nodes));
+        }
+
+        if !remaining.is_empty() {
+            builder.annotate(ChangeAnnotation {
+                label: "This deletion leaves references to the item behind".to_owned(),
+                needs_confirmation: true,
+                description: Some(format!(
+                    "The item is still used at:\n{}",
+                    remaining
+                        .iter()
+                        .map(|&(file_id, range)| location(ctx.db(), file_id, range))
+                        .format("\n")
+                )),
+            });
+        }
+        for (file_id, mut ranges) in ranges {
+            builder.edit_file(file_id.file_id(ctx.db()));
+            ranges.sort_by_key(|it| it.start());
+            ranges.dedup_by(|next, prev| {
+                if prev.end() >= next.start() {
+                    *prev = prev.cover(*next);
+                    true
+                } else {
+                    false
+                }
+            });
+            for range in ranges {
+                builder.delete(range);
+            }
+        }
+    })
+}
+
+/// The items to delete along with the one under the cursor, and the references to it that are
+/// not deleted.
+struct Deletion {
+    /// The items that go along with the deleted one, like the `impl`s of a type.
+    deleted: FxHashMap<EditionedFileId, Vec<SyntaxNode>>,
+    /// The imports of the deleted item.
+    imports: FxHashMap<EditionedFileId, Vec<ast::UseTree>>,
+    /// The references left behind.
+    remaining: Vec<(EditionedFileId, TextRange)>,
+}
+
+impl Deletion {
+    fn new(ctx: &AssistContext<'_>, def: Definition, node: &SyntaxNode, in_list: bool) -> Self {
+        let usages = def.usages(&ctx.sema).all();
+        let mut deleted: FxHashMap<EditionedFileId, Vec<SyntaxNode>> = FxHashMap::default();
+        if !in_list {
+            deleted.entry(ctx.file_id()).or_default().push(node.clone());
+        }
+        for (file_id, references) in usages.iter() {
+            for reference in references {
+                if let Some(item) = dependent_item(def, reference) {
+                    deleted.entry(file_id).or_default().push(item);
+                }
+            }
+        }
+
+        let mut imports: FxHashMap<EditionedFileId, Vec<ast::UseTree>> = FxHashMap::default();
+        let mut remaining = Vec::new();
+        for (file_id, references) in usages.iter() {
+            for reference in references {
+                if is_deleted(ctx, node, &deleted, file_id, reference.range) {
+                    continue;
+                }
+                match use_tree_of(reference) {
+                    Some(tree) => imports.entry(file_id).or_default().push(tree),
+                    None => remaining.push((file_id, reference.range)),
+                }
+            }
+        }
+        Deletion { deleted, imports, remaining }
+    }
+}
+
+/// Whether `range` in `file_id` is inside the deleted `node` or one of the `deleted` items.
+fn is_deleted(
+    ctx: &AssistContext<'_>,
+    node: &SyntaxNode,
+    deleted: &FxHashMap<EditionedFileId, Vec<SyntaxNode>>,
+    file_id: EditionedFileId,
+    range: TextRange,
+) -> bool {
+    (file_id == ctx.file_id() && node.text_range().contains_range(range))
+        || deleted
+            .get(&file_id)
+            .is_some_and(|nodes| nodes.iter().any(|it| it.text_range().contains_range(range)))
+}
+
+/// The definition of the item named `name`, along with the node to delete.
+fn deleted_item(
+    sema: &Semantics<'_, RootDatabase>,
+    name: &ast::Name,
+) -> Option<(Definition, SyntaxNode)> {
+    let db = sema.db;
+    let parent = name.syntax().parent()?;
+    let def: Definition = match_ast! {
+        match parent {
+            ast::Fn(it) => {
+                let func = sema.to_def(&it)?;
+                // Trait methods are deleted along with their implementations, but
+                // the implementation of a trait method can't go on its own.
+                if func.as_assoc_item(db).is_some_and(|it| it.implemented_trait(db).is_some()) {
+                    return None;
+                }
+                func.into()
+            },
+            ast::Struct(it) => hir::Adt::from(sema.to_def(&it)?).into(),
+            ast::Enum(it) => hir::Adt::from(sema.to_def(&it)?).into(),
+            ast::Union(it) => hir::Adt::from(sema.to_def(&it)?).into(),
+            ast::Const(it) => {
+                let konst = sema.to_def(&it)?;
+                if !is_free_or_inherent(konst.as_assoc_item(db), db) {
+                    return None;
+                }
+                konst.into()
+            },
+            ast::Static(it) => sema.to_def(&it)?.into(),
+            ast::TypeAlias(it) => {
+                let alias = sema.to_def(&it)?;
+                if !is_free_or_inherent(alias.as_assoc_item(db), db) {
+                    return None;
+                }
+                alias.into()
+            },
+            ast::RecordField(it) => sema.to_def(&it)?.into(),
+            ast::Variant(it) => sema.to_def(&it)?.into(),
+            _ => return None,
+        }
+    };
+    Some((def, parent))
+}
+
+fn is_free_or_inherent(item: Option<hir::AssocItem>, db: &RootDatabase) -> bool {
+    match item.map(|it| it.container(db)) {
+        None => true,
+        Some(AssocItemContainer::Impl(impl_)) => impl_.trait_(db).is_none(),
+        Some(AssocItemContainer::Trait(_)) => false,
+    }
+}
+
+/// Returns the item that has to go along with `def` because of `reference`:
+/// an `impl` of a deleted type or the implementation of a deleted trait method.
+fn dependent_item(def: Definition, reference: &FileReference) -> Option<SyntaxNode> {
+    let item = match (def, &reference.name) {
+        (Definition::Adt(_), FileReferenceNode::NameRef(name_ref)) => {
+            let impl_ = name_ref.syntax().ancestors().find_map(ast::Impl::cast)?;
+            let ast::Type::PathType(self_ty) = impl_.self_ty()? else { return None };
+            if self_ty.path()?.segment()?.name_ref()? != *name_ref {
+                return None;
+            }
+            impl_.syntax().clone()
+        }
+        (Definition::Function(_), FileReferenceNode::Name(name)) => {
+            let func = name.syntax().parent().and_then(ast::Fn::cast)?;
+            let impl_ = func.syntax().parent()?.parent().and_then(ast::Impl::cast)?;
+            impl_.trait_()?;
+            func.syntax().clone()
+        }
+        _ => return None,
+    };
+    // Items coming from macro expansions can't be deleted.
+    item.ancestors().last().is_some_and(|it| it.kind() == SyntaxKind::SOURCE_FILE).then_some(item)
+}
+
+/// The use tree importing the item `reference` refers to.
+fn use_tree_of(reference: &FileReference) -> Option<ast::UseTree> {
+    if !reference.category.contains(ReferenceCategory::IMPORT) {
+        return None;
+    }
+    let path = reference.name.as_name_ref()?.syntax().ancestors().find_map(ast::Path::cast)?;
+    path.top_path().syntax().parent().and_then(ast::UseTree::cast)
+}
+
+/// The leaves of the use trees in the modules of `nodes` whose imports are only
+/// used by the deleted code.
+fn unused_imports(
+    sema: &Semantics<'_, RootDatabase>,
+    file_id: EditionedFileId,
+    nodes: &[SyntaxNode],
+    is_deleted: &dyn Fn(EditionedFileId, TextRange) -> bool,
+) -> Vec<ast::UseTree> {
+    let referenced: FxHashSet<Definition> = nodes
+        .iter()
+        .flat_map(|it| it.descendants().filter_map(ast::NameRef::cast))
+        .filter_map(|name_ref| match NameRefClass::classify(sema, &name_ref)? {
+            NameRefClass::Definition(def, _) => Some(def),
+            _ => None,
+        })
+        .collect();
+    let containers: FxHashSet<SyntaxNode> = nodes
+        .iter()
+        .filter_map(|it| {
+            it.ancestors()
+                .skip(1)
+                .find(|it| matches!(it.kind(), SyntaxKind::ITEM_LIST | SyntaxKind::SOURCE_FILE))
+        })
+        .collect();
+
+    let mut unused = Vec::new();
+    for container in containers {
+        let scope =
+            SearchScope::file_range(hir::FileRange { file_id, range: container.text_range() });
+        let trees = container
+            .children()
+            .filter_map(ast::Use::cast)
+            .flat_map(|it| it.syntax().descendants().filter_map(ast::UseTree::cast))
+            .filter(|it| it.use_tree_list().is_none() && it.star_token().is_none());
+        for tree in trees {
+            let Some(path) = tree.path() else { continue };
+            let def = match sema.resolve_path(&path) {
+                // Traits are used by method calls, which aren't references to them.
+                Some(PathResolution::Def(hir::ModuleDef::Trait(_))) | None => continue,
+                Some(PathResolution::Def(def)) => Definition::from(def),
+                Some(_) => continue,
+            };
+            if !referenced.contains(&def) {
+                continue;
+            }
+            let rename = tree.rename();
+            let usages = def.usages(sema).in_scope(&scope).with_rename(rename.as_ref()).all();
+            let still_used = usages.iter().any(|(file_id, references)| {
+                references.iter().any(|it| {
+                    !it.category.contains(ReferenceCategory::IMPORT)
+                        && !is_deleted(file_id, it.range)
+                })
+            });
+            if !still_used {
+                unused.push(tree);
+            }
+        }
+    }
+    unused
+}
+
+/// Replaces the trees of a group that goes away entirely by the tree of the group.
+fn top_removed_trees(mut trees: Vec<ast::UseTree>) -> Vec<ast::UseTree> {
+    trees.sort_by_key(|it| it.syntax().text_range().start());
+    trees.dedup();
+    while let Some(list) = trees.iter().find_map(|tree| {
+        let list = tree.syntax().parent().and_then(ast::UseTreeList::cast)?;
+        list.use_trees().all(|it| trees.contains(&it)).then_some(list)
+    }) {
+        trees.retain(|it| it.syntax().parent().as_ref() != Some(list.syntax()));
+        trees.push(list.parent_use_tree());
+    }
+    // Drop the trees that are inside of another removed one.
+    let all = trees.clone();
+    trees.retain(|tree| {
+        !all.iter().any(|it| {
+            it != tree && it.syntax().text_range().contains_range(tree.syntax().text_range())
+        })
+    });
+    trees
+}
+
+/// The range to delete to remove `node` from a comma separated list, like a field
+/// from a field list.
+fn list_element_range(node: &SyntaxNode) -> TextRange {
+    let next = node.siblings(Direction::Next).skip(1).find(|it| it.kind() == node.kind());
+    let prev = node.siblings(Direction::Prev).skip(1).find(|it| it.kind() == node.kind());
+    match (next, prev) {
+        (Some(next), _) => TextRange::new(node.text_range().start(), next.text_range().start()),
+        (None, Some(prev)) => TextRange::new(prev.text_range().end(), node.text_range().end()),
+        (None, None) => {
+            let start = non_trivia_sibling(node.clone().into(), Direction::Prev)
+                .filter(|it| matches!(it.kind(), T!['{'] | T!['(']))
+                .map_or(node.text_range().start(), |it| it.text_range().end());
+            let end = node
+                .siblings_with_tokens(Direction::Next)
+                .skip(1)
+                .find(|it| !matches!(it.kind(), SyntaxKind::WHITESPACE | T![,]))
+                .map_or(node.text_range().end(), |it| it.text_range().start());
+            TextRange::new(start, end)
+        }
+    }
+}
+
+/// A `path:line:column` description of `range`.
+#[cfg(test)]
+mod tests {
+    use crate::{
+        AssistConfig,
+        tests::{
+            TEST_CONFIG, check_assist, check_assist_by_label, check_assist_not_applicable,
+            check_assist_not_applicable_with_config, check_assist_with_config,
+        },
+    };
+
+    use super::*;
+
+    #[test]
+    fn delete_unused_function() {
+        check_assist(
+            safe_delete,
+            r#"
+fn foo$0() -> u32 {
+    1
+}
+
+fn main() {}
+"#,
+            r#"
+fn main() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn delete_type_with_impls_and_imports() {
+        check_assist(
+            safe_delete,
+            r#"
+//- minicore: default
+mod shapes {
+    pub struct Square$0(pub u32);
+
+    impl Square {
+        pub fn area(&self) -> u32 {
+            self.0 * self.0
+        }
+    }
+
+    impl Default for Square {
+        fn default() -> Self {
+            Square(1)
+        }
+    }
+
+    pub struct Circle(pub u32);
+}
+
+use shapes::{Circle, Square};
+
+fn main() {
+    let _ = Circle(1);
+}
+"#,
+            r#"
+mod shapes {
+    pub struct Circle(pub u32);
+}
+
+use shapes::{Circle};
+
+fn main() {
+    let _ = Circle(1);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn delete_whole_import() {
+        check_assist(
+            safe_delete,
+            r#"
+mod a {
+    pub struct Foo$0;
+    pub struct Bar;
+}
+
+use a::Foo;
+use a::Bar;
+
+fn main() {
+    let _ = Bar;
+}
+"#,
+            r#"
+mod a {
+    pub struct Bar;
+}
+
+use a::Bar;
+
+fn main() {
+    let _ = Bar;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn delete_trait_method_with_implementations() {
+        check_assist(
+            safe_delete,
+            r#"
+trait Shape {
+    fn area(&self) -> u32;
+    fn name$0(&self) -> &'static str;
+}
+
+struct Square;
+
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        1
+    }
+    fn name(&self) -> &'static str {
+        "square"
+    }
+}
+"#,
+            r#"
+trait Shape {
+    fn area(&self) -> u32;
+}
+
+struct Square;
+
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        1
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn delete_variant() {
+        check_assist(
+            safe_delete,
+            r#"
+enum Color {
+    Red,
+    Green$0,
+    Blue,
+}
+
+fn main() {
+    let _ = (Color::Red, Color::Blue);
+}
+"#,
+            r#"
+enum Color {
+    Red,
+    Blue,
+}
+
+fn main() {
+    let _ = (Color::Red, Color::Blue);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn delete_imports_only_used_by_deleted_code() {
+        check_assist(
+            safe_delete,
+            r#"
+mod util {
+    pub fn helper() -> u32 {
+        1
+    }
+    pub fn other() -> u32 {
+        2
+    }
+}
+
+use util::{helper, other};
+
+fn foo$0() -> u32 {
+    helper()
+}
+
+fn main() {
+    other();
+}
+"#,
+            r#"
+mod util {
+    pub fn helper() -> u32 {
+        1
+    }
+    pub fn other() -> u32 {
+        2
+    }
+}
+
+use util::{other};
+
+fn main() {
+    other();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn delete_used_field_as_conflict() {
+        check_assist_by_label(
+            safe_delete,
+            r#"
+struct Point {
+    x: u32,
+    y$0: u32,
+}
+
+fn main() {
+    let p = Point { x: 1, y: 2 };
+    let _ = p.x;
+}
+"#,
+            r#"
+struct Point {
+    x: u32,
+}
+
+fn main() {
+    let p = Point { x: 1, y: 2 };
+    let _ = p.x;
+}
+"#,
+            "Delete `y`",
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_used_item_without_conflicts() {
+        check_assist_not_applicable_with_config(
+            safe_delete,
+            AssistConfig { show_rename_conflicts: false, ..TEST_CONFIG },
+            r#"
+struct Point {
+    x: u32,
+    y$0: u32,
+}
+
+fn main() {
+    let p = Point { x: 1, y: 2 };
+    let _ = p.x;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn delete_unused_item_without_conflicts() {
+        check_assist_with_config(
+            safe_delete,
+            AssistConfig { show_rename_conflicts: false, ..TEST_CONFIG },
+            r#"
+struct Meters$0(u32);
+
+impl Meters {
+    fn get(&self) -> u32 { self.0 }
+}
+
+fn main() {}
+"#,
+            r#"
+fn main() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_only_field() {
+        check_assist_not_applicable(
+            safe_delete,
+            r#"
+struct Foo { $0bar: u32 }
+"#,
+        );
+    }
+
+    #[test]
+    fn not_applicable_to_trait_impl_method() {
+        check_assist_not_applicable(
+            safe_delete,
+            r#"
+trait Shape {
+    fn area(&self) -> u32;
+}
+
+struct Square;
+
+impl Shape for Square {
+    fn area$0(&self) -> u32 {
+        1
+    }
+}
+"#,
+        );
+    }
+}
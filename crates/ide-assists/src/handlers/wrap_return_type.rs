@@ -265,7 +265,7 @@ fn wrapper_alias<'db>(
     })
 }
 
-fn tail_cb_impl(acc: &mut Vec<ast::Expr>, e: &ast::Expr) {
+pub(crate) fn tail_cb_impl(acc: &mut Vec<ast::Expr>, e: &ast::Expr) {
     match e {
         Expr::BreakExpr(break_expr) => {
             if let Some(break_expr_arg) = break_expr.expr() {
@@ -117,6 +117,7 @@ mod handlers {
     mod apply_demorgan;
     mod auto_import;
     mod bind_unused_param;
+    mod change_signature;
     mod change_visibility;
     mod convert_bool_then;
     mod convert_bool_to_enum;
@@ -124,6 +125,7 @@ mod handlers {
     mod convert_closure_to_fn;
     mod convert_comment_block;
     mod convert_comment_from_or_to_doc;
+    mod convert_for_loop_to_iterator_chain;
     mod convert_for_to_while_let;
     mod convert_from_to_tryfrom;
     mod convert_integer_literal;
@@ -157,6 +159,7 @@ mod handlers {
     mod flip_or_pattern;
     mod flip_trait_bound;
     mod generate_blanket_trait_impl;
+    mod generate_builder;
     mod generate_constant;
     mod generate_default_from_enum_variant;
     mod generate_default_from_new;
@@ -186,6 +189,8 @@ mod handlers {
     mod into_to_qualified_from;
     mod introduce_named_lifetime;
     mod introduce_named_type_parameter;
+    mod introduce_newtype;
+    mod introduce_parameter_object;
     mod invert_if;
     mod merge_imports;
     mod merge_match_arms;
@@ -194,12 +199,15 @@ mod handlers {
     mod move_const_to_impl;
     mod move_from_mod_rs;
     mod move_guard;
+    mod move_item_to_module;
     mod move_module_to_file;
     mod move_to_mod_rs;
     mod normalize_import;
     mod number_representation;
     mod promote_local_to_const;
+    mod propagate_to_callers;
     mod pull_assignment_up;
+    mod pull_up_method;
     mod qualify_method_call;
     mod qualify_path;
     mod raw_string;
@@ -214,6 +222,7 @@ mod handlers {
     mod reorder_impl_items;
     mod replace_arith_op;
     mod replace_derive_with_manual_impl;
+    mod replace_dyn_trait_with_generic;
     mod replace_if_let_with_match;
     mod replace_is_method_with_if_let_method;
     mod replace_let_with_if_let;
@@ -222,6 +231,7 @@ mod handlers {
     mod replace_qualified_name_with_use;
     mod replace_string_with_char;
     mod replace_turbofish_with_explicit_type;
+    mod safe_delete;
     mod sort_items;
     mod split_import;
     mod term_search;
@@ -254,6 +264,7 @@ mod handlers {
             apply_demorgan::apply_demorgan,
             auto_import::auto_import,
             bind_unused_param::bind_unused_param,
+            change_signature::change_signature,
             change_visibility::change_visibility,
             convert_bool_then::convert_bool_then_to_if,
             convert_bool_then::convert_if_to_bool_then,
@@ -262,6 +273,8 @@ mod handlers {
             convert_closure_to_fn::convert_closure_to_fn,
             convert_comment_block::convert_comment_block,
             convert_comment_from_or_to_doc::convert_comment_from_or_to_doc,
+            convert_for_loop_to_iterator_chain::convert_for_loop_to_iterator_chain,
+            convert_for_loop_to_iterator_chain::convert_iterator_chain_to_for_loop,
             convert_for_to_while_let::convert_for_loop_to_while_let,
             convert_from_to_tryfrom::convert_from_to_tryfrom,
             convert_integer_literal::convert_integer_literal,
@@ -317,6 +330,7 @@ mod handlers {
             generate_trait_from_impl::generate_trait_from_impl,
             generate_single_field_struct_from::generate_single_field_struct_from,
             generate_blanket_trait_impl::generate_blanket_trait_impl,
+            generate_builder::generate_builder,
             inline_call::inline_call,
             inline_call::inline_into_callers,
             inline_const_as_literal::inline_const_as_literal,
@@ -327,6 +341,8 @@ mod handlers {
             into_to_qualified_from::into_to_qualified_from,
             introduce_named_lifetime::introduce_named_lifetime,
             introduce_named_type_parameter::introduce_named_type_parameter,
+            introduce_newtype::introduce_newtype,
+            introduce_parameter_object::introduce_parameter_object,
             invert_if::invert_if,
             merge_imports::merge_imports,
             merge_match_arms::merge_match_arms,
@@ -336,12 +352,17 @@ mod handlers {
             move_from_mod_rs::move_from_mod_rs,
             move_guard::move_arm_cond_to_match_guard,
             move_guard::move_guard_to_arm_body,
+            move_item_to_module::move_item_to_module,
             move_module_to_file::move_module_to_file,
             move_to_mod_rs::move_to_mod_rs,
             normalize_import::normalize_import,
             number_representation::reformat_number_literal,
             promote_local_to_const::promote_local_to_const,
+            propagate_to_callers::make_async_and_propagate,
+            propagate_to_callers::make_fallible_and_propagate,
             pull_assignment_up::pull_assignment_up,
+            pull_up_method::pull_up_method,
+            pull_up_method::push_down_method,
             qualify_method_call::qualify_method_call,
             qualify_path::qualify_path,
             raw_string::add_hash,
@@ -360,6 +381,8 @@ mod handlers {
             replace_arith_op::replace_arith_with_saturating,
             replace_arith_op::replace_arith_with_wrapping,
             replace_derive_with_manual_impl::replace_derive_with_manual_impl,
+            replace_dyn_trait_with_generic::replace_dyn_trait_with_generic,
+            replace_dyn_trait_with_generic::replace_generic_with_dyn_trait,
             replace_if_let_with_match::replace_if_let_with_match,
             replace_if_let_with_match::replace_match_with_if_let,
             replace_is_method_with_if_let_method::replace_is_method_with_if_let_method,
@@ -369,6 +392,7 @@ mod handlers {
             replace_named_generic_with_impl::replace_named_generic_with_impl,
             replace_qualified_name_with_use::replace_qualified_name_with_use,
             replace_turbofish_with_explicit_type::replace_turbofish_with_explicit_type,
+            safe_delete::safe_delete,
             sort_items::sort_items,
             split_import::split_import,
             term_search::term_search,
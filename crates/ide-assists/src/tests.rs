@@ -1,6 +1,6 @@
 mod generated;
 
-use expect_test::expect;
+use expect_test::{Expect, expect};
 use hir::{Semantics, db::HirDatabase, setup_tracing};
 use ide_db::{
     EditionedFileId, FileRange, RootDatabase, SnippetCap,
@@ -39,6 +39,8 @@ pub(crate) const TEST_CONFIG: AssistConfig = AssistConfig {
     expr_fill_default: ExprFillDefaultMode::Todo,
     prefer_self_ty: false,
     show_rename_conflicts: true,
+    generate_builder_with_prefix: false,
+    generate_builder_into_setters: false,
 };
 
 pub(crate) const TEST_CONFIG_NO_GROUPING: AssistConfig = AssistConfig {
@@ -61,6 +63,8 @@ pub(crate) const TEST_CONFIG_NO_GROUPING: AssistConfig = AssistConfig {
     expr_fill_default: ExprFillDefaultMode::Todo,
     prefer_self_ty: false,
     show_rename_conflicts: true,
+    generate_builder_with_prefix: false,
+    generate_builder_into_setters: false,
 };
 
 pub(crate) const TEST_CONFIG_NO_SNIPPET_CAP: AssistConfig = AssistConfig {
@@ -83,6 +87,8 @@ pub(crate) const TEST_CONFIG_NO_SNIPPET_CAP: AssistConfig = AssistConfig {
     expr_fill_default: ExprFillDefaultMode::Todo,
     prefer_self_ty: false,
     show_rename_conflicts: true,
+    generate_builder_with_prefix: false,
+    generate_builder_into_setters: false,
 };
 
 pub(crate) const TEST_CONFIG_IMPORT_ONE: AssistConfig = AssistConfig {
@@ -105,6 +111,8 @@ pub(crate) const TEST_CONFIG_IMPORT_ONE: AssistConfig = AssistConfig {
     expr_fill_default: ExprFillDefaultMode::Todo,
     prefer_self_ty: false,
     show_rename_conflicts: true,
+    generate_builder_with_prefix: false,
+    generate_builder_into_setters: false,
 };
 
 fn assists(
@@ -195,6 +203,17 @@ pub(crate) fn check_assist_by_label(
     check(assist, ra_fixture_before, ExpectedResult::After(&ra_fixture_after), Some(label));
 }
 
+/// Checks the annotations of the changes of the assist with `label`.
+#[track_caller]
+pub(crate) fn check_assist_annotations(
+    assist: Handler,
+    #[rust_analyzer::rust_fixture] ra_fixture: &str,
+    label: &str,
+    expect: Expect,
+) {
+    check(assist, ra_fixture, ExpectedResult::Annotations(expect), Some(label));
+}
+
 // FIXME: instead of having a separate function here, maybe use
 // `extract_ranges` and mark the target as `<target> </target>` in the
 // fixture?
@@ -215,6 +234,15 @@ pub(crate) fn check_assist_not_applicable(
     check(assist, ra_fixture, ExpectedResult::NotApplicable, None);
 }
 
+#[track_caller]
+pub(crate) fn check_assist_not_applicable_with_config(
+    assist: Handler,
+    config: AssistConfig,
+    #[rust_analyzer::rust_fixture] ra_fixture: &str,
+) {
+    check_with_config(config, assist, ra_fixture, ExpectedResult::NotApplicable, None);
+}
+
 #[track_caller]
 pub(crate) fn check_assist_not_applicable_by_label(
     assist: Handler,
@@ -307,6 +335,7 @@ enum ExpectedResult<'a> {
     Unresolved,
     After(&'a str),
     Target(&'a str),
+    Annotations(Expect),
 }
 
 #[track_caller]
@@ -404,6 +433,19 @@ fn check_with_config(
             let range = assist.target;
             assert_eq_text!(&text_without_caret[range], target);
         }
+        (Some(assist), ExpectedResult::Annotations(expect)) => {
+            let source_change = assist.source_change.expect("Assist was not resolved");
+            let mut annotations: Vec<_> = source_change.annotations.into_values().collect();
+            annotations.sort_by(|a, b| a.label.cmp(&b.label));
+            let mut buf = String::new();
+            for annotation in annotations {
+                format_to!(buf, "{}\n", annotation.label);
+                if let Some(description) = annotation.description {
+                    format_to!(buf, "{description}\n");
+                }
+            }
+            expect.assert_eq(&buf);
+        }
         (Some(assist), ExpectedResult::Unresolved) => assert!(
             assist.source_change.is_none(),
             "unresolved assist should not contain source changes"
@@ -411,7 +453,10 @@ fn check_with_config(
         (Some(_), ExpectedResult::NotApplicable) => panic!("assist should not be applicable!"),
         (
             None,
-            ExpectedResult::After(_) | ExpectedResult::Target(_) | ExpectedResult::Unresolved,
+            ExpectedResult::After(_)
+            | ExpectedResult::Target(_)
+            | ExpectedResult::Annotations(_)
+            | ExpectedResult::Unresolved,
         ) => {
             panic!("code action is not applicable")
         }
@@ -367,6 +367,27 @@ fn some_function(x: i32) {
     )
 }
 
+#[test]
+fn doctest_change_signature() {
+    check_doc_test(
+        "change_signature",
+        r#####"
+fn frobnicate(x: i32, $0y: bool) {}
+
+fn main() {
+    frobnicate(92, true);
+}
+"#####,
+        r#####"
+fn frobnicate(y: bool, x: i32) {}
+
+fn main() {
+    frobnicate(true, 92);
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_change_visibility() {
     check_doc_test(
@@ -492,6 +513,31 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_for_loop_to_iterator_chain() {
+    check_doc_test(
+        "convert_for_loop_to_iterator_chain",
+        r#####"
+//- minicore: iterator, copy
+fn total(prices: [u32; 3]) -> u32 {
+    let mut total = 0;
+    for$0 price in prices {
+        if price > 10 {
+            total += price;
+        }
+    }
+    total
+}
+"#####,
+        r#####"
+fn total(prices: [u32; 3]) -> u32 {
+    let total: u32 = prices.into_iter().filter(|&price| price > 10).sum();
+    total
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_for_loop_to_while_let() {
     check_doc_test(
@@ -658,6 +704,31 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_convert_iterator_chain_to_for_loop() {
+    check_doc_test(
+        "convert_iterator_chain_to_for_loop",
+        r#####"
+//- minicore: iterator, copy
+fn total(prices: [u32; 3]) -> u32 {
+    let total: u32 = prices.into_iter().filter(|&price| price > 10).$0sum();
+    total
+}
+"#####,
+        r#####"
+fn total(prices: [u32; 3]) -> u32 {
+    let mut total: u32 = 0;
+    for price in prices {
+        if price > 10 {
+            total += price;
+        }
+    }
+    total
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_convert_let_else_to_match() {
     check_doc_test(
@@ -1515,6 +1586,56 @@ where
     )
 }
 
+#[test]
+fn doctest_generate_builder() {
+    check_doc_test(
+        "generate_builder",
+        r#####"
+//- minicore: option
+struct Person$0 {
+    name: String,
+    email: Option<String>,
+}
+"#####,
+        r#####"
+struct Person {
+    name: String,
+    email: Option<String>,
+}
+
+impl Person {
+    fn builder() -> PersonBuilder {
+        PersonBuilder { name: None, email: None }
+    }
+}
+
+struct PersonBuilder {
+    name: Option<String>,
+    email: Option<String>,
+}
+
+impl PersonBuilder {
+    fn name(mut self, name: String) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    fn email(mut self, email: String) -> Self {
+        self.email = Some(email);
+        self
+    }
+
+    fn build(self) -> Result<Person, &'static str> {
+        Ok(Person {
+            name: self.name.ok_or("missing field `name`")?,
+            email: self.email,
+        })
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_generate_constant() {
     check_doc_test(
@@ -2572,6 +2693,80 @@ fn foo<$0B: Bar>(bar: B) {}
     )
 }
 
+#[test]
+fn doctest_introduce_newtype() {
+    check_doc_test(
+        "introduce_newtype",
+        r#####"
+//- minicore: from
+type UserId$0 = u64;
+
+fn next(id: UserId) -> UserId {
+    id + 1
+}
+
+fn main() {
+    let id = next(1);
+}
+"#####,
+        r#####"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct UserId(u64);
+
+impl From<u64> for UserId {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<UserId> for u64 {
+    fn from(value: UserId) -> Self {
+        value.0
+    }
+}
+
+fn next(id: UserId) -> UserId {
+    UserId(id.0 + 1)
+}
+
+fn main() {
+    let id = next(UserId(1));
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_introduce_parameter_object() {
+    check_doc_test(
+        "introduce_parameter_object",
+        r#####"
+fn draw($0x: i32, y: i32, color: u32$0) {
+    let _ = (x, y, color);
+}
+
+fn main() {
+    draw(1, 2, 0xff);
+}
+"#####,
+        r#####"
+struct DrawParams {
+    x: i32,
+    y: i32,
+    color: u32,
+}
+
+fn draw(params: DrawParams) {
+    let _ = (params.x, params.y, params.color);
+}
+
+fn main() {
+    draw(DrawParams { x: 1, y: 2, color: 0xff });
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_invert_if() {
     check_doc_test(
@@ -2606,6 +2801,53 @@ fn doctest_line_to_block() {
     )
 }
 
+#[test]
+fn doctest_make_async_and_propagate() {
+    check_doc_test(
+        "make_async_and_propagate",
+        r#####"
+fn read_config() -> u32$0 { 0 }
+fn load() -> u32 { read_config() + 1 }
+async fn run() { load(); }
+"#####,
+        r#####"
+async fn read_config() -> u32 { 0 }
+async fn load() -> u32 { read_config().await + 1 }
+async fn run() { load().await; }
+"#####,
+    )
+}
+
+#[test]
+fn doctest_make_fallible_and_propagate() {
+    check_doc_test(
+        "make_fallible_and_propagate",
+        r#####"
+//- minicore: result
+struct Error;
+fn parse$0(s: &str) -> u32 { 0 }
+fn load(s: &str) -> u32 {
+    parse(s) + 1
+}
+fn run() -> Result<(), Error> {
+    load("1");
+    Ok(())
+}
+"#####,
+        r#####"
+struct Error;
+fn parse(s: &str) -> Result<u32, Error> { Ok(0) }
+fn load(s: &str) -> Result<u32, Error> {
+    Ok(parse(s)? + 1)
+}
+fn run() -> Result<(), Error> {
+    load("1")?;
+    Ok(())
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_make_raw_string() {
     check_doc_test(
@@ -2815,6 +3057,33 @@ fn handle(action: Action) {
     )
 }
 
+#[test]
+fn doctest_move_item_to_module() {
+    check_doc_test(
+        "move_item_to_module",
+        r#####"
+mod util {}
+
+fn helper$0() -> u32 { 1 }
+
+fn main() {
+    helper();
+}
+"#####,
+        r#####"
+use util::helper;
+
+mod util {
+    pub(crate) fn helper() -> u32 { 1 }
+}
+
+fn main() {
+    helper();
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_move_module_to_file() {
     check_doc_test(
@@ -2917,6 +3186,114 @@ fn main() {
     )
 }
 
+#[test]
+fn doctest_pull_up_method() {
+    check_doc_test(
+        "pull_up_method",
+        r#####"
+trait Shape {
+    fn area(&self) -> f64;
+}
+
+struct Square(f64);
+
+impl Square {
+    fn $0perimeter(&self) -> f64 {
+        4.0 * self.0
+    }
+}
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+
+struct Circle(f64);
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        3.0 * self.0 * self.0
+    }
+}
+"#####,
+        r#####"
+trait Shape {
+    fn area(&self) -> f64;
+
+    fn perimeter(&self) -> f64;
+}
+
+struct Square(f64);
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+
+    fn perimeter(&self) -> f64 {
+        4.0 * self.0
+    }
+}
+
+struct Circle(f64);
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        3.0 * self.0 * self.0
+    }
+
+    fn perimeter(&self) -> f64 {
+        todo!()
+    }
+}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_push_down_method() {
+    check_doc_test(
+        "push_down_method",
+        r#####"
+trait Animal {
+    fn name(&self) -> &'static str;
+
+    fn $0describe(&self) -> usize {
+        self.name().len()
+    }
+}
+
+struct Dog;
+
+impl Animal for Dog {
+    fn name(&self) -> &'static str {
+        "dog"
+    }
+}
+"#####,
+        r#####"
+trait Animal {
+    fn name(&self) -> &'static str;
+
+    fn describe(&self) -> usize;
+}
+
+struct Dog;
+
+impl Animal for Dog {
+    fn name(&self) -> &'static str {
+        "dog"
+    }
+
+    fn describe(&self) -> usize {
+        self.name().len()
+    }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_qualify_method_call() {
     check_doc_test(
@@ -3284,6 +3661,40 @@ impl Debug for S {
     )
 }
 
+#[test]
+fn doctest_replace_dyn_trait_with_generic() {
+    check_doc_test(
+        "replace_dyn_trait_with_generic",
+        r#####"
+trait Draw {}
+
+fn render(shape: &dyn $0Draw) {}
+"#####,
+        r#####"
+trait Draw {}
+
+fn render<D: Draw + ?Sized>(shape: &D) {}
+"#####,
+    )
+}
+
+#[test]
+fn doctest_replace_generic_with_dyn_trait() {
+    check_doc_test(
+        "replace_generic_with_dyn_trait",
+        r#####"
+trait Draw {}
+
+fn render<$0D: Draw + ?Sized>(shape: &D) {}
+"#####,
+        r#####"
+trait Draw {}
+
+fn render(shape: &dyn Draw) {}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_replace_if_let_with_match() {
     check_doc_test(
@@ -3491,6 +3902,33 @@ fn foo() {
     )
 }
 
+#[test]
+fn doctest_safe_delete() {
+    check_doc_test(
+        "safe_delete",
+        r#####"
+struct Meters(u32);
+
+impl Meters {
+    fn get(&self) -> u32 { self.0 }
+}
+
+struct Feet$0(u32);
+
+impl Feet {
+    fn get(&self) -> u32 { self.0 }
+}
+"#####,
+        r#####"
+struct Meters(u32);
+
+impl Meters {
+    fn get(&self) -> u32 { self.0 }
+}
+"#####,
+    )
+}
+
 #[test]
 fn doctest_sort_items() {
     check_doc_test(
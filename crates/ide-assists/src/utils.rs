@@ -9,8 +9,9 @@ use hir::{
     db::{ExpandDatabase, HirDatabase},
 };
 use ide_db::{
-    RootDatabase,
+    EditionedFileId, LineIndexDatabase, RootDatabase,
     assists::ExprFillDefaultMode,
+    base_db::SourceDatabase,
     famous_defs::FamousDefs,
     path_transform::PathTransform,
     syntax_helpers::{node_ext::preorder_expr, prettify_macro_expansion},
@@ -1256,3 +1257,16 @@ pub(crate) fn is_never_block(
         false
     }
 }
+
+/// Formats the position of `range` as `path:line:column`, for listing affected places in
+/// change annotations.
+pub(crate) fn location(db: &RootDatabase, file_id: EditionedFileId, range: TextRange) -> String {
+    let file_id = file_id.file_id(db);
+    let source_root = db.file_source_root(file_id).source_root_id(db);
+    let source_root = db.source_root(source_root).source_root(db);
+    let position = db.line_index(file_id).line_col(range.start());
+    match source_root.path_for_file(&file_id) {
+        Some(path) => format!("{path}:{}:{}", position.line + 1, position.col + 1),
+        None => format!("{}:{}", position.line + 1, position.col + 1),
+    }
+}
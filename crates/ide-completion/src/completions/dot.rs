@@ -304,6 +304,24 @@ fn foo(s: S) { s.$0 }
         );
     }
 
+    #[test]
+    fn deprecation_is_inherited_from_parent() {
+        check_no_kw(
+            r#"
+#[deprecated]
+struct S { foo: u32 }
+#[deprecated]
+trait Tr { fn bar(&self) {} }
+impl Tr for S {}
+fn foo(s: S) { s.$0 }
+"#,
+            expect![[r#"
+                fd foo                 u32 DEPRECATED
+                me bar() (as Tr) fn(&self) DEPRECATED
+            "#]],
+        );
+    }
+
     #[test]
     fn no_unstable_method_on_stable() {
         check_no_kw(
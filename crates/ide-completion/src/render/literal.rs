@@ -189,7 +189,7 @@ impl Variant {
 
     fn is_deprecated(self, ctx: &RenderContext<'_>) -> bool {
         match self {
-            Variant::Struct(it) => ctx.is_deprecated(it),
+            Variant::Struct(it) => ctx.is_deprecated(hir::Adt::from(it)),
             Variant::EnumVariant(it) => ctx.is_deprecated(it),
         }
     }
@@ -1,7 +1,7 @@
 //! Renderer for patterns.
 
 use hir::{Name, StructKind, db::HirDatabase};
-use ide_db::{SnippetCap, documentation::HasDocs};
+use ide_db::{SnippetCap, defs::Definition, documentation::HasDocs};
 use itertools::Itertools;
 use syntax::{Edition, SmolStr, ToSmolStr};
 
@@ -40,7 +40,7 @@ pub(crate) fn render_struct_pat(
 
     let db = ctx.db();
 
-    Some(build_completion(ctx, label, lookup, pat, strukt, strukt.ty(db), false))
+    Some(build_completion(ctx, label, lookup, pat, hir::Adt::from(strukt), strukt.ty(db), false))
 }
 
 pub(crate) fn render_variant_pat(
@@ -108,7 +108,7 @@ fn build_completion(
     label: SmolStr,
     lookup: SmolStr,
     pat: String,
-    def: impl HasDocs,
+    def: impl HasDocs + Into<Definition>,
     adt_ty: hir::Type<'_>,
     // Missing in context of match statement completions
     is_variant_missing: bool,
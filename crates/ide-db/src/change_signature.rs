@@ -0,0 +1,526 @@
+//! Change signature infrastructure: reorders, adds, removes and retypes the
+//! parameters of a function and rewrites everything that depends on them.
+//!
+//! Besides the function itself this touches the trait declaration and all the
+//! implementations of a trait method, every call (plain, method-call and UFCS
+//! syntax), and uses of the function as a value. The latter are wrapped in a
+//! closure with the previous signature, so that `Fn` trait bounds and function
+//! pointer types they flow into keep working:
+//!
+//! ```ignore
+//! fn f(a: u32, b: bool) {}
+//! // swapping `a` and `b` turns
+//! let g: fn(u32, bool) = f;
+//! // into
+//! let g: fn(u32, bool) = |a, b| f(b, a);
+//! ```
+//!
+//! Sites that can't be rewritten, like calls inside macros, are not touched and
+//! are reported back instead.
+use std::{cmp::Reverse, fmt};
+
+use hir::{AsAssocItem, HasCrate, Semantics};
+use syntax::{
+    AstNode, SourceFile, TextRange,
+    algo::find_node_at_range,
+    ast::{self, HasArgList, HasName},
+};
+
+use crate::{
+    EditionedFileId, FileId, FileRange, FxHashMap, FxHashSet, RootDatabase,
+    defs::Definition,
+    source_change::{SourceChangeBuilder, UnrewrittenSite},
+    traits::as_trait_assoc_def,
+};
+
+pub type Result<T, E = ChangeSignatureError> = std::result::Result<T, E>;
+
+#[derive(Debug)]
+pub struct ChangeSignatureError(pub String);
+
+impl fmt::Display for ChangeSignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// An entry of the new parameter list. The `self` parameter, if any, is always kept in front
+/// and is not part of the list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NewParam {
+    /// The parameter at `index` in the current list, optionally with a new type.
+    Existing { index: usize, ty: Option<String> },
+    /// A new parameter. Existing callers pass `default` for it.
+    Added { pattern: String, ty: String, default: String },
+    /// A new parameter of type `ty` replacing the parameters at `indices`, which become the fields
+    /// of the struct `struct_name`. Existing callers construct the struct from their arguments,
+    /// as a tuple struct when there are no `field_names`.
+    Grouped {
+        pattern: String,
+        ty: String,
+        struct_name: String,
+        indices: Vec<usize>,
+        field_names: Option<Vec<String>>,
+    },
+}
+
+impl NewParam {
+    /// The indices of the current parameters this one is made of.
+    fn indices(&self) -> &[usize] {
+        match self {
+            NewParam::Existing { index, .. } => std::slice::from_ref(index),
+            NewParam::Added { .. } => &[],
+            NewParam::Grouped { indices, .. } => indices,
+        }
+    }
+}
+
+/// The edits changing a signature, along with the sites that could not be rewritten.
+#[derive(Debug)]
+pub struct SignatureEdit {
+    edits: Vec<(FileId, Vec<(TextRange, String)>)>,
+    pub unrewritten: Vec<UnrewrittenSite>,
+}
+
+impl SignatureEdit {
+    /// Records the edits in `builder`.
+    pub fn apply(self, builder: &mut SourceChangeBuilder) {
+        for (file_id, edits) in self.edits {
+            builder.edit_file(file_id);
+            for (range, replacement) in edits {
+                builder.replace(range, replacement);
+            }
+        }
+    }
+}
+
+/// Computes the edits changing the parameter list of `func` to `new_params`.
+///
+/// When `func` is a trait method or implements one, the trait declaration and all of its
+/// implementations are changed alike.
+pub fn change_signature(
+    sema: &Semantics<'_, RootDatabase>,
+    func: hir::Function,
+    new_params: &[NewParam],
+) -> Result<SignatureEdit> {
+    let db = sema.db;
+    let (root, root_fn) = root_fn(sema, func)?;
+    let old_params: Vec<_> = root_fn.param_list().into_iter().flat_map(|it| it.params()).collect();
+
+    let mut seen = FxHashSet::default();
+    for &index in new_params.iter().flat_map(NewParam::indices) {
+        if index >= old_params.len() {
+            return Err(error(format!(
+                "Parameter index {index} is out of bounds, the function has {} parameters",
+                old_params.len()
+            )));
+        }
+        if !seen.insert(index) {
+            return Err(error(format!("Parameter {index} is listed more than once")));
+        }
+    }
+
+    let ctx = Ctx {
+        new_params,
+        old_param_count: old_params.len(),
+        has_self: root.has_self_param(db),
+        is_unsafe: root_fn.unsafe_token().is_some(),
+        closure_params: old_params
+            .iter()
+            .enumerate()
+            .map(|(idx, param)| match param.pat() {
+                Some(ast::Pat::IdentPat(pat))
+                    if pat.ref_token().is_none() && pat.pat().is_none() =>
+                {
+                    pat.name().map_or_else(|| format!("arg{idx}"), |it| it.text().to_string())
+                }
+                _ => format!("arg{idx}"),
+            })
+            .collect(),
+    };
+
+    let mut funcs = vec![root];
+    if let Some(trait_) = root.as_assoc_item(db).and_then(|it| it.container_trait(db)) {
+        let name = root.name(db);
+        funcs.extend(
+            hir::Impl::all_for_trait(db, trait_)
+                .into_iter()
+                .flat_map(|impl_| impl_.items(db).into_iter().filter_map(|it| it.as_function())),
+        );
+        funcs.retain(|it| it.name(db) == name);
+    }
+
+    let mut unrewritten = Vec::new();
+    let mut replacements: FxHashMap<EditionedFileId, Vec<Replacement>> = FxHashMap::default();
+    for &func in &funcs {
+        let Some(source) = sema.source(func) else { continue };
+        let Some(file_id) =
+            source.file_id.file_id().filter(|_| func.krate(db).origin(db).is_local())
+        else {
+            let range = source.syntax().original_file_range_rooted(db).into_file_id(db);
+            unrewritten.push(site(range, "the function is not defined in the workspace source"));
+            continue;
+        };
+        let Some(param_list) = source.value.param_list() else { continue };
+        let params: Vec<_> = param_list.params().collect();
+        if params.len() != ctx.old_param_count {
+            let range =
+                FileRange { file_id: file_id.file_id(db), range: param_list.syntax().text_range() };
+            unrewritten.push(site(range, "the parameter count does not match the trait"));
+            continue;
+        }
+        for (idx, param) in params.iter().enumerate() {
+            if ctx.keeps(idx) {
+                continue;
+            }
+            if let Some(ast::Pat::IdentPat(pat)) = param.pat()
+                && let Some(local) = sema.to_def(&pat)
+                && Definition::Local(local).usages(sema).at_least_one()
+            {
+                return Err(error(format!(
+                    "Cannot remove parameter `{}`, it is still used in the body",
+                    pat.syntax().text()
+                )));
+            }
+        }
+        replacements.entry(file_id).or_default().push(ctx.rewrite_param_list(&param_list, &params));
+    }
+
+    let mut seen_references = FxHashSet::default();
+    for &func in &funcs {
+        for (file_id, references) in Definition::Function(func).usages(sema).all() {
+            let source_file = sema.parse(file_id);
+            for reference in references {
+                if reference.name.as_name_ref().is_none()
+                    || !seen_references.insert((file_id, reference.range))
+                {
+                    continue;
+                }
+                match ctx.rewrite_usage(&source_file, reference.range) {
+                    Ok(Some(replacement)) => {
+                        replacements.entry(file_id).or_default().push(replacement)
+                    }
+                    Ok(None) => (),
+                    Err(reason) => unrewritten.push(site(
+                        FileRange { file_id: file_id.file_id(db), range: reference.range },
+                        reason,
+                    )),
+                }
+            }
+        }
+    }
+
+    let edits = replacements
+        .into_iter()
+        .map(|(file_id, replacements)| {
+            let text = sema.parse(file_id).syntax().text().to_string();
+            (file_id.file_id(db), render(&text, replacements))
+        })
+        .collect();
+    unrewritten.sort_by_key(|it| (it.range.file_id, it.range.range.start()));
+    Ok(SignatureEdit { edits, unrewritten })
+}
+
+/// Checks that the signature of `func` can be changed at all, before computing the change.
+pub fn check_can_change_signature(
+    sema: &Semantics<'_, RootDatabase>,
+    func: hir::Function,
+) -> Result<()> {
+    root_fn(sema, func).map(drop)
+}
+
+/// The function whose signature the others follow, the trait method if `func` implements one.
+fn root_fn(
+    sema: &Semantics<'_, RootDatabase>,
+    func: hir::Function,
+) -> Result<(hir::Function, ast::Fn)> {
+    let db = sema.db;
+    if !func.krate(db).origin(db).is_local() {
+        return Err(error("Cannot change the signature of a non-local function"));
+    }
+    if func.extern_block(db).is_some() {
+        return Err(error("Cannot change the signature of an extern function"));
+    }
+    let root = match as_trait_assoc_def(db, Definition::Function(func)) {
+        Some(Definition::Function(it)) => it,
+        _ => func,
+    };
+    let root_fn = sema
+        .source(root)
+        .filter(|it| it.file_id.file_id().is_some())
+        .ok_or_else(|| error("Cannot change the signature of a function defined by a macro"))?
+        .value;
+    Ok((root, root_fn))
+}
+
+struct Ctx<'a> {
+    new_params: &'a [NewParam],
+    old_param_count: usize,
+    has_self: bool,
+    is_unsafe: bool,
+    /// Names for the parameters of closures adapting the new signature to the old one.
+    closure_params: Vec<String>,
+}
+
+impl Ctx<'_> {
+    fn keeps(&self, index: usize) -> bool {
+        self.new_params.iter().any(|param| param.indices().contains(&index))
+    }
+
+    fn rewrite_param_list(
+        &self,
+        param_list: &ast::ParamList,
+        params: &[ast::Param],
+    ) -> Replacement {
+        let mut items = Vec::new();
+        if let Some(self_param) = param_list.self_param() {
+            items.push(vec![Piece::Source(self_param.syntax().text_range())]);
+        }
+        for new_param in self.new_params {
+            items.push(match new_param {
+                NewParam::Existing { index, ty: None } => {
+                    vec![Piece::Source(params[*index].syntax().text_range())]
+                }
+                NewParam::Existing { index, ty: Some(ty) } => {
+                    let param = params[*index].syntax().text_range();
+                    match params[*index].ty() {
+                        Some(old_ty) => vec![
+                            Piece::Source(TextRange::new(
+                                param.start(),
+                                old_ty.syntax().text_range().start(),
+                            )),
+                            Piece::Text(ty.clone()),
+                        ],
+                        None => vec![Piece::Text(ty.clone())],
+                    }
+                }
+                NewParam::Added { pattern, ty, .. } | NewParam::Grouped { pattern, ty, .. } => {
+                    vec![Piece::Text(format!("{pattern}: {ty}"))]
+                }
+            });
+        }
+        Replacement { range: param_list.syntax().text_range(), pieces: parenthesized(items) }
+    }
+
+    /// Returns the replacement for the reference at `range`, `None` if the reference doesn't
+    /// depend on the parameters, or why it can't be rewritten.
+    fn rewrite_usage(
+        &self,
+        source_file: &SourceFile,
+        range: TextRange,
+    ) -> Result<Option<Replacement>, &'static str> {
+        let Some(name_ref) = find_node_at_range::<ast::NameRef>(source_file.syntax(), range)
+            .filter(|it| it.syntax().text_range() == range)
+        else {
+            return Err("the reference is inside a macro call");
+        };
+        let Some(parent) = name_ref.syntax().parent() else { return Ok(None) };
+        if let Some(method_call) = ast::MethodCallExpr::cast(parent.clone()) {
+            let arg_list = method_call.arg_list().ok_or("the call has no argument list")?;
+            return self.rewrite_args(&arg_list, false).map(Some);
+        }
+        let Some(segment) = ast::PathSegment::cast(parent) else {
+            return Err("the reference is not a call or a path");
+        };
+        let path = segment.parent_path().top_path();
+        let Some(path_parent) = path.syntax().parent() else { return Ok(None) };
+        if ast::UseTree::can_cast(path_parent.kind()) {
+            return Ok(None);
+        }
+        let Some(path_expr) = ast::PathExpr::cast(path_parent) else {
+            return Err("the reference is not a call or a path expression");
+        };
+        if let Some(call) = path_expr.syntax().parent().and_then(ast::CallExpr::cast)
+            && call.expr().is_some_and(|it| it.syntax() == path_expr.syntax())
+        {
+            let arg_list = call.arg_list().ok_or("the call has no argument list")?;
+            return self.rewrite_args(&arg_list, self.has_self).map(Some);
+        }
+        self.wrap_in_closure(&path_expr).map(Some)
+    }
+
+    fn rewrite_args(
+        &self,
+        arg_list: &ast::ArgList,
+        receiver_in_args: bool,
+    ) -> Result<Replacement, &'static str> {
+        let args: Vec<_> = arg_list.args().collect();
+        let receiver = usize::from(receiver_in_args);
+        if args.len() != self.old_param_count + receiver {
+            return Err("the call has the wrong number of arguments");
+        }
+        let mut items = Vec::new();
+        if receiver_in_args {
+            items.push(vec![Piece::Source(args[0].syntax().text_range())]);
+        }
+        items.extend(self.new_params.iter().map(|param| match param {
+            NewParam::Existing { index, .. } => {
+                vec![Piece::Source(args[receiver + index].syntax().text_range())]
+            }
+            NewParam::Added { default, .. } => vec![Piece::Text(default.clone())],
+            NewParam::Grouped { struct_name, indices, field_names, .. } => {
+                let fields = indices.iter().enumerate().map(|(idx, index)| {
+                    let arg = &args[receiver + index];
+                    let source = Piece::Source(arg.syntax().text_range());
+                    match field_names {
+                        // Use the shorthand for `S { a: a }`.
+                        Some(names) if arg.syntax().text() == names[idx].as_str() => vec![source],
+                        Some(names) => vec![Piece::Text(format!("{}: ", names[idx])), source],
+                        None => vec![source],
+                    }
+                });
+                constructor(struct_name, field_names.is_some(), fields.collect())
+            }
+        }));
+        Ok(Replacement { range: arg_list.syntax().text_range(), pieces: parenthesized(items) })
+    }
+
+    /// Replaces a use of the function as a value with a closure taking the old parameters.
+    fn wrap_in_closure(&self, path_expr: &ast::PathExpr) -> Result<Replacement, &'static str> {
+        if self.is_unsafe {
+            return Err("an unsafe function can't be wrapped in a closure");
+        }
+        if self.new_params.iter().any(|it| matches!(it, NewParam::Existing { ty: Some(_), .. })) {
+            return Err("the function is used as a value and its parameter types change");
+        }
+        let receiver = self.has_self.then(|| "this".to_owned());
+        let closure_params: Vec<_> = receiver.iter().chain(&self.closure_params).cloned().collect();
+        let args: Vec<_> = receiver
+            .into_iter()
+            .chain(self.new_params.iter().map(|param| match param {
+                NewParam::Existing { index, .. } => self.closure_params[*index].clone(),
+                NewParam::Added { default, .. } => default.clone(),
+                NewParam::Grouped { struct_name, indices, field_names, .. } => {
+                    let fields: Vec<_> = indices
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, index)| {
+                            let param = &self.closure_params[*index];
+                            match field_names {
+                                Some(names) if names[idx] != *param => {
+                                    format!("{}: {param}", names[idx])
+                                }
+                                _ => param.clone(),
+                            }
+                        })
+                        .collect();
+                    match field_names {
+                        Some(_) => format!("{struct_name} {{ {} }}", fields.join(", ")),
+                        None => format!("{struct_name}({})", fields.join(", ")),
+                    }
+                }
+            }))
+            .collect();
+        let needs_parens = !path_expr.syntax().parent().is_some_and(|it| {
+            ast::ArgList::can_cast(it.kind()) || ast::LetStmt::can_cast(it.kind())
+        });
+        let (open, close) = if needs_parens { ("(", ")") } else { ("", "") };
+        Ok(Replacement {
+            range: path_expr.syntax().text_range(),
+            pieces: vec![
+                Piece::Text(format!("{open}|{}| ", closure_params.join(", "))),
+                Piece::Source(path_expr.syntax().text_range()),
+                Piece::Text(format!("({}){close}", args.join(", "))),
+            ],
+        })
+    }
+}
+
+/// Replacement text for a range, made of new text and pieces of the original source. Pieces of
+/// the source can contain other replacements, e.g. for nested calls like `f(f(a, b), c)`.
+struct Replacement {
+    range: TextRange,
+    pieces: Vec<Piece>,
+}
+
+enum Piece {
+    Source(TextRange),
+    Text(String),
+}
+
+fn parenthesized(items: Vec<Vec<Piece>>) -> Vec<Piece> {
+    let mut pieces = vec![Piece::Text("(".to_owned())];
+    for (idx, item) in items.into_iter().enumerate() {
+        if idx != 0 {
+            pieces.push(Piece::Text(", ".to_owned()));
+        }
+        pieces.extend(item);
+    }
+    pieces.push(Piece::Text(")".to_owned()));
+    pieces
+}
+
+/// The expression constructing the struct `name` from `fields`.
+fn constructor(name: &str, named: bool, fields: Vec<Vec<Piece>>) -> Vec<Piece> {
+    if !named {
+        let mut pieces = vec![Piece::Text(name.to_owned())];
+        pieces.extend(parenthesized(fields));
+        return pieces;
+    }
+    let mut pieces = vec![Piece::Text(format!("{name} {{ "))];
+    for (idx, field) in fields.into_iter().enumerate() {
+        if idx != 0 {
+            pieces.push(Piece::Text(", ".to_owned()));
+        }
+        pieces.extend(field);
+    }
+    pieces.push(Piece::Text(" }".to_owned()));
+    pieces
+}
+
+/// Renders the replacements for a file, innermost first, and returns the outermost ones.
+fn render(text: &str, mut replacements: Vec<Replacement>) -> Vec<(TextRange, String)> {
+    replacements.sort_by_key(|it| (it.range.len(), it.range.start()));
+    let mut rendered: Vec<(TextRange, String)> = Vec::new();
+    for Replacement { range, pieces } in replacements {
+        let mut buf = String::new();
+        for piece in pieces {
+            match piece {
+                Piece::Text(it) => buf.push_str(&it),
+                Piece::Source(source) => splice(&mut buf, text, source, &rendered),
+            }
+        }
+        rendered.push((range, buf));
+    }
+    outermost(rendered.iter().map(|(range, _)| *range), |_| true)
+        .into_iter()
+        .map(|range| rendered.iter().find(|(it, _)| *it == range).unwrap().clone())
+        .collect()
+}
+
+/// Appends `text[range]` to `buf`, with the replacements inside of it applied.
+fn splice(buf: &mut String, text: &str, range: TextRange, rendered: &[(TextRange, String)]) {
+    let mut offset = range.start();
+    for inner in outermost(rendered.iter().map(|(range, _)| *range), |it| range.contains_range(it))
+    {
+        let (_, replacement) = rendered.iter().find(|(it, _)| *it == inner).unwrap();
+        buf.push_str(&text[TextRange::new(offset, inner.start())]);
+        buf.push_str(replacement);
+        offset = inner.end();
+    }
+    buf.push_str(&text[TextRange::new(offset, range.end())]);
+}
+
+/// Returns the ranges accepted by `filter` that aren't contained in another one, sorted.
+fn outermost(
+    ranges: impl Iterator<Item = TextRange>,
+    filter: impl Fn(TextRange) -> bool,
+) -> Vec<TextRange> {
+    let mut ranges: Vec<_> = ranges.filter(|&it| filter(it)).collect();
+    ranges.sort_by_key(|it| (it.start(), Reverse(it.len())));
+    let mut res: Vec<TextRange> = Vec::new();
+    for range in ranges {
+        if res.last().is_none_or(|last| last.end() <= range.start()) {
+            res.push(range);
+        }
+    }
+    res
+}
+
+fn site(range: FileRange, reason: &str) -> UnrewrittenSite {
+    UnrewrittenSite { range, reason: reason.to_owned() }
+}
+
+fn error(message: impl Into<String>) -> ChangeSignatureError {
+    ChangeSignatureError(message.into())
+}
@@ -13,12 +13,13 @@ use crate::famous_defs::FamousDefs;
 use arrayvec::ArrayVec;
 use either::Either;
 use hir::{
-    Adt, AsAssocItem, AsExternAssocItem, AssocItem, AttributeTemplate, BuiltinAttr, BuiltinType,
-    Const, Crate, DefWithBody, DeriveHelper, DisplayTarget, DocLinkDef, ExternAssocItem,
-    ExternCrateDecl, Field, Function, GenericDef, GenericParam, GenericSubstitution, HasContainer,
-    HasVisibility, HirDisplay, Impl, InlineAsmOperand, ItemContainer, Label, Local, Macro, Module,
-    ModuleDef, Name, PathResolution, Semantics, Static, StaticLifetime, Struct, ToolModule, Trait,
-    TupleField, TypeAlias, Variant, VariantDef, Visibility,
+    Adt, AsAssocItem, AsExternAssocItem, AssocItem, AttributeTemplate, AttrsWithOwner, BuiltinAttr,
+    BuiltinType, Const, Crate, DefWithBody, Deprecation, DeriveHelper, DisplayTarget, DocLinkDef,
+    ExternAssocItem, ExternCrateDecl, Field, Function, GenericDef, GenericParam,
+    GenericSubstitution, HasAttrs, HasContainer, HasVisibility, HirDisplay, Impl, InlineAsmOperand,
+    ItemContainer, Label, Local, Macro, Module, ModuleDef, Name, PathResolution, Semantics, Static,
+    StaticLifetime, Struct, ToolModule, Trait, TupleField, TypeAlias, Variant, VariantDef,
+    Visibility,
 };
 use span::Edition;
 use stdx::{format_to, impl_from};
@@ -169,6 +170,40 @@ impl Definition {
         Some(vis)
     }
 
+    pub fn attrs(&self, db: &RootDatabase) -> Option<AttrsWithOwner> {
+        Some(match *self {
+            Definition::Macro(it) => it.attrs(db),
+            Definition::Field(it) => it.attrs(db),
+            Definition::Module(it) => it.attrs(db),
+            Definition::Function(it) => it.attrs(db),
+            Definition::Adt(it) => it.attrs(db),
+            Definition::Variant(it) => it.attrs(db),
+            Definition::Const(it) => it.attrs(db),
+            Definition::Static(it) => it.attrs(db),
+            Definition::Trait(it) => it.attrs(db),
+            Definition::TypeAlias(it) => it.attrs(db),
+            Definition::ExternCrateDecl(it) => it.attrs(db),
+            _ => return None,
+        })
+    }
+
+    /// Returns the `#[deprecated]` attribute that applies to this definition, which is either its
+    /// own or the one of its parent, like the struct of a field or the trait of a method.
+    pub fn deprecation<'db>(&self, db: &'db RootDatabase) -> Option<&'db Deprecation> {
+        if let Some(deprecation) = self.attrs(db)?.deprecation(db) {
+            return Some(deprecation);
+        }
+        let parent = match *self {
+            Definition::Field(it) => Definition::from(it.parent_def(db)),
+            Definition::Variant(it) => Adt::Enum(it.parent_enum(db)).into(),
+            Definition::Function(_) | Definition::Const(_) | Definition::TypeAlias(_) => {
+                self.as_assoc_item(db)?.container_or_implemented_trait(db)?.into()
+            }
+            _ => return None,
+        };
+        parent.deprecation(db)
+    }
+
     pub fn name(&self, db: &RootDatabase) -> Option<Name> {
         let name = match self {
             Definition::Macro(it) => it.name(db),
@@ -90,6 +90,10 @@ impl FamousDefs<'_, '_> {
         self.find_trait("core:iter:traits:collect:IntoIterator")
     }
 
+    pub fn core_iter_FromIterator(&self) -> Option<Trait> {
+        self.find_trait("core:iter:traits:collect:FromIterator")
+    }
+
     pub fn core_iter(&self) -> Option<Module> {
         self.find_module("core:iter")
     }
@@ -1,12 +1,13 @@
 //! Random assortment of ide helpers for high-level ide features that don't fit in any other module.
 
-use std::collections::VecDeque;
+use std::{collections::VecDeque, iter};
 
 use base_db::SourceDatabase;
-use hir::{Crate, ItemInNs, ModuleDef, Name, Semantics};
+use hir::{Crate, HasSource, ItemInNs, ModuleDef, Name, Semantics, db::HirDatabase};
 use span::{Edition, FileId};
 use syntax::{
-    AstToken, SyntaxKind, SyntaxToken, ToSmolStr, TokenAtOffset,
+    AstNode, AstToken, NodeOrToken, SyntaxKind, SyntaxNode, SyntaxToken, T, TextRange, ToSmolStr,
+    TokenAtOffset,
     ast::{self, make},
 };
 
@@ -57,6 +58,118 @@ pub fn mod_path_to_ast(path: &hir::ModPath, edition: Edition) -> ast::Path {
     make::path_from_segments(segments, is_abs)
 }
 
+/// Returns the node carrying the visibility of `def`, along with its range, file and name.
+///
+/// Enum variants, builtin types and macros have no such node.
+pub fn visibility_owner_for_def(
+    db: &dyn HirDatabase,
+    def: hir::ModuleDef,
+) -> Option<(ast::AnyHasVisibility, TextRange, FileId, Option<hir::Name>)> {
+    fn offset_target_and_file_id<S, Ast>(
+        db: &dyn HirDatabase,
+        x: S,
+    ) -> Option<(ast::AnyHasVisibility, TextRange, FileId)>
+    where
+        S: HasSource<Ast = Ast>,
+        Ast: AstNode + ast::HasVisibility,
+    {
+        let source = x.source(db)?;
+        let in_file_syntax = source.syntax();
+        let file_id = in_file_syntax.file_id;
+        let range = in_file_syntax.value.text_range();
+        Some((
+            ast::AnyHasVisibility::new(source.value),
+            range,
+            file_id.original_file(db).file_id(db),
+        ))
+    }
+
+    let target_name;
+    let (owner, range, file_id) = match def {
+        hir::ModuleDef::Function(f) => {
+            target_name = Some(f.name(db));
+            offset_target_and_file_id(db, f)?
+        }
+        hir::ModuleDef::Adt(adt) => {
+            target_name = Some(adt.name(db));
+            match adt {
+                hir::Adt::Struct(s) => offset_target_and_file_id(db, s)?,
+                hir::Adt::Union(u) => offset_target_and_file_id(db, u)?,
+                hir::Adt::Enum(e) => offset_target_and_file_id(db, e)?,
+            }
+        }
+        hir::ModuleDef::Const(c) => {
+            target_name = c.name(db);
+            offset_target_and_file_id(db, c)?
+        }
+        hir::ModuleDef::Static(s) => {
+            target_name = Some(s.name(db));
+            offset_target_and_file_id(db, s)?
+        }
+        hir::ModuleDef::Trait(t) => {
+            target_name = Some(t.name(db));
+            offset_target_and_file_id(db, t)?
+        }
+        hir::ModuleDef::TypeAlias(t) => {
+            target_name = Some(t.name(db));
+            offset_target_and_file_id(db, t)?
+        }
+        hir::ModuleDef::Module(m) => {
+            target_name = m.name(db);
+            let in_file_source = m.declaration_source(db)?;
+            let file_id = in_file_source.file_id.original_file(db);
+            let range = in_file_source.value.syntax().text_range();
+            (ast::AnyHasVisibility::new(in_file_source.value), range, file_id.file_id(db))
+        }
+        // FIXME
+        hir::ModuleDef::Macro(_) => return None,
+        // Enum variants can't be private, we can't modify builtin types
+        hir::ModuleDef::Variant(_) | hir::ModuleDef::BuiltinType(_) => return None,
+    };
+
+    Some((owner, range, file_id, target_name))
+}
+
+/// Returns the ranges to delete to remove `nodes`, items of one file, along with
+/// the whitespace separating them from the items that stay.
+pub fn removal_ranges(nodes: &[SyntaxNode]) -> Vec<TextRange> {
+    let mut ranges: Vec<TextRange> = Vec::new();
+    for node in nodes {
+        let mut range = node.text_range();
+        let next = node.next_sibling_or_token();
+        let has_following_item = iter::successors(next.clone(), |it| it.next_sibling_or_token())
+            .any(|it| match it {
+                NodeOrToken::Node(it) => !nodes.contains(&it),
+                NodeOrToken::Token(it) => !matches!(it.kind(), SyntaxKind::WHITESPACE | T!['}']),
+            });
+        match next {
+            Some(NodeOrToken::Token(ws))
+                if ws.kind() == SyntaxKind::WHITESPACE && has_following_item =>
+            {
+                range = range.cover(ws.text_range());
+            }
+            _ => {
+                if let Some(NodeOrToken::Token(ws)) = node.prev_sibling_or_token()
+                    && ws.kind() == SyntaxKind::WHITESPACE
+                {
+                    range = range.cover(ws.text_range());
+                }
+            }
+        }
+        ranges.push(range);
+    }
+    ranges.sort_by_key(|it| it.start());
+    ranges.dedup_by(|next, prev| {
+        if prev.end() >= next.start() {
+            *prev = prev.cover(*next);
+            true
+        } else {
+            false
+        }
+    });
+    ranges
+}
+
 /// Iterates all `ModuleDef`s and `Impl` blocks of the given file.
 pub fn visit_file_defs(
     sema: &Semantics<'_, RootDatabase>,
@@ -13,12 +13,14 @@ mod apply_change;
 
 pub mod active_parameter;
 pub mod assists;
+pub mod change_signature;
 pub mod defs;
 pub mod documentation;
 pub mod famous_defs;
 pub mod helpers;
 pub mod items_locator;
 pub mod label;
+pub mod move_to_module;
 pub mod path_transform;
 pub mod prime_caches;
 pub mod ra_fixture;
@@ -37,6 +39,7 @@ pub mod imports {
     pub mod import_assets;
     pub mod insert_use;
     pub mod merge_imports;
+    pub mod unused_imports;
 }
 
 pub mod generated {
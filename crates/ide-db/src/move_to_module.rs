@@ -0,0 +1,1047 @@
+//! Move to module infrastructure: moves items into another, already
+//! existing, module of the same crate and fixes up the code around them.
+//!
+//! Besides the items themselves this touches:
+//!
+//! * the moved items, which get imports for the names they use, have their
+//!   `self` and `super` paths rewritten and become `pub(crate)` when they are
+//!   used from outside of their new module, as do their fields and inherent
+//!   associated items;
+//! * the items they refer to, which become `pub(crate)` when they are not
+//!   visible from the new module;
+//! * every reference to the moved items: `use` trees get the new path,
+//!   qualified paths get the new module path and unqualified ones in the old
+//!   module get an import. Paths inside macro calls are rewritten token by
+//!   token.
+//!
+//! References that can't be rewritten are not touched and are reported back.
+use std::{fmt, iter};
+
+use hir::{
+    AsAssocItem, FindPathConfig, HasVisibility, Module, ModuleDef, ModuleSource, PathResolution,
+    Semantics, SemanticsScope,
+};
+use span::Edition;
+use syntax::{
+    AstNode, NodeOrToken, SyntaxKind, SyntaxNode, SyntaxToken, T, TextRange, TextSize,
+    algo::find_node_at_range,
+    ast::{
+        self, HasModuleItem, PathSegmentKind, VisibilityKind,
+        edit::{AstNodeEdit, IndentLevel},
+        make,
+    },
+    ted,
+};
+
+use crate::{
+    EditionedFileId, FileId, FileRange, FxHashMap, FxHashSet, RootDatabase,
+    defs::Definition,
+    helpers::{removal_ranges, visibility_owner_for_def},
+    imports::insert_use::{ImportScope, ImportScopeKind, InsertUseConfig, insert_use},
+    source_change::{SourceChangeBuilder, TreeMutator, UnrewrittenSite},
+    syntax_helpers::tree_diff::diff,
+    text_edit::TextEdit,
+};
+
+pub type Result<T, E = MoveToModuleError> = std::result::Result<T, E>;
+
+#[derive(Debug)]
+pub struct MoveToModuleError(pub String);
+
+impl fmt::Display for MoveToModuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MoveToModuleConfig {
+    pub insert_use: InsertUseConfig,
+    pub find_path: FindPathConfig,
+}
+
+/// Returns the module level items touched by `range`, or the one containing it
+/// when it is empty.
+pub fn items_in_range(source_file: &ast::SourceFile, range: TextRange) -> Vec<ast::Item> {
+    let covering = match source_file.syntax().covering_element(range) {
+        NodeOrToken::Node(it) => it,
+        NodeOrToken::Token(it) => match it.parent() {
+            Some(it) => it,
+            None => return Vec::new(),
+        },
+    };
+    let Some(container) = covering
+        .ancestors()
+        .find(|it| matches!(it.kind(), SyntaxKind::SOURCE_FILE | SyntaxKind::ITEM_LIST))
+    else {
+        return Vec::new();
+    };
+    container
+        .children()
+        .filter_map(ast::Item::cast)
+        .filter(|item| {
+            let item_range = item.syntax().text_range();
+            if range.is_empty() {
+                item_range.contains_range(range)
+            } else {
+                item_range.intersect(range).is_some_and(|it| !it.is_empty())
+            }
+        })
+        .collect()
+}
+
+/// Items of one module that are about to be moved into another one.
+#[derive(Debug)]
+pub struct ItemMove {
+    file_id: EditionedFileId,
+    items: Vec<ast::Item>,
+    source: Module,
+    target: Module,
+    target_file_id: EditionedFileId,
+    /// The `SourceFile` or `ItemList` the items are appended to.
+    target_container: SyntaxNode,
+}
+
+impl ItemMove {
+    /// Checks that the module level `items` of `file_id` can be moved into
+    /// `target`.
+    pub fn new(
+        sema: &Semantics<'_, RootDatabase>,
+        file_id: EditionedFileId,
+        items: Vec<ast::Item>,
+        target: Module,
+    ) -> Result<ItemMove> {
+        let db = sema.db;
+        let first = items.first().ok_or_else(|| error("There are no items to move"))?;
+        if !items.iter().all(is_movable) {
+            return Err(error(
+                "Only functions, types, traits, constants, statics and impls can be moved",
+            ));
+        }
+        let source = sema
+            .scope(first.syntax())
+            .ok_or_else(|| error("The items are not part of a module"))?
+            .module();
+        if source == target {
+            return Err(error("The items are already in this module"));
+        }
+        if source.krate(db) != target.krate(db) {
+            return Err(error("Items can only be moved to a module of the same crate"));
+        }
+
+        let target_source = target.definition_source(db);
+        let target_file_id = target_source
+            .file_id
+            .file_id()
+            .ok_or_else(|| error("Cannot move items into a module defined by a macro"))?;
+        let target_root = sema.parse(target_file_id);
+        let target_container = match target_source.value {
+            ModuleSource::SourceFile(_) => target_root.syntax().clone(),
+            ModuleSource::Module(module) => find_node_at_range::<ast::Module>(
+                target_root.syntax(),
+                module.syntax().text_range(),
+            )
+            .and_then(|it| it.item_list())
+            .ok_or_else(|| error("The target module has no body"))?
+            .syntax()
+            .clone(),
+            ModuleSource::BlockExpr(_) => {
+                return Err(error("Cannot move items into a block"));
+            }
+        };
+
+        let target_names: FxHashSet<_> =
+            target.declarations(db).into_iter().filter_map(|it| it.name(db)).collect();
+        for item in &items {
+            if let Some(name) = item_def(sema, item).and_then(|it| it.name(db))
+                && target_names.contains(&name)
+            {
+                let edition = source.krate(db).edition(db);
+                return Err(error(format!(
+                    "The target module already contains an item named `{}`",
+                    name.display(db, edition)
+                )));
+            }
+        }
+
+        Ok(ItemMove { file_id, items, source, target, target_file_id, target_container })
+    }
+
+    pub fn target(&self) -> Module {
+        self.target
+    }
+
+    /// Moves the items, recording the edits in `builder`. Returns the
+    /// references that could not be rewritten.
+    pub fn apply(
+        self,
+        sema: &Semantics<'_, RootDatabase>,
+        config: &MoveToModuleConfig,
+        builder: &mut SourceChangeBuilder,
+    ) -> Vec<UnrewrittenSite> {
+        let db = sema.db;
+        let Some(target_scope) = sema.scope(&self.target_container) else {
+            return Vec::new();
+        };
+        let mut mover = Mover {
+            sema,
+            config,
+            edition: self.source.krate(db).edition(db),
+            item_edits: vec![Vec::new(); self.items.len()],
+            files: FxHashMap::default(),
+            target_imports: Vec::new(),
+            rewritten_paths: FxHashSet::default(),
+            made_visible: FxHashSet::default(),
+            unrewritten: Vec::new(),
+            mv: &self,
+        };
+        mover.rewrite_references();
+        mover.fix_dependencies(&target_scope);
+        mover.finish(builder)
+    }
+}
+
+fn is_movable(item: &ast::Item) -> bool {
+    matches!(
+        item,
+        ast::Item::Fn(_)
+            | ast::Item::Struct(_)
+            | ast::Item::Enum(_)
+            | ast::Item::Union(_)
+            | ast::Item::Trait(_)
+            | ast::Item::TypeAlias(_)
+            | ast::Item::Const(_)
+            | ast::Item::Static(_)
+            | ast::Item::Impl(_)
+    )
+}
+
+fn item_def(sema: &Semantics<'_, RootDatabase>, item: &ast::Item) -> Option<ModuleDef> {
+    match item {
+        ast::Item::Fn(it) => sema.to_def(it).map(Into::into),
+        ast::Item::Struct(it) => sema.to_def(it).map(Into::into),
+        ast::Item::Enum(it) => sema.to_def(it).map(Into::into),
+        ast::Item::Union(it) => sema.to_def(it).map(Into::into),
+        ast::Item::Trait(it) => sema.to_def(it).map(Into::into),
+        ast::Item::TypeAlias(it) => sema.to_def(it).map(Into::into),
+        ast::Item::Const(it) => sema.to_def(it).map(Into::into),
+        ast::Item::Static(it) => sema.to_def(it).map(Into::into),
+        _ => None,
+    }
+}
+
+/// Edits of a file other than the text of the moved items.
+#[derive(Default)]
+struct FileEdits {
+    replacements: Vec<(TextRange, String)>,
+    /// Edits of `use` items and imports, by the range of the item list they are in.
+    use_edits: FxHashMap<TextRange, UseEdits>,
+}
+
+/// Edits of the `use` items of a `SourceFile`, `ItemList` or `StmtList`.
+///
+/// These are made on a mutable copy of the tree and diffed per container, so
+/// that they don't clash with the plain text edits of the file.
+struct UseEdits {
+    container: SyntaxNode,
+    /// Paths in `use` trees and their replacements.
+    paths: Vec<(ast::Path, ast::Path)>,
+    removed_trees: Vec<ast::UseTree>,
+    imports: Vec<(ImportScope, ast::Path)>,
+}
+
+impl UseEdits {
+    fn into_text_edits(self, cfg: &InsertUseConfig) -> Vec<(TextRange, String)> {
+        // All the mutable nodes have to be created before the tree is changed.
+        let tree = TreeMutator::new(&self.container);
+        let paths: Vec<_> = self
+            .paths
+            .iter()
+            .map(|(path, new_path)| (tree.make_mut(path), new_path.clone_for_update()))
+            .collect();
+        let removed_trees: Vec<_> = self.removed_trees.iter().map(|it| tree.make_mut(it)).collect();
+        let imports: Vec<_> = self
+            .imports
+            .into_iter()
+            .map(|(scope, path)| {
+                let kind = match scope.kind {
+                    ImportScopeKind::File(it) => ImportScopeKind::File(tree.make_mut(&it)),
+                    ImportScopeKind::Module(it) => ImportScopeKind::Module(tree.make_mut(&it)),
+                    ImportScopeKind::Block(it) => ImportScopeKind::Block(tree.make_mut(&it)),
+                };
+                let required_cfgs =
+                    scope.required_cfgs.iter().map(|it| tree.make_mut(it)).collect();
+                (ImportScope { kind, required_cfgs }, path)
+            })
+            .collect();
+        let container = tree.make_syntax_mut(&self.container);
+
+        for (path, new_path) in paths {
+            ted::replace(path.syntax(), new_path.syntax());
+        }
+        for use_tree in removed_trees {
+            use_tree.remove_recursive();
+        }
+        for (scope, path) in imports {
+            insert_use(&scope, path, cfg);
+        }
+
+        let mut edit = TextEdit::builder();
+        diff(&self.container, &container).into_text_edit(&mut edit);
+        edit.finish().into_iter().map(|indel| (indel.delete, indel.insert)).collect()
+    }
+}
+
+struct Mover<'a, 'db> {
+    sema: &'a Semantics<'db, RootDatabase>,
+    config: &'a MoveToModuleConfig,
+    mv: &'a ItemMove,
+    edition: Edition,
+    /// Edits of the text of each moved item, with ranges relative to the file.
+    item_edits: Vec<Vec<(TextRange, String)>>,
+    files: FxHashMap<FileId, FileEdits>,
+    /// Imports the moved items need in the target module.
+    target_imports: Vec<ast::Path>,
+    /// Paths in the moved items that refer to moved items and were rewritten
+    /// already.
+    rewritten_paths: FxHashSet<TextRange>,
+    made_visible: FxHashSet<ModuleDef>,
+    unrewritten: Vec<UnrewrittenSite>,
+}
+
+impl Mover<'_, '_> {
+    fn rewrite_references(&mut self) {
+        let mv = self.mv;
+        for (index, item) in mv.items.iter().enumerate() {
+            if let Some(def) = item_def(self.sema, item)
+                && self.rewrite_usages(def)
+                && let Some(item) = ast::AnyHasVisibility::cast(item.syntax().clone())
+            {
+                self.make_crate_visible(index, &item);
+            }
+
+            let mut members: Vec<(Definition, ast::AnyHasVisibility)> = Vec::new();
+            match item {
+                ast::Item::Struct(it) => match it.field_list() {
+                    Some(ast::FieldList::RecordFieldList(list)) => members.extend(
+                        list.fields()
+                            .filter_map(|it| Some((self.sema.to_def(&it)?.into(), it.into()))),
+                    ),
+                    Some(ast::FieldList::TupleFieldList(list)) => members.extend(
+                        list.fields()
+                            .filter_map(|it| Some((self.sema.to_def(&it)?.into(), it.into()))),
+                    ),
+                    None => (),
+                },
+                ast::Item::Union(it) => members.extend(
+                    it.record_field_list()
+                        .into_iter()
+                        .flat_map(|it| it.fields())
+                        .filter_map(|it| Some((self.sema.to_def(&it)?.into(), it.into()))),
+                ),
+                ast::Item::Impl(it) if it.trait_().is_none() => members.extend(
+                    it.assoc_item_list().into_iter().flat_map(|it| it.assoc_items()).filter_map(
+                        |it| {
+                            let def = match &it {
+                                ast::AssocItem::Fn(it) => self.sema.to_def(it)?.into(),
+                                ast::AssocItem::Const(it) => self.sema.to_def(it)?.into(),
+                                _ => return None,
+                            };
+                            Some((def, ast::AnyHasVisibility::cast(it.syntax().clone())?))
+                        },
+                    ),
+                ),
+                _ => (),
+            }
+            for (def, owner) in members {
+                if self.is_used_outside_target(def) {
+                    self.make_crate_visible(index, &owner);
+                }
+            }
+        }
+    }
+
+    /// Rewrites the references to the moved `def` and returns whether it is
+    /// used from outside of the target module.
+    fn rewrite_usages(&mut self, def: ModuleDef) -> bool {
+        let db = self.sema.db;
+        let mut used_outside = false;
+        for (file_id, references) in Definition::from(def).usages(self.sema).all() {
+            let source_file = self.sema.parse(file_id);
+            for reference in references {
+                let in_moved_item = self.moved_item_index(file_id, reference.range).is_some();
+                let Some(module) = self.module_at(&source_file, reference.range, in_moved_item)
+                else {
+                    continue;
+                };
+                used_outside |= !self.is_within_target(module);
+                let rewritten = self.rewrite_usage(
+                    file_id,
+                    &source_file,
+                    reference.range,
+                    module,
+                    in_moved_item,
+                );
+                if let Err(reason) = rewritten {
+                    self.unrewritten.push(UnrewrittenSite {
+                        range: FileRange { file_id: file_id.file_id(db), range: reference.range },
+                        reason: reason.to_owned(),
+                    });
+                }
+            }
+        }
+        used_outside
+    }
+
+    fn is_used_outside_target(&self, def: Definition) -> bool {
+        def.usages(self.sema).all().into_iter().any(|(file_id, references)| {
+            let source_file = self.sema.parse(file_id);
+            references.iter().any(|reference| {
+                let in_moved_item = self.moved_item_index(file_id, reference.range).is_some();
+                self.module_at(&source_file, reference.range, in_moved_item)
+                    .is_some_and(|module| !self.is_within_target(module))
+            })
+        })
+    }
+
+    fn rewrite_usage(
+        &mut self,
+        file_id: EditionedFileId,
+        source_file: &ast::SourceFile,
+        range: TextRange,
+        module: Module,
+        in_moved_item: bool,
+    ) -> Result<(), &'static str> {
+        let Some(name_ref) = find_node_at_range::<ast::NameRef>(source_file.syntax(), range)
+            .filter(|it| it.syntax().text_range() == range)
+        else {
+            return self.rewrite_macro_usage(file_id, source_file, range, module, in_moved_item);
+        };
+        let path = name_ref
+            .syntax()
+            .parent()
+            .and_then(ast::PathSegment::cast)
+            .map(|it| it.parent_path())
+            .ok_or("the reference is not a path")?;
+        if in_moved_item {
+            self.rewritten_paths.insert(path.syntax().text_range());
+        }
+        let use_tree = path.top_path().syntax().parent().and_then(ast::UseTree::cast);
+        if let Some(use_tree) = &use_tree
+            && !in_moved_item
+        {
+            return self.rewrite_use_tree(file_id, use_tree, &path, &name_ref, module);
+        }
+
+        if path.qualifier().is_none() {
+            if !in_moved_item && module == self.mv.source {
+                let scope = ImportScope::find_insert_use_container(name_ref.syntax(), self.sema)
+                    .ok_or("there is no place for an import of the item")?;
+                let path = make::path_from_text(&format!(
+                    "{}{}",
+                    self.prefix_from(module, true)?,
+                    name_ref.text()
+                ));
+                self.add_import(file_id.file_id(self.sema.db), scope, path);
+            }
+            return Ok(());
+        }
+        let prefix = self.prefix_from(module, use_tree.is_some())?;
+        let prefix_range = TextRange::new(
+            path.syntax().text_range().start(),
+            name_ref.syntax().text_range().start(),
+        );
+        self.edit(file_id.file_id(self.sema.db), prefix_range, prefix);
+        Ok(())
+    }
+
+    fn rewrite_use_tree(
+        &mut self,
+        file_id: EditionedFileId,
+        use_tree: &ast::UseTree,
+        path: &ast::Path,
+        name_ref: &ast::NameRef,
+        module: Module,
+    ) -> Result<(), &'static str> {
+        let file_id = file_id.file_id(self.sema.db);
+        let is_whole_tree = use_tree.path().as_ref() == Some(path)
+            && use_tree.rename().is_none()
+            && use_tree.use_tree_list().is_none()
+            && use_tree.star_token().is_none();
+        if module == self.mv.target {
+            // The item is going to be declared in this very module.
+            if !is_whole_tree {
+                return Err("the import would refer to an item of its own module");
+            }
+            self.use_edits(file_id, use_tree)?.removed_trees.push(use_tree.clone());
+            return Ok(());
+        }
+
+        let new_path = make::path_from_text(&format!(
+            "{}{}",
+            self.prefix_from(module, true)?,
+            name_ref.text()
+        ));
+        if use_tree.syntax().parent().is_some_and(|it| ast::Use::can_cast(it.kind())) {
+            self.use_edits(file_id, use_tree)?.paths.push((path.clone(), new_path));
+            return Ok(());
+        }
+        // A tree in a group, like `Foo` in `use a::{b, Foo}`, is taken out of the
+        // group and imported on its own.
+        if !is_whole_tree {
+            return Err("the reference is part of a nested import");
+        }
+        let scope = ImportScope::find_insert_use_container(use_tree.syntax(), self.sema)
+            .ok_or("there is no place for an import of the item")?;
+        self.use_edits(file_id, use_tree)?.removed_trees.push(use_tree.clone());
+        self.add_import(file_id, scope, new_path);
+        Ok(())
+    }
+
+    fn rewrite_macro_usage(
+        &mut self,
+        file_id: EditionedFileId,
+        source_file: &ast::SourceFile,
+        range: TextRange,
+        module: Module,
+        in_moved_item: bool,
+    ) -> Result<(), &'static str> {
+        let name = source_file
+            .syntax()
+            .covering_element(range)
+            .into_token()
+            .filter(|it| it.kind() == SyntaxKind::IDENT && it.text_range() == range)
+            .filter(|it| it.parent_ancestors().any(|it| ast::TokenTree::can_cast(it.kind())))
+            .ok_or("the reference could not be rewritten")?;
+        match macro_path_start(&name) {
+            Some(start) => {
+                let prefix = self.prefix_from(module, false)?;
+                self.edit(
+                    file_id.file_id(self.sema.db),
+                    TextRange::new(start, range.start()),
+                    prefix,
+                );
+            }
+            None if !in_moved_item && module == self.mv.source => {
+                let scope = name
+                    .parent()
+                    .and_then(|it| ImportScope::find_insert_use_container(&it, self.sema))
+                    .ok_or("there is no place for an import of the item")?;
+                let path = make::path_from_text(&format!(
+                    "{}{}",
+                    self.prefix_from(module, true)?,
+                    name.text()
+                ));
+                self.add_import(file_id.file_id(self.sema.db), scope, path);
+            }
+            None => (),
+        }
+        Ok(())
+    }
+
+    /// Makes the items the moved items refer to visible from the target module,
+    /// and imports the ones they refer to by name there.
+    fn fix_dependencies(&mut self, target_scope: &SemanticsScope<'_>) {
+        let db = self.sema.db;
+        let visible_traits = target_scope.visible_traits();
+        let mv = self.mv;
+        for item in &mv.items {
+            for path in item.syntax().descendants().filter_map(ast::Path::cast) {
+                if let Some(PathResolution::Def(def)) = self.sema.resolve_path(&path) {
+                    self.make_visible(def);
+                }
+                let already_rewritten =
+                    iter::successors(Some(path.clone()), ast::Path::parent_path)
+                        .any(|it| self.rewritten_paths.contains(&it.syntax().text_range()));
+                if path.qualifier().is_none()
+                    && !already_rewritten
+                    && let Err(reason) = self.fix_first_segment(&path, target_scope)
+                {
+                    self.unrewritten.push(UnrewrittenSite {
+                        range: FileRange {
+                            file_id: self.mv.file_id.file_id(db),
+                            range: path.syntax().text_range(),
+                        },
+                        reason: reason.to_owned(),
+                    });
+                }
+            }
+
+            for call in item.syntax().descendants().filter_map(ast::MethodCallExpr::cast) {
+                let Some(func) = self.sema.resolve_method_call(&call) else { continue };
+                self.make_visible(func.into());
+                let Some(trait_) =
+                    func.as_assoc_item(db).and_then(|it| it.container_or_implemented_trait(db))
+                else {
+                    continue;
+                };
+                if self.is_moved(trait_.into()) || visible_traits.contains(&trait_.into()) {
+                    continue;
+                }
+                match self.use_path_to(self.mv.target, trait_.into()) {
+                    Some(path) => self.add_target_import(make::path_from_text(&path)),
+                    None => self.unrewritten.push(UnrewrittenSite {
+                        range: FileRange {
+                            file_id: self.mv.file_id.file_id(db),
+                            range: call.syntax().text_range(),
+                        },
+                        reason: "the trait of the method can't be imported".to_owned(),
+                    }),
+                }
+            }
+        }
+    }
+
+    fn fix_first_segment(
+        &mut self,
+        path: &ast::Path,
+        target_scope: &SemanticsScope<'_>,
+    ) -> Result<(), &'static str> {
+        let db = self.sema.db;
+        let Some(segment) = path.segment() else { return Ok(()) };
+        match segment.kind() {
+            Some(PathSegmentKind::SelfKw | PathSegmentKind::SuperKw) => {
+                self.rewrite_module_prefix(path)
+            }
+            Some(PathSegmentKind::Name(name_ref)) => {
+                // Trees of a group are resolved relative to the group's path.
+                if let Some(use_tree) =
+                    path.top_path().syntax().parent().and_then(ast::UseTree::cast)
+                    && use_tree
+                        .syntax()
+                        .ancestors()
+                        .skip(1)
+                        .filter_map(ast::UseTree::cast)
+                        .any(|it| it.path().is_some())
+                {
+                    return Ok(());
+                }
+                let Some(PathResolution::Def(def)) = self.sema.resolve_path(path) else {
+                    return Ok(());
+                };
+                if self.is_moved(def) {
+                    return Ok(());
+                }
+                match target_scope.speculative_resolve(&make::path_from_text(&name_ref.text())) {
+                    Some(PathResolution::Def(it)) if it == def => return Ok(()),
+                    _ if matches!(def, ModuleDef::Module(_)) => {
+                        return self.rewrite_module_prefix(path);
+                    }
+                    Some(_) => return Err("the name refers to another item in the target module"),
+                    None => (),
+                }
+                if def
+                    .name(db)
+                    .is_none_or(|it| it.as_str() != name_ref.text().trim_start_matches("r#"))
+                {
+                    return Err("the item is referred to by an alias");
+                }
+                let import = self
+                    .use_path_to(self.mv.target, def)
+                    .ok_or("the item can't be imported into the target module")?;
+                self.add_target_import(make::path_from_text(&import));
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Rewrites the longest module path starting at `path`, the first segment of
+    /// a path in a moved item, to one that works from the target module.
+    fn rewrite_module_prefix(&mut self, path: &ast::Path) -> Result<(), &'static str> {
+        let db = self.sema.db;
+        let Some((module_path, module)) =
+            iter::successors(Some(path.clone()), ast::Path::parent_path)
+                .map_while(|it| match self.sema.resolve_path(&it) {
+                    Some(PathResolution::Def(ModuleDef::Module(module))) => Some((it, module)),
+                    _ => None,
+                })
+                .last()
+        else {
+            return Ok(());
+        };
+        let text = if module == self.mv.target {
+            "self".to_owned()
+        } else {
+            self.mv
+                .target
+                .find_path(db, ModuleDef::Module(module), self.config.find_path)
+                .ok_or("the module is not reachable from the target module")?
+                .display(db, self.edition)
+                .to_string()
+        };
+        if module_path.syntax().text() != text.as_str() {
+            self.edit(self.mv.file_id.file_id(db), module_path.syntax().text_range(), text);
+        }
+        Ok(())
+    }
+
+    /// Makes `def`, which a moved item refers to, `pub(crate)` when it isn't
+    /// visible from the target module.
+    fn make_visible(&mut self, def: ModuleDef) {
+        let db = self.sema.db;
+        if self.is_moved(def) || !self.made_visible.insert(def) {
+            return;
+        }
+        // The visibility of trait items follows the one of the trait.
+        if def.as_assoc_item(db).and_then(|it| it.container_or_implemented_trait(db)).is_some() {
+            return;
+        }
+        let Some(module) = def.module(db) else { return };
+        if module.krate(db) != self.mv.target.krate(db)
+            || def.visibility(db).is_visible_from(db, self.mv.target.into())
+        {
+            return;
+        }
+        if let Some((owner, _, file_id, _)) = visibility_owner_for_def(db, def)
+            && let Some((range, text)) = pub_crate_edit(&owner)
+        {
+            self.edit(file_id, range, text);
+        }
+    }
+
+    fn make_crate_visible(&mut self, index: usize, owner: &impl ast::HasVisibility) {
+        if let Some(edit) = pub_crate_edit(owner) {
+            self.item_edits[index].push(edit);
+        }
+    }
+
+    /// The text to put in front of the name of a moved item to refer to it
+    /// from `module`.
+    fn prefix_from(&self, module: Module, in_use_tree: bool) -> Result<String, &'static str> {
+        let db = self.sema.db;
+        let target = self.mv.target;
+        if module == target {
+            return Ok(if in_use_tree { "self::".to_owned() } else { String::new() });
+        }
+        let path = if in_use_tree {
+            module.find_use_path(
+                db,
+                ModuleDef::Module(target),
+                self.config.insert_use.prefix_kind,
+                self.config.find_path,
+            )
+        } else {
+            module.find_path(db, ModuleDef::Module(target), self.config.find_path)
+        };
+        let path = path.ok_or("the target module is not reachable from here")?;
+        Ok(format!("{}::", path.display(db, self.edition)))
+    }
+
+    /// A path to import `def` with into `module`. Items that are only going to
+    /// become visible are imported through their module.
+    fn use_path_to(&self, module: Module, def: ModuleDef) -> Option<String> {
+        let db = self.sema.db;
+        let cfg = self.config.find_path;
+        if let Some(path) = module.find_use_path(db, def, self.config.insert_use.prefix_kind, cfg) {
+            return Some(path.display(db, self.edition).to_string());
+        }
+        let name = def.name(db)?.display(db, self.edition).to_string();
+        let parent = match def {
+            ModuleDef::Macro(_) => return None,
+            ModuleDef::Variant(it) => hir::Adt::from(it.parent_enum(db)).into(),
+            _ => {
+                let parent = def.module(db)?;
+                if parent == module {
+                    return Some(format!("self::{name}"));
+                }
+                ModuleDef::Module(parent)
+            }
+        };
+        Some(format!("{}::{name}", self.use_path_to(module, parent)?))
+    }
+
+    fn add_import(&mut self, file_id: FileId, scope: ImportScope, path: ast::Path) {
+        let container = scope.as_syntax_node().clone();
+        let imports = &mut self.container_edits(file_id, container).imports;
+        if !imports.iter().any(|(_, it)| it.to_string() == path.to_string()) {
+            imports.push((scope, path));
+        }
+    }
+
+    /// The edits of the `use` item list `use_tree` is in.
+    fn use_edits(
+        &mut self,
+        file_id: FileId,
+        use_tree: &ast::UseTree,
+    ) -> Result<&mut UseEdits, &'static str> {
+        let container = use_tree
+            .syntax()
+            .ancestors()
+            .find_map(ast::Use::cast)
+            .and_then(|it| it.syntax().parent())
+            .ok_or("the import could not be rewritten")?;
+        Ok(self.container_edits(file_id, container))
+    }
+
+    fn container_edits(&mut self, file_id: FileId, container: SyntaxNode) -> &mut UseEdits {
+        self.files
+            .entry(file_id)
+            .or_default()
+            .use_edits
+            .entry(container.text_range())
+            .or_insert_with(|| UseEdits {
+                container,
+                paths: Vec::new(),
+                removed_trees: Vec::new(),
+                imports: Vec::new(),
+            })
+    }
+
+    fn add_target_import(&mut self, path: ast::Path) {
+        if !self.target_imports.iter().any(|it| it.to_string() == path.to_string()) {
+            self.target_imports.push(path);
+        }
+    }
+
+    /// Records an edit, which ends up in the text of a moved item when it is
+    /// inside of one.
+    fn edit(&mut self, file_id: FileId, range: TextRange, text: String) {
+        let index = (file_id == self.mv.file_id.file_id(self.sema.db))
+            .then(|| {
+                self.mv.items.iter().position(|it| it.syntax().text_range().contains_range(range))
+            })
+            .flatten();
+        match index {
+            Some(index) => self.item_edits[index].push((range, text)),
+            None => self.files.entry(file_id).or_default().replacements.push((range, text)),
+        }
+    }
+
+    fn moved_item_index(&self, file_id: EditionedFileId, range: TextRange) -> Option<usize> {
+        if file_id != self.mv.file_id {
+            return None;
+        }
+        self.mv.items.iter().position(|it| it.syntax().text_range().contains_range(range))
+    }
+
+    /// Whether `def` is one of the moved items, or is defined inside of one.
+    fn is_moved(&self, def: ModuleDef) -> bool {
+        let db = self.sema.db;
+        let def = match def {
+            ModuleDef::Variant(it) => hir::Adt::from(it.parent_enum(db)).into(),
+            it => it,
+        };
+        let Some((_, range, file_id, _)) = visibility_owner_for_def(db, def) else {
+            return false;
+        };
+        file_id == self.mv.file_id.file_id(db)
+            && self.mv.items.iter().any(|it| it.syntax().text_range().contains_range(range))
+    }
+
+    /// The module a reference is in, which is the target module for references
+    /// from the moved items.
+    fn module_at(
+        &self,
+        source_file: &ast::SourceFile,
+        range: TextRange,
+        in_moved_item: bool,
+    ) -> Option<Module> {
+        if in_moved_item {
+            return Some(self.mv.target);
+        }
+        let node = match source_file.syntax().covering_element(range) {
+            NodeOrToken::Node(it) => it,
+            NodeOrToken::Token(it) => it.parent()?,
+        };
+        Some(self.sema.scope(&node)?.module().nearest_non_block_module(self.sema.db))
+    }
+
+    fn is_within_target(&self, module: Module) -> bool {
+        module.path_to_root(self.sema.db).contains(&self.mv.target)
+    }
+
+    fn finish(mut self, builder: &mut SourceChangeBuilder) -> Vec<UnrewrittenSite> {
+        let db = self.sema.db;
+        let source_file_id = self.mv.file_id.file_id(db);
+        let target_file_id = self.mv.target_file_id.file_id(db);
+        let items: Vec<_> = self
+            .mv
+            .items
+            .iter()
+            .zip(std::mem::take(&mut self.item_edits))
+            .map(|(item, edits)| (IndentLevel::from_node(item.syntax()), edited_text(item, edits)))
+            .collect();
+
+        // Imports of an empty target are written out in front of the moved items.
+        let container = self.mv.target_container.clone();
+        let mut leading_imports = Vec::new();
+        if container.children().any(|it| ast::Item::can_cast(it.kind())) {
+            let kind = match ast::ItemList::cast(container.clone()) {
+                Some(it) => ImportScopeKind::Module(it),
+                None => match ast::SourceFile::cast(container.clone()) {
+                    Some(it) => ImportScopeKind::File(it),
+                    None => return self.unrewritten,
+                },
+            };
+            let scope = ImportScope { kind, required_cfgs: Vec::new() };
+            for path in std::mem::take(&mut self.target_imports) {
+                self.add_import(target_file_id, scope.clone(), path);
+            }
+        } else {
+            leading_imports = std::mem::take(&mut self.target_imports);
+        }
+        let insertion = insertion(&container, &items, &leading_imports, self.edition);
+
+        self.files.entry(source_file_id).or_default();
+        self.files.entry(target_file_id).or_default();
+        for (file_id, edits) in self.files {
+            builder.edit_file(file_id);
+            for use_edits in edits.use_edits.into_values() {
+                for (range, text) in use_edits.into_text_edits(&self.config.insert_use) {
+                    builder.replace(range, text);
+                }
+            }
+            for (range, text) in edits.replacements {
+                builder.replace(range, text);
+            }
+            if file_id == target_file_id {
+                builder.replace(insertion.0, insertion.1.clone());
+            }
+            if file_id == source_file_id {
+                let items: Vec<_> = self.mv.items.iter().map(|it| it.syntax().clone()).collect();
+                for range in removal_ranges(&items) {
+                    builder.delete(range);
+                }
+            }
+        }
+        self.unrewritten
+    }
+}
+
+/// Returns the edit that makes `owner` `pub(crate)`, or `None` when it is
+/// visible in the whole crate already.
+fn pub_crate_edit(owner: &impl ast::HasVisibility) -> Option<(TextRange, String)> {
+    match owner.visibility() {
+        Some(vis) => match vis.kind() {
+            VisibilityKind::Pub | VisibilityKind::PubCrate => None,
+            _ => Some((vis.syntax().text_range(), "pub(crate)".to_owned())),
+        },
+        None => {
+            let start = owner
+                .syntax()
+                .children_with_tokens()
+                .find(|it| {
+                    !matches!(
+                        it.kind(),
+                        SyntaxKind::ATTR | SyntaxKind::COMMENT | SyntaxKind::WHITESPACE
+                    )
+                })?
+                .text_range()
+                .start();
+            Some((TextRange::empty(start), "pub(crate) ".to_owned()))
+        }
+    }
+}
+
+/// Returns the start of the path in front of `name` in a token tree, like
+/// `a::b::` in `a::b::name`.
+fn macro_path_start(name: &SyntaxToken) -> Option<TextSize> {
+    let mut start = None;
+    let mut token = name.prev_token();
+    loop {
+        // Inside of token trees, `::` is made of two `:` tokens.
+        let separator = match token {
+            Some(it) if it.kind() == T![::] => it,
+            Some(it) if it.kind() == T![:] => match it.prev_token() {
+                Some(it) if it.kind() == T![:] => it,
+                _ => break,
+            },
+            _ => break,
+        };
+        match separator.prev_token() {
+            Some(it)
+                if matches!(it.kind(), SyntaxKind::IDENT | T![crate] | T![self] | T![super]) =>
+            {
+                start = Some(it.text_range().start());
+                token = it.prev_token();
+            }
+            _ => {
+                start = Some(separator.text_range().start());
+                break;
+            }
+        }
+    }
+    start
+}
+
+/// The text of `item` with `edits`, whose ranges are relative to the file,
+/// applied.
+fn edited_text(item: &ast::Item, mut edits: Vec<(TextRange, String)>) -> String {
+    let item_start = item.syntax().text_range().start();
+    let mut text = item.syntax().text().to_string();
+    edits.sort_by_key(|(range, _)| (range.start(), range.end()));
+    edits.dedup_by(|a, b| a.0 == b.0);
+    let mut end = TextSize::of(&text);
+    for (range, replacement) in edits.into_iter().rev() {
+        let range = range - item_start;
+        if range.end() > end {
+            continue;
+        }
+        text.replace_range(std::ops::Range::<usize>::from(range), &replacement);
+        end = range.start();
+    }
+    text
+}
+
+/// The edit appending `items`, along with their indentation, to `container`,
+/// a `SourceFile` or an `ItemList`, with `imports` in front of them.
+fn insertion(
+    container: &SyntaxNode,
+    items: &[(IndentLevel, String)],
+    imports: &[ast::Path],
+    edition: Edition,
+) -> (TextRange, String) {
+    let (indent, closing_indent) = match container.parent() {
+        Some(module) => {
+            let indent = IndentLevel::from_node(&module);
+            (indent + 1, Some(indent))
+        }
+        None => (IndentLevel(0), None),
+    };
+
+    let mut blocks = Vec::new();
+    if !imports.is_empty() {
+        let imports: Vec<_> = imports.iter().map(|it| format!("use {it};")).collect();
+        blocks.push(imports.join(&format!("\n{indent}")));
+    }
+    for (from, text) in items {
+        let item = ast::SourceFile::parse(text, edition).tree().items().next();
+        match item {
+            Some(item) if item.syntax().text_range().len() == TextSize::of(text) => {
+                blocks.push(item.dedent(*from).indent(indent).to_string())
+            }
+            _ => blocks.push(text.clone()),
+        }
+    }
+    let body = blocks.join(&format!("\n\n{indent}"));
+
+    let anchor = container
+        .children_with_tokens()
+        .filter(|it| !matches!(it.kind(), SyntaxKind::WHITESPACE | T!['}']))
+        .last();
+    match (anchor, closing_indent) {
+        (Some(anchor), Some(closing_indent)) if anchor.kind() == T!['{'] => {
+            let end = container
+                .last_token()
+                .filter(|it| it.kind() == T!['}'])
+                .map_or(container.text_range().end(), |it| it.text_range().start());
+            (
+                TextRange::new(anchor.text_range().end(), end),
+                format!("\n{indent}{body}\n{closing_indent}"),
+            )
+        }
+        (Some(anchor), closing_indent) => {
+            let newline = match closing_indent {
+                None if anchor.next_sibling_or_token().is_none() => "\n",
+                _ => "",
+            };
+            (TextRange::empty(anchor.text_range().end()), format!("\n\n{indent}{body}{newline}"))
+        }
+        (None, _) => (TextRange::empty(container.text_range().start()), format!("{body}\n")),
+    }
+}
+
+fn error(message: impl Into<String>) -> MoveToModuleError {
+    MoveToModuleError(message.into())
+}
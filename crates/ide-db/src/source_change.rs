@@ -7,7 +7,7 @@ use std::{collections::hash_map::Entry, fmt, iter, mem};
 
 use crate::imports::insert_use::{ImportScope, ImportScopeKind};
 use crate::text_edit::{TextEdit, TextEditBuilder};
-use crate::{SnippetCap, assists::Command, syntax_helpers::tree_diff::diff};
+use crate::{FileRange, SnippetCap, assists::Command, syntax_helpers::tree_diff::diff};
 use base_db::AnchoredPathBuf;
 use itertools::Itertools;
 use macros::UpmapFromRaFixture;
@@ -37,6 +37,13 @@ pub struct ChangeAnnotation {
     pub description: Option<String>,
 }
 
+/// A site that a refactoring could not rewrite and left as is.
+#[derive(Debug, Clone)]
+pub struct UnrewrittenSite {
+    pub range: FileRange,
+    pub reason: String,
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct SourceChange {
     pub source_file_edits: IntMap<FileId, (TextEdit, Option<SnippetEdit>)>,
@@ -232,6 +239,8 @@ pub struct SourceChangeBuilder {
     pub mutated_tree: Option<TreeMutator>,
     /// Keeps track of where to place snippets
     pub snippet_builder: Option<SnippetBuilder>,
+    /// The annotation of all the edits, see [`SourceChangeBuilder::annotate`].
+    pub annotation: Option<ChangeAnnotationId>,
 }
 
 pub struct TreeMutator {
@@ -273,6 +282,7 @@ impl SourceChangeBuilder {
             snippet_annotations: vec![],
             mutated_tree: None,
             snippet_builder: None,
+            annotation: None,
         }
     }
 
@@ -428,6 +438,12 @@ impl SourceChangeBuilder {
         self.command = Some(Command::Rename);
     }
 
+    /// Attaches `annotation` to all the edits, so that clients can ask for a
+    /// confirmation before applying them.
+    pub fn annotate(&mut self, annotation: ChangeAnnotation) {
+        self.annotation = Some(self.source_change.insert_annotation(annotation));
+    }
+
     /// Adds a tabstop snippet to place the cursor before `node`
     pub fn add_tabstop_before(&mut self, _cap: SnippetCap, node: impl AstNode) {
         assert!(node.syntax().parent().is_some());
@@ -501,6 +517,12 @@ impl SourceChangeBuilder {
                 .is_err()
         );
 
+        if let Some(annotation) = self.annotation {
+            for (edit, _) in self.source_change.source_file_edits.values_mut() {
+                edit.set_annotation(Some(annotation));
+            }
+        }
+
         mem::take(&mut self.source_change)
     }
 }
@@ -0,0 +1,81 @@
+use syntax::ast::ArithOp;
+
+use crate::{Diagnostic, DiagnosticCode, DiagnosticsContext};
+
+// Diagnostic: arithmetic-overflow
+//
+// This diagnostic is triggered when an arithmetic operation on integer literals always overflows.
+pub(crate) fn arithmetic_overflow(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::ArithmeticOverflow,
+) -> Diagnostic {
+    let operation = match d.op {
+        ArithOp::Add => "add",
+        ArithOp::Sub => "subtract",
+        ArithOp::Mul => "multiply",
+        ArithOp::Div => "divide",
+        ArithOp::Rem => "calculate the remainder",
+        ArithOp::Shl => "shift left",
+        ArithOp::Shr => "shift right",
+        ArithOp::BitXor | ArithOp::BitOr | ArithOp::BitAnd => "compute",
+    };
+    Diagnostic::new_with_syntax_node_ptr(
+        ctx,
+        DiagnosticCode::RustcLint("arithmetic_overflow"),
+        format!("this arithmetic operation will overflow\nattempt to {operation} with overflow\n"),
+        d.expr.map(Into::into),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn overflowing_literal_arithmetic() {
+        check_diagnostics(
+            r#"
+fn f() {
+    let _: u8 = 255 + 1;
+              //^^^^^^^ error: this arithmetic operation will overflow
+              //      | attempt to add with overflow
+    let _: i8 = -128 - 1;
+              //^^^^^^^^ error: this arithmetic operation will overflow
+              //       | attempt to subtract with overflow
+    let _: u32 = 1 << 32;
+               //^^^^^^^ error: this arithmetic operation will overflow
+               //      | attempt to shift left with overflow
+    let _: u8 = 16 * 16 + 1;
+              //^^^^^^^ error: this arithmetic operation will overflow
+              //      | attempt to multiply with overflow
+    let _: u8 = 254 + 1;
+    let _: i8 = -128;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn non_literal_operands() {
+        check_diagnostics(
+            r#"
+fn f(x: u8) {
+    let _ = x + 255;
+    let _ = [0u8; 2 - 1];
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn allowed() {
+        check_diagnostics(
+            r#"
+#[allow(arithmetic_overflow)]
+fn f() {
+    let _: u8 = 255 + 1;
+}
+"#,
+        );
+    }
+}
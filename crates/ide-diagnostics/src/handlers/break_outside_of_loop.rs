@@ -24,11 +24,11 @@ pub(crate) fn break_outside_of_loop(
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::check_diagnostics;
+    use crate::tests::{check_diagnostics, check_diagnostics_with_disabled};
 
     #[test]
     fn outside_of_loop() {
-        check_diagnostics(
+        check_diagnostics_with_disabled(
             r#"
 fn foo() {
     break;
@@ -37,12 +37,13 @@ fn foo() {
   //^^^^^^^^ error: continue outside of loop
 }
 "#,
+            &["unreachable_code"],
         );
     }
 
     #[test]
     fn async_blocks_are_borders() {
-        check_diagnostics(
+        check_diagnostics_with_disabled(
             r#"
 fn foo() {
     'a: loop {
@@ -55,12 +56,13 @@ fn foo() {
     }
 }
 "#,
+            &["unreachable_code"],
         );
     }
 
     #[test]
     fn closures_are_borders() {
-        check_diagnostics(
+        check_diagnostics_with_disabled(
             r#"
 fn foo() {
     'a: loop {
@@ -73,12 +75,13 @@ fn foo() {
     }
 }
 "#,
+            &["unreachable_code"],
         );
     }
 
     #[test]
     fn blocks_pass_through() {
-        check_diagnostics(
+        check_diagnostics_with_disabled(
             r#"
 fn foo() {
     'a: loop {
@@ -89,12 +92,13 @@ fn foo() {
     }
 }
 "#,
+            &["unreachable_code"],
         );
     }
 
     #[test]
     fn try_blocks_pass_through() {
-        check_diagnostics(
+        check_diagnostics_with_disabled(
             r#"
 fn foo() {
     'a: loop {
@@ -105,12 +109,13 @@ fn foo() {
     }
 }
 "#,
+            &["unreachable_code"],
         );
     }
 
     #[test]
     fn label_blocks() {
-        check_diagnostics(
+        check_diagnostics_with_disabled(
             r#"
 fn foo() {
     'a: {
@@ -121,6 +126,7 @@ fn foo() {
     }
 }
 "#,
+            &["unreachable_code"],
         );
     }
 
@@ -0,0 +1,175 @@
+use crate::{Diagnostic, DiagnosticCode, DiagnosticsContext};
+
+// Diagnostic: conflicting-borrow
+//
+// This diagnostic is triggered when a variable is borrowed mutably while it is still borrowed,
+// or borrowed while it is still borrowed mutably.
+pub(crate) fn conflicting_borrow(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::ConflictingBorrow,
+) -> Diagnostic {
+    let name = d.local.name(ctx.sema.db);
+    let name = name.display(ctx.sema.db, ctx.edition);
+    let (code, message) = match (d.is_mut, d.live_is_mut) {
+        (true, true) => {
+            ("E0499", format!("cannot borrow `{name}` as mutable more than once at a time"))
+        }
+        (true, false) => (
+            "E0502",
+            format!("cannot borrow `{name}` as mutable because it is also borrowed as immutable"),
+        ),
+        (false, _) => (
+            "E0502",
+            format!("cannot borrow `{name}` as immutable because it is also borrowed as mutable"),
+        ),
+    };
+    Diagnostic::new_with_syntax_node_ptr(ctx, DiagnosticCode::RustcHardError(code), message, d.span)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn two_mutable_borrows() {
+        check_diagnostics(
+            r#"
+fn f() {
+    let mut x = 0;
+    let a = &mut x;
+    let b = &mut x;
+          //^^^^^^ error: cannot borrow `x` as mutable more than once at a time
+    *a = 1;
+    *b = 2;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn mutable_and_shared_borrows() {
+        check_diagnostics(
+            r#"
+fn by_ref(_: &i32) {}
+
+fn f() {
+    let mut x = 0;
+    let r = &x;
+    let m = &mut x;
+          //^^^^^^ error: cannot borrow `x` as mutable because it is also borrowed as immutable
+    *m = 1;
+    by_ref(r);
+
+    let mut y = 0;
+    let m = &mut y;
+    let r = &y;
+          //^^ error: cannot borrow `y` as immutable because it is also borrowed as mutable
+    by_ref(r);
+    *m = 1;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn borrow_through_method_call() {
+        check_diagnostics(
+            r#"
+struct V;
+impl V {
+    fn push(&mut self, _: usize) {}
+    fn len(&self) -> usize { 0 }
+    fn first(&self) -> &usize { &0 }
+}
+fn by_ref(_: &usize) {}
+
+fn f() {
+    let mut v = V;
+    v.push(v.len());
+    let first = v.first();
+    v.push(1);
+  //^ error: cannot borrow `v` as mutable because it is also borrowed as immutable
+    by_ref(first);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn result_not_tied_to_receiver() {
+        check_diagnostics(
+            r#"
+//- minicore: copy
+struct Lexer<'a> {
+    src: &'a str,
+}
+impl<'a> Lexer<'a> {
+    fn next_tok(&mut self) -> &'a str {
+        self.src
+    }
+}
+fn use_it(_: &str) {}
+
+fn f(mut lx: Lexer<'_>) {
+    let t = lx.next_tok();
+    let u = lx.next_tok();
+    use_it(t);
+    use_it(u);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn result_tied_to_one_argument() {
+        check_diagnostics(
+            r#"
+fn pick<'a>(a: &'a mut i32, _: &mut i32) -> &'a mut i32 { a }
+fn by_ref(_: &i32) {}
+
+fn f() {
+    let mut x = 0;
+    let mut y = 0;
+    let r = pick(&mut x, &mut y);
+    let s = &mut y;
+    let t = &mut x;
+          //^^^^^^ error: cannot borrow `x` as mutable more than once at a time
+    by_ref(s);
+    by_ref(r);
+    by_ref(t);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn non_lexical_lifetimes() {
+        check_diagnostics(
+            r#"
+fn by_ref(_: &i32) {}
+
+struct P {
+    a: i32,
+    b: i32,
+}
+
+fn f(mut p: P) {
+    let mut x = 0;
+    let a = &mut x;
+    *a += 1;
+    let b = &mut x;
+    *b += 1;
+    let r = &x;
+    let s = &x;
+    by_ref(r);
+    by_ref(s);
+
+    let a = &mut p.a;
+    let b = &mut p.b;
+    *a += 1;
+    *b += 1;
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{Diagnostic, DiagnosticCode, DiagnosticsContext};
+
+// Diagnostic: const-eval-failed
+//
+// This diagnostic is triggered when the evaluation of a constant, a static or an array length
+// panics, for example because of an arithmetic overflow or an index that is out of bounds.
+pub(crate) fn const_eval_failed(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::ConstEvalFailed,
+) -> Diagnostic {
+    Diagnostic::new_with_syntax_node_ptr(
+        ctx,
+        DiagnosticCode::RustcHardError("E0080"),
+        format!("evaluation of constant value failed\n{}\n", d.message),
+        d.expr.map(Into::into),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn const_item() {
+        check_diagnostics(
+            r#"
+const N: u8 = 255 + 1;
+            //^^^^^^^ error: evaluation of constant value failed
+            //      | Overflow in Add
+const M: u8 = 254 + 1;
+"#,
+        );
+    }
+
+    #[test]
+    fn static_item() {
+        check_diagnostics(
+            r#"
+//- minicore: index, slice
+const A: [i32; 3] = [1, 2, 3];
+static S: i32 = A[5];
+              //^^^^ error: evaluation of constant value failed
+              //   | index out of bounds: the length is 3 but the index is 5
+static T: i32 = A[2];
+"#,
+        );
+    }
+
+    #[test]
+    fn array_length() {
+        check_diagnostics(
+            r#"
+fn f() {
+    let _ = [0u8; 1 - 2];
+                //^^^^^ error: evaluation of constant value failed
+                //    | Overflow in Sub
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,264 @@
+use hir::{
+    AsAssocItem, InFile, Semantics,
+    dead_code::{DeadCodeItem, Liveness},
+};
+use ide_db::{
+    EditionedFileId, FileRange, RootDatabase, defs::Definition, search::ReferenceCategory,
+};
+use syntax::{
+    AstNode, SyntaxNode, SyntaxNodePtr,
+    ast::{self, HasName, HasVisibility},
+    match_ast,
+};
+
+use crate::{Diagnostic, DiagnosticCode, DiagnosticsContext};
+
+// Diagnostic: dead-code
+//
+// This diagnostic is triggered when a private function, constant, static, field or enum variant
+// is not reachable from the public API, `main`, tests or trait implementations of its crate.
+pub(crate) fn dead_code(
+    ctx: &DiagnosticsContext<'_>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: EditionedFileId,
+) {
+    if !ctx.config.dead_code {
+        return;
+    }
+    let db = ctx.sema.db;
+    let source_file = ctx.sema.parse(file_id);
+    let mut liveness = Liveness::new(db, |item| users(&ctx.sema, item));
+
+    for node in source_file.syntax().descendants() {
+        let Some((item, name, kind)) = candidate(&ctx.sema, &node) else { continue };
+        if name.text().starts_with('_') || liveness.is_live(item) {
+            continue;
+        }
+        acc.push(
+            Diagnostic::new(
+                DiagnosticCode::RustcLint("dead_code"),
+                format!("{kind} `{}` is never used", name.text()),
+                FileRange { file_id: file_id.file_id(db), range: name.syntax().text_range() },
+            )
+            .stable()
+            .with_unused(true)
+            .with_main_node(InFile::new(file_id.into(), SyntaxNodePtr::new(&node))),
+        );
+    }
+}
+
+/// Resolves `node` to an item we may report, together with its name and how to call it.
+fn candidate(
+    sema: &Semantics<'_, RootDatabase>,
+    node: &SyntaxNode,
+) -> Option<(DeadCodeItem, ast::Name, &'static str)> {
+    let db = sema.db;
+    match_ast! {
+        match node {
+            ast::Fn(it) => {
+                let func = sema.to_def(&it)?;
+                let kind = match func.as_assoc_item(db) {
+                    Some(_) if func.has_self_param(db) => "method",
+                    Some(_) => "associated function",
+                    None => "function",
+                };
+                Some((func.into(), it.name()?, kind))
+            },
+            ast::Const(it) => {
+                let konst = sema.to_def(&it)?;
+                let kind = if konst.as_assoc_item(db).is_some() { "associated constant" } else { "constant" };
+                Some((konst.into(), it.name()?, kind))
+            },
+            ast::Static(it) => Some((sema.to_def(&it)?.into(), it.name()?, "static")),
+            ast::RecordField(it) => Some((sema.to_def(&it)?.into(), it.name()?, "field")),
+            ast::Variant(it) => Some((sema.to_def(&it)?.into(), it.name()?, "variant")),
+            _ => None,
+        }
+    }
+}
+
+/// Finds the items whose definitions use `item`, or `None` if a use keeps it alive on its own.
+fn users(sema: &Semantics<'_, RootDatabase>, item: DeadCodeItem) -> Option<Vec<DeadCodeItem>> {
+    let def = match item {
+        DeadCodeItem::Function(it) => Definition::Function(it),
+        DeadCodeItem::Const(it) => Definition::Const(it),
+        DeadCodeItem::Static(it) => Definition::Static(it),
+        DeadCodeItem::Field(it) => Definition::Field(it),
+        DeadCodeItem::Variant(it) => Definition::Variant(it),
+    };
+    let mut users = Vec::new();
+    for reference in def.usages(sema).all().references.into_values().flatten() {
+        let parent = reference.name.syntax().parent()?;
+        if reference.category.contains(ReferenceCategory::IMPORT) {
+            // Only re-exports make an item usable, other imports count through their own uses.
+            match parent.ancestors().find_map(ast::Use::cast) {
+                Some(use_) if use_.visibility().is_none() => continue,
+                _ => return None,
+            }
+        }
+        let (user, ..) =
+            sema.ancestors_with_macros(parent).find_map(|node| candidate(sema, &node))?;
+        users.push(user);
+    }
+    Some(users)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DiagnosticsConfig, tests::check_diagnostics_with_config};
+
+    fn config() -> DiagnosticsConfig {
+        let mut config = DiagnosticsConfig::test_sample();
+        config.dead_code = true;
+        config.disabled.insert("inactive-code".to_owned());
+        config.disabled.insert("unconditional_recursion".to_owned());
+        config
+    }
+
+    #[test]
+    fn unused_private_items() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+  fn unused() {}
+   //^^^^^^ warn: function `unused` is never used
+  const UNUSED: u32 = 0;
+      //^^^^^^ warn: constant `UNUSED` is never used
+  static UNUSED_STATIC: u32 = 0;
+       //^^^^^^^^^^^^^ warn: static `UNUSED_STATIC` is never used
+  fn _ignored() {}
+
+  fn used() {}
+
+  pub fn exported() {
+      used();
+  }
+"#,
+        );
+    }
+
+    #[test]
+    fn unused_fields_and_variants() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+struct S {
+    used: u32,
+    unused: u32,
+  //^^^^^^ warn: field `unused` is never used
+}
+
+enum E {
+    Used,
+    Unused,
+  //^^^^^^ warn: variant `Unused` is never used
+}
+
+pub fn f(s: S) -> E {
+    let _ = s.used;
+    E::Used
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unused_methods() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+pub struct S;
+
+impl S {
+    fn method(&self) {}
+     //^^^^^^ warn: method `method` is never used
+    fn new() -> S { S }
+     //^^^ warn: associated function `new` is never used
+    const C: u32 = 0;
+        //^ warn: associated constant `C` is never used
+}
+
+trait Tr {
+    fn required(&self);
+}
+
+impl Tr for S {
+    fn required(&self) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn transitively_unused() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+  fn a() { b() }
+   //^ warn: function `a` is never used
+  fn b() { a() }
+   //^ warn: function `b` is never used
+  fn recursive() { recursive() }
+   //^^^^^^^^^ warn: function `recursive` is never used
+
+  fn helper() {}
+  fn from_main() { helper() }
+
+  fn main() {
+      from_main();
+  }
+"#,
+        );
+    }
+
+    #[test]
+    fn roots() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+//- minicore: test
+#[test]
+fn test() {
+    tested();
+}
+fn tested() {}
+
+#[no_mangle]
+fn exported_symbol() {}
+
+mod inner {
+    pub(crate) fn reexported() {}
+}
+pub use inner::reexported;
+
+mod imported {
+    pub(crate) fn only_imported() {}
+  //              ^^^^^^^^^^^^^ warn: function `only_imported` is never used
+}
+use imported::only_imported;
+"#,
+        );
+    }
+
+    #[test]
+    fn respects_allow_and_cfg() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+#[allow(dead_code)]
+fn allowed() {}
+
+#[expect(dead_code)]
+fn expected() {}
+
+#[allow(unused)]
+mod m {
+    fn allowed_by_group() {}
+}
+
+#[cfg(never)]
+fn cfged_out() {}
+"#,
+        );
+    }
+}
@@ -0,0 +1,245 @@
+use hir::{AsAssocItem, InFile, Name, PathResolution};
+use ide_db::{EditionedFileId, FileRange, defs::Definition};
+use syntax::{
+    AstNode, SyntaxNode, SyntaxNodePtr,
+    ast::{self, HasAttrs},
+};
+
+use crate::{Diagnostic, DiagnosticCode, DiagnosticsContext};
+
+// Diagnostic: deprecated
+//
+// This diagnostic is triggered when an item marked `#[deprecated]` is used, either directly or
+// through its parent, like the fields of a deprecated struct or the methods of a deprecated trait,
+// or when it's used through a `use` item marked `#[deprecated]`.
+pub(crate) fn deprecated(
+    ctx: &DiagnosticsContext<'_>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: EditionedFileId,
+    name_refs: &[(ast::NameRef, Definition)],
+) {
+    let db = ctx.sema.db;
+
+    for (name_ref, def) in name_refs {
+        let Some(kind) = kind(ctx, *def) else { continue };
+        let (name, deprecation) = match def.deprecation(db) {
+            Some(deprecation) => match def.name(db) {
+                Some(name) => (name, deprecation),
+                None => continue,
+            },
+            None => {
+                let name = Name::new_root(name_ref.text().as_str());
+                match import_deprecation(ctx, name_ref, &name) {
+                    Some(deprecation) => (name, deprecation),
+                    None => continue,
+                }
+            }
+        };
+        if is_in_deprecated_item(name_ref.syntax()) {
+            continue;
+        }
+
+        let mut message = format!("use of deprecated {kind} `{}`", name.display(db, ctx.edition));
+        if let Some(note) = &deprecation.note {
+            message.push_str(": ");
+            message.push_str(note.as_str());
+        }
+        if let Some(since) = &deprecation.since {
+            message.push_str(&format!("\ndeprecated since {}\n", since.as_str()));
+        }
+        acc.push(
+            Diagnostic::new(
+                DiagnosticCode::RustcLint("deprecated"),
+                message,
+                FileRange { file_id: file_id.file_id(db), range: name_ref.syntax().text_range() },
+            )
+            .with_deprecated(true)
+            .with_main_node(InFile::new(file_id.into(), SyntaxNodePtr::new(name_ref.syntax()))),
+        );
+    }
+}
+
+/// The deprecation of the `use` item `name_ref` is resolved through, for an item re-exported by a
+/// `#[deprecated] pub use`.
+fn import_deprecation<'db>(
+    ctx: &DiagnosticsContext<'db>,
+    name_ref: &ast::NameRef,
+    name: &Name,
+) -> Option<&'db hir::Deprecation> {
+    let db = ctx.sema.db;
+    let segment = name_ref.syntax().parent().and_then(ast::PathSegment::cast)?;
+    if let Some(qualifier) = segment.parent_path().qualifier() {
+        return match ctx.sema.resolve_path(&qualifier)? {
+            PathResolution::Def(hir::ModuleDef::Module(module)) => {
+                module.import_deprecation(db, name)
+            }
+            _ => None,
+        };
+    }
+    // An unqualified name can come from the scope of an enclosing block or module.
+    let mut module = ctx.sema.scope(name_ref.syntax())?.module();
+    loop {
+        if let Some(deprecation) = module.import_deprecation(db, name) {
+            return Some(deprecation);
+        }
+        if module == module.nearest_non_block_module(db) {
+            return None;
+        }
+        module = module.parent(db)?;
+    }
+}
+
+fn kind(ctx: &DiagnosticsContext<'_>, def: Definition) -> Option<&'static str> {
+    let db = ctx.sema.db;
+    Some(match def {
+        Definition::Macro(_) => "macro",
+        Definition::Field(_) => "field",
+        Definition::Module(_) => "module",
+        Definition::Function(it) => match it.as_assoc_item(db) {
+            Some(_) if it.has_self_param(db) => "method",
+            Some(_) => "associated function",
+            None => "function",
+        },
+        Definition::Adt(hir::Adt::Struct(_)) => "struct",
+        Definition::Adt(hir::Adt::Enum(_)) => "enum",
+        Definition::Adt(hir::Adt::Union(_)) => "union",
+        Definition::Variant(_) => "variant",
+        Definition::Const(it) if it.as_assoc_item(db).is_some() => "associated constant",
+        Definition::Const(_) => "constant",
+        Definition::Static(_) => "static",
+        Definition::Trait(_) => "trait",
+        Definition::TypeAlias(it) if it.as_assoc_item(db).is_some() => "associated type",
+        Definition::TypeAlias(_) => "type alias",
+        Definition::ExternCrateDecl(_) => "crate",
+        _ => return None,
+    })
+}
+
+/// Uses of deprecated items inside other deprecated items are not reported.
+fn is_in_deprecated_item(node: &SyntaxNode) -> bool {
+    node.ancestors().filter_map(ast::AnyHasAttrs::cast).any(|it| {
+        it.attrs().any(|attr| attr.simple_name().is_some_and(|name| name == "deprecated"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn deprecated_items() {
+        check_diagnostics(
+            r#"
+#[deprecated]
+fn old() {}
+#[deprecated(since = "1.2.0", note = "use `new` instead")]
+fn older() {}
+#[deprecated = "gone"]
+struct Gone;
+
+fn f() {
+    old();
+  //^^^ warn: use of deprecated function `old`
+    older();
+  //^^^^^ warn: use of deprecated function `older`: use `new` instead
+  //    | deprecated since 1.2.0
+    let _ = Gone;
+          //^^^^ warn: use of deprecated struct `Gone`: gone
+}
+
+#[deprecated]
+fn wrapper() {
+    old();
+}
+
+#[allow(deprecated)]
+fn allowed() {
+    old();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn inherited_deprecation() {
+        check_diagnostics(
+            r#"
+#[deprecated]
+enum Old { A }
+struct S {
+    #[deprecated = "use `b`"]
+    a: u32,
+}
+impl S {
+    #[deprecated]
+    fn get(&self) {}
+}
+
+fn f(s: S) {
+    let _ = s.a;
+            //^ warn: use of deprecated field `a`: use `b`
+    s.get();
+    //^^^ warn: use of deprecated method `get`
+    let _ = Old::A;
+          //^^^ warn: use of deprecated enum `Old`
+               //^ warn: use of deprecated variant `A`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn deprecated_through_reexport() {
+        check_diagnostics(
+            r#"
+mod m {
+    #[deprecated(note = "use `Fresh`")]
+    pub struct Stale;
+}
+pub use m::Stale as Renamed;
+         //^^^^^ warn: use of deprecated struct `Stale`: use `Fresh`
+
+fn f() {
+    let _ = Renamed;
+          //^^^^^^^ warn: use of deprecated struct `Stale`: use `Fresh`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn deprecated_reexport() {
+        check_diagnostics(
+            r#"
+mod m {
+    pub struct Fresh;
+    pub fn make() {}
+}
+#[deprecated = "use `m::Fresh`"]
+pub use m::Fresh as Stale;
+#[deprecated]
+pub use m::make;
+
+mod n {
+    use crate::Stale;
+             //^^^^^ warn: use of deprecated struct `Stale`: use `m::Fresh`
+}
+
+fn f() {
+    let _ = Stale;
+          //^^^^^ warn: use of deprecated struct `Stale`: use `m::Fresh`
+    let _ = crate::Stale;
+                 //^^^^^ warn: use of deprecated struct `Stale`: use `m::Fresh`
+    let _ = m::Fresh;
+    make();
+  //^^^^ warn: use of deprecated function `make`
+    {
+        fn inner() {}
+        make();
+      //^^^^ warn: use of deprecated function `make`
+    }
+}
+"#,
+        );
+    }
+}
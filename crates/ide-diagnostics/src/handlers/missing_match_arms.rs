@@ -390,7 +390,7 @@ fn main() {
 
     #[test]
     fn expr_diverges() {
-        check_diagnostics(
+        check_diagnostics_with_disabled(
             r#"
 enum Either { A, B }
 
@@ -413,6 +413,7 @@ fn main() {
     }
 }
 "#,
+            &["unreachable_code"],
         );
     }
 
@@ -812,10 +813,12 @@ fn main() {
     match Foo::A {
         ref _x => {}
         Foo::A => {}
+      //^^^^^^ 💡 warn: unreachable pattern
     }
     match (true,) {
         (ref _x,) => {}
         (true,) => {}
+      //^^^^^^^ 💡 warn: unreachable pattern
     }
 }
 "#,
@@ -917,6 +920,7 @@ fn main() {
     match &Foo::A(true) {
         _ => {}
         Foo::A(_) => {}
+      //^^^^^^^^^ 💡 warn: unreachable pattern
     }
 }
 "#,
@@ -1087,6 +1091,7 @@ fn test() {
     match Result::<u8, Void>::Ok(2) { Ok(_) => () }
     match (2, loop {}) {}
     match Result::<!, !>::Ok(loop {}) {}
+  //^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ 💡 warn: unreachable statement
     match (&loop {}) {} // https://github.com/rust-lang/rust/issues/50642#issuecomment-388234919
     //    ^^^^^^^^^^ error: missing match arm: type `&!` is non-empty
 }",
@@ -433,6 +433,7 @@ fn main() {
 fn main() {
     return;
     let mut x = 2;
+  //^^^^^^^^^^^^^^ 💡 warn: unreachable statement
       //^^^^^ 💡 warn: unused variable
     &mut x;
 }
@@ -443,6 +444,7 @@ fn main() {
 fn main() {
     loop {}
     let mut x = 2;
+  //^^^^^^^^^^^^^^ 💡 warn: unreachable statement
       //^^^^^ 💡 warn: unused variable
     &mut x;
 }
@@ -479,6 +481,7 @@ fn main(b: bool) {
         return;
     }
     let mut x = 2;
+  //^^^^^^^^^^^^^^ 💡 warn: unreachable statement
       //^^^^^ 💡 warn: unused variable
     &mut x;
 }
@@ -627,6 +630,8 @@ fn main() {
         check_diagnostics(
             r#"
 fn f(mut x: i32) {
+ //^ warn: function cannot return without recursing
+ //| recursive call site: `f(x + 2)`
    //^^^^^ 💡 warn: variable does not need to be mutable
    f(x + 2);
 }
@@ -1223,6 +1228,7 @@ fn main2() {
 fn f() {
     loop {}
     for _ in 0..2 {}
+  //^^^^^^^^^^^^^^^^ 💡 warn: unreachable expression
 }
 "#,
         );
@@ -1185,6 +1185,7 @@ fn f() -> i32 {
     return;
  // ^^^^^^ error: expected i32, found ()
     0
+  //^ 💡 warn: unreachable expression
 }
 fn g() { return; }
 "#,
@@ -1201,6 +1202,7 @@ fn f() {
         return;
      // ^^^^^^ error: expected i32, found ()
         0
+      //^ 💡 warn: unreachable expression
     }
 }
 "#,
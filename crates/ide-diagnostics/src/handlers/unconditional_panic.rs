@@ -0,0 +1,71 @@
+use hir::UnconditionalPanicKind;
+
+use crate::{Diagnostic, DiagnosticCode, DiagnosticsContext};
+
+// Diagnostic: unconditional-panic
+//
+// This diagnostic is triggered when an operation always panics, like a division of a literal by
+// zero or an index into an array that is out of bounds.
+pub(crate) fn unconditional_panic(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::UnconditionalPanic,
+) -> Diagnostic {
+    let note = match d.kind {
+        UnconditionalPanicKind::DivisionByZero => "attempt to divide by zero".to_owned(),
+        UnconditionalPanicKind::RemainderByZero => {
+            "attempt to calculate the remainder with a divisor of zero".to_owned()
+        }
+        UnconditionalPanicKind::IndexOutOfBounds { len, index } => {
+            format!("index out of bounds: the length is {len} but the index is {index}")
+        }
+    };
+    Diagnostic::new_with_syntax_node_ptr(
+        ctx,
+        DiagnosticCode::RustcLint("unconditional_panic"),
+        format!("this operation will panic at runtime\n{note}\n"),
+        d.expr.map(Into::into),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn division_by_zero() {
+        check_diagnostics(
+            r#"
+fn f() {
+    let _ = 1 / 0;
+          //^^^^^ error: this operation will panic at runtime
+          //    | attempt to divide by zero
+    let _ = 1 % 0;
+          //^^^^^ error: this operation will panic at runtime
+          //    | attempt to calculate the remainder with a divisor of zero
+    let _ = 1 / 1;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn index_out_of_bounds() {
+        check_diagnostics(
+            r#"
+//- minicore: index, slice
+const A: [i32; 3] = [1, 2, 3];
+
+fn f(b: &[i32; 2]) {
+    let _ = A[5];
+          //^^^^ error: this operation will panic at runtime
+          //   | index out of bounds: the length is 3 but the index is 5
+    let _ = b[2];
+          //^^^^ error: this operation will panic at runtime
+          //   | index out of bounds: the length is 2 but the index is 2
+    let _ = A[2];
+    let _ = b[1];
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use syntax::{AstNode, ast::HasName};
+
+use crate::{Diagnostic, DiagnosticCode, DiagnosticsContext, adjusted_display_range};
+
+// Diagnostic: unconditional-recursion
+//
+// This diagnostic is triggered when a function cannot return without calling itself, for example
+// a `PartialEq` impl that compares `self` with `==`.
+pub(crate) fn unconditional_recursion(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::UnconditionalRecursion,
+) -> Diagnostic {
+    let mut message = "function cannot return without recursing\n".to_owned();
+    for call_site in &d.call_sites {
+        let root = ctx.sema.parse_or_expand(call_site.file_id);
+        let text = call_site.value.to_node(&root).text().to_string();
+        let text = match text.split_once('\n') {
+            Some((first_line, _)) => format!("{} …", first_line.trim_end()),
+            None => text,
+        };
+        message.push_str(&format!("recursive call site: `{text}`\n"));
+    }
+    let display_range =
+        adjusted_display_range(ctx, d.func, &|func| Some(func.name()?.syntax().text_range()));
+    Diagnostic::new(DiagnosticCode::RustcLint("unconditional_recursion"), message, display_range)
+        .with_main_node(d.func.map(Into::into))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn direct_recursion() {
+        check_diagnostics(
+            r#"
+fn f(n: u32) -> u32 {
+ //^ warn: function cannot return without recursing
+ //| recursive call site: `f(n - 1)`
+    f(n - 1) + 1
+}
+
+fn g(n: u32) -> u32 {
+    if n == 0 { 0 } else { g(n - 1) }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn recursion_on_every_branch() {
+        check_diagnostics(
+            r#"
+fn f(n: u32) {
+ //^ warn: function cannot return without recursing
+ //| recursive call site: `f(n + 1)`
+ //| recursive call site: `f(n - 1)`
+    if n == 0 { f(n + 1) } else { f(n - 1) }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn recursion_through_trait_method() {
+        check_diagnostics(
+            r#"
+//- minicore: eq
+struct S;
+impl PartialEq for S {
+    fn eq(&self, other: &Self) -> bool {
+     //^^ warn: function cannot return without recursing
+     // | recursive call site: `*self == *other`
+        *self == *other
+    }
+}
+
+struct T;
+impl PartialEq for T {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn recursion_through_deref() {
+        check_diagnostics(
+            r#"
+//- minicore: deref
+struct S(u32);
+impl core::ops::Deref for S {
+    type Target = u32;
+    fn deref(&self) -> &u32 {
+     //^^^^^ warn: function cannot return without recursing
+     //    | recursive call site: `**self`
+        &**self
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn loops_and_diverging_paths() {
+        check_diagnostics(
+            r#"
+fn f() {
+    loop {}
+}
+
+fn g(n: u32) {
+    loop {
+        if n == 0 {
+            return;
+        }
+        g(n);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn allowed() {
+        check_diagnostics(
+            r#"
+#[allow(unconditional_recursion)]
+fn f() {
+    f()
+}
+"#,
+        );
+    }
+}
@@ -17,11 +17,11 @@ pub(crate) fn undeclared_label(
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::check_diagnostics;
+    use crate::tests::{check_diagnostics, check_diagnostics_with_disabled};
 
     #[test]
     fn smoke_test() {
-        check_diagnostics(
+        check_diagnostics_with_disabled(
             r#"
 fn foo() {
     break 'a;
@@ -32,6 +32,7 @@ fn foo() {
            //^^ error: use of undeclared label `'a`
 }
 "#,
+            &["unreachable_code"],
         );
     }
 
@@ -58,7 +59,7 @@ fn foo() {
 
     #[test]
     fn for_loop() {
-        check_diagnostics(
+        check_diagnostics_with_disabled(
             r#"
 //- minicore: iterator
 fn foo() {
@@ -78,6 +79,7 @@ fn foo() {
     }
 }
 "#,
+            &["unreachable_code"],
         );
     }
 
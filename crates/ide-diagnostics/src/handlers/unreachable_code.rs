@@ -0,0 +1,166 @@
+use hir::db::ExpandDatabase;
+use ide_db::{FileRange, source_change::SourceChange, text_edit::TextEdit};
+use syntax::{AstNode, SyntaxKind, SyntaxNode, TextRange, ast};
+
+use crate::{Diagnostic, DiagnosticCode, DiagnosticsContext, fix};
+
+// Diagnostic: unreachable-code
+//
+// This diagnostic is triggered when a statement or expression follows one that diverges, e.g. a
+// `return`, a `panic!()`, an infinite `loop {}` or a call to a function returning `!`.
+pub(crate) fn unreachable_code(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::UnreachableCode,
+) -> Option<Diagnostic> {
+    if d.node.file_id.macro_file().is_some() {
+        // FIXME: Our infra can't handle allow from within macro expansions rn
+        return None;
+    }
+    let root = ctx.sema.db.parse_or_expand(d.node.file_id);
+    let node = d.node.value.to_node(&root);
+    let message = if ast::Stmt::can_cast(node.kind()) {
+        "unreachable statement"
+    } else {
+        "unreachable expression"
+    };
+    let FileRange { file_id, range } = ctx.sema.diagnostics_display_range(d.node);
+
+    // Removes everything from the first unreachable statement to the end of its block.
+    let mut delete_range = unreachable_range(&node);
+    if let Some(ws) = node.first_token().and_then(|it| it.prev_token())
+        && ws.kind() == SyntaxKind::WHITESPACE
+    {
+        delete_range = TextRange::new(ws.text_range().start(), delete_range.end());
+    }
+    let source_change = SourceChange::from_text_edit(file_id, TextEdit::delete(delete_range));
+
+    Some(
+        Diagnostic::new(
+            DiagnosticCode::RustcLint("unreachable_code"),
+            message,
+            FileRange { file_id, range },
+        )
+        .stable()
+        .with_unused(true)
+        .with_main_node(d.node)
+        .with_fixes(Some(vec![fix(
+            "remove_unreachable_code",
+            "Remove unreachable code",
+            source_change,
+            range,
+        )])),
+    )
+}
+
+/// Everything from `node` up to the end of its block is never executed.
+fn unreachable_range(node: &SyntaxNode) -> TextRange {
+    let end = node
+        .parent()
+        .and_then(ast::StmtList::cast)
+        .and_then(|stmt_list| {
+            stmt_list
+                .tail_expr()
+                .map(|it| it.syntax().clone())
+                .or_else(|| stmt_list.statements().last().map(|it| it.syntax().clone()))
+        })
+        .map_or(node.text_range().end(), |it| it.text_range().end());
+    TextRange::new(node.text_range().start(), end)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fix};
+
+    #[test]
+    fn after_return() {
+        check_diagnostics(
+            r#"
+fn f() -> u32 {
+    return 0;
+    f();
+  //^^^^ 💡 warn: unreachable statement
+    1
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn after_diverging_call() {
+        check_diagnostics(
+            r#"
+fn never() -> ! { loop {} }
+
+fn f() {
+    never();
+    f();
+  //^^^^ 💡 warn: unreachable statement
+}
+
+fn g() -> u32 {
+    loop {}
+    0
+  //^ 💡 warn: unreachable expression
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn after_panic() {
+        check_diagnostics(
+            r#"
+//- minicore: panic
+fn f() {
+    panic!();
+    f();
+  //^^^^ 💡 warn: unreachable statement
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn reachable() {
+        check_diagnostics(
+            r#"
+fn f(b: bool) -> u32 {
+    if b {
+        return 1;
+    }
+    fn item() {}
+    loop {
+        if b {
+            break;
+        }
+    }
+    0
+}
+
+#[allow(unreachable_code)]
+fn g() -> u32 {
+    return 1;
+    0
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_unreachable_code() {
+        check_fix(
+            r#"
+fn f() -> u32 {
+    return 0;
+    f$0();
+    1
+}
+"#,
+            r#"
+fn f() -> u32 {
+    return 0;
+}
+"#,
+        );
+    }
+}
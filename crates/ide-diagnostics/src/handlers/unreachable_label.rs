@@ -17,11 +17,11 @@ pub(crate) fn unreachable_label(
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::check_diagnostics;
+    use crate::tests::check_diagnostics_with_disabled;
 
     #[test]
     fn async_blocks_are_borders() {
-        check_diagnostics(
+        check_diagnostics_with_disabled(
             r#"
 fn foo() {
     'a: loop {
@@ -36,12 +36,13 @@ fn foo() {
     }
 }
 "#,
+            &["unreachable_code"],
         );
     }
 
     #[test]
     fn closures_are_borders() {
-        check_diagnostics(
+        check_diagnostics_with_disabled(
             r#"
 fn foo() {
     'a: loop {
@@ -56,12 +57,13 @@ fn foo() {
     }
 }
 "#,
+            &["unreachable_code"],
         );
     }
 
     #[test]
     fn blocks_pass_through() {
-        check_diagnostics(
+        check_diagnostics_with_disabled(
             r#"
 fn foo() {
     'a: loop {
@@ -72,12 +74,13 @@ fn foo() {
     }
 }
 "#,
+            &["unreachable_code"],
         );
     }
 
     #[test]
     fn try_blocks_pass_through() {
-        check_diagnostics(
+        check_diagnostics_with_disabled(
             r#"
 fn foo() {
     'a: loop {
@@ -88,6 +91,7 @@ fn foo() {
     }
 }
 "#,
+            &["unreachable_code"],
         );
     }
 }
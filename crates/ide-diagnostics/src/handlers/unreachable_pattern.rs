@@ -0,0 +1,123 @@
+use hir::db::ExpandDatabase;
+use ide_db::{assists::Assist, source_change::SourceChange, text_edit::TextEdit};
+use syntax::{AstNode, SyntaxKind, TextRange, ast};
+
+use crate::{Diagnostic, DiagnosticCode, DiagnosticsContext, fix};
+
+// Diagnostic: unreachable-pattern
+//
+// This diagnostic is triggered when a match arm can never be taken because the arms before it
+// already cover every value it matches.
+pub(crate) fn unreachable_pattern(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::UnreachablePattern,
+) -> Option<Diagnostic> {
+    if d.pat.file_id.macro_file().is_some() {
+        // FIXME: Our infra can't handle allow from within macro expansions rn
+        return None;
+    }
+    let node = d.pat.map(Into::into);
+    Some(
+        Diagnostic::new_with_syntax_node_ptr(
+            ctx,
+            DiagnosticCode::RustcLint("unreachable_patterns"),
+            "unreachable pattern",
+            node,
+        )
+        .stable()
+        .with_fixes(fixes(ctx, d)),
+    )
+}
+
+fn fixes(ctx: &DiagnosticsContext<'_>, d: &hir::UnreachablePattern) -> Option<Vec<Assist>> {
+    let root = ctx.sema.db.parse_or_expand(d.pat.file_id);
+    let pat = d.pat.value.to_node(&root);
+    let arm = pat.syntax().parent().and_then(ast::MatchArm::cast)?;
+    let file_id = ctx.sema.diagnostics_display_range(d.pat.map(Into::into)).file_id;
+
+    let mut range = arm.syntax().text_range();
+    if let Some(ws) = arm.syntax().prev_sibling_or_token()
+        && ws.kind() == SyntaxKind::WHITESPACE
+    {
+        range = TextRange::new(ws.text_range().start(), range.end());
+    }
+    let source_change = SourceChange::from_text_edit(file_id, TextEdit::delete(range));
+    Some(vec![fix(
+        "remove_unreachable_arm",
+        "Remove unreachable match arm",
+        source_change,
+        arm.syntax().text_range(),
+    )])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fix};
+
+    #[test]
+    fn covered_by_earlier_arms() {
+        check_diagnostics(
+            r#"
+enum E { A, B }
+
+fn f(e: E, x: u32) {
+    match e {
+        E::A => {}
+        E::B => {}
+        _ => {}
+      //^ 💡 warn: unreachable pattern
+    }
+    match x {
+        0 => {}
+        _ => {}
+        1 => {}
+      //^ 💡 warn: unreachable pattern
+    }
+    match (e, x) {
+        (_, 0) => {}
+        (E::A, _) if x > 1 => {}
+        (E::A | E::B, _) => {}
+        (E::B, 0) => {}
+      //^^^^^^^^^ 💡 warn: unreachable pattern
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn respects_allow() {
+        check_diagnostics(
+            r#"
+#[allow(unreachable_patterns)]
+fn f(x: u32) {
+    match x {
+        _ => {}
+        1 => {}
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_unreachable_arm() {
+        check_fix(
+            r#"
+fn f(x: u32) {
+    match x {
+        _ => {}
+        1$0 => {}
+    }
+}
+"#,
+            r#"
+fn f(x: u32) {
+    match x {
+        _ => {}
+    }
+}
+"#,
+        );
+    }
+}
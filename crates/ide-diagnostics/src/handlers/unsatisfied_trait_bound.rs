@@ -0,0 +1,352 @@
+use hir::{HasSource, HirDisplay, db::ExpandDatabase};
+use ide_db::{assists::Assist, helpers::is_editable_crate, source_change::SourceChange};
+use syntax::{
+    AstNode, SyntaxKind, SyntaxNode, TextRange, TextSize,
+    ast::{self, HasAttrs, HasGenericParams, edit::IndentLevel},
+};
+
+use crate::{Diagnostic, DiagnosticCode, DiagnosticsContext, adjusted_display_range, fix};
+
+/// Traits from the standard library that can be implemented with a built-in derive.
+const DERIVABLE_TRAITS: &[&str] =
+    &["Clone", "Copy", "Debug", "Default", "Eq", "Hash", "Ord", "PartialEq", "PartialOrd"];
+
+// Diagnostic: unsatisfied-trait-bound
+//
+// This diagnostic is triggered when a trait bound required by an expression is known to never hold.
+pub(crate) fn unsatisfied_trait_bound<'db>(
+    ctx: &DiagnosticsContext<'db>,
+    d: &hir::UnsatisfiedTraitBound<'db>,
+) -> Diagnostic {
+    let db = ctx.sema.db;
+    let mut message = format!(
+        "the trait bound `{}: {}` is not satisfied",
+        d.trait_ref.self_ty().display(db, ctx.display_target),
+        d.trait_ref.display(db, ctx.display_target),
+    );
+    if !d.required_by.is_empty() {
+        message.push('\n');
+        for required_by in d.required_by.iter().rev() {
+            message.push_str(&format!(
+                "required because of the impl of `{}` for `{}`\n",
+                required_by.display(db, ctx.display_target),
+                required_by.self_ty().display(db, ctx.display_target),
+            ));
+        }
+    }
+    let display_range = adjusted_display_range(ctx, d.expr_or_pat, &|node| {
+        Some(match node.left()? {
+            ast::Expr::MethodCallExpr(it) => it.name_ref()?.syntax().text_range(),
+            _ => return None,
+        })
+    });
+    Diagnostic::new(DiagnosticCode::RustcHardError("E0277"), message, display_range)
+        .with_fixes(fixes(ctx, d))
+}
+
+fn fixes<'db>(
+    ctx: &DiagnosticsContext<'db>,
+    d: &hir::UnsatisfiedTraitBound<'db>,
+) -> Option<Vec<Assist>> {
+    let db = ctx.sema.db;
+    let root = db.parse_or_expand(d.expr_or_pat.file_id);
+    let node = d.expr_or_pat.value.to_node(&root);
+    let target = d.expr_or_pat.with_value(node.syntax()).original_file_range_opt(db)?.0.range;
+
+    let self_ty = d.trait_ref.self_ty().to_type(db);
+    let mut fixes = Vec::new();
+    if let Some(param) = self_ty.as_type_param(db) {
+        fixes.extend(add_where_bound_fix(ctx, d, param, target));
+    }
+    if let Some(adt) = self_ty.as_adt() {
+        fixes.extend(derive_fix(ctx, d, adt, target));
+    }
+    if fixes.is_empty() { None } else { Some(fixes) }
+}
+
+fn add_where_bound_fix<'db>(
+    ctx: &DiagnosticsContext<'db>,
+    d: &hir::UnsatisfiedTraitBound<'db>,
+    param: hir::TypeParam,
+    target: TextRange,
+) -> Option<Assist> {
+    let db = ctx.sema.db;
+    if param.is_implicit(db) {
+        return None;
+    }
+    let source = param.merge().source(db)?;
+    let file_id = source.file_id.file_id()?.file_id(db);
+    let param_node = source.value.left()?;
+    let (where_clause, anchor) = match param.parent(db) {
+        hir::GenericDef::Function(_) => {
+            let func = param_node.syntax().ancestors().find_map(ast::Fn::cast)?;
+            let anchor = match func.ret_type() {
+                Some(ret_type) => ret_type.syntax().clone(),
+                None => func.param_list()?.syntax().clone(),
+            };
+            (func.where_clause(), anchor)
+        }
+        hir::GenericDef::Impl(_) => {
+            let impl_ = param_node.syntax().ancestors().find_map(ast::Impl::cast)?;
+            (impl_.where_clause(), impl_.self_ty()?.syntax().clone())
+        }
+        _ => return None,
+    };
+
+    let module = param.module(db);
+    let bound = format!(
+        "{}: {}",
+        param.name(db).display(db, ctx.edition),
+        d.trait_ref.display_source_code(db, module.into(), true).ok()?,
+    );
+    let (offset, text) = match where_clause {
+        Some(where_clause) => match where_clause.predicates().last() {
+            Some(_) if where_clause.syntax().text().to_string().trim_end().ends_with(',') => {
+                (where_clause.syntax().text_range().end(), format!(" {bound},"))
+            }
+            Some(pred) => (pred.syntax().text_range().end(), format!(", {bound}")),
+            None => (where_clause.syntax().text_range().end(), format!(" {bound}")),
+        },
+        None => (anchor.text_range().end(), format!(" where {bound}")),
+    };
+
+    Some(fix(
+        "add_where_bound",
+        &format!("Add `{bound}` bound"),
+        SourceChange::from_text_edit(file_id, ide_db::text_edit::TextEdit::insert(offset, text)),
+        target,
+    ))
+}
+
+fn derive_fix<'db>(
+    ctx: &DiagnosticsContext<'db>,
+    d: &hir::UnsatisfiedTraitBound<'db>,
+    adt: hir::Adt,
+    target: TextRange,
+) -> Option<Assist> {
+    let db = ctx.sema.db;
+    let trait_ = d.trait_ref.trait_();
+    let trait_name = trait_.name(db);
+    if !trait_.module(db).krate(db).origin(db).is_lang()
+        || !DERIVABLE_TRAITS.contains(&trait_name.as_str())
+        || !is_editable_crate(adt.module(db).krate(db), db)
+    {
+        return None;
+    }
+    let source = adt.source(db)?;
+    let file_id = source.file_id.file_id()?.file_id(db);
+    let adt_node = source.value;
+    let trait_name = trait_name.display(db, ctx.edition).to_string();
+
+    let existing_derive = adt_node.attrs().find_map(|attr| {
+        let (name, tt) = attr.as_simple_call()?;
+        (name == "derive").then_some(tt)
+    });
+    let (offset, text) = match existing_derive {
+        Some(tt) => {
+            let r_paren = tt.right_delimiter_token()?;
+            let has_args =
+                tt.syntax().children_with_tokens().any(|it| it.kind() == SyntaxKind::IDENT);
+            let text = if has_args { format!(", {trait_name}") } else { trait_name.clone() };
+            (r_paren.text_range().start(), text)
+        }
+        None => {
+            let indent = IndentLevel::from_node(adt_node.syntax());
+            (item_start(adt_node.syntax()), format!("#[derive({trait_name})]\n{indent}"))
+        }
+    };
+
+    Some(fix(
+        "derive_trait",
+        &format!("Derive `{trait_name}`"),
+        SourceChange::from_text_edit(file_id, ide_db::text_edit::TextEdit::insert(offset, text)),
+        target,
+    ))
+}
+
+/// Returns the offset right after the item's attributes and doc comments.
+fn item_start(item: &SyntaxNode) -> TextSize {
+    item.children_with_tokens()
+        .find(|it| {
+            !matches!(it.kind(), SyntaxKind::ATTR | SyntaxKind::COMMENT | SyntaxKind::WHITESPACE)
+        })
+        .map_or_else(|| item.text_range().start(), |it| it.text_range().start())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fix};
+
+    #[test]
+    fn unsatisfied_bound_on_call() {
+        check_diagnostics(
+            r#"
+//- minicore: fmt
+struct Foo;
+fn print<T: core::fmt::Debug>(_: T) {}
+
+fn f() {
+    print(Foo);
+  //^^^^^ 💡 error: the trait bound `Foo: Debug` is not satisfied
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unsatisfied_bound_on_method_call() {
+        check_diagnostics(
+            r#"
+//- minicore: clone
+struct Foo;
+struct S;
+impl S {
+    fn dup<T: Clone>(&self, _: T) {}
+}
+
+fn f() {
+    S.dup(Foo);
+    //^^^ 💡 error: the trait bound `Foo: Clone` is not satisfied
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn required_by_impl() {
+        check_diagnostics(
+            r#"
+//- minicore: clone
+struct Foo;
+struct Wrapper<T>(T);
+impl<T: Clone> Clone for Wrapper<T> {
+    fn clone(&self) -> Self { loop {} }
+}
+fn dup<T: Clone>(_: T) {}
+
+fn f() {
+    dup(Wrapper(Foo));
+  //^^^ 💡 error: the trait bound `Foo: Clone` is not satisfied
+  //  | required because of the impl of `Clone` for `Wrapper<Foo>`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn satisfied_bounds() {
+        check_diagnostics(
+            r#"
+//- minicore: clone, derive
+#[derive(Clone)]
+struct Foo;
+fn dup<T: Clone>(_: T) {}
+fn generic<T: Clone>(t: T) {
+    dup(t);
+}
+
+fn f() {
+    dup(Foo);
+    generic(Foo);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_where_bound() {
+        check_fix(
+            r#"
+//- minicore: clone
+fn dup<T: Clone>(_: T) {}
+fn f<T>(t: T) {
+    $0dup(t);
+}
+"#,
+            r#"
+fn dup<T: Clone>(_: T) {}
+fn f<T>(t: T) where T: Clone {
+    dup(t);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extend_where_clause() {
+        check_fix(
+            r#"
+//- minicore: clone, copy
+fn dup<T: Clone>(_: T) {}
+fn f<T, U>(t: T) -> U
+where
+    U: Copy,
+{
+    $0dup(t);
+    loop {}
+}
+"#,
+            r#"
+fn dup<T: Clone>(_: T) {}
+fn f<T, U>(t: T) -> U
+where
+    U: Copy, T: Clone,
+{
+    dup(t);
+    loop {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn derive_trait() {
+        check_fix(
+            r#"
+//- minicore: clone, derive
+/// Docs.
+struct Foo;
+fn dup<T: Clone>(_: T) {}
+
+fn f() {
+    $0dup(Foo);
+}
+"#,
+            r#"
+/// Docs.
+#[derive(Clone)]
+struct Foo;
+fn dup<T: Clone>(_: T) {}
+
+fn f() {
+    dup(Foo);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn extend_existing_derive() {
+        check_fix(
+            r#"
+//- minicore: clone, derive, fmt
+#[derive(Debug)]
+struct Foo;
+fn dup<T: Clone>(_: T) {}
+
+fn f() {
+    $0dup(Foo);
+}
+"#,
+            r#"
+#[derive(Debug, Clone)]
+struct Foo;
+fn dup<T: Clone>(_: T) {}
+
+fn f() {
+    dup(Foo);
+}
+"#,
+        );
+    }
+}
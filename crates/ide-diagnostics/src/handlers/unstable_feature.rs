@@ -0,0 +1,301 @@
+use hir::{InFile, Symbol};
+use ide_db::{
+    EditionedFileId, FileRange, assists::Assist, defs::Definition, source_change::SourceChange,
+    text_edit::TextEdit,
+};
+use syntax::{
+    AstNode, SyntaxNode, SyntaxNodePtr, SyntaxToken, TextRange, TextSize,
+    ast::{self, HasAttrs},
+    match_ast,
+};
+
+use crate::{Diagnostic, DiagnosticCode, DiagnosticsContext, fix};
+
+// Diagnostic: unstable-feature
+//
+// This diagnostic is triggered when an unstable item of the standard library or unstable syntax
+// is used without enabling its feature with `#![feature(...)]`.
+pub(crate) fn unstable_feature(
+    ctx: &DiagnosticsContext<'_>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: EditionedFileId,
+    name_refs: &[(ast::NameRef, Definition)],
+) {
+    if !ctx.config.feature_gates {
+        return;
+    }
+    let db = ctx.sema.db;
+    let Some(krate) = ctx.sema.file_to_module_def(file_id.file_id(db)).map(|it| it.krate(db))
+    else {
+        return;
+    };
+    let source_file = ctx.sema.parse(file_id);
+    let is_enabled = |features: &[&str]| {
+        features.iter().any(|it| krate.is_unstable_feature_enabled(db, &Symbol::intern(it)))
+    };
+
+    for (name_ref, def) in name_refs {
+        let Some(feature) = unstable_library_feature(ctx, krate, *def) else { continue };
+        if is_enabled(&[feature.as_str()]) {
+            continue;
+        }
+        acc.push(diagnostic(
+            ctx,
+            file_id,
+            krate,
+            name_ref.syntax(),
+            name_ref.syntax().text_range(),
+            feature.as_str(),
+            format!("use of unstable library feature `{}`", feature.as_str()),
+        ));
+    }
+    for node in source_file.syntax().descendants() {
+        let Some((token, features, description)) = gated_syntax(&node) else { continue };
+        if is_enabled(features) {
+            continue;
+        }
+        acc.push(diagnostic(
+            ctx,
+            file_id,
+            krate,
+            &node,
+            token.text_range(),
+            features[0],
+            description.to_owned(),
+        ));
+    }
+}
+
+fn diagnostic(
+    ctx: &DiagnosticsContext<'_>,
+    file_id: EditionedFileId,
+    krate: hir::Crate,
+    node: &SyntaxNode,
+    range: TextRange,
+    feature: &str,
+    message: String,
+) -> Diagnostic {
+    let fixes = if ctx.is_nightly { add_feature_fix(ctx, krate, feature, range) } else { None };
+    let range = FileRange { file_id: file_id.file_id(ctx.sema.db), range };
+    Diagnostic::new(DiagnosticCode::RustcHardError("E0658"), message, range)
+        .with_main_node(InFile::new(file_id.into(), SyntaxNodePtr::new(node)))
+        .with_fixes(fixes.map(|it| vec![it]))
+}
+
+/// Returns the feature of `def` if it's an unstable standard library item.
+fn unstable_library_feature(
+    ctx: &DiagnosticsContext<'_>,
+    krate: hir::Crate,
+    def: Definition,
+) -> Option<Symbol> {
+    let db = ctx.sema.db;
+    let def_krate = def.krate(db)?;
+    if def_krate == krate || !def_krate.origin(db).is_lang() {
+        return None;
+    }
+    def.attrs(db)?.unstable_feature(db).cloned()
+}
+
+/// Returns the keyword of unstable syntax, the features that enable it and a description of it.
+fn gated_syntax(node: &SyntaxNode) -> Option<(SyntaxToken, &'static [&'static str], &'static str)> {
+    const SPECIALIZATION: &[&str] = &["specialization", "min_specialization"];
+    match_ast! {
+        match node {
+            ast::BlockExpr(it) => match it.try_token() {
+                Some(token) => Some((token, &["try_blocks"], "`try` expression is experimental")),
+                None => Some((it.gen_token()?, &["gen_blocks"], "gen blocks are experimental")),
+            },
+            ast::BoxPat(it) => {
+                Some((it.box_token()?, &["box_patterns"], "box pattern syntax is experimental"))
+            },
+            ast::YieldExpr(it) => {
+                Some((it.yield_token()?, &["coroutines"], "yield syntax is experimental"))
+            },
+            ast::YeetExpr(it) => {
+                Some((it.do_token()?, &["yeet_expr"], "`do yeet` expression is experimental"))
+            },
+            ast::BecomeExpr(it) => Some((
+                it.become_token()?,
+                &["explicit_tail_calls"],
+                "`become` expression is experimental",
+            )),
+            ast::Trait(it) => Some((
+                it.auto_token()?,
+                &["auto_traits"],
+                "auto traits are experimental and possibly buggy",
+            )),
+            ast::Impl(it) => match it.excl_token() {
+                Some(token) => Some((
+                    token,
+                    &["negative_impls"],
+                    "negative trait bounds are not fully implemented; use marker types for now",
+                )),
+                None => Some((it.default_token()?, SPECIALIZATION, "specialization is unstable")),
+            },
+            ast::Fn(it) => Some((it.default_token()?, SPECIALIZATION, "specialization is unstable")),
+            ast::Const(it) => {
+                Some((it.default_token()?, SPECIALIZATION, "specialization is unstable"))
+            },
+            ast::TypeAlias(it) => {
+                Some((it.default_token()?, SPECIALIZATION, "specialization is unstable"))
+            },
+            ast::NeverType(it) => {
+                // `!` is stable as a return type.
+                if it.syntax().parent().is_some_and(|parent| ast::RetType::can_cast(parent.kind())) {
+                    return None;
+                }
+                Some((it.excl_token()?, &["never_type"], "the `!` type is experimental"))
+            },
+            _ => None,
+        }
+    }
+}
+
+fn add_feature_fix(
+    ctx: &DiagnosticsContext<'_>,
+    krate: hir::Crate,
+    feature: &str,
+    target: TextRange,
+) -> Option<Assist> {
+    let root_file_id = krate.root_file(ctx.sema.db);
+    let root = ctx.sema.parse(ctx.sema.attach_first_edition(root_file_id));
+    let inner_attrs: Vec<_> = root.attrs().filter(|attr| attr.excl_token().is_some()).collect();
+    let existing = inner_attrs.iter().find_map(|attr| {
+        let (name, tt) = attr.as_simple_call()?;
+        (name == "feature").then_some(tt)
+    });
+    let edit = match (existing, inner_attrs.last()) {
+        (Some(tt), _) => TextEdit::insert(
+            tt.right_delimiter_token()?.text_range().start(),
+            format!(", {feature}"),
+        ),
+        (None, Some(last)) => {
+            TextEdit::insert(last.syntax().text_range().end(), format!("\n#![feature({feature})]"))
+        }
+        (None, None) => TextEdit::insert(TextSize::new(0), format!("#![feature({feature})]\n")),
+    };
+    Some(fix(
+        "add_feature_attribute",
+        &format!("Add `#![feature({feature})]` to the crate root"),
+        SourceChange::from_text_edit(root_file_id, edit),
+        target,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        DiagnosticsConfig,
+        tests::{check_diagnostics_with_config, check_nth_fix_with_config},
+    };
+
+    fn config() -> DiagnosticsConfig {
+        let mut config = DiagnosticsConfig::test_sample();
+        config.feature_gates = true;
+        config.disabled.insert("inactive-code".to_owned());
+        config
+    }
+
+    #[test]
+    fn unstable_library_item() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+//- /main.rs crate:main deps:core
+fn f() {
+    core::hint::spin();
+              //^^^^ error: use of unstable library feature `spin_hint`
+    core::hint::stable();
+}
+//- /core.rs crate:core
+pub mod hint {
+    #[unstable(feature = "spin_hint", issue = "none")]
+    pub fn spin() {}
+    pub fn stable() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn enabled_library_feature() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+//- /main.rs crate:main deps:core
+#![feature(spin_hint)]
+fn f() {
+    core::hint::spin();
+}
+//- /core.rs crate:core
+pub mod hint {
+    #[unstable(feature = "spin_hint", issue = "none")]
+    pub fn spin() {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unstable_syntax() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+  auto trait Marker {}
+//^^^^ error: auto traits are experimental and possibly buggy
+struct S;
+impl !Marker for S {}
+   //^ error: negative trait bounds are not fully implemented; use marker types for now
+fn g(_: !) {}
+      //^ error: the `!` type is experimental
+fn h() -> ! { loop {} }
+"#,
+        );
+    }
+
+    #[test]
+    fn enabled_syntax_feature() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+#![feature(never_type)]
+fn g(_: !) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_feature_on_nightly() {
+        check_nth_fix_with_config(
+            config(),
+            0,
+            r#"
+//- toolchain:nightly
+#![allow(unused)]
+fn g(_: $0!) {}
+"#,
+            r#"
+#![allow(unused)]
+#![feature(never_type)]
+fn g(_: !) {}
+"#,
+        );
+    }
+
+    #[test]
+    fn extend_feature_attribute() {
+        check_nth_fix_with_config(
+            config(),
+            0,
+            r#"
+//- toolchain:nightly
+#![feature(never_type)]
+$0auto trait Marker {}
+"#,
+            r#"
+#![feature(never_type, auto_traits)]
+auto trait Marker {}
+"#,
+        );
+    }
+}
@@ -0,0 +1,365 @@
+use hir::{InFile, Semantics};
+use ide_db::{
+    EditionedFileId, FileRange, RootDatabase,
+    imports::unused_imports::unused_use_trees,
+    source_change::{SourceChange, SourceChangeBuilder},
+};
+use itertools::Itertools;
+use syntax::{
+    AstNode, SyntaxNode, SyntaxNodePtr, TextRange,
+    ast::{self, HasAttrs, HasVisibility},
+};
+
+use crate::{Diagnostic, DiagnosticCode, DiagnosticsContext, unresolved_fix};
+
+// Diagnostic: unused-imports
+//
+// This diagnostic is triggered when an import is not used by the module that contains it.
+pub(crate) fn unused_imports(
+    ctx: &DiagnosticsContext<'_>,
+    acc: &mut Vec<Diagnostic>,
+    file_id: EditionedFileId,
+) {
+    if !ctx.config.unused_imports {
+        return;
+    }
+    let db = ctx.sema.db;
+    let unused = unused_in_file(&ctx.sema, file_id);
+    if unused.is_empty() {
+        return;
+    }
+
+    // These are the same for every diagnostic, so only compute them once.
+    let mut remove_in_file = None;
+    let mut remove_in_crate = None;
+
+    for use_tree in &unused {
+        let range = diagnostic_range(use_tree);
+        let mut fixes = Vec::new();
+
+        let mut fix = unresolved_fix("remove_unused_import", "Remove unused import", range);
+        if ctx.resolve.should_resolve(&fix.id) {
+            let mut builder = SourceChangeBuilder::new(file_id.file_id(db));
+            remove_use_trees(&mut builder, [use_tree.clone()]);
+            fix.source_change = Some(builder.finish());
+        }
+        fixes.push(fix);
+
+        if unused.len() > 1 {
+            let mut fix = unresolved_fix(
+                "remove_unused_imports_in_file",
+                "Remove all unused imports in file",
+                range,
+            );
+            if ctx.resolve.should_resolve(&fix.id) {
+                fix.source_change = Some(
+                    remove_in_file
+                        .get_or_insert_with(|| {
+                            let mut builder = SourceChangeBuilder::new(file_id.file_id(db));
+                            remove_use_trees(&mut builder, unused.iter().cloned());
+                            builder.finish()
+                        })
+                        .clone(),
+                );
+            }
+            fixes.push(fix);
+        }
+
+        let mut fix = unresolved_fix(
+            "remove_unused_imports_in_crate",
+            "Remove all unused imports in crate",
+            range,
+        );
+        if ctx.resolve.should_resolve(&fix.id) {
+            fix.source_change = Some(
+                remove_in_crate
+                    .get_or_insert_with(|| remove_in_crate_of(ctx, file_id).unwrap_or_default())
+                    .clone(),
+            );
+        }
+        fixes.push(fix);
+
+        acc.push(
+            Diagnostic::new(
+                DiagnosticCode::RustcLint("unused_imports"),
+                format!("unused import: `{}`", use_tree.syntax().text()),
+                FileRange { file_id: file_id.file_id(db), range },
+            )
+            .stable()
+            .with_unused(true)
+            .with_main_node(InFile::new(file_id.into(), SyntaxNodePtr::new(use_tree.syntax())))
+            .with_fixes(Some(fixes)),
+        );
+    }
+}
+
+/// Returns the unused imports of `file_id`, skipping re-exports and imports that are cfg'd out.
+fn unused_in_file(
+    sema: &Semantics<'_, RootDatabase>,
+    file_id: EditionedFileId,
+) -> Vec<ast::UseTree> {
+    let source_file = sema.parse(file_id);
+    let uses = source_file
+        .syntax()
+        .descendants()
+        .filter_map(ast::Use::cast)
+        .filter(|it| it.visibility().is_none() && !is_cfg_disabled(sema, it.syntax()))
+        .collect::<Vec<_>>();
+    unused_use_trees(sema, uses).collect()
+}
+
+fn is_cfg_disabled(sema: &Semantics<'_, RootDatabase>, node: &SyntaxNode) -> bool {
+    node.ancestors().filter_map(ast::AnyHasAttrs::cast).flat_map(|it| it.attrs()).any(|attr| {
+        matches!(
+            attr.as_simple_call(),
+            Some((name, tt)) if name == "cfg" && sema.check_cfg_attr(&tt) == Some(false)
+        )
+    })
+}
+
+/// Highlights the whole `use` item if nothing of it is used.
+fn diagnostic_range(use_tree: &ast::UseTree) -> TextRange {
+    match use_tree.syntax().parent().and_then(ast::Use::cast) {
+        Some(use_item) => use_item.syntax().text_range(),
+        None => use_tree.syntax().text_range(),
+    }
+}
+
+fn remove_in_crate_of(
+    ctx: &DiagnosticsContext<'_>,
+    file_id: EditionedFileId,
+) -> Option<SourceChange> {
+    let db = ctx.sema.db;
+    let krate = ctx.sema.file_to_module_def(file_id.file_id(db))?.krate(db);
+    let mut builder = SourceChangeBuilder::new(file_id.file_id(db));
+    for file_id in krate.modules(db).into_iter().filter_map(|it| it.as_source_file_id(db)).unique()
+    {
+        let unused = unused_in_file(&ctx.sema, file_id);
+        if unused.is_empty() {
+            continue;
+        }
+        builder.edit_file(file_id.file_id(db));
+        remove_use_trees(&mut builder, unused);
+    }
+    Some(builder.finish())
+}
+
+fn remove_use_trees(
+    builder: &mut SourceChangeBuilder,
+    use_trees: impl IntoIterator<Item = ast::UseTree>,
+) {
+    let use_trees = use_trees.into_iter().map(|it| builder.make_mut(it)).collect::<Vec<_>>();
+    for use_tree in use_trees {
+        use_tree.remove_recursive();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        DiagnosticsConfig,
+        tests::{check_diagnostics_with_config, check_nth_fix_with_config},
+    };
+
+    fn config() -> DiagnosticsConfig {
+        let mut config = DiagnosticsConfig::test_sample();
+        config.unused_imports = true;
+        config.disabled.insert("inactive-code".to_owned());
+        config
+    }
+
+    #[test]
+    fn unused_imports() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+mod foo {
+    pub struct Used;
+    pub struct Unused;
+    pub fn func() {}
+}
+
+  use foo::Used;
+  use foo::Unused;
+//^^^^^^^^^^^^^^^^ 💡 warn: unused import: `foo::Unused`
+  use foo::{func, Unused as Renamed};
+//                ^^^^^^^^^^^^^^^^^ 💡 warn: unused import: `Unused as Renamed`
+
+fn main() {
+    let _ = Used;
+    func();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unused_glob_import() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+mod foo {
+    pub struct S;
+}
+
+mod bar {
+    use super::foo::*;
+  //^^^^^^^^^^^^^^^^^^ 💡 warn: unused import: `super::foo::*`
+}
+
+mod baz {
+    use super::foo::*;
+
+    fn f(_: S) {}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn respects_allow_and_re_exports() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+mod foo {
+    pub struct S;
+}
+
+#[allow(unused_imports)]
+use foo::S;
+
+pub use foo::S as Reexported;
+
+#[allow(unused)]
+mod bar {
+    use super::foo::S;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn skips_cfged_out_imports() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+mod foo {
+    pub struct S;
+}
+
+#[cfg(never)]
+use foo::S;
+
+#[cfg(never)]
+mod bar {
+    use super::foo::S;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn trait_used_through_method() {
+        check_diagnostics_with_config(
+            config(),
+            r#"
+mod foo {
+    pub trait Tr {
+        fn method(&self) {}
+    }
+    impl Tr for () {}
+}
+
+use foo::Tr;
+
+fn main() {
+    ().method();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_unused_import() {
+        check_nth_fix_with_config(
+            config(),
+            0,
+            r#"
+mod foo {
+    pub struct A;
+    pub struct B;
+}
+
+use foo::{A, B$0};
+
+fn main() {
+    let _ = A;
+}
+"#,
+            r#"
+mod foo {
+    pub struct A;
+    pub struct B;
+}
+
+use foo::A;
+
+fn main() {
+    let _ = A;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_unused_imports_in_file() {
+        check_nth_fix_with_config(
+            config(),
+            1,
+            r#"
+mod foo {
+    pub struct A;
+    pub struct B;
+}
+
+use foo::A;
+use foo::B$0;
+"#,
+            r#"
+mod foo {
+    pub struct A;
+    pub struct B;
+}
+
+"#,
+        );
+    }
+
+    #[test]
+    fn remove_unused_imports_in_crate() {
+        check_nth_fix_with_config(
+            config(),
+            2,
+            r#"
+mod foo {
+    pub struct A;
+}
+
+mod bar {
+    use crate::foo::A;
+}
+
+use foo::A$0;
+"#,
+            r#"
+mod foo {
+    pub struct A;
+}
+
+mod bar {
+}
+
+"#,
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use hir::{UnusedMustUseKind, db::ExpandDatabase};
+use ide_db::{
+    assists::Assist, famous_defs::FamousDefs, source_change::SourceChange, text_edit::TextEdit,
+};
+use syntax::{AstNode, ast};
+
+use crate::{Diagnostic, DiagnosticCode, DiagnosticsContext, fix};
+
+// Diagnostic: unused-must-use
+//
+// This diagnostic is triggered when the value of an expression statement is `#[must_use]`,
+// either because of its type or because of the function that returned it, or when it is
+// a future that is never awaited.
+pub(crate) fn unused_must_use(ctx: &DiagnosticsContext<'_>, d: &hir::UnusedMustUse) -> Diagnostic {
+    let db = ctx.sema.db;
+    let mut message = match d.kind {
+        UnusedMustUseKind::Type(adt) => {
+            format!("unused `{}` that must be used", adt.name(db).display(db, ctx.edition))
+        }
+        UnusedMustUseKind::Function(func) => format!(
+            "unused return value of `{}` that must be used",
+            func.name(db).display(db, ctx.edition)
+        ),
+        UnusedMustUseKind::Future => "unused future that must be awaited".to_owned(),
+    };
+    if let Some(reason) = &d.reason {
+        message.push('\n');
+        message.push_str(reason.as_str());
+        message.push('\n');
+    }
+    Diagnostic::new_with_syntax_node_ptr(
+        ctx,
+        DiagnosticCode::RustcLint("unused_must_use"),
+        message,
+        d.expr.map(Into::into),
+    )
+    .with_fixes(fixes(ctx, d))
+}
+
+fn fixes(ctx: &DiagnosticsContext<'_>, d: &hir::UnusedMustUse) -> Option<Vec<Assist>> {
+    if d.expr.file_id.is_macro() {
+        return None;
+    }
+    let file_id = d.expr.file_id.original_file(ctx.sema.db).file_id(ctx.sema.db);
+    let root = ctx.sema.db.parse_or_expand(d.expr.file_id);
+    let expr = d.expr.value.to_node(&root).left()?;
+    let range = expr.syntax().text_range();
+
+    let mut fixes = vec![fix(
+        "ignore_must_use_value",
+        "Assign the value to `_`",
+        SourceChange::from_text_edit(file_id, TextEdit::insert(range.start(), "let _ = ".into())),
+        range,
+    )];
+    match d.kind {
+        UnusedMustUseKind::Future if is_in_async_context(&expr) => fixes.push(fix(
+            "await_future",
+            "Add `.await`",
+            SourceChange::from_text_edit(file_id, TextEdit::insert(range.end(), ".await".into())),
+            range,
+        )),
+        UnusedMustUseKind::Type(_) | UnusedMustUseKind::Function(_)
+            if can_propagate(ctx, &expr) =>
+        {
+            fixes.push(fix(
+                "propagate_must_use_value",
+                "Propagate the value with `?`",
+                SourceChange::from_text_edit(file_id, TextEdit::insert(range.end(), "?".into())),
+                range,
+            ))
+        }
+        _ => {}
+    }
+    Some(fixes)
+}
+
+/// Whether `.await` can be used at `expr`.
+fn is_in_async_context(expr: &ast::Expr) -> bool {
+    for node in expr.syntax().ancestors() {
+        if let Some(block) = ast::BlockExpr::cast(node.clone())
+            && block.async_token().is_some()
+        {
+            return true;
+        }
+        if let Some(closure) = ast::ClosureExpr::cast(node.clone()) {
+            return closure.async_token().is_some();
+        }
+        if let Some(func) = ast::Fn::cast(node) {
+            return func.async_token().is_some();
+        }
+    }
+    false
+}
+
+/// Whether `?` can be applied to `expr` because it is a `Result` or an `Option` and the
+/// function containing it returns the same kind of value.
+fn can_propagate(ctx: &DiagnosticsContext<'_>, expr: &ast::Expr) -> bool {
+    let db = ctx.sema.db;
+    let Some(scope) = ctx.sema.scope(expr.syntax()) else { return false };
+    let Some(adt) = ctx.sema.type_of_expr(expr).and_then(|it| it.original.as_adt()) else {
+        return false;
+    };
+    let famous_defs = FamousDefs(&ctx.sema, scope.krate());
+    let propagatable = [famous_defs.core_result_Result(), famous_defs.core_option_Option()];
+    if !propagatable.into_iter().flatten().any(|it| hir::Adt::from(it) == adt) {
+        return false;
+    }
+    for node in expr.syntax().ancestors() {
+        if ast::ClosureExpr::can_cast(node.kind()) {
+            return false;
+        }
+        if let Some(block) = ast::BlockExpr::cast(node.clone())
+            && (block.async_token().is_some() || block.try_token().is_some())
+        {
+            return false;
+        }
+        if let Some(func) = ast::Fn::cast(node) {
+            return ctx.sema.to_def(&func).and_then(|func| func.ret_type(db).as_adt()) == Some(adt);
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{check_diagnostics, check_fix, check_fixes};
+
+    #[test]
+    fn must_use_type() {
+        check_diagnostics(
+            r#"
+#[must_use = "guards are released when dropped"]
+struct Guard;
+#[must_use]
+enum Status { Done }
+fn lock() -> Guard { Guard }
+
+fn f() {
+    lock();
+  //^^^^^^ 💡 warn: unused `Guard` that must be used
+  //     | guards are released when dropped
+    Status::Done;
+  //^^^^^^^^^^^^ 💡 warn: unused `Status` that must be used
+    let _ = lock();
+    let _guard = lock();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn must_use_function() {
+        check_diagnostics(
+            r#"
+#[must_use]
+fn compute() -> i32 { 0 }
+#[must_use = "the new value is returned"]
+fn next(v: i32) -> i32 { v + 1 }
+fn unmarked() -> i32 { 0 }
+
+fn f() {
+    compute();
+  //^^^^^^^^^ 💡 warn: unused return value of `compute` that must be used
+    next(1);
+  //^^^^^^^ 💡 warn: unused return value of `next` that must be used
+  //      | the new value is returned
+    unmarked();
+    let _ = compute();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn must_use_method() {
+        check_diagnostics(
+            r#"
+struct S;
+impl S {
+    #[must_use]
+    fn get(&self) -> i32 { 0 }
+}
+
+fn f(s: S) {
+    s.get();
+  //^^^^^^^ 💡 warn: unused return value of `get` that must be used
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unused_future() {
+        check_diagnostics(
+            r#"
+//- minicore: future
+async fn work() {}
+
+async fn f() {
+    work();
+  //^^^^^^ 💡 warn: unused future that must be awaited
+    work().await;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fix_let_underscore() {
+        check_fix(
+            r#"
+#[must_use]
+fn compute() -> i32 { 0 }
+
+fn f() {
+    $0compute();
+}
+"#,
+            r#"
+#[must_use]
+fn compute() -> i32 { 0 }
+
+fn f() {
+    let _ = compute();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn fix_question_mark() {
+        check_fixes(
+            r#"
+//- minicore: result
+#[must_use]
+fn foo() -> Result<(), ()> { Ok(()) }
+
+fn f() -> Result<(), ()> {
+    $0foo();
+    Ok(())
+}
+"#,
+            vec![
+                r#"
+#[must_use]
+fn foo() -> Result<(), ()> { Ok(()) }
+
+fn f() -> Result<(), ()> {
+    let _ = foo();
+    Ok(())
+}
+"#,
+                r#"
+#[must_use]
+fn foo() -> Result<(), ()> { Ok(()) }
+
+fn f() -> Result<(), ()> {
+    foo()?;
+    Ok(())
+}
+"#,
+            ],
+        );
+    }
+
+    #[test]
+    fn fix_await() {
+        check_fixes(
+            r#"
+//- minicore: future
+async fn work() {}
+
+async fn f() {
+    $0work();
+}
+"#,
+            vec![
+                r#"
+async fn work() {}
+
+async fn f() {
+    let _ = work();
+}
+"#,
+                r#"
+async fn work() {}
+
+async fn f() {
+    work().await;
+}
+"#,
+            ],
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{Diagnostic, DiagnosticCode, DiagnosticsContext};
+
+// Diagnostic: use-of-moved-value
+//
+// This diagnostic is triggered when a variable is used after its value has been moved out.
+pub(crate) fn use_of_moved_value(
+    ctx: &DiagnosticsContext<'_>,
+    d: &hir::UseOfMovedValue,
+) -> Diagnostic {
+    Diagnostic::new_with_syntax_node_ptr(
+        ctx,
+        DiagnosticCode::RustcHardError("E0382"),
+        format!(
+            "use of moved value: `{}`",
+            d.local.name(ctx.sema.db).display(ctx.sema.db, ctx.edition)
+        ),
+        d.span,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::check_diagnostics;
+
+    #[test]
+    fn use_after_move() {
+        check_diagnostics(
+            r#"
+struct S;
+fn consume(_: S) {}
+
+fn f() {
+    let s = S;
+    consume(s);
+    consume(s);
+          //^ error: use of moved value: `s`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn maybe_moved_and_reinitialized() {
+        check_diagnostics(
+            r#"
+struct S;
+fn consume(_: S) {}
+
+fn f(c: bool) {
+    let mut s = S;
+    if c {
+        consume(s);
+    }
+    consume(s);
+          //^ error: use of moved value: `s`
+    s = S;
+    consume(s);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn moved_in_previous_iteration() {
+        check_diagnostics(
+            r#"
+struct S;
+fn consume(_: S) {}
+
+fn f() {
+    let s = S;
+    loop {
+        consume(s);
+              //^ error: use of moved value: `s`
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn partial_moves() {
+        check_diagnostics(
+            r#"
+struct S;
+struct P {
+    a: S,
+    b: S,
+}
+fn consume(_: S) {}
+fn consume_p(_: P) {}
+
+fn f(p: P, q: P) {
+    consume(p.a);
+    consume(p.b);
+    consume(p.a);
+          //^^^ error: use of moved value: `p`
+    consume(q.a);
+    consume_p(q);
+            //^ error: use of moved value: `q`
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn no_false_positive() {
+        check_diagnostics(
+            r#"
+struct S;
+fn consume(_: S) {}
+fn by_ref(_: &S) {}
+fn by_mut(_: &mut S) {}
+
+fn f(x: u32, r: &mut S) {
+    let _a = x;
+    let _b = x;
+    let s = S;
+    by_ref(&s);
+    by_ref(&s);
+    consume(s);
+    by_mut(r);
+    by_mut(r);
+}
+"#,
+        );
+    }
+}
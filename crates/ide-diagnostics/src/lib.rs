@@ -29,9 +29,12 @@
 extern crate rustc_driver as _;
 
 mod handlers {
+    pub(crate) mod arithmetic_overflow;
     pub(crate) mod await_outside_of_async;
     pub(crate) mod bad_rtn;
     pub(crate) mod break_outside_of_loop;
+    pub(crate) mod conflicting_borrow;
+    pub(crate) mod const_eval_failed;
     pub(crate) mod elided_lifetimes_in_path;
     pub(crate) mod expected_function;
     pub(crate) mod generic_args_prohibited;
@@ -65,9 +68,13 @@ mod handlers {
     pub(crate) mod trait_impl_redundant_assoc_item;
     pub(crate) mod type_mismatch;
     pub(crate) mod typed_hole;
+    pub(crate) mod unconditional_panic;
+    pub(crate) mod unconditional_recursion;
     pub(crate) mod undeclared_label;
     pub(crate) mod unimplemented_builtin_macro;
+    pub(crate) mod unreachable_code;
     pub(crate) mod unreachable_label;
+    pub(crate) mod unreachable_pattern;
     pub(crate) mod unresolved_assoc_item;
     pub(crate) mod unresolved_extern_crate;
     pub(crate) mod unresolved_field;
@@ -76,12 +83,19 @@ mod handlers {
     pub(crate) mod unresolved_macro_call;
     pub(crate) mod unresolved_method;
     pub(crate) mod unresolved_module;
+    pub(crate) mod unsatisfied_trait_bound;
+    pub(crate) mod unused_must_use;
     pub(crate) mod unused_variables;
+    pub(crate) mod use_of_moved_value;
 
     // The handlers below are unusual, the implement the diagnostics as well.
+    pub(crate) mod dead_code;
+    pub(crate) mod deprecated;
     pub(crate) mod field_shorthand;
     pub(crate) mod json_is_not_rust;
     pub(crate) mod unlinked_file;
+    pub(crate) mod unstable_feature;
+    pub(crate) mod unused_imports;
     pub(crate) mod useless_braces;
 }
 
@@ -94,9 +108,10 @@ use hir::{
     Crate, DisplayTarget, InFile, Semantics, db::ExpandDatabase, diagnostics::AnyDiagnostic,
 };
 use ide_db::{
-    FileId, FileRange, FxHashMap, FxHashSet, RootDatabase, Severity, SnippetCap,
+    EditionedFileId, FileId, FileRange, FxHashMap, FxHashSet, RootDatabase, Severity, SnippetCap,
     assists::{Assist, AssistId, AssistResolveStrategy, ExprFillDefaultMode},
     base_db::{ReleaseChannel, RootQueryDb as _},
+    defs::{Definition, NameRefClass},
     generated::lints::{CLIPPY_LINT_GROUPS, DEFAULT_LINT_GROUPS, DEFAULT_LINTS, Lint, LintGroup},
     imports::insert_use::InsertUseConfig,
     label::Label,
@@ -156,6 +171,7 @@ pub struct Diagnostic {
     pub range: FileRange,
     pub severity: Severity,
     pub unused: bool,
+    pub deprecated: bool,
     pub experimental: bool,
     pub fixes: Option<Vec<Assist>>,
     // The node that will be affected by `#[allow]` and similar attributes.
@@ -183,6 +199,7 @@ impl Diagnostic {
                 DiagnosticCode::Ra(_, s) => s,
             },
             unused: false,
+            deprecated: false,
             experimental: true,
             fixes: None,
             main_node: None,
@@ -218,6 +235,11 @@ impl Diagnostic {
         self.unused = unused;
         self
     }
+
+    fn with_deprecated(mut self, deprecated: bool) -> Diagnostic {
+        self.deprecated = deprecated;
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -230,6 +252,12 @@ pub struct DiagnosticsConfig {
     pub disabled: FxHashSet<String>,
     pub expr_fill_default: ExprFillDefaultMode,
     pub style_lints: bool,
+    /// Whether to report unused imports, which requires searching for usages of every import.
+    pub unused_imports: bool,
+    /// Whether to report unused private items, which requires searching for usages of every item.
+    pub dead_code: bool,
+    /// Whether to report unstable library items and syntax used without their `#![feature]`.
+    pub feature_gates: bool,
     // FIXME: We may want to include a whole `AssistConfig` here
     pub snippet_cap: Option<SnippetCap>,
     pub insert_use: InsertUseConfig,
@@ -254,6 +282,11 @@ impl DiagnosticsConfig {
             disabled: Default::default(),
             expr_fill_default: Default::default(),
             style_lints: true,
+            // Most test fixtures have imports they don't use.
+            unused_imports: false,
+            dead_code: false,
+            // Most test fixtures use unstable syntax without enabling its feature.
+            feature_gates: false,
             snippet_cap: SnippetCap::new(true),
             insert_use: InsertUseConfig {
                 granularity: ImportGranularity::Item,
@@ -377,6 +410,16 @@ pub fn semantic_diagnostics(
         Some(m) => {
             if db.parse_errors(editioned_file_id).is_none_or(|es| es.len() < 16) {
                 m.diagnostics(db, &mut diags, config.style_lints);
+                handlers::unused_imports::unused_imports(&ctx, &mut res, editioned_file_id);
+                handlers::dead_code::dead_code(&ctx, &mut res, editioned_file_id);
+                let name_refs = name_ref_defs(&ctx, editioned_file_id);
+                handlers::deprecated::deprecated(&ctx, &mut res, editioned_file_id, &name_refs);
+                handlers::unstable_feature::unstable_feature(
+                    &ctx,
+                    &mut res,
+                    editioned_file_id,
+                    &name_refs,
+                );
             }
         }
         None => {
@@ -386,8 +429,11 @@ pub fn semantic_diagnostics(
 
     for diag in diags {
         let d = match diag {
+            AnyDiagnostic::ArithmeticOverflow(d) => handlers::arithmetic_overflow::arithmetic_overflow(&ctx, &d),
             AnyDiagnostic::AwaitOutsideOfAsync(d) => handlers::await_outside_of_async::await_outside_of_async(&ctx, &d),
             AnyDiagnostic::CastToUnsized(d) => handlers::invalid_cast::cast_to_unsized(&ctx, &d),
+            AnyDiagnostic::ConflictingBorrow(d) => handlers::conflicting_borrow::conflicting_borrow(&ctx, &d),
+            AnyDiagnostic::ConstEvalFailed(d) => handlers::const_eval_failed::const_eval_failed(&ctx, &d),
             AnyDiagnostic::ExpectedFunction(d) => handlers::expected_function::expected_function(&ctx, &d),
             AnyDiagnostic::InactiveCode(d) => match handlers::inactive_code::inactive_code(&ctx, &d) {
                 Some(it) => it,
@@ -431,9 +477,19 @@ pub fn semantic_diagnostics(
             AnyDiagnostic::TraitImplOrphan(d) => handlers::trait_impl_orphan::trait_impl_orphan(&ctx, &d),
             AnyDiagnostic::TypedHole(d) => handlers::typed_hole::typed_hole(&ctx, &d),
             AnyDiagnostic::TypeMismatch(d) => handlers::type_mismatch::type_mismatch(&ctx, &d),
+            AnyDiagnostic::UnconditionalPanic(d) => handlers::unconditional_panic::unconditional_panic(&ctx, &d),
+            AnyDiagnostic::UnconditionalRecursion(d) => handlers::unconditional_recursion::unconditional_recursion(&ctx, &d),
             AnyDiagnostic::UndeclaredLabel(d) => handlers::undeclared_label::undeclared_label(&ctx, &d),
             AnyDiagnostic::UnimplementedBuiltinMacro(d) => handlers::unimplemented_builtin_macro::unimplemented_builtin_macro(&ctx, &d),
+            AnyDiagnostic::UnreachableCode(d) => match handlers::unreachable_code::unreachable_code(&ctx, &d) {
+                Some(it) => it,
+                None => continue,
+            },
             AnyDiagnostic::UnreachableLabel(d) => handlers::unreachable_label::unreachable_label(&ctx, &d),
+            AnyDiagnostic::UnreachablePattern(d) => match handlers::unreachable_pattern::unreachable_pattern(&ctx, &d) {
+                Some(it) => it,
+                None => continue,
+            },
             AnyDiagnostic::UnresolvedAssocItem(d) => handlers::unresolved_assoc_item::unresolved_assoc_item(&ctx, &d),
             AnyDiagnostic::UnresolvedExternCrate(d) => handlers::unresolved_extern_crate::unresolved_extern_crate(&ctx, &d),
             AnyDiagnostic::UnresolvedField(d) => handlers::unresolved_field::unresolved_field(&ctx, &d),
@@ -442,6 +498,8 @@ pub fn semantic_diagnostics(
             AnyDiagnostic::UnresolvedMacroCall(d) => handlers::unresolved_macro_call::unresolved_macro_call(&ctx, &d),
             AnyDiagnostic::UnresolvedMethodCall(d) => handlers::unresolved_method::unresolved_method(&ctx, &d),
             AnyDiagnostic::UnresolvedModule(d) => handlers::unresolved_module::unresolved_module(&ctx, &d),
+            AnyDiagnostic::UnsatisfiedTraitBound(d) => handlers::unsatisfied_trait_bound::unsatisfied_trait_bound(&ctx, &d),
+            AnyDiagnostic::UnusedMustUse(d) => handlers::unused_must_use::unused_must_use(&ctx, &d),
             AnyDiagnostic::UnusedMut(d) => match handlers::mutability_errors::unused_mut(&ctx, &d) {
                 Some(it) => it,
                 None => continue,
@@ -450,6 +508,7 @@ pub fn semantic_diagnostics(
                 Some(it) => it,
                 None => continue,
             },
+            AnyDiagnostic::UseOfMovedValue(d) => handlers::use_of_moved_value::use_of_moved_value(&ctx, &d),
             AnyDiagnostic::BreakOutsideOfLoop(d) => handlers::break_outside_of_loop::break_outside_of_loop(&ctx, &d),
             AnyDiagnostic::MismatchedTupleStructPatArgCount(d) => handlers::mismatched_arg_count::mismatched_tuple_struct_pat_arg_count(&ctx, &d),
             AnyDiagnostic::RemoveTrailingReturn(d) => match handlers::remove_trailing_return::remove_trailing_return(&ctx, &d) {
@@ -737,6 +796,29 @@ fn lint_groups(lint: &DiagnosticCode, edition: Edition) -> LintGroups {
     LintGroups { groups, inside_warnings }
 }
 
+/// The names in the file along with the definitions they refer to, shared by the diagnostics for
+/// the uses of deprecated and unstable items.
+fn name_ref_defs(
+    ctx: &DiagnosticsContext<'_>,
+    file_id: EditionedFileId,
+) -> Vec<(ast::NameRef, Definition)> {
+    let source_file = ctx.sema.parse(file_id);
+    source_file
+        .syntax()
+        .descendants()
+        .filter_map(ast::NameRef::cast)
+        .filter(|it| !matches!(it.text().as_str(), "self" | "Self" | "super" | "crate"))
+        .filter_map(|name_ref| {
+            let def = match NameRefClass::classify(&ctx.sema, &name_ref)? {
+                NameRefClass::Definition(def, _) => def,
+                NameRefClass::FieldShorthand { field_ref, .. } => Definition::Field(field_ref),
+                NameRefClass::ExternCrateShorthand { .. } => return None,
+            };
+            Some((name_ref, def))
+        })
+        .collect()
+}
+
 fn fix(id: &'static str, label: &str, source_change: SourceChange, target: TextRange) -> Assist {
     let mut res = unresolved_fix(id, label, target);
     res.source_change = Some(source_change);
@@ -1,13 +1,13 @@
 use hir::{HasSource, InFile, InRealFile, Semantics};
 use ide_db::{
-    FileId, FilePosition, FileRange, FxIndexSet, MiniCore, RootDatabase, defs::Definition,
-    helpers::visit_file_defs,
+    FileId, FilePosition, FileRange, FxIndexSet, LineIndexDatabase, MiniCore, RootDatabase,
+    defs::Definition, helpers::visit_file_defs,
 };
 use itertools::Itertools;
 use syntax::{AstNode, TextRange, ast::HasName};
 
 use crate::{
-    NavigationTarget, RunnableKind,
+    LineCoverage, NavigationTarget, RunnableKind,
     annotations::fn_references::find_all_methods,
     goto_implementation::{GotoImplementationConfig, goto_implementation},
     navigation_target,
@@ -20,7 +20,8 @@ mod fn_references;
 // Feature: Annotations
 //
 // Provides user with annotations above items for looking up references or impl blocks
-// and running/debugging binaries.
+// and running/debugging binaries. When a coverage report is loaded, functions are annotated
+// with the share of their lines that were executed.
 //
 // ![Annotations](https://user-images.githubusercontent.com/48062697/113020672-b7c34f00-917a-11eb-8f6e-858735660a0e.png)
 #[derive(Debug, Hash, PartialEq, Eq)]
@@ -32,8 +33,21 @@ pub struct Annotation {
 #[derive(Debug, Hash, PartialEq, Eq)]
 pub enum AnnotationKind {
     Runnable(Runnable),
-    HasImpls { pos: FilePosition, data: Option<Vec<NavigationTarget>> },
-    HasReferences { pos: FilePosition, data: Option<Vec<FileRange>> },
+    HasImpls {
+        pos: FilePosition,
+        data: Option<Vec<NavigationTarget>>,
+    },
+    HasReferences {
+        pos: FilePosition,
+        data: Option<Vec<FileRange>>,
+    },
+    /// `uncovered` are the ranges of the instrumented lines that were never executed.
+    Coverage {
+        file_id: FileId,
+        covered: u32,
+        total: u32,
+        uncovered: Vec<TextRange>,
+    },
 }
 
 pub struct AnnotationConfig<'a> {
@@ -46,6 +60,8 @@ pub struct AnnotationConfig<'a> {
     pub location: AnnotationLocation,
     pub filter_adjacent_derive_implementations: bool,
     pub minicore: MiniCore<'a>,
+    /// The coverage of the file, if a report covering it is loaded.
+    pub coverage: Option<&'a LineCoverage>,
 }
 
 pub enum AnnotationLocation {
@@ -82,7 +98,46 @@ pub(crate) fn annotations(
         (annotation_range, target_pos)
     };
 
+    let line_index = config.coverage.map(|_| db.line_index(file_id));
+
     visit_file_defs(&Semantics::new(db), file_id, &mut |def| {
+        if let (Some(coverage), Some(line_index)) = (config.coverage, &line_index) {
+            let functions = match def {
+                Definition::Function(func) => vec![func],
+                Definition::SelfType(impl_) => impl_
+                    .items(db)
+                    .into_iter()
+                    .filter_map(|item| match item {
+                        hir::AssocItem::Function(func) => Some(func),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => vec![],
+            };
+            for func in functions {
+                let Some(range) = func.source(db).and_then(|node| name_range(db, node, file_id))
+                else {
+                    continue;
+                };
+                let lines = line_index.line_col(range.0.start()).line
+                    ..=line_index.line_col(range.0.end()).line;
+                let (covered, total) = coverage.count(lines.clone());
+                if total == 0 {
+                    continue;
+                }
+                let uncovered = coverage
+                    .uncovered(lines)
+                    .into_iter()
+                    .filter_map(|line| line_index.line(line))
+                    .collect();
+                let (annotation_range, _) = mk_ranges(range);
+                annotations.insert(Annotation {
+                    range: annotation_range,
+                    kind: AnnotationKind::Coverage { file_id, covered, total, uncovered },
+                });
+            }
+        }
+
         let range = match def {
             Definition::Const(konst) if config.annotate_references => {
                 konst.source(db).and_then(|node| name_range(db, node, file_id))
@@ -246,7 +301,7 @@ mod tests {
     use expect_test::{Expect, expect};
     use ide_db::MiniCore;
 
-    use crate::{Annotation, AnnotationConfig, fixture};
+    use crate::{Annotation, AnnotationConfig, LineCoverage, fixture};
 
     use super::AnnotationLocation;
 
@@ -260,6 +315,7 @@ mod tests {
         location: AnnotationLocation::AboveName,
         minicore: MiniCore::default(),
         filter_adjacent_derive_implementations: false,
+        coverage: None,
     };
 
     fn check_with_config(
@@ -375,6 +431,68 @@ fn main() {
         );
     }
 
+    #[test]
+    fn coverage_annotations() {
+        let mut coverage = LineCoverage::default();
+        for (line, hits) in [(0, 1), (1, 1), (2, 0), (3, 1), (4, 1), (8, 2)] {
+            coverage.add(line, hits);
+        }
+        check_with_config(
+            r#"
+fn partly(x: bool) {
+    if x {
+        return;
+    }
+}
+
+struct S;
+impl S {
+    fn method(&self) {}
+}
+
+fn uninstrumented() {}
+"#,
+            expect![[r#"
+                [
+                    Annotation {
+                        range: 3..9,
+                        kind: Coverage {
+                            file_id: FileId(
+                                0,
+                            ),
+                            covered: 4,
+                            total: 5,
+                            uncovered: [
+                                32..48,
+                            ],
+                        },
+                    },
+                    Annotation {
+                        range: 83..89,
+                        kind: Coverage {
+                            file_id: FileId(
+                                0,
+                            ),
+                            covered: 1,
+                            total: 1,
+                            uncovered: [],
+                        },
+                    },
+                ]
+            "#]],
+            &AnnotationConfig {
+                binary_target: false,
+                annotate_runnables: false,
+                annotate_impls: false,
+                annotate_references: false,
+                annotate_method_references: false,
+                annotate_enum_variant_references: false,
+                coverage: Some(&coverage),
+                ..DEFAULT_CONFIG
+            },
+        );
+    }
+
     #[test]
     fn struct_references_annotations() {
         check(
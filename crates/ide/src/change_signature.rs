@@ -0,0 +1,334 @@
+//! Change signature: reorders, adds, removes and retypes the parameters of the
+//! function at the cursor.
+//!
+//! This is a front-end for [`ide_db::change_signature`], it also includes the
+//! tests.
+
+use hir::Semantics;
+use ide_db::{
+    RootDatabase,
+    defs::{Definition, NameRefClass},
+    source_change::{SourceChangeBuilder, UnrewrittenSite},
+};
+use syntax::{AstNode, SyntaxNode, TextSize, algo::find_node_at_offset, ast};
+
+use crate::{FilePosition, SourceChange};
+
+pub use ide_db::change_signature::{ChangeSignatureError, NewParam};
+
+#[derive(Debug)]
+pub struct SignatureChange {
+    pub source_change: SourceChange,
+    /// Uses of the function that were left as is.
+    pub unrewritten: Vec<UnrewrittenSite>,
+}
+
+pub(crate) fn change_signature(
+    db: &RootDatabase,
+    position: FilePosition,
+    new_params: &[NewParam],
+) -> Result<SignatureChange, ChangeSignatureError> {
+    let sema = Semantics::new(db);
+    let file_id = sema.attach_first_edition(position.file_id);
+    let source_file = sema.parse(file_id);
+    let func = find_function(&sema, source_file.syntax(), position.offset)
+        .ok_or_else(|| ChangeSignatureError("No function found at position".to_owned()))?;
+    let edit = ide_db::change_signature::change_signature(&sema, func, new_params)?;
+    let unrewritten = edit.unrewritten.clone();
+    let mut builder = SourceChangeBuilder::new(position.file_id);
+    edit.apply(&mut builder);
+    Ok(SignatureChange { source_change: builder.finish(), unrewritten })
+}
+
+/// Finds the function referenced at `offset`, or the one whose definition contains it.
+fn find_function(
+    sema: &Semantics<'_, RootDatabase>,
+    syntax: &SyntaxNode,
+    offset: TextSize,
+) -> Option<hir::Function> {
+    if let Some(name_ref) = find_node_at_offset::<ast::NameRef>(syntax, offset)
+        && let Some(NameRefClass::Definition(Definition::Function(func), _)) =
+            NameRefClass::classify(sema, &name_ref)
+    {
+        return Some(func);
+    }
+    sema.to_def(&find_node_at_offset::<ast::Fn>(syntax, offset)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use stdx::trim_indent;
+    use test_utils::assert_eq_text;
+
+    use crate::fixture;
+
+    use super::NewParam;
+
+    fn existing(index: usize) -> NewParam {
+        NewParam::Existing { index, ty: None }
+    }
+
+    #[track_caller]
+    fn check(
+        new_params: &[NewParam],
+        #[rust_analyzer::rust_fixture] ra_fixture_before: &str,
+        #[rust_analyzer::rust_fixture] ra_fixture_after: &str,
+    ) {
+        let (analysis, position) = fixture::position(ra_fixture_before);
+        let change = analysis
+            .change_signature(position, new_params.to_vec())
+            .unwrap()
+            .unwrap_or_else(|err| panic!("Change signature failed unexpectedly: {err}"));
+        assert!(change.unrewritten.is_empty(), "unexpected unrewritten sites: {change:#?}");
+        let (&file_id, (edit, _)) =
+            change.source_change.source_file_edits.iter().exactly_one().unwrap();
+        let mut result = analysis.file_text(file_id).unwrap().to_string();
+        edit.apply(&mut result);
+        assert_eq_text!(&trim_indent(ra_fixture_after), &*result);
+    }
+
+    #[track_caller]
+    fn check_unrewritten(new_params: &[NewParam], #[rust_analyzer::rust_fixture] ra_fixture: &str) {
+        let (analysis, position, expected) = fixture::annotations(ra_fixture);
+        let change = analysis.change_signature(position, new_params.to_vec()).unwrap().unwrap();
+        let unrewritten = change
+            .unrewritten
+            .into_iter()
+            .map(|site| (site.range, site.reason))
+            .sorted_by_key(|(range, _)| range.range.start())
+            .collect_vec();
+        let expected =
+            expected.into_iter().sorted_by_key(|(range, _)| range.range.start()).collect_vec();
+        assert_eq!(expected, unrewritten);
+    }
+
+    #[track_caller]
+    fn check_error(
+        new_params: &[NewParam],
+        #[rust_analyzer::rust_fixture] ra_fixture: &str,
+        expected: &str,
+    ) {
+        let (analysis, position) = fixture::position(ra_fixture);
+        let err = analysis.change_signature(position, new_params.to_vec()).unwrap().unwrap_err();
+        assert_eq!(expected, err.to_string());
+    }
+
+    #[test]
+    fn reorder_params_of_free_function() {
+        check(
+            &[existing(1), existing(0)],
+            r#"
+fn foo$0(a: u32, b: bool) -> u32 {
+    if b { a } else { 0 }
+}
+
+fn main() {
+    foo(foo(1, true), false);
+}
+"#,
+            r#"
+fn foo(b: bool, a: u32) -> u32 {
+    if b { a } else { 0 }
+}
+
+fn main() {
+    foo(false, foo(true, 1));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn reorder_params_of_trait_method() {
+        check(
+            &[existing(1), existing(0)],
+            r#"
+trait Shape {
+    fn scale(&self, factor: u32, offset: u32) -> u32;
+}
+
+struct Square(u32);
+
+impl Shape for Square {
+    fn scale(&self, factor: u32, offset: u32) -> u32 {
+        self.0 * factor + offset
+    }
+}
+
+fn main() {
+    let s = Square(2);
+    s.scale$0(2, 1);
+    Shape::scale(&s, 3, 0);
+    <Square as Shape>::scale(&s, 4, 1);
+}
+"#,
+            r#"
+trait Shape {
+    fn scale(&self, offset: u32, factor: u32) -> u32;
+}
+
+struct Square(u32);
+
+impl Shape for Square {
+    fn scale(&self, offset: u32, factor: u32) -> u32 {
+        self.0 * factor + offset
+    }
+}
+
+fn main() {
+    let s = Square(2);
+    s.scale(1, 2);
+    Shape::scale(&s, 0, 3);
+    <Square as Shape>::scale(&s, 1, 4);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn add_remove_and_retype_params() {
+        check(
+            &[
+                existing(0),
+                NewParam::Existing { index: 2, ty: Some("u16".to_owned()) },
+                NewParam::Added {
+                    pattern: "verbose".to_owned(),
+                    ty: "bool".to_owned(),
+                    default: "false".to_owned(),
+                },
+            ],
+            r#"
+fn log$0(msg: &str, unused: u32, level: u8) {
+    let _ = (msg, level);
+}
+
+fn main() {
+    log("a", 1, 2);
+}
+"#,
+            r#"
+fn log(msg: &str, level: u16, verbose: bool) {
+    let _ = (msg, level);
+}
+
+fn main() {
+    log("a", 2, false);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn wrap_value_uses_in_closures() {
+        check(
+            &[
+                existing(1),
+                existing(0),
+                NewParam::Added {
+                    pattern: "scale".to_owned(),
+                    ty: "u32".to_owned(),
+                    default: "1".to_owned(),
+                },
+            ],
+            r#"
+//- minicore: fn
+fn foo$0(a: u32, b: bool) -> u32 {
+    0
+}
+
+fn apply(f: impl Fn(u32, bool) -> u32) -> u32 {
+    f(1, true)
+}
+
+fn main() {
+    let g: fn(u32, bool) -> u32 = foo;
+    apply(foo);
+}
+"#,
+            r#"
+fn foo(b: bool, a: u32, scale: u32) -> u32 {
+    0
+}
+
+fn apply(f: impl Fn(u32, bool) -> u32) -> u32 {
+    f(1, true)
+}
+
+fn main() {
+    let g: fn(u32, bool) -> u32 = |a, b| foo(b, a, 1);
+    apply(|a, b| foo(b, a, 1));
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn report_unrewritten_sites() {
+        check_unrewritten(
+            &[existing(1)],
+            r#"
+macro_rules! id {
+    ($e:expr) => { $e };
+}
+
+fn foo(a: u32, b: u32) -> u32 {
+    b$0
+}
+
+fn main() {
+    id!(foo(1, 2));
+      //^^^ the reference is inside a macro call
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn refuse_removing_used_param() {
+        check_error(
+            &[existing(1)],
+            r#"
+fn foo$0(a: u32, b: u32) -> u32 {
+    a + b
+}
+"#,
+            "Cannot remove parameter `a`, it is still used in the body",
+        );
+    }
+
+    #[test]
+    fn report_unsafe_and_retyped_value_uses() {
+        check_unrewritten(
+            &[existing(1), existing(0)],
+            r#"
+unsafe fn foo$0(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+fn main() {
+    let f: unsafe fn(u32, u32) -> u32 = foo;
+                                      //^^^ an unsafe function can't be wrapped in a closure
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn invalid_param_list() {
+        check_error(
+            &[existing(0), existing(0)],
+            r#"
+fn foo$0(a: u32) {}
+"#,
+            "Parameter 0 is listed more than once",
+        );
+        check_error(
+            &[existing(1)],
+            r#"
+fn foo$0(a: u32) {}
+"#,
+            "Parameter index 1 is out of bounds, the function has 1 parameters",
+        );
+    }
+}
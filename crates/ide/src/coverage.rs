@@ -0,0 +1,50 @@
+//! Line coverage loaded from an external report, e.g. one produced by `cargo llvm-cov`.
+
+use std::ops::RangeInclusive;
+
+use ide_db::FxHashMap;
+
+/// How often each line of a file was executed, according to a coverage report.
+///
+/// Lines that are not instrumented, like comments or blank lines, have no entry.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LineCoverage {
+    /// Zero-based line numbers mapped to their execution counts.
+    hits: FxHashMap<u32, u64>,
+}
+
+impl LineCoverage {
+    /// Records that the zero-based `line` was executed `hits` times, adding to the counts
+    /// of earlier records for the same line.
+    pub fn add(&mut self, line: u32, hits: u64) {
+        let entry = self.hits.entry(line).or_default();
+        *entry = entry.saturating_add(hits);
+    }
+
+    pub fn hits(&self, line: u32) -> Option<u64> {
+        self.hits.get(&line).copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hits.is_empty()
+    }
+
+    /// Returns the instrumented lines with their execution counts, ordered by line.
+    pub fn lines(&self) -> Vec<(u32, u64)> {
+        let mut lines: Vec<_> = self.hits.iter().map(|(&line, &hits)| (line, hits)).collect();
+        lines.sort_unstable();
+        lines
+    }
+
+    /// Returns the number of executed lines and the number of instrumented lines in `lines`.
+    pub(crate) fn count(&self, lines: RangeInclusive<u32>) -> (u32, u32) {
+        lines
+            .filter_map(|line| self.hits(line))
+            .fold((0, 0), |(covered, total), hits| (covered + u32::from(hits > 0), total + 1))
+    }
+
+    /// Returns the instrumented lines in `lines` that were never executed.
+    pub(crate) fn uncovered(&self, lines: RangeInclusive<u32>) -> Vec<u32> {
+        lines.filter(|&line| self.hits(line) == Some(0)).collect()
+    }
+}
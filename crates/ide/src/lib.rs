@@ -20,7 +20,9 @@ mod navigation_target;
 
 mod annotations;
 mod call_hierarchy;
+mod change_signature;
 mod child_modules;
+mod coverage;
 mod doc_links;
 mod expand_macro;
 mod extend_selection;
@@ -40,6 +42,7 @@ mod markdown_remove;
 mod matching_brace;
 mod moniker;
 mod move_item;
+mod move_to_module;
 mod parent_module;
 mod references;
 mod rename;
@@ -82,6 +85,8 @@ use crate::navigation_target::ToNav;
 pub use crate::{
     annotations::{Annotation, AnnotationConfig, AnnotationKind, AnnotationLocation},
     call_hierarchy::{CallHierarchyConfig, CallItem},
+    change_signature::{ChangeSignatureError, NewParam, SignatureChange},
+    coverage::LineCoverage,
     expand_macro::ExpandedMacro,
     file_structure::{FileStructureConfig, StructureNode, StructureNodeKind},
     folding_ranges::{Fold, FoldKind},
@@ -105,6 +110,7 @@ pub use crate::{
         PackageInformation, SymbolInformationKind,
     },
     move_item::Direction,
+    move_to_module::{MoveToModuleConfig, MoveToModuleError, MovedItems},
     navigation_target::{NavigationTarget, TryToNav, UpmappingResult},
     references::{FindAllRefsConfig, ReferenceSearchResult},
     rename::{RenameConfig, RenameError},
@@ -117,7 +123,7 @@ pub use crate::{
         HighlightConfig, HlRange,
         tags::{Highlight, HlMod, HlMods, HlOperator, HlPunct, HlTag},
     },
-    test_explorer::{TestItem, TestItemKind},
+    test_explorer::{TestItem, TestItemKind, TestRunResult},
 };
 pub use hir::Semantics;
 pub use ide_assists::{
@@ -136,7 +142,7 @@ pub use ide_db::{
     line_index::{LineCol, LineIndex},
     prime_caches::ParallelPrimeCachesProgress,
     search::{ReferenceCategory, SearchScope},
-    source_change::{FileSystemEdit, SnippetEdit, SourceChange},
+    source_change::{FileSystemEdit, SnippetEdit, SourceChange, UnrewrittenSite},
     symbol_index::Query,
     text_edit::{Indel, TextEdit},
 };
@@ -412,6 +418,15 @@ impl Analysis {
         self.with_db(|db| test_explorer::discover_tests_in_file(db, file_id))
     }
 
+    pub fn tests_selected_by_test_id(&self, test_id: &str) -> Cancellable<Vec<String>> {
+        self.with_db(|db| test_explorer::tests_selected_by_test_id(db, test_id))
+    }
+
+    /// Runs a test in the mir interpreter, returning `None` if there is no test with this id.
+    pub fn interpret_test(&self, test_id: &str) -> Cancellable<Option<TestRunResult>> {
+        self.with_db(|db| test_explorer::interpret_test(db, test_id))
+    }
+
     /// Renders the crate graph to GraphViz "dot" syntax.
     pub fn view_crate_graph(&self, full: bool) -> Cancellable<Result<String, String>> {
         self.with_db(|db| view_crate_graph::view_crate_graph(db, full))
@@ -880,6 +895,15 @@ impl Analysis {
         })
     }
 
+    /// Changes the parameter list of the function at `position` and rewrites its uses.
+    pub fn change_signature(
+        &self,
+        position: FilePosition,
+        new_params: Vec<NewParam>,
+    ) -> Cancellable<Result<SignatureChange, ChangeSignatureError>> {
+        self.with_db(|db| change_signature::change_signature(db, position, &new_params))
+    }
+
     pub fn annotations(
         &self,
         config: &AnnotationConfig<'_>,
@@ -904,6 +928,17 @@ impl Analysis {
         self.with_db(|db| move_item::move_item(db, range, direction))
     }
 
+    /// Moves the items in `frange` into the module at path `target` and fixes up
+    /// the references to them.
+    pub fn move_to_module(
+        &self,
+        frange: FileRange,
+        target: &str,
+        config: &MoveToModuleConfig,
+    ) -> Cancellable<Result<MovedItems, MoveToModuleError>> {
+        self.with_db(|db| move_to_module::move_to_module(db, frange, target, config))
+    }
+
     pub fn get_recursive_memory_layout(
         &self,
         position: FilePosition,
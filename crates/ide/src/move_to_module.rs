@@ -0,0 +1,433 @@
+//! Move to module: moves the items in the selection into another module of the
+//! same crate and fixes up the imports and paths around them.
+//!
+//! This is a front-end for [`ide_db::move_to_module`], it also includes the
+//! tests.
+
+use hir::{FindPathConfig, ModuleDef, PathResolution, Semantics};
+use ide_db::{
+    FileRange, RootDatabase,
+    imports::insert_use::InsertUseConfig,
+    move_to_module::{ItemMove, items_in_range},
+    source_change::{SourceChangeBuilder, UnrewrittenSite},
+};
+use syntax::{AstNode, ast};
+
+use crate::SourceChange;
+
+pub use ide_db::move_to_module::MoveToModuleError;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MoveToModuleConfig {
+    pub insert_use: InsertUseConfig,
+    pub prefer_no_std: bool,
+    pub prefer_prelude: bool,
+    pub prefer_absolute: bool,
+}
+
+impl MoveToModuleConfig {
+    fn ide_db_config(&self) -> ide_db::move_to_module::MoveToModuleConfig {
+        ide_db::move_to_module::MoveToModuleConfig {
+            insert_use: self.insert_use,
+            find_path: FindPathConfig {
+                prefer_no_std: self.prefer_no_std,
+                prefer_prelude: self.prefer_prelude,
+                prefer_absolute: self.prefer_absolute,
+                allow_unstable: true,
+            },
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MovedItems {
+    pub source_change: SourceChange,
+    /// References to the moved items that were left as is.
+    pub unrewritten: Vec<UnrewrittenSite>,
+}
+
+/// Moves the items in `frange` into the module at path `target`, which is
+/// resolved from the module the items are in, or else from the crate root.
+pub(crate) fn move_to_module(
+    db: &RootDatabase,
+    frange: FileRange,
+    target: &str,
+    config: &MoveToModuleConfig,
+) -> Result<MovedItems, MoveToModuleError> {
+    let sema = Semantics::new(db);
+    let file_id = sema.attach_first_edition(frange.file_id);
+    let source_file = sema.parse(file_id);
+    let items = items_in_range(&source_file, frange.range);
+    let first =
+        items.first().ok_or_else(|| MoveToModuleError("No item found to move".to_owned()))?;
+    let scope = sema
+        .scope(first.syntax())
+        .ok_or_else(|| MoveToModuleError("No item found to move".to_owned()))?;
+
+    let unresolved = || MoveToModuleError(format!("Unresolved module `{target}`"));
+    let resolve = |target: &str| {
+        let parse = ast::SourceFile::parse(&format!("use {target};"), file_id.edition(db));
+        if !parse.errors().is_empty() {
+            return None;
+        }
+        let path = parse
+            .tree()
+            .syntax()
+            .descendants()
+            .find_map(ast::UseTree::cast)
+            .filter(|tree| tree.use_tree_list().is_none() && tree.star_token().is_none())
+            .and_then(|tree| tree.path())?;
+        match scope.speculative_resolve(&path)? {
+            PathResolution::Def(ModuleDef::Module(module)) => Some(module),
+            _ => None,
+        }
+    };
+    let target =
+        resolve(target).or_else(|| resolve(&format!("crate::{target}"))).ok_or_else(unresolved)?;
+
+    let mv = ItemMove::new(&sema, file_id, items, target)?;
+    let mut builder = SourceChangeBuilder::new(frange.file_id);
+    let unrewritten = mv.apply(&sema, &config.ide_db_config(), &mut builder);
+    Ok(MovedItems { source_change: builder.finish(), unrewritten })
+}
+
+#[cfg(test)]
+mod tests {
+    use ide_db::{
+        FileId, FileRange,
+        imports::insert_use::{ImportGranularity, InsertUseConfig},
+    };
+    use itertools::Itertools;
+    use stdx::trim_indent;
+    use syntax::TextRange;
+    use test_utils::{FixtureWithProjectMeta, assert_eq_text};
+
+    use crate::fixture;
+
+    use super::MoveToModuleConfig;
+
+    const TEST_CONFIG: MoveToModuleConfig = MoveToModuleConfig {
+        insert_use: InsertUseConfig {
+            granularity: ImportGranularity::Crate,
+            prefix_kind: hir::PrefixKind::Plain,
+            enforce_granularity: true,
+            group: true,
+            skip_glob_imports: true,
+        },
+        prefer_no_std: false,
+        prefer_prelude: true,
+        prefer_absolute: false,
+    };
+
+    #[track_caller]
+    fn check(
+        target: &str,
+        #[rust_analyzer::rust_fixture] ra_fixture_before: &str,
+        #[rust_analyzer::rust_fixture] ra_fixture_after: &str,
+    ) {
+        let (analysis, file_id, range) = fixture::range_or_position(ra_fixture_before);
+        let frange = FileRange { file_id, range: range.into() };
+        let moved = analysis
+            .move_to_module(frange, target, &TEST_CONFIG)
+            .unwrap()
+            .unwrap_or_else(|err| panic!("Move to module failed unexpectedly: {err}"));
+        assert!(moved.unrewritten.is_empty(), "unexpected unrewritten sites: {moved:#?}");
+
+        let expected = FixtureWithProjectMeta::parse(ra_fixture_after).fixture;
+        for (i, file) in expected.into_iter().enumerate() {
+            let file_id = FileId::from_raw(i as u32);
+            let mut result = analysis.file_text(file_id).unwrap().to_string();
+            if let Some((edit, _)) = moved.source_change.source_file_edits.get(&file_id) {
+                edit.apply(&mut result);
+            }
+            assert_eq_text!(&trim_indent(&file.text), &*result);
+        }
+    }
+
+    #[track_caller]
+    fn check_unrewritten(target: &str, #[rust_analyzer::rust_fixture] ra_fixture: &str) {
+        let (analysis, position, expected) = fixture::annotations(ra_fixture);
+        let frange =
+            FileRange { file_id: position.file_id, range: TextRange::empty(position.offset) };
+        let moved = analysis.move_to_module(frange, target, &TEST_CONFIG).unwrap().unwrap();
+        let unrewritten = moved
+            .unrewritten
+            .into_iter()
+            .map(|site| (site.range, site.reason))
+            .sorted_by_key(|(range, _)| range.range.start())
+            .collect_vec();
+        let expected =
+            expected.into_iter().sorted_by_key(|(range, _)| range.range.start()).collect_vec();
+        assert_eq!(expected, unrewritten);
+    }
+
+    #[track_caller]
+    fn check_error(target: &str, #[rust_analyzer::rust_fixture] ra_fixture: &str, expected: &str) {
+        let (analysis, file_id, range) = fixture::range_or_position(ra_fixture);
+        let frange = FileRange { file_id, range: range.into() };
+        let err = analysis.move_to_module(frange, target, &TEST_CONFIG).unwrap().unwrap_err();
+        assert_eq!(expected, err.to_string());
+    }
+
+    #[test]
+    fn move_function_into_child_module() {
+        check(
+            "a",
+            r#"
+mod a {
+    pub fn helper() -> u32 {
+        1
+    }
+}
+
+fn foo$0() -> u32 {
+    a::helper()
+}
+
+fn main() {
+    foo();
+    self::foo();
+}
+"#,
+            r#"
+use a::foo;
+
+mod a {
+    pub fn helper() -> u32 {
+        1
+    }
+
+    pub(crate) fn foo() -> u32 {
+        self::helper()
+    }
+}
+
+fn main() {
+    foo();
+    a::foo();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn move_items_into_parent_module() {
+        check(
+            "super",
+            r#"
+mod a {
+    $0struct Point {
+        x: u32,
+    }
+
+    fn origin() -> Point {
+        Point { x: 0 }
+    }$0
+
+    pub fn print() {
+        let _ = origin().x;
+    }
+}
+"#,
+            r#"
+mod a {
+    use crate::origin;
+
+    pub fn print() {
+        let _ = origin().x;
+    }
+}
+
+struct Point {
+    x: u32,
+}
+
+fn origin() -> Point {
+    Point { x: 0 }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn make_dependencies_visible() {
+        check(
+            "crate::b",
+            r#"
+mod a {
+    fn make() -> u32 {
+        2
+    }
+
+    fn foo$0() -> u32 {
+        make()
+    }
+
+    pub fn bar() -> u32 {
+        foo()
+    }
+}
+
+mod b {}
+"#,
+            r#"
+mod a {
+    use crate::b::foo;
+
+    pub(crate) fn make() -> u32 {
+        2
+    }
+
+    pub fn bar() -> u32 {
+        foo()
+    }
+}
+
+mod b {
+    use crate::a::make;
+
+    pub(crate) fn foo() -> u32 {
+        make()
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn move_into_module_file() {
+        check(
+            "inner",
+            r#"
+//- /main.rs
+mod inner;
+
+pub struct Id(pub u32);
+
+fn next$0(id: Id) -> Id {
+    Id(id.0 + 1)
+}
+
+fn main() {
+    let _ = next(Id(0));
+}
+//- /inner.rs
+pub fn zero() -> u32 {
+    0
+}
+"#,
+            r#"
+//- /main.rs
+use inner::next;
+
+mod inner;
+
+pub struct Id(pub u32);
+
+fn main() {
+    let _ = next(Id(0));
+}
+//- /inner.rs
+use crate::Id;
+
+pub fn zero() -> u32 {
+    0
+}
+
+pub(crate) fn next(id: Id) -> Id {
+    Id(id.0 + 1)
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn rewrite_use_trees() {
+        check(
+            "b",
+            r#"
+mod a {
+    pub struct Foo$0;
+}
+mod b {}
+
+use a::Foo;
+
+fn main() {
+    let _ = (Foo, a::Foo);
+}
+"#,
+            r#"
+mod a {
+}
+mod b {
+    pub struct Foo;
+}
+
+use b::Foo;
+
+fn main() {
+    let _ = (Foo, b::Foo);
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn report_renamed_nested_import() {
+        check_unrewritten(
+            "crate::b",
+            r#"
+mod a {
+    pub struct Foo$0;
+}
+mod b {}
+mod c {
+    use crate::a::{Foo as Renamed};
+                // ^^^ the reference is part of a nested import
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn unresolved_target() {
+        check_error(
+            "nope",
+            r#"
+fn foo$0() {}
+"#,
+            "Unresolved module `nope`",
+        );
+    }
+
+    #[test]
+    fn name_clash_in_target() {
+        check_error(
+            "a",
+            r#"
+mod a {
+    fn foo() {}
+}
+
+fn foo$0() {}
+"#,
+            "The target module already contains an item named `foo`",
+        );
+    }
+
+    #[test]
+    fn not_movable() {
+        check_error(
+            "a",
+            r#"
+mod a {}
+
+macro_rules! m$0 {
+    () => {};
+}
+"#,
+            "Only functions, types, traits, constants, statics and impls can be moved",
+        );
+    }
+}
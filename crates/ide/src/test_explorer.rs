@@ -1,11 +1,14 @@
 //! Discovers tests
 
-use hir::{Crate, Module, ModuleDef, Semantics};
+use hir::{ConstEvalError, Crate, Function, HasCrate, Module, ModuleDef, Semantics};
 use ide_db::base_db;
 use ide_db::{FileId, RootDatabase, base_db::RootQueryDb};
 use syntax::TextRange;
 
-use crate::{NavigationTarget, Runnable, TryToNav, runnables::runnable_fn};
+use crate::{
+    NavigationTarget, Runnable, TryToNav, interpret::render_const_eval_error,
+    runnables::runnable_fn,
+};
 
 #[derive(Debug)]
 pub enum TestItemKind {
@@ -25,6 +28,14 @@ pub struct TestItem {
     pub runnable: Option<Runnable>,
 }
 
+/// The outcome of running a test in the mir interpreter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestRunResult {
+    Passed,
+    Ignored,
+    Failed { message: String },
+}
+
 pub(crate) fn discover_test_roots(db: &RootDatabase) -> Vec<TestItem> {
     db.all_crates()
         .iter()
@@ -194,3 +205,335 @@ pub(crate) fn discover_tests_in_crate(
     r.extend(discover_tests_in_module(db, module, crate_test_id, false));
     r
 }
+
+/// Returns the ids of all tests that are selected by `test_id`, which can be the id of a crate,
+/// a module or a test function.
+pub(crate) fn tests_selected_by_test_id(db: &RootDatabase, test_id: &str) -> Vec<String> {
+    let crate_test_id = test_id.split_once("::").map_or(test_id, |it| it.0);
+    let Some(crate_id) = find_crate_by_id(db, crate_test_id) else {
+        return vec![];
+    };
+    discover_tests_in_crate(db, crate_id)
+        .into_iter()
+        .filter(|it| matches!(it.kind, TestItemKind::Function))
+        .map(|it| it.id)
+        .filter(|id| {
+            id.strip_prefix(test_id).is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+        })
+        .collect()
+}
+
+/// Runs the test with the given id in the mir interpreter, without building the project.
+pub(crate) fn interpret_test(db: &RootDatabase, test_id: &str) -> Option<TestRunResult> {
+    let test = find_test_fn(db, test_id)?;
+    if test.is_ignore(db) {
+        return Some(TestRunResult::Ignored);
+    }
+    let display_target = test.krate(db).to_display_target(db);
+    let (result, output) = match test.eval_with_output(db) {
+        Ok(it) => it,
+        Err(e) => {
+            let message = render_const_eval_error(db, e, display_target);
+            return Some(TestRunResult::Failed { message });
+        }
+    };
+    let mut message = match result {
+        Ok(()) if test.is_should_panic(db) => "test did not panic as expected\n".to_owned(),
+        Ok(()) => return Some(TestRunResult::Passed),
+        Err(e) if test.is_should_panic(db) && e.is_panic().is_some() => {
+            return Some(TestRunResult::Passed);
+        }
+        Err(e) => render_const_eval_error(db, ConstEvalError::MirEvalError(e), display_target),
+    };
+    let stdout = output.stdout();
+    if !stdout.is_empty() {
+        message += "\n--------- stdout ---------\n";
+        message += &stdout;
+    }
+    let stderr = output.stderr();
+    if !stderr.is_empty() {
+        message += "\n--------- stderr ---------\n";
+        message += &stderr;
+    }
+    Some(TestRunResult::Failed { message })
+}
+
+fn find_test_fn(db: &RootDatabase, test_id: &str) -> Option<Function> {
+    let (crate_test_id, path) = test_id.split_once("::")?;
+    let crate_id = find_crate_by_id(db, crate_test_id)?;
+    let (module_path, fn_name) = path.rsplit_once("::").unwrap_or(("", path));
+    let mut module = Crate::from(crate_id).root_module(db);
+    for segment in module_path.split("::").filter(|it| !it.is_empty()) {
+        module = module
+            .children(db)
+            .find(|it| it.name(db).is_some_and(|name| name.as_str() == segment))?;
+    }
+    module.declarations(db).into_iter().find_map(|def| match def {
+        ModuleDef::Function(f) if f.is_test(db) && f.name(db).as_str() == fn_name => Some(f),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::{Expect, expect};
+    use itertools::Itertools;
+
+    use crate::fixture;
+
+    use super::*;
+
+    fn check(#[rust_analyzer::rust_fixture] ra_fixture: &str, expect: Expect) {
+        let (analysis, file_id) = fixture::file(ra_fixture);
+        let tests = hir::attach_db(&analysis.db, || discover_tests_in_file(&analysis.db, file_id));
+        let actual = tests
+            .iter()
+            .map(|it| {
+                let kind = match it.kind {
+                    TestItemKind::Crate(_) => "crate",
+                    TestItemKind::Module => "module",
+                    TestItemKind::Function => "function",
+                };
+                format!("{kind} {}", it.id)
+            })
+            .join("\n");
+        expect.assert_eq(&actual);
+    }
+
+    #[test]
+    fn discovers_tests_generated_by_harness_macros() {
+        check(
+            r#"
+macro_rules! cases {
+    ($name:ident { $($case:ident: $value:expr),* $(,)? }) => {
+        mod $name {
+            $(
+                #[test]
+                fn $case() {
+                    assert!($value);
+                }
+            )*
+        }
+    };
+}
+
+cases!(parity {
+    even: 2 % 2 == 0,
+    odd: 3 % 2 == 1,
+});
+
+#[test]
+fn plain() {}
+"#,
+            expect![[r#"
+                crate ra_test_fixture
+                module ra_test_fixture::parity
+                function ra_test_fixture::parity::even
+                function ra_test_fixture::parity::odd
+                function ra_test_fixture::plain"#]],
+        );
+    }
+
+    #[test]
+    fn discovers_rstest_cases() {
+        // `input_replace` stands in for `#[rstest]`, expanding to what rstest generates.
+        check(
+            r#"
+//- proc_macros: input_replace
+#[proc_macros::input_replace(
+    fn sum(a: u32, b: u32) {
+        assert_eq!(a + b, 3);
+    }
+    mod sum {
+        use super::*;
+        #[test]
+        fn case_1() {
+            sum(1, 2)
+        }
+        #[test]
+        fn case_2_swapped() {
+            sum(2, 1)
+        }
+    }
+)]
+fn sum(#[case] a: u32, #[case] b: u32) {
+    assert_eq!(a + b, 3);
+}
+"#,
+            expect![[r#"
+                crate ra_test_fixture
+                module ra_test_fixture::sum
+                function ra_test_fixture::sum::case_1
+                function ra_test_fixture::sum::case_2_swapped"#]],
+        );
+    }
+
+    #[test]
+    fn discovers_test_case_cases() {
+        // `input_replace` stands in for `#[test_case(..)]`, expanding to what test_case
+        // generates.
+        check(
+            r#"
+//- proc_macros: input_replace
+#[proc_macros::input_replace(
+    mod is_even {
+        use super::*;
+        fn is_even(n: u32) {
+            assert_eq!(n % 2, 0);
+        }
+        #[test]
+        fn two() {
+            is_even(2)
+        }
+        #[test]
+        fn four() {
+            is_even(4)
+        }
+    }
+)]
+fn is_even(n: u32) {
+    assert_eq!(n % 2, 0);
+}
+"#,
+            expect![[r#"
+                crate ra_test_fixture
+                module ra_test_fixture::is_even
+                function ra_test_fixture::is_even::two
+                function ra_test_fixture::is_even::four"#]],
+        );
+    }
+
+    #[test]
+    fn discovers_proptest_tests() {
+        // The shape of `proptest::proptest!`, which turns the strategy arguments into a runner.
+        check(
+            r#"
+macro_rules! proptest {
+    ($(#[$meta:meta])* fn $name:ident($($arg:ident in $strategy:expr),*) $body:block $($rest:tt)*) => {
+        $(#[$meta])*
+        fn $name() {
+            $(let $arg = $strategy;)*
+            $body
+        }
+        proptest! { $($rest)* }
+    };
+    () => {};
+}
+
+proptest! {
+    #[test]
+    fn add_is_commutative(a in 0..10, b in 0..10) {
+        let _ = (a, b);
+    }
+
+    #[test]
+    fn parses_back(s in "[a-z]*") {
+        let _ = s;
+    }
+}
+"#,
+            expect![[r#"
+                crate ra_test_fixture
+                function ra_test_fixture::add_is_commutative
+                function ra_test_fixture::parses_back"#]],
+        );
+    }
+
+    #[test]
+    fn selects_tests_by_id() {
+        let (analysis, _) = fixture::file(
+            r#"
+#[test]
+fn top() {}
+
+mod a {
+    #[test]
+    fn first() {}
+
+    mod b {
+        #[test]
+        fn second() {}
+    }
+}
+
+mod ab {
+    #[test]
+    fn third() {}
+}
+"#,
+        );
+        let selected = |test_id| {
+            analysis.tests_selected_by_test_id(test_id).unwrap().into_iter().sorted().collect_vec()
+        };
+        assert_eq!(
+            selected("ra_test_fixture::a"),
+            ["ra_test_fixture::a::b::second", "ra_test_fixture::a::first"]
+        );
+        assert_eq!(selected("ra_test_fixture::top"), ["ra_test_fixture::top"]);
+        assert_eq!(selected("ra_test_fixture::a::b::second"), ["ra_test_fixture::a::b::second"]);
+        assert_eq!(selected("ra_test_fixture").len(), 4);
+        assert!(selected("ra_test_fixture::missing").is_empty());
+        assert!(selected("missing").is_empty());
+    }
+
+    #[test]
+    fn interprets_tests() {
+        let (analysis, _) = fixture::file(
+            r#"
+//- minicore: panic
+fn double(x: i32) -> i32 {
+    x * 2
+}
+
+#[test]
+fn passes() {
+    if double(2) != 4 {
+        panic!("wrong");
+    }
+}
+
+#[test]
+fn fails() {
+    panic!("always fails");
+}
+
+#[test]
+#[should_panic]
+fn panics() {
+    panic!("expected");
+}
+
+#[test]
+#[should_panic]
+fn does_not_panic() {}
+
+#[test]
+#[ignore]
+fn ignored() {
+    panic!("not run");
+}
+"#,
+        );
+        let result = |test_id: &str| {
+            let result = analysis.interpret_test(&format!("ra_test_fixture::{test_id}")).unwrap();
+            match result {
+                Some(TestRunResult::Failed { message }) => {
+                    format!("failed: {}", message.trim_end().lines().last().unwrap_or_default())
+                }
+                it => format!("{it:?}"),
+            }
+        };
+        expect![[r#"
+            passes: Some(Passed)
+            fails: failed: "always fails"
+            panics: Some(Passed)
+            does_not_panic: failed: test did not panic as expected
+            ignored: Some(Ignored)
+            missing: None"#]]
+        .assert_eq(
+            &["passes", "fails", "panics", "does_not_panic", "ignored", "missing"]
+                .map(|it| format!("{it}: {}", result(it)))
+                .join("\n"),
+        );
+    }
+}
@@ -1,12 +1,14 @@
-use std::fmt;
+use std::{fmt, ops::Range};
 
-use hir::{DisplayTarget, Field, HirDisplay, Layout, Semantics, Type};
+use hir::{DisplayTarget, Field, HirDisplay, Layout, LayoutNiche, LayoutTag, Semantics, Type};
 use ide_db::{
     RootDatabase,
     defs::Definition,
     helpers::{get_definition, pick_best_token},
 };
-use syntax::{AstNode, SyntaxKind};
+use itertools::Itertools;
+use stdx::format_to;
+use syntax::{AstNode, SyntaxKind, SyntaxToken, ast};
 
 use crate::FilePosition;
 
@@ -19,13 +21,16 @@ pub struct MemoryLayoutNode {
     pub parent_idx: i64,
     pub children_start: i64,
     pub children_len: u64,
+    /// Byte ranges, relative to the node, that are not covered by any of its fields.
+    pub padding: Vec<Range<u64>>,
+    pub niche: Option<LayoutNiche>,
+    pub tag: Option<LayoutTag>,
 }
 
 pub struct RecursiveMemoryLayout {
     pub nodes: Vec<MemoryLayoutNode>,
 }
 
-// NOTE: this is currently strictly for testing and so isn't super useful as a visualization tool, however it could be adapted to become one?
 impl fmt::Display for RecursiveMemoryLayout {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         fn process(
@@ -36,10 +41,26 @@ impl fmt::Display for RecursiveMemoryLayout {
         ) -> fmt::Result {
             let mut out = "\t".repeat(depth);
             let node = &nodes[idx];
-            out += &format!(
-                "{}: {} (size: {}, align: {}, field offset: {})\n",
-                node.item_name, node.typename, node.size, node.alignment, node.offset
+            format_to!(
+                out,
+                "{}: {} (size: {}, align: {}, field offset: {}",
+                node.item_name,
+                node.typename,
+                node.size,
+                node.alignment,
+                node.offset
             );
+            if !node.padding.is_empty() {
+                let padding = node.padding.iter().map(|r| format!("{}..{}", r.start, r.end));
+                format_to!(out, ", padding: [{}]", padding.format(", "));
+            }
+            if let Some(niche) = &node.niche {
+                format_to!(out, ", niche: {}", render_niche(niche));
+            }
+            if let Some(tag) = &node.tag {
+                format_to!(out, ", tag: {}", render_tag(tag));
+            }
+            out += ")\n";
             write!(fmt, "{out}")?;
             if node.children_start != -1 {
                 for j in nodes[idx].children_start
@@ -55,6 +76,153 @@ impl fmt::Display for RecursiveMemoryLayout {
     }
 }
 
+fn render_niche(niche: &LayoutNiche) -> String {
+    let (start, end) = niche.valid_range;
+    format!(
+        "{}..{} valid {start}..={end} ({} available)",
+        niche.offset,
+        niche.offset + niche.size,
+        niche.available
+    )
+}
+
+fn render_tag(tag: &LayoutTag) -> String {
+    match tag {
+        LayoutTag::Direct { offset, size } => format!("direct at {offset}..{}", offset + size),
+        LayoutTag::Niche { offset, size, untagged_variant, niche_variants, niche_start } => {
+            format!(
+                "niche at {offset}..{} (variants {}..={} from {niche_start}, untagged {untagged_variant})",
+                offset + size,
+                niche_variants.start(),
+                niche_variants.end(),
+            )
+        }
+    }
+}
+
+impl RecursiveMemoryLayout {
+    /// Renders the layout as an HTML page, with an SVG diagram of the bytes covered by every
+    /// node followed by a table of the nodes. Padding, niche and tag ranges in the table are
+    /// relative to their node.
+    pub fn to_html(&self) -> String {
+        const WIDTH: f64 = 800.0;
+        const ROW_HEIGHT: u64 = 32;
+
+        let mut offsets = Vec::with_capacity(self.nodes.len());
+        let mut depths = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            let (offset, depth) = match usize::try_from(node.parent_idx) {
+                Ok(parent) => (offsets[parent] + node.offset, depths[parent] + 1),
+                Err(_) => (node.offset, 0),
+            };
+            offsets.push(offset);
+            depths.push(depth);
+        }
+
+        let root = &self.nodes[0];
+        let scale = WIDTH / root.size.max(1) as f64;
+        let x = |offset: u64| offset as f64 * scale;
+        let height = (depths.iter().max().copied().unwrap_or(0) + 1) * ROW_HEIGHT;
+
+        let mut buf = String::new();
+        buf.push_str(STYLE);
+        format_to!(buf, "<h1>{}</h1>\n", html_escape(&root.typename));
+        format_to!(
+            buf,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{WIDTH}\" height=\"{height}\">\n"
+        );
+        for (idx, node) in self.nodes.iter().enumerate() {
+            let offset = offsets[idx];
+            let y = depths[idx] * ROW_HEIGHT;
+            format_to!(
+                buf,
+                "<g><title>{}: {} ({}..{})</title>\
+                 <rect class=\"node\" x=\"{:.1}\" y=\"{y}\" width=\"{:.1}\" height=\"{ROW_HEIGHT}\"/>\
+                 <text x=\"{:.1}\" y=\"{}\">{}</text></g>\n",
+                html_escape(&node.item_name),
+                html_escape(&node.typename),
+                offset,
+                offset + node.size,
+                x(offset),
+                x(node.size),
+                x(offset) + 4.0,
+                y + ROW_HEIGHT / 2 + 4,
+                html_escape(&node.item_name),
+            );
+            for padding in &node.padding {
+                format_to!(
+                    buf,
+                    "<rect class=\"padding\" x=\"{:.1}\" y=\"{y}\" width=\"{:.1}\" height=\"{ROW_HEIGHT}\"/>\n",
+                    x(offset + padding.start),
+                    x(padding.end - padding.start),
+                );
+            }
+            let tag_range = match &node.tag {
+                Some(
+                    LayoutTag::Direct { offset, size } | LayoutTag::Niche { offset, size, .. },
+                ) => Some((*offset, *size)),
+                None => None,
+            };
+            let niche_range = node.niche.as_ref().map(|niche| (niche.offset, niche.size));
+            for (class, range) in [("niche", niche_range), ("tag", tag_range)] {
+                if let Some((start, size)) = range {
+                    format_to!(
+                        buf,
+                        "<rect class=\"{class}\" x=\"{:.1}\" y=\"{}\" width=\"{:.1}\" height=\"6\"/>\n",
+                        x(offset + start),
+                        y + ROW_HEIGHT - 6,
+                        x(size),
+                    );
+                }
+            }
+        }
+        buf.push_str("</svg>\n");
+
+        buf.push_str(
+            "<table>\n<tr><th>item</th><th>type</th><th>offset</th><th>size</th><th>align</th>\
+             <th>padding</th><th>niche</th><th>tag</th></tr>\n",
+        );
+        for (idx, node) in self.nodes.iter().enumerate() {
+            let padding = node.padding.iter().map(|r| format!("{}..{}", r.start, r.end)).join(", ");
+            format_to!(
+                buf,
+                "<tr><td style=\"padding-left: {}em\">{}</td><td>{}</td><td>{}</td><td>{}</td>\
+                 <td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                depths[idx],
+                html_escape(&node.item_name),
+                html_escape(&node.typename),
+                offsets[idx],
+                node.size,
+                node.alignment,
+                padding,
+                node.niche.as_ref().map(render_niche).unwrap_or_default(),
+                node.tag.as_ref().map(render_tag).unwrap_or_default(),
+            );
+        }
+        buf.push_str("</table>\n");
+        buf
+    }
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+const STYLE: &str = "
+<style>
+body                { font-family: monospace; }
+svg                 { display: block; margin-bottom: 1em; }
+svg text            { font-size: 12px; pointer-events: none; }
+table               { border-collapse: collapse; }
+td, th              { border: 1px solid #CCCCCC; padding: 0.2em 0.6em; text-align: left; }
+
+.node               { fill: #DDEEFF; stroke: #336699; }
+.padding            { fill: #FFCCCC; stroke: #CC6666; stroke-dasharray: 2; }
+.niche              { fill: #99CC66; }
+.tag                { fill: #CC9933; }
+</style>
+";
+
 #[derive(Copy, Clone)]
 enum FieldOrTupleIdx {
     Field(Field),
@@ -72,7 +240,9 @@ impl FieldOrTupleIdx {
 
 // Feature: View Memory Layout
 //
-// Displays the recursive memory layout of a datatype.
+// Displays the recursive memory layout of a datatype, including its padding bytes, niches and
+// the encoding of enum discriminants. When the cursor is inside a type, the layout of that
+// type with its generic arguments is shown, e.g. for `Option<Box<T>>`.
 //
 // | Editor  | Action Name |
 // |---------|-------------|
@@ -90,18 +260,33 @@ pub(crate) fn view_memory_layout(
             _ => 0,
         })?;
 
-    let def = get_definition(&sema, token)?;
-
-    let ty = match def {
-        Definition::Adt(it) => it.ty(db),
-        Definition::TypeAlias(it) => it.ty(db),
-        Definition::BuiltinType(it) => it.ty(db),
-        Definition::SelfType(it) => it.self_ty(db),
-        Definition::Local(it) => it.ty(db),
-        Definition::Field(it) => it.ty(db).to_type(db),
-        Definition::Const(it) => it.ty(db),
-        Definition::Static(it) => it.ty(db),
-        _ => return None,
+    let (item_name, ty) = match type_at_token(&sema, &token) {
+        Some(ty) => ("[ROOT]".to_owned(), ty),
+        None => {
+            let def = get_definition(&sema, token)?;
+            let ty = match def {
+                Definition::Adt(it) => it.ty(db),
+                Definition::TypeAlias(it) => it.ty(db),
+                Definition::BuiltinType(it) => it.ty(db),
+                Definition::SelfType(it) => it.self_ty(db),
+                Definition::Local(it) => it.ty(db),
+                Definition::Field(it) => it.ty(db).to_type(db),
+                Definition::Const(it) => it.ty(db),
+                Definition::Static(it) => it.ty(db),
+                _ => return None,
+            };
+            let item_name = match def {
+                // def is a datatype
+                Definition::Adt(_)
+                | Definition::TypeAlias(_)
+                | Definition::BuiltinType(_)
+                | Definition::SelfType(_) => "[ROOT]".to_owned(),
+
+                // def is an item
+                def => def.name(db).map(|n| n.as_str().to_owned()).unwrap_or("[ROOT]".to_owned()),
+            };
+            (item_name, ty)
+        }
     };
 
     fn read_layout(
@@ -137,22 +322,31 @@ pub(crate) fn view_memory_layout(
         nodes[parent_idx].children_start = children_start as i64;
         nodes[parent_idx].children_len = fields.len() as u64;
 
+        let mut padding = Vec::new();
+        let mut covered = 0;
+        // The bytes of a field without a layout are unknown, so the padding can't be found.
+        let mut all_laid_out = true;
         for (field, child_ty) in fields.iter() {
             if let Ok(child_layout) = child_ty.layout(db) {
-                nodes.push(MemoryLayoutNode {
-                    item_name: field.name(db),
-                    typename: { child_ty.display(db, display_target).to_string() },
-                    size: child_layout.size(),
-                    alignment: child_layout.align(),
-                    offset: match *field {
-                        FieldOrTupleIdx::Field(f) => layout.field_offset(f).unwrap_or(0),
-                        FieldOrTupleIdx::TupleIdx(f) => layout.tuple_field_offset(f).unwrap_or(0),
-                    },
-                    parent_idx: parent_idx as i64,
-                    children_start: -1,
-                    children_len: 0,
-                });
+                let offset = match *field {
+                    FieldOrTupleIdx::Field(f) => layout.field_offset(f).unwrap_or(0),
+                    FieldOrTupleIdx::TupleIdx(f) => layout.tuple_field_offset(f).unwrap_or(0),
+                };
+                if offset > covered {
+                    padding.push(covered..offset);
+                }
+                covered = covered.max(offset + child_layout.size());
+                nodes.push(layout_node(
+                    field.name(db),
+                    child_ty,
+                    &child_layout,
+                    offset,
+                    parent_idx as i64,
+                    db,
+                    display_target,
+                ));
             } else {
+                all_laid_out = false;
                 nodes.push(MemoryLayoutNode {
                     item_name: field.name(db)
                         + format!("(no layout data: {:?})", child_ty.layout(db).unwrap_err())
@@ -164,9 +358,18 @@ pub(crate) fn view_memory_layout(
                     parent_idx: parent_idx as i64,
                     children_start: -1,
                     children_len: 0,
+                    padding: Vec::new(),
+                    niche: None,
+                    tag: None,
                 });
             }
         }
+        if layout.size() > covered {
+            padding.push(covered..layout.size());
+        }
+        if all_laid_out {
+            nodes[parent_idx].padding = padding;
+        }
 
         for (i, (_, child_ty)) in fields.iter().enumerate() {
             if let Ok(child_layout) = child_ty.layout(db) {
@@ -177,29 +380,7 @@ pub(crate) fn view_memory_layout(
 
     ty.layout(db)
         .map(|layout| {
-            let item_name = match def {
-                // def is a datatype
-                Definition::Adt(_)
-                | Definition::TypeAlias(_)
-                | Definition::BuiltinType(_)
-                | Definition::SelfType(_) => "[ROOT]".to_owned(),
-
-                // def is an item
-                def => def.name(db).map(|n| n.as_str().to_owned()).unwrap_or("[ROOT]".to_owned()),
-            };
-
-            let typename = ty.display(db, display_target).to_string();
-
-            let mut nodes = vec![MemoryLayoutNode {
-                item_name,
-                typename,
-                size: layout.size(),
-                offset: 0,
-                alignment: layout.align(),
-                parent_idx: -1,
-                children_start: -1,
-                children_len: 0,
-            }];
+            let mut nodes = vec![layout_node(item_name, &ty, &layout, 0, -1, db, display_target)];
             read_layout(&mut nodes, db, &ty, &layout, 0, display_target);
 
             RecursiveMemoryLayout { nodes }
@@ -207,12 +388,65 @@ pub(crate) fn view_memory_layout(
         .ok()
 }
 
+fn layout_node(
+    item_name: String,
+    ty: &Type<'_>,
+    layout: &Layout,
+    offset: u64,
+    parent_idx: i64,
+    db: &RootDatabase,
+    display_target: DisplayTarget,
+) -> MemoryLayoutNode {
+    MemoryLayoutNode {
+        item_name,
+        typename: ty.display(db, display_target).to_string(),
+        size: layout.size(),
+        alignment: layout.align(),
+        offset,
+        parent_idx,
+        children_start: -1,
+        children_len: 0,
+        padding: Vec::new(),
+        niche: layout.niche().filter(|niche| niche.available != 0),
+        tag: layout.tag(),
+    }
+}
+
+/// Finds the outermost type reference the token is part of, so that generic arguments written
+/// at the cursor are taken into account.
+fn type_at_token<'db>(
+    sema: &Semantics<'db, RootDatabase>,
+    token: &SyntaxToken,
+) -> Option<Type<'db>> {
+    let ty = token
+        .parent_ancestors()
+        .take_while(|it| {
+            ast::Type::can_cast(it.kind())
+                || matches!(
+                    it.kind(),
+                    SyntaxKind::PATH
+                        | SyntaxKind::PATH_SEGMENT
+                        | SyntaxKind::NAME_REF
+                        | SyntaxKind::GENERIC_ARG_LIST
+                        | SyntaxKind::TYPE_ARG
+                )
+        })
+        .filter_map(ast::Type::cast)
+        .last()?;
+    // Without generic arguments the type is resolved through the definition under the cursor
+    // instead, which also handles the names of items.
+    if !ty.syntax().descendants().any(|it| it.kind() == SyntaxKind::GENERIC_ARG_LIST) {
+        return None;
+    }
+    sema.resolve_type(&ty)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use crate::fixture;
-    use expect_test::expect;
+    use expect_test::{expect, expect_file};
 
     fn make_memory_layout(
         #[rust_analyzer::rust_fixture] ra_fixture: &str,
@@ -249,7 +483,7 @@ fn main() {
     #[test]
     fn view_memory_layout_constant() {
         expect![[r#"
-            BLAH: bool (size: 1, align: 1, field offset: 0)
+            BLAH: bool (size: 1, align: 1, field offset: 0, niche: 0..1 valid 0..=1 (254 available))
         "#]]
         .assert_eq(
             &make_memory_layout(
@@ -265,7 +499,7 @@ const BLAH$0: bool = 0;
     #[test]
     fn view_memory_layout_static() {
         expect![[r#"
-            BLAH: bool (size: 1, align: 1, field offset: 0)
+            BLAH: bool (size: 1, align: 1, field offset: 0, niche: 0..1 valid 0..=1 (254 available))
         "#]]
         .assert_eq(
             &make_memory_layout(
@@ -281,7 +515,7 @@ static BLAH$0: bool = 0;
     #[test]
     fn view_memory_layout_tuple() {
         expect![[r#"
-            x: (f64, u8, i64) (size: 24, align: 8, field offset: 0)
+            x: (f64, u8, i64) (size: 24, align: 8, field offset: 0, padding: [9..16])
             	.0: f64 (size: 8, align: 8, field offset: 0)
             	.1: u8 (size: 1, align: 1, field offset: 8)
             	.2: i64 (size: 8, align: 8, field offset: 16)
@@ -302,9 +536,9 @@ fn main() {
     #[test]
     fn view_memory_layout_c_struct() {
         expect![[r#"
-            [ROOT]: Blah (size: 16, align: 4, field offset: 0)
+            [ROOT]: Blah (size: 16, align: 4, field offset: 0, padding: [13..16])
             	a: u32 (size: 4, align: 4, field offset: 0)
-            	b: (i32, u8) (size: 8, align: 4, field offset: 4)
+            	b: (i32, u8) (size: 8, align: 4, field offset: 4, padding: [5..8])
             		.0: i32 (size: 4, align: 4, field offset: 0)
             		.1: u8 (size: 1, align: 1, field offset: 4)
             	c: i8 (size: 1, align: 1, field offset: 12)
@@ -328,8 +562,8 @@ struct Blah$0 {
     #[test]
     fn view_memory_layout_struct() {
         expect![[r#"
-            [ROOT]: Blah (size: 16, align: 4, field offset: 0)
-            	b: (i32, u8) (size: 8, align: 4, field offset: 0)
+            [ROOT]: Blah (size: 16, align: 4, field offset: 0, padding: [13..16])
+            	b: (i32, u8) (size: 8, align: 4, field offset: 0, padding: [5..8])
             		.0: i32 (size: 4, align: 4, field offset: 0)
             		.1: u8 (size: 1, align: 1, field offset: 4)
             	a: u32 (size: 4, align: 4, field offset: 8)
@@ -353,7 +587,7 @@ struct Blah$0 {
     #[test]
     fn view_memory_layout_member() {
         expect![[r#"
-            a: bool (size: 1, align: 1, field offset: 0)
+            a: bool (size: 1, align: 1, field offset: 0, niche: 0..1 valid 0..=1 (254 available))
         "#]]
         .assert_eq(
             &make_memory_layout(
@@ -369,6 +603,85 @@ struct Oof {
         );
     }
 
+    #[test]
+    fn view_memory_layout_enum() {
+        expect![[r#"
+            [ROOT]: Foo (size: 1, align: 1, field offset: 0, niche: 0..1 valid 0..=2 (253 available), tag: direct at 0..1)
+        "#]]
+        .assert_eq(
+            &make_memory_layout(
+                r#"
+enum Foo$0 {
+    A,
+    B,
+    C,
+}
+"#,
+            )
+            .unwrap()
+            .to_string(),
+        );
+    }
+
+    #[test]
+    fn view_memory_layout_niche_encoded_enum() {
+        expect![[r#"
+            [ROOT]: Option<bool> (size: 1, align: 1, field offset: 0, niche: 0..1 valid 0..=2 (253 available), tag: niche at 0..1 (variants 0..=0 from 2, untagged 1))
+        "#]]
+        .assert_eq(
+            &make_memory_layout(
+                r#"
+//- minicore: option
+fn main() {
+    let x: Option$0<bool> = None;
+}
+"#,
+            )
+            .unwrap()
+            .to_string(),
+        );
+    }
+
+    #[test]
+    fn view_memory_layout_generic_instantiation() {
+        expect![[r#"
+            [ROOT]: Pair<u16> (size: 4, align: 2, field offset: 0, padding: [3..4])
+            	a: u16 (size: 2, align: 2, field offset: 0)
+            	b: u8 (size: 1, align: 1, field offset: 2)
+        "#]]
+        .assert_eq(
+            &make_memory_layout(
+                r#"
+struct Pair<T> {
+    a: T,
+    b: u8,
+}
+
+fn main() {
+    let _: Pair<u$016>;
+}
+"#,
+            )
+            .unwrap()
+            .to_string(),
+        );
+    }
+
+    #[test]
+    fn view_memory_layout_html() {
+        let html = make_memory_layout(
+            r#"
+struct Foo$0 {
+    a: u16,
+    b: bool,
+}
+"#,
+        )
+        .unwrap()
+        .to_html();
+        expect_file!["./test_data/view_memory_layout.html"].assert_eq(&html);
+    }
+
     #[test]
     fn view_memory_layout_alias() {
         let ml_a = make_memory_layout(
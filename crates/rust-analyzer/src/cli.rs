@@ -3,6 +3,7 @@
 #![allow(clippy::print_stdout, clippy::print_stderr)]
 
 mod analysis_stats;
+mod debug_adapter;
 mod diagnostics;
 pub mod flags;
 mod highlight;
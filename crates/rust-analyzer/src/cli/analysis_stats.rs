@@ -1192,6 +1192,9 @@ impl flags::AnalysisStats {
                     prefer_prelude: true,
                     prefer_absolute: false,
                     style_lints: false,
+                    unused_imports: false,
+                    dead_code: false,
+                    feature_gates: false,
                     term_search_fuel: 400,
                     term_search_borrowck: true,
                     show_rename_conflicts: true,
@@ -1261,6 +1264,7 @@ impl flags::AnalysisStats {
             location: ide::AnnotationLocation::AboveName,
             filter_adjacent_derive_implementations: false,
             minicore: MiniCore::default(),
+            coverage: None,
         };
         for &file_id in file_ids {
             let msg = format!("annotations: {}", vfs.file_path(file_id.file_id(db)));
@@ -0,0 +1,532 @@
+//! A minimal Debug Adapter Protocol server, which steps through a function using the mir
+//! interpreter.
+//!
+//! The adapter speaks DAP over stdio. The `launch` request expects a `program` argument
+//! pointing to a directory with `Cargo.toml` or `rust-project.json`, and a `function`
+//! argument with the full path of a function without parameters, e.g. `my_crate::foo::bar`.
+
+use std::{
+    io::{self, BufRead, Read, Write},
+    ops::ControlFlow,
+    path::PathBuf,
+};
+
+use anyhow::{Context, format_err};
+use hir::{
+    Crate, DebugFrame, DebugHook, DefWithBody, DisplayTarget, Function, HasCrate, db::HirDatabase,
+};
+use ide_db::{FxHashMap, LineIndexDatabase, RootDatabase};
+use load_cargo::{LoadCargoConfig, ProcMacroServerChoice, load_workspace_at};
+use project_model::{CargoConfig, RustLibSource};
+use serde_json::{Value, json};
+use syntax::TextRange;
+use vfs::{AbsPathBuf, FileId, Vfs, VfsPath};
+
+use crate::cli::{flags, full_name_of_item};
+
+impl flags::DebugAdapter {
+    pub fn run(self) -> anyhow::Result<()> {
+        let mut conn = Connection::new(Box::new(io::stdin().lock()), Box::new(io::stdout()));
+        // The proc-macro server has to live as long as the database using it.
+        let mut proc_macro_client = None;
+        serve(&mut conn, |program| {
+            let cargo_config = CargoConfig {
+                sysroot: Some(RustLibSource::Discover),
+                all_targets: true,
+                set_test: true,
+                ..Default::default()
+            };
+            let load_cargo_config = LoadCargoConfig {
+                load_out_dirs_from_check: true,
+                with_proc_macro_server: ProcMacroServerChoice::Sysroot,
+                prefill_caches: false,
+                proc_macro_processes: 1,
+            };
+            let (db, vfs, client) = load_workspace_at(
+                &PathBuf::from(program),
+                &cargo_config,
+                &load_cargo_config,
+                &|_| {},
+            )?;
+            proc_macro_client = client;
+            Ok((db, vfs))
+        })
+    }
+}
+
+/// Serves a debug session over `conn`, loading the workspace of the `program` launch argument
+/// with `load`.
+fn serve(
+    conn: &mut Connection,
+    load: impl FnOnce(&str) -> anyhow::Result<(RootDatabase, Vfs)>,
+) -> anyhow::Result<()> {
+    let mut breakpoints = FxHashMap::default();
+    let mut launch = None;
+    // Handle the configuration phase, until the client tells us to start.
+    loop {
+        let Some(req) = conn.recv()? else { return Ok(()) };
+        match req["command"].as_str().unwrap_or_default() {
+            "initialize" => {
+                conn.respond(&req, json!({ "supportsConfigurationDoneRequest": true }))?;
+                conn.event("initialized", json!({}))?;
+            }
+            "launch" => {
+                launch = Some(req["arguments"].clone());
+                conn.respond(&req, json!({}))?;
+            }
+            "setBreakpoints" => {
+                let body = set_breakpoints(&mut breakpoints, &req["arguments"]);
+                conn.respond(&req, body)?;
+            }
+            "configurationDone" => {
+                conn.respond(&req, json!({}))?;
+                break;
+            }
+            "disconnect" | "terminate" => {
+                conn.respond(&req, json!({}))?;
+                return Ok(());
+            }
+            _ => conn.respond(&req, json!({}))?,
+        }
+    }
+    let launch = launch.context("`configurationDone` received before `launch`")?;
+    let output = match run_function(conn, breakpoints, &launch, load) {
+        Ok(it) => it,
+        Err(e) => format!("{e:#}\n"),
+    };
+    conn.event("output", json!({ "category": "stdout", "output": output }))?;
+    conn.event("exited", json!({ "exitCode": i32::from(output.trim() != "pass") }))?;
+    conn.event("terminated", json!({}))?;
+    while let Some(req) = conn.recv()? {
+        conn.respond(&req, json!({}))?;
+        if matches!(req["command"].as_str(), Some("disconnect" | "terminate")) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn run_function(
+    conn: &mut Connection,
+    breakpoints: FxHashMap<VfsPath, Vec<u32>>,
+    launch: &Value,
+    load: impl FnOnce(&str) -> anyhow::Result<(RootDatabase, Vfs)>,
+) -> anyhow::Result<String> {
+    let program = launch["program"].as_str().context("missing `program` launch argument")?;
+    let function = launch["function"].as_str().context("missing `function` launch argument")?;
+    let (ref db, vfs) = load(program)?;
+    let func = find_function(db, function)
+        .ok_or_else(|| format_err!("function `{function}` not found"))?;
+    let mut session = Session {
+        conn,
+        db,
+        vfs: &vfs,
+        breakpoints,
+        display_target: func.krate(db).to_display_target(db),
+        mode: if launch["stopOnEntry"].as_bool() == Some(true) {
+            StepMode::StepIn
+        } else {
+            StepMode::Continue
+        },
+        last_location: None,
+    };
+    let span_formatter = |file_id, text_range: TextRange| {
+        let path = vfs.file_path(file_id);
+        match db.line_index(file_id).try_line_col(text_range.start()) {
+            Some(line_col) => format!("{path}:{}:{}", line_col.line + 1, line_col.col + 1),
+            None => format!("{path} range {text_range:?}"),
+        }
+    };
+    let result = hir::attach_db(db, || {
+        func.eval_with_debug_hook(db, Box::new(Hook(&mut session)), span_formatter)
+    });
+    result.map_err(|e| format_err!("{e:?}"))
+}
+
+fn find_function(db: &dyn HirDatabase, path: &str) -> Option<Function> {
+    let mut worklist: Vec<_> = Crate::all(db)
+        .into_iter()
+        .filter(|krate| krate.origin(db).is_local())
+        .map(|krate| krate.root_module(db))
+        .collect();
+    while let Some(module) = worklist.pop() {
+        worklist.extend(module.children(db));
+        let found = module.declarations(db).into_iter().find_map(|def| match def {
+            hir::ModuleDef::Function(f)
+                if full_name_of_item(db, f.module(db), f.name(db)) == path =>
+            {
+                Some(f)
+            }
+            _ => None,
+        });
+        if found.is_some() {
+            return found;
+        }
+    }
+    None
+}
+
+fn set_breakpoints(breakpoints: &mut FxHashMap<VfsPath, Vec<u32>>, args: &Value) -> Value {
+    let Some(path) = args["source"]["path"].as_str() else {
+        return json!({ "breakpoints": [] });
+    };
+    let lines: Vec<u32> = args["breakpoints"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|it| it["line"].as_u64())
+        .map(|line| line as u32)
+        .collect();
+    // Breakpoints can only be matched with the files of the workspace by their absolute path.
+    let Ok(path) = AbsPathBuf::try_from(path) else {
+        let result = lines.iter().map(|line| {
+            json!({ "verified": false, "line": line, "message": "the source path is not absolute" })
+        });
+        return json!({ "breakpoints": result.collect::<Vec<_>>() });
+    };
+    let result = lines.iter().map(|line| json!({ "verified": true, "line": line })).collect();
+    breakpoints.insert(VfsPath::from(path), lines);
+    json!({ "breakpoints": Value::Array(result) })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StepMode {
+    Continue,
+    StepIn,
+    Next { depth: usize },
+    StepOut { depth: usize },
+}
+
+struct Session<'a> {
+    conn: &'a mut Connection,
+    db: &'a RootDatabase,
+    vfs: &'a Vfs,
+    /// One-based line numbers of the breakpoints, per file.
+    breakpoints: FxHashMap<VfsPath, Vec<u32>>,
+    display_target: DisplayTarget,
+    mode: StepMode,
+    /// The line of the previous step, so that we stop at most once per line.
+    last_location: Option<(FileId, u32)>,
+}
+
+struct Hook<'s, 'a>(&'s mut Session<'a>);
+
+impl<'db> DebugHook<'db> for Hook<'_, '_> {
+    fn on_step(&mut self, frame: &DebugFrame<'_, 'db>) -> ControlFlow<()> {
+        self.0.on_step(frame)
+    }
+}
+
+impl Session<'_> {
+    fn on_step(&mut self, frame: &DebugFrame<'_, '_>) -> ControlFlow<()> {
+        let Some(location) = self.location(frame.owner(), frame.span()) else {
+            return ControlFlow::Continue(());
+        };
+        if self.last_location == Some(location) {
+            return ControlFlow::Continue(());
+        }
+        self.last_location = Some(location);
+        let depth = frame.depth();
+        let reason = match self.mode {
+            StepMode::StepIn => Some("step"),
+            StepMode::Next { depth: start } if depth <= start => Some("step"),
+            StepMode::StepOut { depth: start } if depth < start => Some("step"),
+            _ => None,
+        };
+        let reason = reason.or_else(|| {
+            let (file_id, line) = location;
+            let lines = self.breakpoints.get(self.vfs.file_path(file_id))?;
+            lines.contains(&(line + 1)).then_some("breakpoint")
+        });
+        match reason {
+            Some(reason) => self.pause(frame, reason).unwrap_or(ControlFlow::Break(())),
+            None => ControlFlow::Continue(()),
+        }
+    }
+
+    /// Reports the stop to the client and serves its requests until it asks to resume.
+    fn pause(&mut self, frame: &DebugFrame<'_, '_>, reason: &str) -> io::Result<ControlFlow<()>> {
+        self.conn.event("stopped", json!({ "reason": reason, "threadId": 1 }))?;
+        let depth = frame.depth();
+        while let Some(req) = self.conn.recv()? {
+            let args = &req["arguments"];
+            let mode = match req["command"].as_str().unwrap_or_default() {
+                "continue" => StepMode::Continue,
+                "next" => StepMode::Next { depth },
+                "stepIn" => StepMode::StepIn,
+                "stepOut" => StepMode::StepOut { depth },
+                "disconnect" | "terminate" => {
+                    self.conn.respond(&req, json!({}))?;
+                    return Ok(ControlFlow::Break(()));
+                }
+                command => {
+                    let body = match command {
+                        "threads" => json!({ "threads": [{ "id": 1, "name": "main" }] }),
+                        "stackTrace" => self.stack_trace(frame),
+                        "scopes" => {
+                            let frame_id = args["frameId"].as_u64().unwrap_or_default();
+                            json!({ "scopes": [{
+                                "name": "Locals",
+                                "variablesReference": frame_id + 1,
+                                "expensive": false,
+                            }] })
+                        }
+                        "variables" => {
+                            // Scope references are one-based, `0` means there are no variables.
+                            let Some(frame_id) = args["variablesReference"]
+                                .as_u64()
+                                .and_then(|it| it.checked_sub(1))
+                            else {
+                                self.conn.respond_error(&req, "invalid `variablesReference`")?;
+                                continue;
+                            };
+                            self.variables(frame, frame_id as usize)
+                        }
+                        "setBreakpoints" => set_breakpoints(&mut self.breakpoints, args),
+                        _ => json!({}),
+                    };
+                    self.conn.respond(&req, body)?;
+                    continue;
+                }
+            };
+            self.conn.respond(&req, json!({ "allThreadsContinued": true }))?;
+            self.mode = mode;
+            return Ok(ControlFlow::Continue(()));
+        }
+        Ok(ControlFlow::Break(()))
+    }
+
+    fn stack_trace(&self, frame: &DebugFrame<'_, '_>) -> Value {
+        let db = frame.db();
+        let frames: Vec<_> = frame
+            .stack()
+            .into_iter()
+            .enumerate()
+            .map(|(id, (owner, span))| {
+                let name = DefWithBody::from(owner)
+                    .name(db)
+                    .map(|it| it.display(db, self.display_target.edition).to_string())
+                    .unwrap_or_else(|| "<anonymous>".to_owned());
+                let mut result = json!({ "id": id, "name": name, "line": 0, "column": 0 });
+                if let Some((file_id, line)) = self.location(owner, span) {
+                    result["source"] = json!({ "path": self.vfs.file_path(file_id).to_string() });
+                    result["line"] = json!(line + 1);
+                    result["column"] = json!(1);
+                }
+                result
+            })
+            .collect();
+        json!({ "stackFrames": frames, "totalFrames": frames.len() })
+    }
+
+    fn variables(&self, frame: &DebugFrame<'_, '_>, frame_id: usize) -> Value {
+        let variables: Vec<_> = frame
+            .locals(frame_id, self.display_target)
+            .into_iter()
+            .map(|local| {
+                json!({
+                    "name": local.name.as_str(),
+                    "type": local.ty,
+                    "value": local.value.as_deref().unwrap_or("<unavailable>"),
+                    "variablesReference": 0,
+                })
+            })
+            .collect();
+        json!({ "variables": variables })
+    }
+
+    /// Returns the file and the zero-based line of a span, if it is not inside a macro expansion.
+    fn location(
+        &self,
+        owner: hir_def::DefWithBodyId,
+        span: hir::mir::MirSpan,
+    ) -> Option<(FileId, u32)> {
+        let source = hir::mir::mir_span_to_syntax(self.db, owner, span)?;
+        let file_id = source.file_id.file_id()?.file_id(self.db);
+        let line_col =
+            self.db.line_index(file_id).try_line_col(source.value.text_range().start())?;
+        Some((file_id, line_col.line))
+    }
+}
+
+/// Reads and writes DAP messages, which use the same base protocol as LSP.
+struct Connection {
+    seq: u64,
+    input: Box<dyn BufRead>,
+    output: Box<dyn Write>,
+}
+
+impl Connection {
+    fn new(input: Box<dyn BufRead>, output: Box<dyn Write>) -> Connection {
+        Connection { seq: 0, input, output }
+    }
+
+    fn recv(&mut self) -> io::Result<Option<Value>> {
+        let mut size = None;
+        let mut buf = String::new();
+        loop {
+            buf.clear();
+            if self.input.read_line(&mut buf)? == 0 {
+                return Ok(None);
+            }
+            let line = buf.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some(len) = line.strip_prefix("Content-Length: ") {
+                size = len.parse::<usize>().ok();
+            }
+        }
+        let size =
+            size.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no Content-Length"))?;
+        let mut body = vec![0; size];
+        self.input.read_exact(&mut body)?;
+        Ok(Some(serde_json::from_slice(&body)?))
+    }
+
+    fn respond(&mut self, req: &Value, body: Value) -> io::Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": req["seq"],
+            "command": req["command"],
+            "success": true,
+            "body": body,
+        }))
+    }
+
+    fn respond_error(&mut self, req: &Value, message: &str) -> io::Result<()> {
+        self.send(json!({
+            "type": "response",
+            "request_seq": req["seq"],
+            "command": req["command"],
+            "success": false,
+            "message": message,
+        }))
+    }
+
+    fn event(&mut self, event: &str, body: Value) -> io::Result<()> {
+        self.send(json!({ "type": "event", "event": event, "body": body }))
+    }
+
+    fn send(&mut self, mut msg: Value) -> io::Result<()> {
+        self.seq += 1;
+        msg["seq"] = json!(self.seq);
+        let msg = serde_json::to_string(&msg)?;
+        write!(self.output, "Content-Length: {}\r\n\r\n{msg}", msg.len())?;
+        self.output.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use expect_test::expect;
+    use itertools::Itertools;
+    use test_fixture::ChangeFixture;
+
+    use super::*;
+
+    /// Collects what the adapter writes, to be read after the session.
+    #[derive(Clone, Default)]
+    struct Output(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Output {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn stops_at_breakpoint() {
+        let fixture = r#"
+//- /main.rs
+fn add(a: i32, b: i32) -> i32 {
+    let sum = a + b;
+    sum
+}
+
+fn main() {
+    let x = 1;
+    let _y = add(x, 2);
+}
+"#;
+        let root = AbsPathBuf::assert_utf8(std::env::current_dir().unwrap());
+        let main_rs = root.join("main.rs");
+        let requests = [
+            json!({ "command": "initialize", "arguments": {} }),
+            json!({ "command": "setBreakpoints", "arguments": {
+                "source": { "path": main_rs.to_string() },
+                "breakpoints": [{ "line": 3 }],
+            } }),
+            json!({ "command": "setBreakpoints", "arguments": {
+                "source": { "path": "relative/main.rs" },
+                "breakpoints": [{ "line": 1 }],
+            } }),
+            json!({ "command": "launch", "arguments": {
+                "program": "unused",
+                "function": "main",
+            } }),
+            json!({ "command": "configurationDone" }),
+            json!({ "command": "stackTrace", "arguments": { "threadId": 1 } }),
+            json!({ "command": "variables", "arguments": { "variablesReference": 1 } }),
+            json!({ "command": "variables", "arguments": { "variablesReference": 2 } }),
+            json!({ "command": "continue", "arguments": { "threadId": 1 } }),
+            json!({ "command": "disconnect" }),
+        ];
+        let mut input = String::new();
+        for (seq, mut req) in requests.into_iter().enumerate() {
+            req["seq"] = json!(seq + 1);
+            req["type"] = json!("request");
+            let req = req.to_string();
+            input += &format!("Content-Length: {}\r\n\r\n{req}", req.len());
+        }
+        let output = Output::default();
+        let mut conn = Connection::new(Box::new(io::Cursor::new(input)), Box::new(output.clone()));
+        serve(&mut conn, |_| {
+            let fixture = ChangeFixture::parse(fixture);
+            let mut db = RootDatabase::default();
+            db.apply_change(fixture.change);
+            // The fixture numbers its files like the vfs does.
+            let mut vfs = Vfs::default();
+            vfs.set_file_contents(VfsPath::from(main_rs.clone()), Some(Vec::new()));
+            Ok((db, vfs))
+        })
+        .unwrap();
+
+        let output = String::from_utf8(output.0.take()).unwrap();
+        let messages = output
+            .split("Content-Length: ")
+            .filter_map(|it| it.split_once("\r\n\r\n"))
+            .map(|(_, msg)| {
+                let msg: Value = serde_json::from_str(msg).unwrap();
+                let (kind, name) = match msg["type"].as_str() {
+                    Some("event") => ("event", &msg["event"]),
+                    _ => ("response", &msg["command"]),
+                };
+                let name = name.as_str().unwrap_or_default();
+                format!("{kind} {name}: {}", msg["body"]).replace(root.as_str(), "$ROOT")
+            })
+            .join("\n");
+        expect![[r#"
+            response initialize: {"supportsConfigurationDoneRequest":true}
+            event initialized: {}
+            response setBreakpoints: {"breakpoints":[{"verified":true,"line":3}]}
+            response setBreakpoints: {"breakpoints":[{"verified":false,"line":1,"message":"the source path is not absolute"}]}
+            response launch: {}
+            response configurationDone: {}
+            event stopped: {"reason":"breakpoint","threadId":1}
+            response stackTrace: {"stackFrames":[{"id":0,"name":"add","line":3,"column":1,"source":{"path":"$ROOT/main.rs"}},{"id":1,"name":"main","line":8,"column":1,"source":{"path":"$ROOT/main.rs"}}],"totalFrames":2}
+            response variables: {"variables":[{"name":"a","type":"i32","value":"1","variablesReference":0},{"name":"b","type":"i32","value":"2","variablesReference":0},{"name":"sum","type":"i32","value":"3","variablesReference":0}]}
+            response variables: {"variables":[{"name":"_y","type":"i32","value":"<unavailable>","variablesReference":0},{"name":"x","type":"i32","value":"1","variablesReference":0}]}
+            response continue: {"allThreadsContinued":true}
+            event output: {"category":"stdout","output":"pass"}
+            event exited: {"exitCode":0}
+            event terminated: {}
+            response disconnect: {}"#]].assert_eq(&messages);
+    }
+}
@@ -116,6 +116,9 @@ xflags::xflags! {
             optional --filter path: String
         }
 
+        /// Run a Debug Adapter Protocol server on stdio, stepping through code using mir interpreter
+        cmd debug-adapter {}
+
         cmd diagnostics {
             /// Directory with Cargo.toml or rust-project.json.
             required path: PathBuf
@@ -215,6 +218,7 @@ pub enum RustAnalyzerCmd {
     AnalysisStats(AnalysisStats),
     RunTests(RunTests),
     RustcTests(RustcTests),
+    DebugAdapter(DebugAdapter),
     Diagnostics(Diagnostics),
     UnresolvedReferences(UnresolvedReferences),
     PrimeCaches(PrimeCaches),
@@ -280,6 +284,9 @@ pub struct RustcTests {
     pub filter: Option<String>,
 }
 
+#[derive(Debug)]
+pub struct DebugAdapter;
+
 #[derive(Debug)]
 pub struct Diagnostics {
     pub path: PathBuf,
@@ -11,8 +11,9 @@ use ide::{
     AnnotationConfig, AssistConfig, CallHierarchyConfig, CallableSnippets, CompletionConfig,
     CompletionFieldsToResolve, DiagnosticsConfig, GenericParameterHints, GotoDefinitionConfig,
     GotoImplementationConfig, HighlightConfig, HighlightRelatedConfig, HoverConfig, HoverDocFormat,
-    InlayFieldsToResolve, InlayHintsConfig, JoinLinesConfig, MemoryLayoutHoverConfig,
-    MemoryLayoutHoverRenderKind, RenameConfig, Snippet, SnippetScope, SourceRootId,
+    InlayFieldsToResolve, InlayHintsConfig, JoinLinesConfig, LineCoverage, MemoryLayoutHoverConfig,
+    MemoryLayoutHoverRenderKind, MoveToModuleConfig, RenameConfig, Snippet, SnippetScope,
+    SourceRootId,
 };
 use ide_db::{
     MiniCore, SnippetCap,
@@ -40,6 +41,7 @@ use crate::{
     flycheck::{CargoOptions, FlycheckConfig},
     lsp::capabilities::ClientCapabilities,
     lsp_ext::{WorkspaceSymbolSearchKind, WorkspaceSymbolSearchScope},
+    test_runner::TestRunner,
 };
 
 type FxIndexMap<K, V> = indexmap::IndexMap<K, V, rustc_hash::FxBuildHasher>;
@@ -91,6 +93,13 @@ config_data! {
         completion_snippets_custom: FxIndexMap<String, SnippetDef> =
             Config::completion_snippets_default(),
 
+        /// Path to a coverage report in lcov format or in the JSON format of `llvm-cov export`,
+        /// e.g. produced by `cargo llvm-cov --lcov --output-path lcov.info`. Relative paths are
+        /// resolved against the workspace root.
+        ///
+        /// The report is reloaded when it changes, if the client watches files.
+        coverage_reportPath: Option<Utf8PathBuf> = None,
+
         /// List of files to ignore
         ///
         /// These paths (file/directories) will be ignored by rust-analyzer. They are relative to
@@ -328,6 +337,11 @@ config_data! {
         /// Join lines unwraps trivial blocks.
         joinLines_unwrapTrivialBlock: bool = true,
 
+        /// Show `Coverage` lens with the share of executed lines for functions, when a coverage
+        /// report is configured with `#rust-analyzer.coverage.reportPath#`. Only applies when
+        /// `#rust-analyzer.lens.enable#` is set.
+        lens_coverage_enable: bool = true,
+
         /// Show `Debug` lens. Only applies when `#rust-analyzer.lens.enable#` is set.
         lens_debug_enable: bool = true,
 
@@ -613,6 +627,13 @@ config_data! {
         /// Placeholder expression to use for missing expressions in assists.
         assist_expressionFillDefault: ExprFillDefaultDef = ExprFillDefaultDef::Todo,
 
+        /// Make the setters of builders generated by the "generate builder" assist take
+        /// `impl Into<T>` instead of `T`.
+        assist_generateBuilder_intoSetters: bool = false,
+
+        /// Prefix the setters of builders generated by the "generate builder" assist with `with_`.
+        assist_generateBuilder_withPrefix: bool = false,
+
         /// Prefer to use `Self` over the type name when inserting a type (e.g. in "fill match arms" assist).
         assist_preferSelf: bool = false,
 
@@ -698,6 +719,10 @@ config_data! {
         /// Term search fuel in "units of work" for autocompletion (Defaults to 1000).
         completion_termSearch_fuel: usize = 1000,
 
+        /// Report private functions, constants, statics, fields and enum variants that are never
+        /// used, without waiting for `cargo check`.
+        diagnostics_deadCode_enable: bool = false,
+
         /// List of rust-analyzer diagnostics to disable.
         diagnostics_disabled: FxHashSet<String> = FxHashSet::default(),
 
@@ -708,6 +733,10 @@ config_data! {
         /// usual.
         diagnostics_experimental_enable: bool = false,
 
+        /// Report unstable standard library items and unstable syntax that are used without
+        /// enabling their feature with `#![feature(...)]`.
+        diagnostics_featureGates_enable: bool = false,
+
         /// Map of prefixes to be substituted when parsing diagnostic file paths. This should be the
         /// reverse mapping of what is passed to `rustc` as `--remap-path-prefix`.
         diagnostics_remapPrefix: FxHashMap<String, String> = FxHashMap::default(),
@@ -715,6 +744,9 @@ config_data! {
         /// Run additional style lints.
         diagnostics_styleLints_enable: bool = false,
 
+        /// Report imports that are not used by their module, without waiting for `cargo check`.
+        diagnostics_unusedImports_enable: bool = false,
+
         /// List of warnings that should be displayed with hint severity.
         ///
         /// The warnings will be indicated by faded text or three dots in code and will not show up
@@ -981,6 +1013,18 @@ config_data! {
         /// replace the package name, target option (such as `--bin` or `--example`), the target name and
         /// the test name (name of test function or test mod path).
         runnables_test_overrideCommand: Option<Vec<String>> = None,
+        /// The test runner used by the test explorer.
+        ///
+        /// cargo-nextest reports its results through its experimental libtest JSON output,
+        /// which requires a version of cargo-nextest that supports `--message-format libtest-json`.
+        /// Of `#rust-analyzer.runnables.extraTestBinaryArgs#`, only the arguments cargo-nextest
+        /// emulates (`--exact`, `--ignored`, `--include-ignored` and `--skip`) are passed to it.
+        runnables_test_runner: TestRunnerDef = TestRunnerDef::Cargo,
+        /// Run tests from the test explorer in the MIR interpreter instead of with `cargo test`.
+        ///
+        /// This gives fast feedback for tests of pure logic without building the project, but
+        /// tests relying on I/O, FFI or other features the interpreter does not support will fail.
+        runnables_test_useInterpreter: bool = false,
 
         /// Path to the Cargo.toml of the rust compiler workspace, for usage in rustc_private
         /// projects, or "discover" to try to automatically find it if the `rustc-dev` component
@@ -1501,6 +1545,9 @@ pub struct LensConfig {
     pub refs_trait: bool, // for Struct, Enum, Union and Trait
     pub enum_variant_refs: bool,
 
+    // coverage
+    pub coverage: bool,
+
     // annotations
     pub location: AnnotationLocation,
     pub filter_adjacent_derive_implementations: bool,
@@ -1532,6 +1579,7 @@ impl LensConfig {
             || self.refs_adt
             || self.refs_trait
             || self.enum_variant_refs
+            || self.coverage
     }
 
     pub fn none(&self) -> bool {
@@ -1550,6 +1598,7 @@ impl LensConfig {
         self,
         binary_target: bool,
         minicore: MiniCore<'a>,
+        coverage: Option<&'a LineCoverage>,
     ) -> AnnotationConfig<'a> {
         AnnotationConfig {
             binary_target,
@@ -1561,6 +1610,7 @@ impl LensConfig {
             location: self.location.into(),
             minicore,
             filter_adjacent_derive_implementations: self.filter_adjacent_derive_implementations,
+            coverage: coverage.filter(|_| self.coverage),
         }
     }
 }
@@ -1804,6 +1854,8 @@ impl Config {
             },
             prefer_self_ty: *self.assist_preferSelf(source_root),
             show_rename_conflicts: *self.rename_showConflicts(source_root),
+            generate_builder_with_prefix: *self.assist_generateBuilder_withPrefix(source_root),
+            generate_builder_into_setters: *self.assist_generateBuilder_intoSetters(source_root),
         }
     }
 
@@ -1816,6 +1868,15 @@ impl Config {
         }
     }
 
+    pub fn move_to_module(&self, source_root: Option<SourceRootId>) -> MoveToModuleConfig {
+        MoveToModuleConfig {
+            insert_use: self.insert_use_config(source_root),
+            prefer_no_std: self.imports_preferNoStd(source_root).to_owned(),
+            prefer_prelude: self.imports_preferPrelude(source_root).to_owned(),
+            prefer_absolute: self.imports_prefixExternPrelude(source_root).to_owned(),
+        }
+    }
+
     pub fn call_hierarchy<'a>(&self, minicore: MiniCore<'a>) -> CallHierarchyConfig<'a> {
         CallHierarchyConfig { exclude_tests: self.references_excludeTests().to_owned(), minicore }
     }
@@ -1910,6 +1971,9 @@ impl Config {
             prefer_prelude: self.imports_preferPrelude(source_root).to_owned(),
             prefer_absolute: self.imports_prefixExternPrelude(source_root).to_owned(),
             style_lints: self.diagnostics_styleLints_enable(source_root).to_owned(),
+            unused_imports: self.diagnostics_unusedImports_enable(source_root).to_owned(),
+            dead_code: self.diagnostics_deadCode_enable(source_root).to_owned(),
+            feature_gates: self.diagnostics_featureGates_enable(source_root).to_owned(),
             term_search_fuel: self.assist_termSearch_fuel(source_root).to_owned() as u64,
             term_search_borrowck: self.assist_termSearch_borrowcheck(source_root).to_owned(),
             show_rename_conflicts: *self.rename_showConflicts(source_root),
@@ -2261,6 +2325,11 @@ impl Config {
         extra_env
     }
 
+    pub fn coverage_report_path(&self) -> Option<AbsPathBuf> {
+        let path = self.coverage_reportPath().clone()?;
+        Some(AbsPathBuf::try_from(path).unwrap_or_else(|path| self.root_path.join(path)))
+    }
+
     pub fn lru_parse_query_capacity(&self) -> Option<u16> {
         self.lru_capacity().to_owned()
     }
@@ -2494,6 +2563,17 @@ impl Config {
         }
     }
 
+    pub(crate) fn test_explorer_use_interpreter(&self) -> bool {
+        *self.runnables_test_useInterpreter(None)
+    }
+
+    pub(crate) fn test_runner(&self) -> TestRunner {
+        match self.runnables_test_runner(None) {
+            TestRunnerDef::Cargo => TestRunner::Cargo,
+            TestRunnerDef::Nextest => TestRunner::Nextest,
+        }
+    }
+
     pub(crate) fn flycheck(&self, source_root: Option<SourceRootId>) -> FlycheckConfig {
         match &self.check_overrideCommand(source_root) {
             Some(args) if !args.is_empty() => {
@@ -2618,6 +2698,7 @@ impl Config {
             refs_adt: *self.lens_enable() && *self.lens_references_adt_enable(),
             refs_trait: *self.lens_enable() && *self.lens_references_trait_enable(),
             enum_variant_refs: *self.lens_enable() && *self.lens_references_enumVariant_enable(),
+            coverage: *self.lens_enable() && *self.lens_coverage_enable(),
             location: *self.lens_location(),
             filter_adjacent_derive_implementations: *self
                 .gotoImplementations_filterAdjacentDerives(),
@@ -3070,6 +3151,13 @@ enum WorkspaceSymbolSearchScopeDef {
     WorkspaceAndDependencies,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+enum TestRunnerDef {
+    Cargo,
+    Nextest,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "snake_case")]
 enum SignatureDetail {
@@ -3852,6 +3940,14 @@ fn field_props(field: &str, ty: &str, doc: &[&str], default: &str) -> serde_json
                 "Do no snippet completions for callables."
             ]
         },
+        "TestRunnerDef" => set! {
+            "type": "string",
+            "enum": ["cargo", "nextest"],
+            "enumDescriptions": [
+                "Run tests with `cargo test`.",
+                "Run tests with `cargo nextest run`."
+            ],
+        },
         "SignatureDetail" => set! {
             "type": "string",
             "enum": ["full", "parameters"],
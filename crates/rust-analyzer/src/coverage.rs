@@ -0,0 +1,175 @@
+//! Loading of line coverage reports produced by `llvm-cov`, either in the lcov
+//! format or as the JSON of `llvm-cov export`.
+//!
+//! Reports are keyed by absolute source file path; paths that are relative in
+//! the report are resolved against the directory containing the report.
+
+use std::{fs, io};
+
+use anyhow::Context;
+use ide::LineCoverage;
+use paths::{AbsPath, AbsPathBuf, Utf8PathBuf};
+use rustc_hash::FxHashMap;
+use serde::Deserialize;
+
+#[derive(Debug, Default)]
+pub(crate) struct CoverageReport {
+    files: FxHashMap<AbsPathBuf, LineCoverage>,
+}
+
+impl CoverageReport {
+    pub(crate) fn load(path: &AbsPath) -> anyhow::Result<CoverageReport> {
+        let _p = tracing::info_span!("CoverageReport::load", ?path).entered();
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            // The report may legitimately not exist yet, it is picked up once written.
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(CoverageReport::default()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("failed to read coverage report {path}"));
+            }
+        };
+        let base = path.parent().unwrap_or(path);
+        if text.trim_start().starts_with('{') {
+            CoverageReport::from_llvm_cov_json(base, &text)
+        } else {
+            Ok(CoverageReport::from_lcov(base, &text))
+        }
+        .with_context(|| format!("failed to parse coverage report {path}"))
+    }
+
+    pub(crate) fn file(&self, path: &AbsPath) -> Option<&LineCoverage> {
+        self.files.get(path)
+    }
+
+    fn file_mut(&mut self, base: &AbsPath, path: &str) -> &mut LineCoverage {
+        let path = AbsPathBuf::try_from(Utf8PathBuf::from(path))
+            .unwrap_or_else(|path| base.join(path))
+            .normalize();
+        self.files.entry(path).or_default()
+    }
+
+    fn from_lcov(base: &AbsPath, text: &str) -> CoverageReport {
+        let mut report = CoverageReport::default();
+        let mut current = None;
+        for line in text.lines() {
+            let line = line.trim();
+            if let Some(path) = line.strip_prefix("SF:") {
+                current = Some(path);
+            } else if line == "end_of_record" {
+                current = None;
+            } else if let Some(data) = line.strip_prefix("DA:") {
+                let Some(path) = current else { continue };
+                let mut fields = data.split(',');
+                let (Some(Ok(line)), Some(Ok(hits))) =
+                    (fields.next().map(str::parse::<u32>), fields.next().map(str::parse::<u64>))
+                else {
+                    continue;
+                };
+                if line == 0 {
+                    continue;
+                }
+                report.file_mut(base, path).add(line - 1, hits);
+            }
+        }
+        report
+    }
+
+    fn from_llvm_cov_json(base: &AbsPath, text: &str) -> anyhow::Result<CoverageReport> {
+        #[derive(Deserialize)]
+        struct Export {
+            data: Vec<ExportData>,
+        }
+        #[derive(Deserialize)]
+        struct ExportData {
+            files: Vec<ExportFile>,
+        }
+        #[derive(Deserialize)]
+        struct ExportFile {
+            filename: String,
+            segments: Vec<Segment>,
+        }
+
+        let export: Export = serde_json::from_str(text)?;
+        let mut report = CoverageReport::default();
+        for file in export.data.into_iter().flat_map(|data| data.files) {
+            add_segments(report.file_mut(base, &file.filename), &file.segments);
+        }
+        Ok(report)
+    }
+}
+
+/// `[line, column, count, has_count, is_region_entry, is_gap_region]`, with one-based lines.
+type Segment = (u32, u32, u64, bool, bool, bool);
+
+/// Derives per-line execution counts from region segments, following what
+/// `llvm-cov` itself does: a line is instrumented if a region starts on it or
+/// if it is inside a counted region that started on an earlier line.
+fn add_segments(coverage: &mut LineCoverage, segments: &[Segment]) {
+    let (Some(first), Some(last)) = (segments.first(), segments.last()) else {
+        return;
+    };
+    let mut wrapped: Option<&Segment> = None;
+    let mut idx = 0;
+    for line in first.0.max(1)..=last.0 {
+        let start = idx;
+        while idx < segments.len() && segments[idx].0 == line {
+            idx += 1;
+        }
+        let line_segments = &segments[start..idx];
+
+        let mut count = wrapped.filter(|it| it.3 && !it.5).map(|it| it.2);
+        for segment in line_segments.iter().filter(|it| it.3 && it.4 && !it.5) {
+            count = Some(count.map_or(segment.2, |count| count.max(segment.2)));
+        }
+        if let Some(count) = count {
+            coverage.add(line - 1, count);
+        }
+
+        if let Some(segment) = line_segments.last() {
+            wrapped = Some(segment);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> AbsPathBuf {
+        AbsPathBuf::assert_utf8(std::env::current_dir().unwrap()).join("workspace")
+    }
+
+    #[test]
+    fn parses_lcov() {
+        let base = base();
+        let report = CoverageReport::from_lcov(
+            &base,
+            "\
+TN:
+SF:src/lib.rs
+FN:1,foo
+DA:1,3
+DA:2,0
+DA:3,3
+end_of_record
+",
+        );
+        let coverage = report.file(&base.join("src/lib.rs")).unwrap();
+        assert_eq!(coverage.lines(), vec![(0, 3), (1, 0), (2, 3)]);
+    }
+
+    #[test]
+    fn parses_llvm_cov_json() {
+        let base = base();
+        let path = base.join("src/main.rs");
+        let text = format!(
+            r#"{{"type":"llvm.coverage.json.export","version":"2.0.1","data":[{{"files":[{{
+                "filename":"{path}",
+                "segments":[[1,11,1,true,true,false],[2,8,0,true,true,false],[4,6,1,true,false,false],[5,2,0,false,false,false]]
+            }}]}}]}}"#
+        );
+        let report = CoverageReport::from_llvm_cov_json(&base, &text).unwrap();
+        let coverage = report.file(&path).unwrap();
+        assert_eq!(coverage.lines(), vec![(0, 1), (1, 1), (2, 0), (3, 0), (4, 1)]);
+    }
+}
@@ -364,7 +364,18 @@ pub(crate) fn convert_diagnostic(
         source: Some("rust-analyzer".to_owned()),
         message: d.message,
         related_information: None,
-        tags: d.unused.then(|| vec![lsp_types::DiagnosticTag::UNNECESSARY]),
+        tags: match (d.unused, d.deprecated) {
+            (false, false) => None,
+            (unused, deprecated) => Some(
+                [
+                    unused.then_some(lsp_types::DiagnosticTag::UNNECESSARY),
+                    deprecated.then_some(lsp_types::DiagnosticTag::DEPRECATED),
+                ]
+                .into_iter()
+                .flatten()
+                .collect(),
+            ),
+        },
         data: None,
     }
 }
@@ -33,6 +33,7 @@ use vfs::{AbsPathBuf, AnchoredPathBuf, ChangeKind, Vfs, VfsPath};
 
 use crate::{
     config::{Config, ConfigChange, ConfigErrors, RatomlFileKind},
+    coverage::CoverageReport,
     diagnostics::{CheckFixes, DiagnosticCollection},
     discover,
     flycheck::{FlycheckHandle, FlycheckMessage, PackageSpecifier},
@@ -45,7 +46,7 @@ use crate::{
     reload,
     target_spec::{CargoTargetSpec, ProjectJsonTargetSpec, TargetSpec},
     task_pool::{DeferredTaskQueue, TaskPool},
-    test_runner::{CargoTestHandle, CargoTestMessage},
+    test_runner::{CargoTestMessage, TestRunHandle},
 };
 
 #[derive(Debug)]
@@ -115,7 +116,7 @@ pub(crate) struct GlobalState {
     pub(crate) flycheck_formatted_commands: Vec<String>,
 
     // Test explorer
-    pub(crate) test_run_session: Option<Vec<CargoTestHandle>>,
+    pub(crate) test_run_session: Option<Vec<TestRunHandle>>,
     pub(crate) test_run_sender: Sender<CargoTestMessage>,
     pub(crate) test_run_receiver: Receiver<CargoTestMessage>,
     pub(crate) test_run_remaining_jobs: usize,
@@ -195,6 +196,9 @@ pub(crate) struct GlobalState {
 
     pub(crate) minicore: MiniCoreRustAnalyzerInternalOnly,
     pub(crate) last_gc_revision: Revision,
+
+    // coverage
+    pub(crate) coverage: Arc<CoverageReport>,
 }
 
 // FIXME: This should move to the VFS once the rewrite is done.
@@ -217,6 +221,7 @@ pub(crate) struct GlobalStateSnapshot {
     // FIXME: Can we derive this from somewhere else?
     pub(crate) proc_macros_loaded: bool,
     pub(crate) flycheck: Arc<[FlycheckHandle]>,
+    pub(crate) coverage: Arc<CoverageReport>,
     minicore: MiniCoreRustAnalyzerInternalOnly,
 }
 
@@ -324,9 +329,11 @@ impl GlobalState {
 
             minicore: MiniCoreRustAnalyzerInternalOnly::default(),
             last_gc_revision,
+            coverage: Arc::default(),
         };
         // Apply any required database inputs from the config.
         this.update_configuration(config);
+        this.load_coverage();
         this
     }
 
@@ -577,6 +584,7 @@ impl GlobalState {
             proc_macros_loaded: !self.config.expand_proc_macros()
                 || self.fetch_proc_macros_queue.last_op_result().copied().unwrap_or(false),
             flycheck: self.flycheck.clone(),
+            coverage: Arc::clone(&self.coverage),
         }
     }
 
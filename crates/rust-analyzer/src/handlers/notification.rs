@@ -292,8 +292,14 @@ pub(crate) fn handle_did_change_watched_files(
     // we want to trigger flycheck if a file outside of our workspaces has changed,
     // as to reduce stale diagnostics when outside changes happen
     let mut trigger_flycheck = false;
+    let mut reload_coverage = false;
+    let coverage_report_path = state.config.coverage_report_path();
     for change in params.changes.iter().unique_by(|&it| &it.uri) {
         if let Ok(path) = from_proto::abs_path(&change.uri) {
+            if coverage_report_path.as_ref() == Some(&path) {
+                reload_coverage = true;
+                continue;
+            }
             if !trigger_flycheck {
                 trigger_flycheck =
                     state.config.workspace_roots().iter().any(|root| !path.starts_with(root));
@@ -307,6 +313,9 @@ pub(crate) fn handle_did_change_watched_files(
             flycheck.restart_workspace(None);
         }
     }
+    if reload_coverage {
+        state.load_coverage();
+    }
     Ok(())
 }
 
@@ -9,7 +9,7 @@ use base64::{Engine, prelude::BASE64_STANDARD};
 use ide::{
     AssistKind, AssistResolveStrategy, Cancellable, CompletionFieldsToResolve, FilePosition,
     FileRange, FileStructureConfig, FindAllRefsConfig, HoverAction, HoverGotoTypeData,
-    InlayFieldsToResolve, Query, RangeInfo, ReferenceCategory, Runnable, RunnableKind,
+    InlayFieldsToResolve, NewParam, Query, RangeInfo, ReferenceCategory, Runnable, RunnableKind,
     SingleResolve, SourceChange, TextEdit,
 };
 use ide_db::{FxHashMap, SymbolKind};
@@ -53,7 +53,7 @@ use crate::{
         FetchDependencyListResult, PositionOrRange, ViewCrateGraphParams, WorkspaceSymbolParams,
     },
     target_spec::{CargoTargetSpec, TargetSpec},
-    test_runner::{CargoTestHandle, TestTarget},
+    test_runner::{CargoTestHandle, InterpretedTestHandle, TestRunHandle, TestTarget},
     try_default,
 };
 
@@ -255,6 +255,10 @@ pub(crate) fn handle_run_test(
         state.send_notification::<lsp_ext::EndRunTest>(());
     }
 
+    if state.config.test_explorer_use_interpreter() {
+        return run_tests_in_interpreter(state, params);
+    }
+
     let mut handles = vec![];
     for ws in &*state.workspaces {
         if let ProjectWorkspaceKind::Cargo { cargo, .. } = &ws.kind {
@@ -286,9 +290,10 @@ pub(crate) fn handle_run_test(
                     cargo.workspace_root(),
                     Some(cargo.target_directory().as_ref()),
                     target,
+                    state.config.test_runner(),
                     state.test_run_sender.clone(),
                 )?;
-                handles.push(handle);
+                handles.push(TestRunHandle::Cargo { _handle: handle });
             }
         }
     }
@@ -298,6 +303,40 @@ pub(crate) fn handle_run_test(
     Ok(())
 }
 
+fn run_tests_in_interpreter(
+    state: &mut GlobalState,
+    params: lsp_ext::RunTestParams,
+) -> anyhow::Result<()> {
+    let analysis = state.analysis_host.analysis();
+    // Tests are run by one job per crate, keyed by the crate's test id.
+    let mut jobs: FxHashMap<String, Vec<String>> = FxHashMap::default();
+    let include = match params.include {
+        Some(include) => include.into_iter().unique().collect(),
+        None => analysis.discover_test_roots()?.into_iter().map(|root| root.id).collect_vec(),
+    };
+    for test in include {
+        let crate_id = test.split_once("::").map_or(&*test, |it| it.0).to_owned();
+        jobs.entry(crate_id).or_default().push(test);
+    }
+    let mut handles = vec![];
+    for (crate_id, include) in jobs {
+        let handle = InterpretedTestHandle::new(
+            state.analysis_host.analysis(),
+            crate_id,
+            include,
+            state.test_run_sender.clone(),
+        )?;
+        handles.push(TestRunHandle::Interpreted { _handle: handle });
+    }
+    if handles.is_empty() {
+        state.send_notification::<lsp_ext::EndRunTest>(());
+        return Ok(());
+    }
+    state.test_run_remaining_jobs = handles.len();
+    state.test_run_session = Some(handles);
+    Ok(())
+}
+
 pub(crate) fn handle_discover_test(
     snap: GlobalStateSnapshot,
     params: lsp_ext::DiscoverTestParams,
@@ -1651,6 +1690,8 @@ pub(crate) fn handle_code_lens(
 
     let file_id = try_default!(from_proto::file_id(&snap, &params.text_document.uri)?);
     let target_spec = TargetSpec::for_file(&snap, file_id)?;
+    let file_path = snap.file_id_to_file_path(file_id);
+    let coverage = file_path.as_path().and_then(|path| snap.coverage.file(path));
 
     let annotations = snap.analysis.annotations(
         &lens_config.into_annotation_config(
@@ -1663,6 +1704,7 @@ pub(crate) fn handle_code_lens(
                 })
                 .unwrap_or(false),
             snap.minicore(),
+            coverage,
         ),
         file_id,
     )?;
@@ -1686,7 +1728,7 @@ pub(crate) fn handle_code_lens_resolve(
     let Some(annotation) = from_proto::annotation(&snap, code_lens.range, resolve)? else {
         return Ok(code_lens);
     };
-    let config = snap.config.lens().into_annotation_config(false, snap.minicore());
+    let config = snap.config.lens().into_annotation_config(false, snap.minicore(), None);
     let annotation = snap.analysis.resolve_annotation(&config, annotation)?;
 
     let mut acc = Vec::new();
@@ -1753,6 +1795,68 @@ pub(crate) fn handle_ssr(
     to_proto::workspace_edit(&snap, source_change).map_err(Into::into)
 }
 
+pub(crate) fn handle_change_signature(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::ChangeSignatureParams,
+) -> anyhow::Result<lsp_ext::ChangeSignatureResult> {
+    let _p = tracing::info_span!("handle_change_signature").entered();
+    let position = try_default!(from_proto::file_position(&snap, params.position)?);
+    let new_params = params
+        .parameters
+        .into_iter()
+        .map(|param| match param {
+            lsp_ext::ChangeSignatureParameter::Existing { index, ty } => {
+                NewParam::Existing { index, ty }
+            }
+            lsp_ext::ChangeSignatureParameter::Added { pattern, ty, default } => {
+                NewParam::Added { pattern, ty, default }
+            }
+        })
+        .collect();
+    let change = snap
+        .analysis
+        .change_signature(position, new_params)?
+        .map_err(|err| invalid_params_error(err.to_string()))?;
+    let unrewritten = change
+        .unrewritten
+        .into_iter()
+        .map(|site| {
+            Ok(lsp_ext::UnrewrittenSite {
+                location: to_proto::location(&snap, site.range)?,
+                reason: site.reason,
+            })
+        })
+        .collect::<Cancellable<_>>()?;
+    let edit = to_proto::workspace_edit(&snap, change.source_change)?;
+    Ok(lsp_ext::ChangeSignatureResult { edit, unrewritten })
+}
+
+pub(crate) fn handle_move_item_to_module(
+    snap: GlobalStateSnapshot,
+    params: lsp_ext::MoveItemToModuleParams,
+) -> anyhow::Result<lsp_ext::MoveItemToModuleResult> {
+    let _p = tracing::info_span!("handle_move_item_to_module").entered();
+    let frange = try_default!(from_proto::file_range(&snap, &params.text_document, params.range)?);
+    let source_root = snap.analysis.source_root_id(frange.file_id).ok();
+    let config = snap.config.move_to_module(source_root);
+    let moved = snap
+        .analysis
+        .move_to_module(frange, &params.target_module, &config)?
+        .map_err(|err| invalid_params_error(err.to_string()))?;
+    let unrewritten = moved
+        .unrewritten
+        .into_iter()
+        .map(|site| {
+            Ok(lsp_ext::UnrewrittenSite {
+                location: to_proto::location(&snap, site.range)?,
+                reason: site.reason,
+            })
+        })
+        .collect::<Cancellable<_>>()?;
+    let edit = to_proto::workspace_edit(&snap, moved.source_change)?;
+    Ok(lsp_ext::MoveItemToModuleResult { edit, unrewritten })
+}
+
 pub(crate) fn handle_inlay_hints(
     snap: GlobalStateSnapshot,
     params: InlayHintParams,
@@ -2149,11 +2253,88 @@ pub(crate) fn handle_view_recursive_memory_layout(
                 parent_idx: n.parent_idx,
                 children_start: n.children_start,
                 children_len: n.children_len,
+                padding: n
+                    .padding
+                    .iter()
+                    .map(|r| lsp_ext::MemoryLayoutRange { start: r.start, end: r.end })
+                    .collect(),
+                niche: n.niche.map(|niche| lsp_ext::MemoryLayoutNiche {
+                    offset: niche.offset,
+                    size: niche.size,
+                    valid_start: niche.valid_range.0.to_string(),
+                    valid_end: niche.valid_range.1.to_string(),
+                    available: niche.available.to_string(),
+                }),
+                tag: n.tag.as_ref().map(|tag| match *tag {
+                    hir::LayoutTag::Direct { offset, size } => {
+                        lsp_ext::MemoryLayoutTag::Direct { offset, size }
+                    }
+                    hir::LayoutTag::Niche {
+                        offset,
+                        size,
+                        untagged_variant,
+                        ref niche_variants,
+                        niche_start,
+                    } => lsp_ext::MemoryLayoutTag::Niche {
+                        offset,
+                        size,
+                        untagged_variant,
+                        niche_variants_start: *niche_variants.start(),
+                        niche_variants_end: *niche_variants.end(),
+                        niche_start: niche_start.to_string(),
+                    },
+                }),
             })
             .collect(),
     }))
 }
 
+pub(crate) fn handle_view_memory_layout_html(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::TextDocumentPositionParams,
+) -> anyhow::Result<Option<String>> {
+    let _p = tracing::info_span!("handle_view_memory_layout_html").entered();
+    let position = try_default!(from_proto::file_position(&snap, params)?);
+    let res = snap.analysis.get_recursive_memory_layout(position)?;
+    Ok(res.map(|it| it.to_html()))
+}
+
+pub(crate) fn handle_file_coverage(
+    snap: GlobalStateSnapshot,
+    params: TextDocumentIdentifier,
+) -> anyhow::Result<Option<lsp_ext::FileCoverageResult>> {
+    let _p = tracing::info_span!("handle_file_coverage").entered();
+    let path = from_proto::abs_path(&params.uri)?;
+    let Some(coverage) = snap.coverage.file(&path) else {
+        return Ok(None);
+    };
+
+    let mut res = lsp_ext::FileCoverageResult { covered: Vec::new(), uncovered: Vec::new() };
+    let mut push = |(covered, start, end): (bool, u32, u32)| {
+        let range = Range::new(Position::new(start, 0), Position::new(end + 1, 0));
+        if covered { res.covered.push(range) } else { res.uncovered.push(range) }
+    };
+    // Merge adjacent lines with the same state into a single range.
+    let mut current: Option<(bool, u32, u32)> = None;
+    for (line, hits) in coverage.lines() {
+        let covered = hits > 0;
+        if let Some((it, _, end)) = &mut current
+            && *it == covered
+            && *end + 1 == line
+        {
+            *end = line;
+            continue;
+        }
+        if let Some(lines) = current.replace((covered, line, line)) {
+            push(lines);
+        }
+    }
+    if let Some(lines) = current {
+        push(lines);
+    }
+    Ok(Some(res))
+}
+
 fn to_command_link(command: lsp_types::Command, tooltip: String) -> lsp_ext::CommandLink {
     lsp_ext::CommandLink { tooltip: Some(tooltip), command }
 }
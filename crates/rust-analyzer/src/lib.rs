@@ -31,6 +31,7 @@ pub const MINIMUM_SUPPORTED_TOOLCHAIN_VERSION: semver::Version = semver::Version
 pub mod cli;
 
 mod command;
+mod coverage;
 mod diagnostics;
 mod discover;
 mod flycheck;
@@ -166,6 +166,8 @@ pub fn server_capabilities(config: &Config) -> ServerCapabilities {
                 "kinds": [ "cargo" ],
             },
             "ssr": true,
+            "changeSignature": true,
+            "moveItemToModule": true,
             "workspaceSymbolScopeKindFiltering": true,
         })),
         diagnostic_provider: Some(lsp_types::DiagnosticServerCapabilities::Options(
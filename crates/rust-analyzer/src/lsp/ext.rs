@@ -340,6 +340,69 @@ pub struct MemoryLayoutNode {
     pub parent_idx: i64,
     pub children_start: i64,
     pub children_len: u64,
+    pub padding: Vec<MemoryLayoutRange>,
+    pub niche: Option<MemoryLayoutNiche>,
+    pub tag: Option<MemoryLayoutTag>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryLayoutRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryLayoutNiche {
+    pub offset: u64,
+    pub size: u64,
+    /// Values are serialized as strings, as they may not fit into a JavaScript number.
+    pub valid_start: String,
+    pub valid_end: String,
+    pub available: String,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase", tag = "kind")]
+pub enum MemoryLayoutTag {
+    Direct {
+        offset: u64,
+        size: u64,
+    },
+    Niche {
+        offset: u64,
+        size: u64,
+        untagged_variant: usize,
+        niche_variants_start: usize,
+        niche_variants_end: usize,
+        niche_start: String,
+    },
+}
+
+pub enum ViewMemoryLayoutHtml {}
+
+impl Request for ViewMemoryLayoutHtml {
+    type Params = lsp_types::TextDocumentPositionParams;
+    type Result = Option<String>;
+    const METHOD: &'static str = "rust-analyzer/viewMemoryLayoutHtml";
+}
+
+pub enum FileCoverage {}
+
+impl Request for FileCoverage {
+    type Params = lsp_types::TextDocumentIdentifier;
+    type Result = Option<FileCoverageResult>;
+    const METHOD: &'static str = "rust-analyzer/fileCoverage";
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FileCoverageResult {
+    /// Line ranges that were executed at least once.
+    pub covered: Vec<lsp_types::Range>,
+    /// Instrumented line ranges that were never executed.
+    pub uncovered: Vec<lsp_types::Range>,
 }
 
 pub enum CancelFlycheck {}
@@ -532,6 +595,84 @@ pub struct SsrParams {
     pub selections: Vec<lsp_types::Range>,
 }
 
+pub enum ChangeSignature {}
+
+impl Request for ChangeSignature {
+    type Params = ChangeSignatureParams;
+    type Result = ChangeSignatureResult;
+    const METHOD: &'static str = "experimental/changeSignature";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSignatureParams {
+    /// Position of the function, or of a reference to it.
+    #[serde(flatten)]
+    pub position: lsp_types::TextDocumentPositionParams,
+
+    /// The new parameter list, not counting `self`.
+    pub parameters: Vec<ChangeSignatureParameter>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum ChangeSignatureParameter {
+    /// The parameter at `index` in the current list, optionally with a new type.
+    Existing {
+        index: usize,
+        #[serde(rename = "type")]
+        ty: Option<String>,
+    },
+    /// A new parameter. Existing callers pass `default` for it.
+    Added {
+        pattern: String,
+        #[serde(rename = "type")]
+        ty: String,
+        default: String,
+    },
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSignatureResult {
+    pub edit: lsp_types::WorkspaceEdit,
+    /// Uses of the function that were left as is.
+    pub unrewritten: Vec<UnrewrittenSite>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnrewrittenSite {
+    pub location: lsp_types::Location,
+    pub reason: String,
+}
+
+pub enum MoveItemToModule {}
+
+impl Request for MoveItemToModule {
+    type Params = MoveItemToModuleParams;
+    type Result = MoveItemToModuleResult;
+    const METHOD: &'static str = "experimental/moveItemToModule";
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveItemToModuleParams {
+    pub text_document: TextDocumentIdentifier,
+    /// The items touched by this range are moved.
+    pub range: Range,
+    /// Path of the target module, resolved from the module of the items.
+    pub target_module: String,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveItemToModuleResult {
+    pub edit: lsp_types::WorkspaceEdit,
+    /// References to the moved items that were left as is.
+    pub unrewritten: Vec<UnrewrittenSite>,
+}
+
 pub enum ServerStatusNotification {}
 
 impl Notification for ServerStatusNotification {
@@ -1794,6 +1794,29 @@ pub(crate) fn code_lens(
                 })(),
             })
         }
+        AnnotationKind::Coverage { file_id, covered, total, uncovered } => {
+            if !client_commands_config.show_reference {
+                return Ok(());
+            }
+            let line_index = snap.file_line_index(file_id)?;
+            let annotation_range = range(&line_index, annotation.range);
+            let url = url(snap, file_id);
+            // Clicking the lens lists the lines that were never executed.
+            let locations = uncovered
+                .into_iter()
+                .filter_map(|range| location(snap, FileRange { file_id, range }).ok())
+                .collect();
+            acc.push(lsp_types::CodeLens {
+                range: annotation_range,
+                command: Some(command::show_references(
+                    coverage_title(covered, total),
+                    &url,
+                    annotation_range.start,
+                    locations,
+                )),
+                data: None,
+            })
+        }
         AnnotationKind::HasReferences { pos, data } => {
             if !client_commands_config.show_reference {
                 return Ok(());
@@ -1994,6 +2017,11 @@ pub(crate) fn reference_title(count: usize) -> String {
     if count == 1 { "1 reference".into() } else { format!("{count} references") }
 }
 
+pub(crate) fn coverage_title(covered: u32, total: u32) -> String {
+    let percent = u64::from(covered) * 100 / u64::from(total.max(1));
+    format!("Coverage: {percent}% ({covered}/{total} lines)")
+}
+
 pub(crate) fn markup_content(
     markup: Markup,
     kind: ide::HoverDocFormat,
@@ -18,6 +18,7 @@ use vfs::{AbsPathBuf, FileId, loader::LoadingProgress};
 
 use crate::{
     config::Config,
+    coverage::CoverageReport,
     diagnostics::{DiagnosticsGeneration, NativeDiagnosticsFetchKind, fetch_native_diagnostics},
     discover::{DiscoverArgument, DiscoverCommand, DiscoverProjectMessage},
     flycheck::{self, ClearDiagnosticsKind, ClearScope, FlycheckMessage},
@@ -120,6 +121,7 @@ pub(crate) enum Task {
     FetchWorkspace(ProjectWorkspaceProgress),
     FetchBuildData(BuildDataProgress),
     LoadProcMacros(ProcMacroProgress),
+    LoadCoverage(anyhow::Result<CoverageReport>),
     // FIXME: Remove this in favor of a more general QueuedTask, see `handle_did_save_text_document`
     BuildDepsHaveChanged,
 }
@@ -891,6 +893,16 @@ impl GlobalState {
                     self.report_progress("Loading proc-macros", state, msg, None, None);
                 }
             }
+            Task::LoadCoverage(report) => {
+                let report = report.unwrap_or_else(|e| {
+                    self.show_and_log_error(format!("{e:#}"), None);
+                    CoverageReport::default()
+                });
+                self.coverage = triomphe::Arc::new(report);
+                if self.config.code_lens_refresh() {
+                    self.send_request::<lsp_types::request::CodeLensRefresh>((), |_, _| ());
+                }
+            }
             Task::BuildDepsHaveChanged => self.build_deps_changed = true,
             Task::DiscoverTest(tests) => {
                 self.send_notification::<lsp_ext::DiscoveredTests>(tests);
@@ -1330,7 +1342,11 @@ impl GlobalState {
             .on::<RETRY, lsp_ext::DiscoverTest>(handlers::handle_discover_test)
             .on::<RETRY, lsp_ext::WorkspaceSymbol>(handlers::handle_workspace_symbol)
             .on::<NO_RETRY, lsp_ext::Ssr>(handlers::handle_ssr)
+            .on::<NO_RETRY, lsp_ext::ChangeSignature>(handlers::handle_change_signature)
+            .on::<NO_RETRY, lsp_ext::MoveItemToModule>(handlers::handle_move_item_to_module)
             .on::<NO_RETRY, lsp_ext::ViewRecursiveMemoryLayout>(handlers::handle_view_recursive_memory_layout)
+            .on::<NO_RETRY, lsp_ext::ViewMemoryLayoutHtml>(handlers::handle_view_memory_layout_html)
+            .on::<NO_RETRY, lsp_ext::FileCoverage>(handlers::handle_file_coverage)
             .on::<NO_RETRY, lsp_ext::ViewSyntaxTree>(handlers::handle_view_syntax_tree)
             .on::<NO_RETRY, lsp_ext::ViewHir>(handlers::handle_view_hir)
             .on::<NO_RETRY, lsp_ext::ViewMir>(handlers::handle_view_mir)
@@ -34,6 +34,7 @@ use vfs::{AbsPath, AbsPathBuf, ChangeKind};
 
 use crate::{
     config::{Config, FilesWatcher, LinkedProject},
+    coverage::CoverageReport,
     flycheck::{FlycheckConfig, FlycheckHandle},
     global_state::{
         FetchBuildDataResponse, FetchWorkspaceRequest, FetchWorkspaceResponse, GlobalState,
@@ -127,6 +128,23 @@ impl GlobalState {
             let req = FetchWorkspaceRequest { path: None, force_crate_graph_reload: false };
             self.fetch_workspaces_queue.request_op("cfg_set_test config changed".to_owned(), req)
         }
+
+        if self.config.coverage_report_path() != old_config.coverage_report_path() {
+            self.load_coverage();
+        }
+    }
+
+    /// Reads the configured coverage report in the background, replacing the
+    /// currently loaded one once done.
+    pub(crate) fn load_coverage(&mut self) {
+        let path = self.config.coverage_report_path();
+        self.task_pool.handle.spawn(ThreadIntent::Worker, move || {
+            let report = match path {
+                Some(path) => CoverageReport::load(&path),
+                None => Ok(CoverageReport::default()),
+            };
+            Task::LoadCoverage(report)
+        });
     }
 
     pub(crate) fn current_status(&self) -> lsp_ext::ServerStatusParams {
@@ -632,6 +650,13 @@ impl GlobalState {
                 }
             }
 
+            if let Some(path) = self.config.coverage_report_path() {
+                watchers.push(lsp_types::FileSystemWatcher {
+                    glob_pattern: lsp_types::GlobPattern::String(path.to_string()),
+                    kind: None,
+                });
+            }
+
             watchers.extend(
                 iter::once(Config::user_config_dir_path().as_deref())
                     .chain(self.workspaces.iter().map(|ws| ws.manifest().map(ManifestPath::as_ref)))
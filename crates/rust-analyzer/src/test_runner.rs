@@ -1,7 +1,16 @@
-//! This module provides the functionality needed to run `cargo test` in a background
-//! thread and report the result of each test in a channel.
+//! This module provides the functionality needed to run `cargo test` or `cargo nextest run`
+//! in a background thread and report the result of each test in a channel.
+//!
+//! Alternatively, tests can be run in the mir interpreter, which reports its results through
+//! the same channel.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
 
 use crossbeam_channel::Sender;
+use ide::{Analysis, Cancellable, TestRunResult};
 use paths::{AbsPath, Utf8Path};
 use project_model::TargetKind;
 use serde::Deserialize as _;
@@ -47,13 +56,22 @@ pub(crate) enum CargoTestOutput {
     },
 }
 
+/// The program that runs the tests of a target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TestRunner {
+    Cargo,
+    /// cargo-nextest, in its libtest compatible JSON output mode.
+    Nextest,
+}
+
 pub(crate) struct CargoTestOutputParser {
     pub target: TestTarget,
+    pub runner: TestRunner,
 }
 
 impl CargoTestOutputParser {
-    pub(crate) fn new(test_target: &TestTarget) -> Self {
-        Self { target: test_target.clone() }
+    pub(crate) fn new(test_target: &TestTarget, runner: TestRunner) -> Self {
+        Self { target: test_target.clone(), runner }
     }
 }
 
@@ -62,14 +80,20 @@ impl JsonLinesParser<CargoTestMessage> for CargoTestOutputParser {
         let mut deserializer = serde_json::Deserializer::from_str(line);
         deserializer.disable_recursion_limit();
 
-        Some(CargoTestMessage {
-            target: self.target.clone(),
-            output: if let Ok(message) = CargoTestOutput::deserialize(&mut deserializer) {
-                message
-            } else {
-                CargoTestOutput::Custom { text: line.to_owned() }
-            },
-        })
+        let output = match CargoTestOutput::deserialize(&mut deserializer) {
+            // nextest prefixes test names with the id of the test binary, e.g.
+            // `my-crate::bin/my-bin$module::test`.
+            Ok(CargoTestOutput::Test { name, state }) if self.runner == TestRunner::Nextest => {
+                let name = match name.split_once('$') {
+                    Some((_binary_id, name)) => name.to_owned(),
+                    None => name,
+                };
+                CargoTestOutput::Test { name, state }
+            }
+            Ok(message) => message,
+            Err(_) => CargoTestOutput::Custom { text: line.to_owned() },
+        };
+        Some(CargoTestMessage { target: self.target.clone(), output })
     }
 
     fn from_eof(&self) -> Option<CargoTestMessage> {
@@ -77,6 +101,13 @@ impl JsonLinesParser<CargoTestMessage> for CargoTestOutputParser {
     }
 }
 
+/// Keeps a test run alive, the run is aborted when this is dropped.
+#[derive(Debug)]
+pub(crate) enum TestRunHandle {
+    Cargo { _handle: CargoTestHandle },
+    Interpreted { _handle: InterpretedTestHandle },
+}
+
 #[derive(Debug)]
 pub(crate) struct CargoTestHandle {
     _handle: CommandHandle<CargoTestMessage>,
@@ -85,6 +116,10 @@ pub(crate) struct CargoTestHandle {
 // Example of a cargo test command:
 //
 // cargo test --package my-package --bin my_bin --no-fail-fast -- module::func -Z unstable-options --format=json
+//
+// and of a cargo nextest command:
+//
+// NEXTEST_EXPERIMENTAL_LIBTEST_JSON=1 cargo nextest run --package my-package --bin my_bin --no-fail-fast --message-format libtest-json -- module::func
 
 #[derive(Debug, Clone)]
 pub(crate) struct TestTarget {
@@ -100,12 +135,28 @@ impl CargoTestHandle {
         root: &AbsPath,
         ws_target_dir: Option<&Utf8Path>,
         test_target: TestTarget,
+        runner: TestRunner,
         sender: Sender<CargoTestMessage>,
     ) -> anyhow::Result<Self> {
         let mut cmd = toolchain::command(Tool::Cargo.path(), root, &options.extra_env);
-        cmd.env("RUSTC_BOOTSTRAP", "1");
-        cmd.arg("--color=always");
-        cmd.arg(&options.subcommand); // test, usually
+        match runner {
+            TestRunner::Cargo => {
+                cmd.env("RUSTC_BOOTSTRAP", "1");
+                cmd.arg("--color=always");
+                cmd.arg(&options.subcommand); // test, usually
+            }
+            TestRunner::Nextest => {
+                if options.subcommand != "test" {
+                    tracing::warn!(
+                        "cargo-nextest runs tests with `nextest run`, ignoring the `{}` subcommand",
+                        options.subcommand
+                    );
+                }
+                cmd.env("NEXTEST_EXPERIMENTAL_LIBTEST_JSON", "1");
+                cmd.args(["nextest", "run", "--color=always"]);
+                cmd.args(["--message-format", "libtest-json"]);
+            }
+        }
 
         cmd.arg("--package");
         cmd.arg(&test_target.package);
@@ -129,20 +180,190 @@ impl CargoTestHandle {
         if let Some(path) = path {
             cmd.arg(path);
         }
-        cmd.args(["-Z", "unstable-options"]);
-        cmd.arg("--format=json");
+        match runner {
+            TestRunner::Cargo => {
+                cmd.args(["-Z", "unstable-options"]);
+                cmd.arg("--format=json");
 
-        for extra_arg in options.extra_test_bin_args {
-            cmd.arg(extra_arg);
+                for extra_arg in options.extra_test_bin_args {
+                    cmd.arg(extra_arg);
+                }
+            }
+            TestRunner::Nextest => {
+                cmd.args(nextest_test_bin_args(options.extra_test_bin_args));
+            }
         }
 
         Ok(Self {
             _handle: CommandHandle::spawn(
                 cmd,
-                CargoTestOutputParser::new(&test_target),
+                CargoTestOutputParser::new(&test_target, runner),
                 sender,
                 None,
             )?,
         })
     }
 }
+
+/// nextest runs every test in its own process and only emulates some of the arguments of libtest
+/// test binaries, so keep those and drop the rest.
+fn nextest_test_bin_args(args: Vec<String>) -> Vec<String> {
+    let mut res = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--exact" | "--ignored" | "--include-ignored" => res.push(arg),
+            "--skip" => {
+                res.push(arg);
+                res.extend(args.next());
+            }
+            // nextest captures the output of every test and reports it in its JSON messages.
+            "--nocapture" | "--show-output" => (),
+            _ => tracing::warn!("cargo-nextest does not support the test binary argument `{arg}`"),
+        }
+    }
+    res
+}
+
+/// Runs the tests of a crate in the mir interpreter on a background thread, reporting the
+/// results as if they came from `cargo test`. The remaining tests are skipped when the handle
+/// is dropped.
+#[derive(Debug)]
+pub(crate) struct InterpretedTestHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl InterpretedTestHandle {
+    /// `include` are the ids of the tests, modules or the crate to run.
+    pub(crate) fn new(
+        analysis: Analysis,
+        crate_test_id: String,
+        include: Vec<String>,
+        sender: Sender<CargoTestMessage>,
+    ) -> anyhow::Result<Self> {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let target = TestTarget {
+            package: crate_test_id.clone(),
+            target: crate_test_id,
+            kind: TargetKind::Lib { is_proc_macro: false },
+        };
+        let flag = cancelled.clone();
+        stdx::thread::Builder::new(stdx::thread::ThreadIntent::Worker, "InterpretedTests")
+            // A test that is being interpreted can't be interrupted, so don't wait for it.
+            .allow_leak(true)
+            .spawn(move || {
+                // The run ends early if the analysis is cancelled by a change.
+                _ = run_interpreted_tests(&analysis, &target, &include, &flag, &sender);
+                _ = sender.send(CargoTestMessage { target, output: CargoTestOutput::Finished });
+            })?;
+        Ok(Self { cancelled })
+    }
+}
+
+impl Drop for InterpretedTestHandle {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+fn run_interpreted_tests(
+    analysis: &Analysis,
+    target: &TestTarget,
+    include: &[String],
+    cancelled: &AtomicBool,
+    sender: &Sender<CargoTestMessage>,
+) -> Cancellable<()> {
+    let mut tests = vec![];
+    for test_id in include {
+        tests.extend(analysis.tests_selected_by_test_id(test_id)?);
+    }
+    tests.sort();
+    tests.dedup();
+    for test_id in tests {
+        if cancelled.load(Ordering::Relaxed) {
+            break;
+        }
+        // Test ids are prefixed with the crate, which is the target here.
+        let Some((_, name)) = test_id.split_once("::") else { continue };
+        let send = |state| {
+            let output = CargoTestOutput::Test { name: name.to_owned(), state };
+            _ = sender.send(CargoTestMessage { target: target.clone(), output });
+        };
+        send(TestState::Started);
+        let state = match analysis.interpret_test(&test_id)? {
+            Some(TestRunResult::Passed) => TestState::Ok,
+            Some(TestRunResult::Ignored) => TestState::Ignored,
+            Some(TestRunResult::Failed { message }) => TestState::Failed { stdout: message },
+            None => continue,
+        };
+        send(state);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use expect_test::expect;
+
+    use super::*;
+
+    fn parse(runner: TestRunner, line: &str) -> CargoTestOutput {
+        let target = TestTarget {
+            package: "my-crate".to_owned(),
+            target: "my_crate".to_owned(),
+            kind: TargetKind::Lib { is_proc_macro: false },
+        };
+        CargoTestOutputParser::new(&target, runner)
+            .from_line(line, &mut String::new())
+            .unwrap()
+            .output
+    }
+
+    #[test]
+    fn nextest_names_are_stripped_of_binary_id() {
+        // Output of `cargo nextest run --message-format libtest-json` for the lib and an
+        // integration test binary of `my-crate`.
+        let lines = [
+            r#"{"type":"suite","event":"started","test_count":1,"nextest":{"crate":"my-crate","test_binary":"my_crate","kind":"lib"}}"#,
+            r#"{"type":"test","event":"started","name":"my-crate$tests::it_works"}"#,
+            r#"{"type":"test","event":"ok","name":"my-crate$tests::it_works","exec_time":0.001972611}"#,
+            r#"{"type":"suite","event":"ok","passed":1,"failed":0,"ignored":0,"measured":0,"filtered_out":0,"exec_time":0.001972611,"nextest":{"crate":"my-crate","test_binary":"my_crate","kind":"lib"}}"#,
+            r#"{"type":"suite","event":"started","test_count":1,"nextest":{"crate":"my-crate","test_binary":"integration","kind":"test"}}"#,
+            r#"{"type":"test","event":"started","name":"my-crate::integration$fails"}"#,
+            r#"{"type":"test","event":"failed","name":"my-crate::integration$fails","exec_time":0.002466843,"stdout":"\nrunning 1 test\n\nthread 'fails' panicked at tests/integration.rs:3:5:\nboom\n"}"#,
+            r#"{"type":"suite","event":"failed","passed":0,"failed":1,"ignored":0,"measured":0,"filtered_out":0,"exec_time":0.002466843,"nextest":{"crate":"my-crate","test_binary":"integration","kind":"test"}}"#,
+        ];
+        let actual = lines
+            .into_iter()
+            .map(|line| format!("{:?}", parse(TestRunner::Nextest, line)))
+            .collect::<Vec<_>>()
+            .join("\n");
+        expect![[r#"
+            Suite
+            Test { name: "tests::it_works", state: Started }
+            Test { name: "tests::it_works", state: Ok }
+            Suite
+            Suite
+            Test { name: "fails", state: Started }
+            Test { name: "fails", state: Failed { stdout: "\nrunning 1 test\n\nthread 'fails' panicked at tests/integration.rs:3:5:\nboom\n" } }
+            Suite"#]]
+        .assert_eq(&actual);
+    }
+
+    #[test]
+    fn libtest_names_are_kept() {
+        let output =
+            parse(TestRunner::Cargo, r#"{"type":"test","event":"ok","name":"tests::a$b"}"#);
+        let CargoTestOutput::Test { name, state: TestState::Ok } = output else {
+            panic!("unexpected output: {output:?}");
+        };
+        assert_eq!(name, "tests::a$b");
+    }
+
+    #[test]
+    fn nextest_keeps_emulated_test_bin_args() {
+        let args = ["--nocapture", "--skip", "slow", "--exact", "--test-threads=1"];
+        let args = nextest_test_bin_args(args.map(ToOwned::to_owned).to_vec());
+        assert_eq!(args, ["--skip", "slow", "--exact"]);
+    }
+}
@@ -213,6 +213,7 @@ pub fn add_cursor(text: &str, offset: TextSize) -> String {
 ///
 /// // ^^^ first line
 /// //   | second line
+/// //   | third line
 ///
 /// Trailing whitespace is sometimes desired but usually stripped by the editor
 /// if at the end of a line, or incorrectly sized if followed by another
@@ -261,9 +262,13 @@ pub fn extract_annotations(text: &str) -> Vec<(TextRange, String)> {
                             .iter()
                             .find(|&&(off, _idx)| off == offset)
                             .unwrap();
-                        res[idx].1.push('\n');
+                        // Consecutive continuations already end with a newline.
+                        if !res[idx].1.ends_with('\n') {
+                            res[idx].1.push('\n');
+                        }
                         res[idx].1.push_str(&content);
                         res[idx].1.push('\n');
+                        this_line_annotations.push((offset, idx));
                     }
                 }
             }
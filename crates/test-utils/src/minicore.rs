@@ -67,6 +67,7 @@
 //!     slice:
 //!     str:
 //!     sync: sized
+//!     test:
 //!     transmute:
 //!     try: infallible
 //!     tuple:
@@ -2032,6 +2033,15 @@ mod macros {
     }
     // endregion:derive
 
+    // region:test
+    pub(crate) mod test {
+        #[rustc_builtin_macro]
+        pub macro test($item:item) {
+            /* compiler built-in */
+        }
+    }
+    // endregion:test
+
     // region:include
     #[rustc_builtin_macro]
     #[macro_export]
@@ -2130,6 +2140,7 @@ pub mod prelude {
             hash::derive::Hash,                           // :hash, derive
             iter::{FromIterator, IntoIterator, Iterator}, // :iterator
             macros::builtin::{derive, derive_const},      // :derive
+            macros::test::test,                           // :test
             marker::Copy,                                 // :copy
             marker::Send,                                 // :send
             marker::Sized,                                // :sized
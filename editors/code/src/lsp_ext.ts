@@ -206,11 +206,31 @@ export const serverStatus = new lc.NotificationType<ServerStatusParams>(
     "experimental/serverStatus",
 );
 export const ssr = new lc.RequestType<SsrParams, lc.WorkspaceEdit, void>("experimental/ssr");
+export const changeSignature = new lc.RequestType<
+    ChangeSignatureParams,
+    ChangeSignatureResult,
+    void
+>("experimental/changeSignature");
+export const moveItemToModule = new lc.RequestType<
+    MoveItemToModuleParams,
+    MoveItemToModuleResult,
+    void
+>("experimental/moveItemToModule");
 export const viewRecursiveMemoryLayout = new lc.RequestType<
     lc.TextDocumentPositionParams,
     RecursiveMemoryLayout | null,
     void
 >("rust-analyzer/viewRecursiveMemoryLayout");
+export const fileCoverage = new lc.RequestType<
+    lc.TextDocumentIdentifier,
+    FileCoverage | null,
+    void
+>("rust-analyzer/fileCoverage");
+
+export type FileCoverage = {
+    covered: lc.Range[];
+    uncovered: lc.Range[];
+};
 
 export type JoinLinesParams = {
     textDocument: lc.TextDocumentIdentifier;
@@ -299,6 +319,28 @@ export type SsrParams = {
     selections: readonly lc.Range[];
 };
 
+export type ChangeSignatureParameter =
+    | { index: number; type?: string }
+    | { pattern: string; type: string; default: string };
+export type ChangeSignatureParams = {
+    textDocument: lc.TextDocumentIdentifier;
+    position: lc.Position;
+    parameters: ChangeSignatureParameter[];
+};
+export type ChangeSignatureResult = {
+    edit: lc.WorkspaceEdit;
+    unrewritten: { location: lc.Location; reason: string }[];
+};
+export type MoveItemToModuleParams = {
+    textDocument: lc.TextDocumentIdentifier;
+    range: lc.Range;
+    targetModule: string;
+};
+export type MoveItemToModuleResult = {
+    edit: lc.WorkspaceEdit;
+    unrewritten: { location: lc.Location; reason: string }[];
+};
+
 export type RecursiveMemoryLayoutNode = {
     item_name: string;
     typename: string;
@@ -308,6 +350,26 @@ export type RecursiveMemoryLayoutNode = {
     parent_idx: number;
     children_start: number;
     children_len: number;
+    padding: { start: number; end: number }[];
+    niche: {
+        offset: number;
+        size: number;
+        validStart: string;
+        validEnd: string;
+        available: string;
+    } | null;
+    tag:
+        | { kind: "direct"; offset: number; size: number }
+        | {
+              kind: "niche";
+              offset: number;
+              size: number;
+              untaggedVariant: number;
+              nicheVariantsStart: number;
+              nicheVariantsEnd: number;
+              nicheStart: string;
+          }
+        | null;
 };
 export type RecursiveMemoryLayout = {
     nodes: RecursiveMemoryLayoutNode[];
@@ -195,6 +195,8 @@ fn check_test_attrs(path: &Path, text: &str) {
         // Generated code from lints contains doc tests in string literals.
         "ide-db/src/generated/lints.rs",
         "proc-macro-srv/src/tests/mod.rs",
+        // Fixtures checking that interpreted `#[should_panic]` tests are reported correctly.
+        "ide/src/test_explorer.rs",
     ];
     if need_panic.iter().any(|p| path.ends_with(p)) {
         return;